@@ -1,5 +1,7 @@
 //! Main [`Asset`] type
 
+#[cfg(feature = "serde")]
+use std::borrow::Cow;
 use std::fmt::{Debug, Formatter};
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::mem::size_of;
@@ -22,7 +24,7 @@ use unreal_asset_base::{
     reader::{ArchiveReader, ArchiveTrait, ArchiveType, ArchiveWriter, RawReader, RawWriter},
     types::{fname::FNameContainer, FName, GenerationInfo, PackageIndex},
     unversioned::Usmap,
-    FNameContainer, Guid, Import,
+    FNameContainer, Guid, Import, ParseLimits,
 };
 use unreal_asset_exports::{BaseExport, Export, ExportBaseTrait, ExportNormalTrait, ExportTrait};
 use unreal_asset_properties::world_tile_property::FWorldTileInfo;
@@ -149,14 +151,16 @@ impl UAssetExportMapEntry {
             >= ObjectVersion::VER_UE4_PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS
         {
             entry.first_export_dependency_offset = archive.read_i32::<LE>()?;
-            entry.serialization_before_serialization_dependencies =
-                Vec::with_capacity(archive.read_i32::<LE>()? as usize);
-            entry.create_before_serialization_dependencies =
-                Vec::with_capacity(archive.read_i32::<LE>()? as usize);
-            entry.serialization_before_create_dependencies =
-                Vec::with_capacity(archive.read_i32::<LE>()? as usize);
-            entry.create_before_create_dependencies =
-                Vec::with_capacity(archive.read_i32::<LE>()? as usize);
+
+            // the dependency counts are kept as capacities until the preload dependencies are read
+            let mut dependencies = || -> Result<Vec<PackageIndex>, Error> {
+                let count = archive.read_i32::<LE>()?;
+                Ok(Vec::with_capacity(archive.check_array_length(count)?))
+            };
+            entry.serialization_before_serialization_dependencies = dependencies()?;
+            entry.create_before_serialization_dependencies = dependencies()?;
+            entry.serialization_before_create_dependencies = dependencies()?;
+            entry.create_before_create_dependencies = dependencies()?;
         }
 
         Ok(entry)
@@ -458,18 +462,36 @@ impl<'a, C: Read + Seek> Asset<C> {
         bulk_data: Option<C>,
         engine_version: EngineVersion,
         mappings: Option<Usmap>,
+    ) -> Result<Self, Error> {
+        Self::new_with_limits(
+            asset_data,
+            bulk_data,
+            engine_version,
+            mappings,
+            ParseLimits::default(),
+        )
+    }
+
+    /// Create an asset from a binary file, rejecting files which exceed the given limits
+    pub fn new_with_limits(
+        asset_data: C,
+        bulk_data: Option<C>,
+        engine_version: EngineVersion,
+        mappings: Option<Usmap>,
+        limits: ParseLimits,
     ) -> Result<Self, Error> {
         let use_event_driven_loader = bulk_data.is_some();
 
         let chain = Chain::new(asset_data, bulk_data);
         let name_map = NameMap::new();
-        let raw_reader = RawReader::new(
+        let mut raw_reader = RawReader::new(
             chain,
             ObjectVersion::UNKNOWN,
             ObjectVersionUE5::UNKNOWN,
             use_event_driven_loader,
             name_map.clone(),
         );
+        raw_reader.limits = limits;
 
         let mut asset = Asset {
             raw_reader,
@@ -712,8 +734,7 @@ impl<'a, C: Read + Seek> Asset<C> {
         } else if self.asset_data.object_version
             >= ObjectVersion::VER_UE4_ADDED_CHUNKID_TO_ASSETDATA_AND_UPACKAGE
         {
-            self.chunk_ids = vec![];
-            self.chunk_ids[0] = self.read_i32::<LE>()?;
+            self.chunk_ids = vec![self.read_i32::<LE>()?];
         }
 
         if self.asset_data.object_version
@@ -827,7 +848,7 @@ impl<'a, C: Read + Seek> Asset<C> {
         }
 
         let index = -index.index - 1;
-        if index < 0 || index > self.imports.len() as i32 {
+        if index < 0 || index >= self.imports.len() as i32 {
             return None;
         }
 
@@ -906,10 +927,11 @@ impl<'a, C: Read + Seek> Asset<C> {
             }
         }
 
-        let mut export_map = Vec::with_capacity(self.asset_data.summary.export_count as usize);
+        let export_count = self.check_array_length(self.asset_data.summary.export_count)?;
+        let mut export_map = Vec::with_capacity(export_count);
         if self.export_offset > 0 {
             self.seek(SeekFrom::Start(self.export_offset as u64))?;
-            for _i in 0..self.asset_data.summary.export_count {
+            for _i in 0..export_count {
                 export_map.push(UAssetExportMapEntry::read(self)?);
             }
         }
@@ -920,14 +942,14 @@ impl<'a, C: Read + Seek> Asset<C> {
         if self.depends_offset > 0
             || depends_offset_zero_version_range.contains(&self.get_object_version())
         {
-            let mut depends_map = Vec::with_capacity(self.asset_data.summary.export_count as usize);
+            let mut depends_map = Vec::with_capacity(export_count);
 
             // 4.14-4.15 the depends offset wasnt updated so always serialized as 0
             if self.depends_offset > 0 {
                 self.seek(SeekFrom::Start(self.depends_offset as u64))?;
             }
 
-            for _i in 0..self.asset_data.summary.export_count as usize {
+            for _i in 0..export_count {
                 let size = self.read_i32::<LE>()?;
                 let mut data: Vec<i32> = Vec::new();
                 for _j in 0..size {
@@ -939,12 +961,13 @@ impl<'a, C: Read + Seek> Asset<C> {
         }
 
         if self.soft_package_reference_offset > 0 {
-            let mut soft_package_reference_list =
-                Vec::with_capacity(self.soft_package_reference_count as usize);
+            let soft_package_reference_count =
+                self.check_array_length(self.soft_package_reference_count)?;
+            let mut soft_package_reference_list = Vec::with_capacity(soft_package_reference_count);
 
             self.seek(SeekFrom::Start(self.soft_package_reference_offset as u64))?;
 
-            for _i in 0..self.soft_package_reference_count as usize {
+            for _i in 0..soft_package_reference_count {
                 if let Some(reference) = self.read_fstring()? {
                     soft_package_reference_list.push(reference);
                 }
@@ -995,7 +1018,7 @@ impl<'a, C: Read + Seek> Asset<C> {
 
                 let next_starting = match i < (map_len - 1) {
                     true => serial_offsets[i + 1],
-                    false => self.data_length()? - 4,
+                    false => self.data_length()?.saturating_sub(4),
                 };
 
                 let export = self.read_export(base_export, next_starting)?;
@@ -1650,3 +1673,177 @@ impl<C: Read + Seek> Debug for Asset<C> {
             .finish()
     }
 }
+
+/// Serialized form of an [`Asset`]
+///
+/// Header values which are written back as they were read are kept so the asset can be written
+/// byte for byte identical after a round trip.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedAsset<'a> {
+    info: Cow<'a, str>,
+    legacy_file_version: i32,
+    generations: Cow<'a, [GenerationInfo]>,
+    package_guid: Guid,
+    engine_version_recorded: Cow<'a, FEngineVersion>,
+    engine_version_compatible: Cow<'a, FEngineVersion>,
+    chunk_ids: Cow<'a, [i32]>,
+    package_source: u32,
+    folder_name: Cow<'a, str>,
+
+    header_offset: i32,
+    name_count: i32,
+    name_offset: i32,
+    soft_object_paths_count: i32,
+    soft_object_paths_offset: i32,
+    gatherable_text_data_count: i32,
+    gatherable_text_data_offset: i32,
+    export_offset: i32,
+    import_offset: i32,
+    depends_offset: i32,
+    soft_package_reference_count: i32,
+    soft_package_reference_offset: i32,
+    searchable_names_offset: i32,
+    thumbnail_table_offset: i32,
+    compression_flags: u32,
+    asset_registry_data_offset: i32,
+    bulk_data_start_offset: i64,
+    world_tile_info_offset: i32,
+    preload_dependency_count: i32,
+    preload_dependency_offset: i32,
+    names_referenced_from_export_data_count: i32,
+    payload_toc_offset: i64,
+    data_resource_offset: i32,
+
+    override_name_map_hashes: Cow<'a, IndexedMap<String, u32>>,
+    name_map: Cow<'a, [String]>,
+    imports: Cow<'a, [Import]>,
+    depends_map: Cow<'a, Option<Vec<Vec<i32>>>>,
+    soft_package_reference_list: Cow<'a, Option<Vec<String>>>,
+    asset_data: Cow<'a, AssetData<PackageIndex>>,
+}
+
+/// Mappings are not serialized and have to be set again after deserializing
+#[cfg(feature = "serde")]
+impl<C: Read + Seek> serde::Serialize for Asset<C> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let name_map = self.name_map.get_ref();
+        SerializedAsset {
+            info: Cow::Borrowed(&self.info),
+            legacy_file_version: self.legacy_file_version,
+            generations: Cow::Borrowed(&self.generations),
+            package_guid: self.package_guid,
+            engine_version_recorded: Cow::Borrowed(&self.engine_version_recorded),
+            engine_version_compatible: Cow::Borrowed(&self.engine_version_compatible),
+            chunk_ids: Cow::Borrowed(&self.chunk_ids),
+            package_source: self.package_source,
+            folder_name: Cow::Borrowed(&self.folder_name),
+            header_offset: self.header_offset,
+            name_count: self.name_count,
+            name_offset: self.name_offset,
+            soft_object_paths_count: self.soft_object_paths_count,
+            soft_object_paths_offset: self.soft_object_paths_offset,
+            gatherable_text_data_count: self.gatherable_text_data_count,
+            gatherable_text_data_offset: self.gatherable_text_data_offset,
+            export_offset: self.export_offset,
+            import_offset: self.import_offset,
+            depends_offset: self.depends_offset,
+            soft_package_reference_count: self.soft_package_reference_count,
+            soft_package_reference_offset: self.soft_package_reference_offset,
+            searchable_names_offset: self.searchable_names_offset,
+            thumbnail_table_offset: self.thumbnail_table_offset,
+            compression_flags: self.compression_flags,
+            asset_registry_data_offset: self.asset_registry_data_offset,
+            bulk_data_start_offset: self.bulk_data_start_offset,
+            world_tile_info_offset: self.world_tile_info_offset,
+            preload_dependency_count: self.preload_dependency_count,
+            preload_dependency_offset: self.preload_dependency_offset,
+            names_referenced_from_export_data_count: self.names_referenced_from_export_data_count,
+            payload_toc_offset: self.payload_toc_offset,
+            data_resource_offset: self.data_resource_offset,
+            override_name_map_hashes: Cow::Borrowed(&self.override_name_map_hashes),
+            name_map: Cow::Borrowed(name_map.get_name_map_index_list()),
+            imports: Cow::Borrowed(&self.imports),
+            depends_map: Cow::Borrowed(&self.depends_map),
+            soft_package_reference_list: Cow::Borrowed(&self.soft_package_reference_list),
+            asset_data: Cow::Borrowed(&self.asset_data),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Deserialized assets are not backed by any data, they can only be modified and written
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Asset<std::io::Cursor<Vec<u8>>> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let serialized = SerializedAsset::deserialize(deserializer)?;
+
+        // duplicate names are kept, names which are referenced by index need them
+        let mut name_map = NameMap::new();
+        for name in serialized.name_map.into_owned() {
+            name_map.get_mut().add_name_reference(name, true);
+        }
+
+        let asset_data = serialized.asset_data.into_owned();
+        let raw_reader = RawReader::new(
+            Chain::new(std::io::Cursor::new(Vec::new()), None),
+            asset_data.object_version,
+            asset_data.object_version_ue5,
+            asset_data.use_event_driven_loader,
+            name_map.clone(),
+        );
+
+        let mut asset = Asset {
+            raw_reader,
+            info: serialized.info.into_owned(),
+            asset_data,
+            legacy_file_version: serialized.legacy_file_version,
+            generations: serialized.generations.into_owned(),
+            package_guid: serialized.package_guid,
+            engine_version_recorded: serialized.engine_version_recorded.into_owned(),
+            engine_version_compatible: serialized.engine_version_compatible.into_owned(),
+            chunk_ids: serialized.chunk_ids.into_owned(),
+            package_source: serialized.package_source,
+            folder_name: serialized.folder_name.into_owned(),
+            header_offset: serialized.header_offset,
+            name_count: serialized.name_count,
+            name_offset: serialized.name_offset,
+            soft_object_paths_count: serialized.soft_object_paths_count,
+            soft_object_paths_offset: serialized.soft_object_paths_offset,
+            gatherable_text_data_count: serialized.gatherable_text_data_count,
+            gatherable_text_data_offset: serialized.gatherable_text_data_offset,
+            export_offset: serialized.export_offset,
+            import_offset: serialized.import_offset,
+            depends_offset: serialized.depends_offset,
+            soft_package_reference_count: serialized.soft_package_reference_count,
+            soft_package_reference_offset: serialized.soft_package_reference_offset,
+            searchable_names_offset: serialized.searchable_names_offset,
+            thumbnail_table_offset: serialized.thumbnail_table_offset,
+            compression_flags: serialized.compression_flags,
+            asset_registry_data_offset: serialized.asset_registry_data_offset,
+            bulk_data_start_offset: serialized.bulk_data_start_offset,
+            world_tile_info_offset: serialized.world_tile_info_offset,
+            preload_dependency_count: serialized.preload_dependency_count,
+            preload_dependency_offset: serialized.preload_dependency_offset,
+            names_referenced_from_export_data_count: serialized
+                .names_referenced_from_export_data_count,
+            payload_toc_offset: serialized.payload_toc_offset,
+            data_resource_offset: serialized.data_resource_offset,
+            override_name_map_hashes: serialized.override_name_map_hashes.into_owned(),
+            name_map: name_map.clone(),
+            imports: serialized.imports.into_owned(),
+            depends_map: serialized.depends_map.into_owned(),
+            soft_package_reference_list: serialized.soft_package_reference_list.into_owned(),
+            parent_class: None,
+        };
+
+        asset.traverse_fnames(&mut |name| name.rebind(&mut name_map));
+        Ok(asset)
+    }
+}
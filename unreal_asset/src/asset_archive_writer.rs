@@ -56,7 +56,7 @@ impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageIndex>>
         }
 
         let index = -index.index - 1;
-        if index < 0 || index > self.imports.len() as i32 {
+        if index < 0 || index >= self.imports.len() as i32 {
             return None;
         }
 
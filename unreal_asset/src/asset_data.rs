@@ -30,6 +30,7 @@ use crate::package_file_summary::PackageFileSummary;
 
 /// Unreal asset data, this is relevant for all assets
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AssetData<Index: PackageIndexTrait> {
     /// Does asset use the event driven loader
     pub use_event_driven_loader: bool,
@@ -49,6 +50,7 @@ pub struct AssetData<Index: PackageIndexTrait> {
 
     /// .usmap mappings
     #[container_ignore]
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub mappings: Option<Usmap>,
 
     /// Object exports
@@ -79,6 +81,7 @@ pub struct AssetData<Index: PackageIndexTrait> {
 ///
 /// This is needed because export reading may want to modify [`AssetData`] which upsets the borrow checker
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadExport<Index: PackageIndexTrait> {
     export: Export<Index>,
     new_map_key_overrides: IndexedMap<String, String>,
@@ -423,9 +426,7 @@ pub trait ExportReaderTrait<Index: PackageIndexTrait>:
                 new_array_overrides,
             ));
         } else if let Some(normal_export) = export.get_normal_export_mut() {
-            let mut extras = vec![0u8; extras_len as usize];
-            self.read_exact(&mut extras)?;
-            normal_export.extras = extras;
+            normal_export.extras = self.read_data(extras_len)?;
         }
 
         Ok(ReadExport::new(
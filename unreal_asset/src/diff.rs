@@ -0,0 +1,832 @@
+//! Property level diff between two assets
+//!
+//! Exports are matched by their object path and properties by their name and duplication index,
+//! so the diff stays readable when a patch shuffles names, imports or exports around.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Seek};
+
+use unreal_asset_base::{
+    containers::IndexedMap,
+    types::{fname::FNameContainer, FName, PackageIndex, PackageIndexTrait},
+    Import,
+};
+use unreal_asset_exports::{Export, ExportBaseTrait, ExportNormalTrait};
+use unreal_asset_properties::{
+    delegate_property::Delegate, int_property::BytePropertyValue, object_property::SoftObjectPath,
+    Property, PropertyDataTrait,
+};
+
+use crate::asset::Asset;
+
+/// What a [`DiffEntry`] refers to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DiffTarget {
+    /// Name map entry
+    Name,
+    /// Import
+    Import,
+    /// Export, changes of the export itself and not its properties
+    Export,
+    /// Property of an export
+    Property,
+}
+
+/// Change of a single item
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DiffChange {
+    /// Only present in the new asset
+    Added {
+        /// New value
+        value: String,
+    },
+    /// Only present in the old asset
+    Removed {
+        /// Old value
+        value: String,
+    },
+    /// Present in both assets with a different value
+    Changed {
+        /// Old value
+        old: String,
+        /// New value
+        new: String,
+    },
+}
+
+/// Single difference between two assets
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiffEntry {
+    /// What this entry refers to
+    pub target: DiffTarget,
+    /// Path of the changed item
+    ///
+    /// Names are the name itself and imports and exports their object path.
+    /// Properties are the path of their export followed by the property path,
+    /// e.g. `Default__BP_Player_C.Inventory[2].Count`.
+    /// Struct members are separated by `.`, array elements and map entries use `[index]` and `[key]`,
+    /// duplication indices of static arrays are written as `Name(index)`.
+    pub path: String,
+    /// The change
+    pub change: DiffChange,
+}
+
+/// Differences between two assets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetDiff {
+    /// Differences, grouped by target
+    pub entries: Vec<DiffEntry>,
+}
+
+impl AssetDiff {
+    /// Compare two assets
+    ///
+    /// Only properties are compared in detail, other export data is reported as changed if
+    /// it is not parsed into properties.
+    pub fn new<C1: Read + Seek, C2: Read + Seek>(old: &Asset<C1>, new: &Asset<C2>) -> Self {
+        let mut differ = Differ {
+            old: ObjectPaths::new(&old.imports, &old.asset_data.exports),
+            new: ObjectPaths::new(&new.imports, &new.asset_data.exports),
+            entries: Vec::new(),
+        };
+
+        differ.diff_names(
+            old.get_name_map().get_ref().get_name_map_index_list(),
+            new.get_name_map().get_ref().get_name_map_index_list(),
+        );
+        differ.diff_imports(&old.imports, &new.imports);
+        differ.diff_exports(&old.asset_data.exports, &new.asset_data.exports);
+
+        AssetDiff {
+            entries: differ.entries,
+        }
+    }
+
+    /// Are the assets equal
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get differences of one target
+    pub fn get_entries(&self, target: DiffTarget) -> impl Iterator<Item = &DiffEntry> {
+        self.entries.iter().filter(move |e| e.target == target)
+    }
+}
+
+impl Display for AssetDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences");
+        }
+
+        for (target, header) in [
+            (DiffTarget::Name, "Names"),
+            (DiffTarget::Import, "Imports"),
+            (DiffTarget::Export, "Exports"),
+            (DiffTarget::Property, "Properties"),
+        ] {
+            let mut entries = self.get_entries(target).peekable();
+            if entries.peek().is_none() {
+                continue;
+            }
+
+            writeln!(f, "{header}:")?;
+            for entry in entries {
+                match &entry.change {
+                    DiffChange::Added { value } if value.is_empty() => {
+                        writeln!(f, "  + {}", entry.path)?
+                    }
+                    DiffChange::Removed { value } if value.is_empty() => {
+                        writeln!(f, "  - {}", entry.path)?
+                    }
+                    DiffChange::Added { value } => writeln!(f, "  + {}: {}", entry.path, value)?,
+                    DiffChange::Removed { value } => writeln!(f, "  - {}: {}", entry.path, value)?,
+                    DiffChange::Changed { old, new } => {
+                        writeln!(f, "  ~ {}: {} -> {}", entry.path, old, new)?
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Format an `FName` the way the engine prints it
+fn name_text(name: &FName) -> String {
+    match name.get_number() {
+        0 => name.get_owned_content(),
+        number => format!("{}_{}", name.get_owned_content(), number - 1),
+    }
+}
+
+/// Append a property key to a path
+fn join_path(path: &str, key: &str) -> String {
+    match path.is_empty() {
+        true => key.to_string(),
+        false => format!("{path}.{key}"),
+    }
+}
+
+/// Key properties are matched by inside of exports and structs
+fn property_key(property: &Property) -> String {
+    match property.get_duplication_index() {
+        0 => name_text(&property.get_name()),
+        index => format!("{}({})", name_text(&property.get_name()), index),
+    }
+}
+
+/// Resolves package indices to object paths
+struct ObjectPaths<'a> {
+    /// Asset imports
+    imports: &'a [Import],
+    /// Asset exports
+    exports: &'a [Export<PackageIndex>],
+}
+
+impl<'a> ObjectPaths<'a> {
+    /// Create a new `ObjectPaths` instance
+    fn new(imports: &'a [Import], exports: &'a [Export<PackageIndex>]) -> Self {
+        ObjectPaths { imports, exports }
+    }
+
+    /// Get the name and outer of an object
+    fn get_object(&self, index: PackageIndex) -> Option<(&FName, PackageIndex)> {
+        if index.is_import() {
+            let import = self.imports.get((-index.index - 1) as usize)?;
+            Some((&import.object_name, import.outer_index))
+        } else {
+            let export = self.exports.get((index.index - 1) as usize)?;
+            let base_export = export.get_base_export();
+            Some((&base_export.object_name, base_export.outer_index))
+        }
+    }
+
+    /// Get the object path of an import or export, e.g. `/Script/Engine.Actor`
+    ///
+    /// Exports are relative to the asset, e.g. `PersistentLevel.StaticMeshActor_1`
+    fn get_path(&self, index: PackageIndex) -> String {
+        if index.index == 0 {
+            return String::from("None");
+        }
+
+        let mut names = Vec::new();
+        let mut current = index;
+        // limit the chain length in case of outer cycles
+        while current.index != 0 && names.len() <= self.imports.len() + self.exports.len() {
+            let Some((name, outer)) = self.get_object(current) else {
+                names.push(format!("Invalid({})", current.index));
+                break;
+            };
+            names.push(name_text(name));
+            current = outer;
+        }
+        names.reverse();
+
+        // imports start with their package, the first subobject of an asset is separated by `:`
+        let separators: &[char] = match index.is_import() {
+            true => &['.', ':'],
+            false => &[':'],
+        };
+        let mut path = String::new();
+        for (i, name) in names.iter().enumerate() {
+            if i > 0 {
+                path.push(*separators.get(i - 1).unwrap_or(&'.'));
+            }
+            path.push_str(name);
+        }
+        path
+    }
+
+    /// Get a readable representation of a property value
+    fn format_value(&self, property: &Property) -> String {
+        match property {
+            Property::BoolProperty(e) => e.value.to_string(),
+            Property::Int8Property(e) => e.value.to_string(),
+            Property::Int16Property(e) => e.value.to_string(),
+            Property::IntProperty(e) => e.value.to_string(),
+            Property::Int64Property(e) => e.value.to_string(),
+            Property::UInt16Property(e) => e.value.to_string(),
+            Property::UInt32Property(e) => e.value.to_string(),
+            Property::UInt64Property(e) => e.value.to_string(),
+            Property::FloatProperty(e) => e.value.to_string(),
+            Property::DoubleProperty(e) => e.value.to_string(),
+            Property::ByteProperty(e) => match &e.value {
+                BytePropertyValue::Byte(value) => value.to_string(),
+                BytePropertyValue::FName(value) => name_text(value),
+            },
+            Property::EnumProperty(e) => e
+                .value
+                .as_ref()
+                .map(name_text)
+                .unwrap_or_else(|| String::from("None")),
+            Property::NameProperty(e) => name_text(&e.value),
+            Property::StrProperty(e) => format_string(e.value.as_deref()),
+            Property::TextProperty(e) => {
+                format_string(e.culture_invariant_string.as_deref().or(e.value.as_deref()))
+            }
+            Property::ObjectProperty(e) => self.get_path(e.value),
+            Property::DelegateProperty(e) => self.format_delegate(&e.value),
+            Property::MulticastDelegateProperty(e) => self.format_delegates(&e.value),
+            Property::MulticastSparseDelegateProperty(e) => self.format_delegates(&e.value),
+            Property::MulticastInlineDelegateProperty(e) => self.format_delegates(&e.value),
+            Property::SoftObjectProperty(e) => format_soft_object_path(&e.value),
+            Property::GuidProperty(e) => e.value.to_string(),
+            Property::VectorProperty(e) => {
+                format!("({}, {}, {})", e.value.x, e.value.y, e.value.z)
+            }
+            Property::RotatorProperty(e) => {
+                format!("({}, {}, {})", e.value.x, e.value.y, e.value.z)
+            }
+            Property::Vector2DProperty(e) => format!("({}, {})", e.value.x, e.value.y),
+            Property::IntPointProperty(e) => format!("({}, {})", e.value.x, e.value.y),
+            Property::Vector4Property(e) => format!(
+                "({}, {}, {}, {})",
+                e.value.x, e.value.y, e.value.z, e.value.w
+            ),
+            Property::QuatProperty(e) => format!(
+                "({}, {}, {}, {})",
+                e.value.x, e.value.y, e.value.z, e.value.w
+            ),
+            Property::ColorProperty(e) => format!(
+                "(R={}, G={}, B={}, A={})",
+                e.color.r, e.color.g, e.color.b, e.color.a
+            ),
+            Property::LinearColorProperty(e) => format!(
+                "(R={}, G={}, B={}, A={})",
+                e.color.r, e.color.g, e.color.b, e.color.a
+            ),
+            Property::StructProperty(e) => {
+                let members = e
+                    .value
+                    .iter()
+                    .map(|e| format!("{}: {}", property_key(e), self.format_value(e)))
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", members.join(", "))
+            }
+            Property::ArrayProperty(e) => self.format_elements(&e.value),
+            Property::SetProperty(e) => self.format_elements(&e.value.value),
+            Property::MapProperty(e) => {
+                let entries = e
+                    .value
+                    .iter()
+                    .map(|(_, key, value)| {
+                        format!("{}: {}", self.format_value(key), self.format_value(value))
+                    })
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", entries.join(", "))
+            }
+            other => format!("{:?}", self.to_comparable(other)),
+        }
+    }
+
+    /// Format a delegate as the path of its object followed by the function name
+    fn format_delegate(&self, delegate: &Delegate) -> String {
+        format!(
+            "{}.{}",
+            self.get_path(delegate.object),
+            name_text(&delegate.delegate)
+        )
+    }
+
+    /// Format the delegates of a multicast delegate
+    fn format_delegates(&self, delegates: &[Delegate]) -> String {
+        let delegates = delegates
+            .iter()
+            .map(|e| self.format_delegate(e))
+            .collect::<Vec<_>>();
+        format!("[{}]", delegates.join(", "))
+    }
+
+    /// Convert a property to a form which can be compared between assets
+    ///
+    /// Names are compared by their content instead of their name map index and package indices
+    /// by the object paths they resolve to, which are returned in the order they are referenced.
+    fn to_comparable(&self, property: &Property) -> (Property, Vec<String>) {
+        let mut property = property.clone();
+        property.traverse_fnames(&mut |name| {
+            *name = FName::new_dummy(name.get_owned_content(), name.get_number());
+        });
+
+        let mut paths = Vec::new();
+        traverse_package_indices(&mut property, &mut |index| {
+            paths.push(self.get_path(*index));
+            *index = PackageIndex::new(0);
+        });
+        (property, paths)
+    }
+
+    /// Format array elements
+    fn format_elements(&self, elements: &[Property]) -> String {
+        let elements = elements
+            .iter()
+            .map(|e| self.format_value(e))
+            .collect::<Vec<_>>();
+        format!("[{}]", elements.join(", "))
+    }
+}
+
+/// Format an optional string
+fn format_string(value: Option<&str>) -> String {
+    match value {
+        Some(value) => format!("{value:?}"),
+        None => String::from("None"),
+    }
+}
+
+/// Format a soft object path
+fn format_soft_object_path(path: &SoftObjectPath) -> String {
+    let mut formatted = match &path.asset_path.package_name {
+        Some(package_name) => format!(
+            "{}.{}",
+            name_text(package_name),
+            name_text(&path.asset_path.asset_name)
+        ),
+        None => name_text(&path.asset_path.asset_name),
+    };
+    if let Some(sub_path) = &path.sub_path_string {
+        formatted.push(':');
+        formatted.push_str(sub_path);
+    }
+    formatted
+}
+
+/// Call `traverse` for every package index of a property, including the ones of nested properties
+fn traverse_package_indices<F: FnMut(&mut PackageIndex)>(
+    property: &mut Property,
+    traverse: &mut F,
+) {
+    let traverse_all = |properties: &mut [Property], traverse: &mut F| {
+        for property in properties {
+            traverse_package_indices(property, traverse);
+        }
+    };
+
+    match property {
+        Property::ObjectProperty(e) => traverse(&mut e.value),
+        Property::MovieSceneSequenceInstanceDataPtrProperty(e) => traverse(&mut e.value),
+        Property::DelegateProperty(e) => traverse(&mut e.value.object),
+        Property::MulticastDelegateProperty(e) => {
+            e.value.iter_mut().for_each(|e| traverse(&mut e.object))
+        }
+        Property::MulticastSparseDelegateProperty(e) => {
+            e.value.iter_mut().for_each(|e| traverse(&mut e.object))
+        }
+        Property::MulticastInlineDelegateProperty(e) => {
+            e.value.iter_mut().for_each(|e| traverse(&mut e.object))
+        }
+        Property::StructProperty(e) => traverse_all(&mut e.value, traverse),
+        Property::ArrayProperty(e) => {
+            traverse_all(&mut e.value, traverse);
+            if let Some(dummy) = &mut e.dummy_property {
+                traverse_all(&mut dummy.value, traverse);
+            }
+        }
+        Property::SetProperty(e) => {
+            traverse_all(&mut e.value.value, traverse);
+            traverse_all(&mut e.removed_items.value, traverse);
+        }
+        Property::MapProperty(e) => {
+            // keys are hashed, so the map has to be rebuilt
+            let mut value = IndexedMap::with_capacity(e.value.len());
+            for (_, mut key, mut entry) in std::mem::take(&mut e.value) {
+                traverse_package_indices(&mut key, traverse);
+                traverse_package_indices(&mut entry, traverse);
+                value.insert(key, entry);
+            }
+            e.value = value;
+            if let Some(keys_to_remove) = &mut e.keys_to_remove {
+                traverse_all(keys_to_remove, traverse);
+            }
+        }
+        Property::ClothLodDataProperty(e) => traverse_all(&mut e.struct_property.value, traverse),
+        Property::NiagaraVariableProperty(e) => {
+            traverse_all(&mut e.struct_property.value, traverse)
+        }
+        Property::NiagaraVariableWithOffsetProperty(e) => {
+            traverse_all(&mut e.niagara_variable.struct_property.value, traverse)
+        }
+        Property::MovieSceneEvalTemplatePtrProperty(e) => traverse_all(&mut e.value, traverse),
+        Property::MovieSceneTrackImplementationPtrProperty(e) => {
+            traverse_all(&mut e.value, traverse)
+        }
+        Property::MovieSceneSegmentProperty(e) => {
+            for properties in &mut e.value.impls {
+                traverse_all(properties, traverse);
+            }
+        }
+        Property::SectionEvaluationDataTreeProperty(e) => {
+            for properties in &mut e.value.tree.data.items {
+                traverse_all(properties, traverse);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects differences between two assets
+struct Differ<'a> {
+    /// Object paths of the old asset
+    old: ObjectPaths<'a>,
+    /// Object paths of the new asset
+    new: ObjectPaths<'a>,
+    /// Found differences
+    entries: Vec<DiffEntry>,
+}
+
+impl<'a> Differ<'a> {
+    /// Add a difference
+    fn push(&mut self, target: DiffTarget, path: String, change: DiffChange) {
+        self.entries.push(DiffEntry {
+            target,
+            path,
+            change,
+        });
+    }
+
+    /// Compare name maps, the order of names is ignored
+    fn diff_names(&mut self, old: &[String], new: &[String]) {
+        let old_names = old.iter().collect::<HashSet<_>>();
+        let new_names = new.iter().collect::<HashSet<_>>();
+
+        let removed = old.iter().filter(|e| !new_names.contains(e));
+        let added = new.iter().filter(|e| !old_names.contains(e));
+        for (name, change) in removed
+            .map(|e| {
+                (
+                    e,
+                    DiffChange::Removed {
+                        value: String::new(),
+                    },
+                )
+            })
+            .chain(added.map(|e| {
+                (
+                    e,
+                    DiffChange::Added {
+                        value: String::new(),
+                    },
+                )
+            }))
+        {
+            self.push(DiffTarget::Name, name.clone(), change);
+        }
+    }
+
+    /// Compare imports by their object path and class
+    fn diff_imports(&mut self, old: &[Import], new: &[Import]) {
+        let key = |paths: &ObjectPaths, i: usize, import: &Import| {
+            (
+                paths.get_path(PackageIndex::new(-(i as i32) - 1)),
+                format!(
+                    "{}.{}",
+                    name_text(&import.class_package),
+                    name_text(&import.class_name)
+                ),
+            )
+        };
+        let old_imports = old
+            .iter()
+            .enumerate()
+            .map(|(i, e)| key(&self.old, i, e))
+            .collect::<Vec<_>>();
+        let new_imports = new
+            .iter()
+            .enumerate()
+            .map(|(i, e)| key(&self.new, i, e))
+            .collect::<Vec<_>>();
+
+        let old_set = old_imports.iter().collect::<HashSet<_>>();
+        let new_set = new_imports.iter().collect::<HashSet<_>>();
+        for (path, class) in old_imports.iter().filter(|e| !new_set.contains(e)) {
+            let change = DiffChange::Removed {
+                value: class.clone(),
+            };
+            self.push(DiffTarget::Import, path.clone(), change);
+        }
+        for (path, class) in new_imports.iter().filter(|e| !old_set.contains(e)) {
+            let change = DiffChange::Added {
+                value: class.clone(),
+            };
+            self.push(DiffTarget::Import, path.clone(), change);
+        }
+    }
+
+    /// Compare exports matched by their object path
+    fn diff_exports(&mut self, old: &[Export<PackageIndex>], new: &[Export<PackageIndex>]) {
+        let paths = |paths: &ObjectPaths, exports: &[Export<PackageIndex>]| {
+            (0..exports.len())
+                .map(|i| paths.get_path(PackageIndex::new(i as i32 + 1)))
+                .collect::<Vec<_>>()
+        };
+        let old_paths = paths(&self.old, old);
+        let new_paths = paths(&self.new, new);
+
+        let mut new_by_path = HashMap::with_capacity(new.len());
+        for (path, export) in new_paths.iter().zip(new) {
+            new_by_path.entry(path).or_insert(export);
+        }
+        let old_by_path = old_paths.iter().collect::<HashSet<_>>();
+
+        for (path, old_export) in old_paths.iter().zip(old) {
+            match new_by_path.remove(path) {
+                Some(new_export) => self.diff_export(path, old_export, new_export),
+                None => {
+                    let change = DiffChange::Removed {
+                        value: self.old.get_path(old_export.get_base_export().class_index),
+                    };
+                    self.push(DiffTarget::Export, path.clone(), change);
+                }
+            }
+        }
+        for (path, new_export) in new_paths.iter().zip(new) {
+            if !old_by_path.contains(path) {
+                let change = DiffChange::Added {
+                    value: self.new.get_path(new_export.get_base_export().class_index),
+                };
+                self.push(DiffTarget::Export, path.clone(), change);
+            }
+        }
+    }
+
+    /// Compare two exports with the same object path
+    fn diff_export(&mut self, path: &str, old: &Export<PackageIndex>, new: &Export<PackageIndex>) {
+        let old_class = self.old.get_path(old.get_base_export().class_index);
+        let new_class = self.new.get_path(new.get_base_export().class_index);
+        if old_class != new_class {
+            let change = DiffChange::Changed {
+                old: old_class,
+                new: new_class,
+            };
+            self.push(DiffTarget::Export, path.to_string(), change);
+            return;
+        }
+
+        match (old, new) {
+            (Export::RawExport(old), Export::RawExport(new)) => {
+                if old.data != new.data {
+                    let change = DiffChange::Changed {
+                        old: format!("{} bytes of data", old.data.len()),
+                        new: format!("{} bytes of data", new.data.len()),
+                    };
+                    self.push(DiffTarget::Export, path.to_string(), change);
+                }
+            }
+            (Export::DataTableExport(_), Export::DataTableExport(_)) => {
+                self.diff_normal_exports(path, old, new);
+                let rows = |export: &Export<PackageIndex>| match export {
+                    Export::DataTableExport(e) => e
+                        .table
+                        .data
+                        .iter()
+                        .cloned()
+                        .map(Property::StructProperty)
+                        .collect::<Vec<_>>(),
+                    _ => Vec::new(),
+                };
+                // rows are always compared, equal package indices can resolve to different objects
+                self.diff_properties(path, &rows(old), &rows(new));
+            }
+            _ => self.diff_normal_exports(path, old, new),
+        }
+    }
+
+    /// Compare properties of exports if both have them
+    fn diff_normal_exports(
+        &mut self,
+        path: &str,
+        old: &Export<PackageIndex>,
+        new: &Export<PackageIndex>,
+    ) {
+        if let (Some(old), Some(new)) = (old.get_normal_export(), new.get_normal_export()) {
+            self.diff_properties(path, &old.properties, &new.properties);
+        }
+    }
+
+    /// Compare properties matched by their name and duplication index
+    ///
+    /// Properties with the same key are matched in the order they appear in.
+    fn diff_properties(&mut self, path: &str, old: &[Property], new: &[Property]) {
+        let mut new_by_key: HashMap<String, VecDeque<&Property>> =
+            HashMap::with_capacity(new.len());
+        for property in new {
+            new_by_key
+                .entry(property_key(property))
+                .or_default()
+                .push_back(property);
+        }
+
+        for old_property in old {
+            let key = property_key(old_property);
+            let property_path = join_path(path, &key);
+            match new_by_key.get_mut(&key).and_then(VecDeque::pop_front) {
+                Some(new_property) => self.diff_property(property_path, old_property, new_property),
+                None => {
+                    let value = self.old.format_value(old_property);
+                    self.push(
+                        DiffTarget::Property,
+                        property_path,
+                        DiffChange::Removed { value },
+                    );
+                }
+            }
+        }
+
+        // unmatched properties are the last ones of their key, report them in order
+        for new_property in new {
+            let key = property_key(new_property);
+            let Some(properties) = new_by_key.get_mut(&key) else {
+                continue;
+            };
+            if !properties
+                .front()
+                .is_some_and(|e| std::ptr::eq(*e, new_property))
+            {
+                continue;
+            }
+
+            properties.pop_front();
+            let value = self.new.format_value(new_property);
+            self.push(
+                DiffTarget::Property,
+                join_path(path, &key),
+                DiffChange::Added { value },
+            );
+        }
+    }
+
+    /// Compare two properties with the same path
+    fn diff_property(&mut self, path: String, old: &Property, new: &Property) {
+        match (old, new) {
+            (Property::StructProperty(old_struct), Property::StructProperty(new_struct))
+                if old_struct
+                    .struct_type
+                    .as_ref()
+                    .map(FName::get_owned_content)
+                    == new_struct
+                        .struct_type
+                        .as_ref()
+                        .map(FName::get_owned_content) =>
+            {
+                self.diff_properties(&path, &old_struct.value, &new_struct.value)
+            }
+            (Property::ArrayProperty(old_array), Property::ArrayProperty(new_array)) => {
+                self.diff_elements(&path, &old_array.value, &new_array.value)
+            }
+            (Property::SetProperty(old_set), Property::SetProperty(new_set)) => {
+                self.diff_set(&path, &old_set.value.value, &new_set.value.value)
+            }
+            (Property::MapProperty(old_map), Property::MapProperty(new_map)) => {
+                let old_entries = old_map
+                    .value
+                    .iter()
+                    .map(|(_, key, value)| (self.old.format_value(key), value))
+                    .collect::<Vec<_>>();
+                let new_entries = new_map
+                    .value
+                    .iter()
+                    .map(|(_, key, value)| (self.new.format_value(key), value))
+                    .collect::<Vec<_>>();
+                self.diff_map(&path, &old_entries, &new_entries)
+            }
+            _ => {
+                if !self.values_equal(old, new) {
+                    let change = DiffChange::Changed {
+                        old: self.old.format_value(old),
+                        new: self.new.format_value(new),
+                    };
+                    self.push(DiffTarget::Property, path, change);
+                }
+            }
+        }
+    }
+
+    /// Compare two properties which are not containers
+    fn values_equal(&self, old: &Property, new: &Property) -> bool {
+        self.old.to_comparable(old) == self.new.to_comparable(new)
+    }
+
+    /// Compare array elements by their index
+    fn diff_elements(&mut self, path: &str, old: &[Property], new: &[Property]) {
+        for (i, (old_element, new_element)) in old.iter().zip(new).enumerate() {
+            self.diff_property(format!("{path}[{i}]"), old_element, new_element);
+        }
+        for (i, old_element) in old.iter().enumerate().skip(new.len()) {
+            let value = self.old.format_value(old_element);
+            self.push(
+                DiffTarget::Property,
+                format!("{path}[{i}]"),
+                DiffChange::Removed { value },
+            );
+        }
+        for (i, new_element) in new.iter().enumerate().skip(old.len()) {
+            let value = self.new.format_value(new_element);
+            self.push(
+                DiffTarget::Property,
+                format!("{path}[{i}]"),
+                DiffChange::Added { value },
+            );
+        }
+    }
+
+    /// Compare set elements by their value
+    fn diff_set(&mut self, path: &str, old: &[Property], new: &[Property]) {
+        let old_values = old
+            .iter()
+            .map(|e| self.old.format_value(e))
+            .collect::<Vec<_>>();
+        let new_values = new
+            .iter()
+            .map(|e| self.new.format_value(e))
+            .collect::<Vec<_>>();
+
+        let old_set = old_values.iter().collect::<HashSet<_>>();
+        let new_set = new_values.iter().collect::<HashSet<_>>();
+        for value in old_values.iter().filter(|e| !new_set.contains(e)) {
+            let change = DiffChange::Removed {
+                value: value.clone(),
+            };
+            self.push(DiffTarget::Property, format!("{path}[{value}]"), change);
+        }
+        for value in new_values.iter().filter(|e| !old_set.contains(e)) {
+            let change = DiffChange::Added {
+                value: value.clone(),
+            };
+            self.push(DiffTarget::Property, format!("{path}[{value}]"), change);
+        }
+    }
+
+    /// Compare map entries by their formatted key
+    fn diff_map(&mut self, path: &str, old: &[(String, &Property)], new: &[(String, &Property)]) {
+        let mut new_by_key = new
+            .iter()
+            .map(|(key, value)| (key, *value))
+            .collect::<HashMap<_, _>>();
+        let old_keys = old.iter().map(|(key, _)| key).collect::<HashSet<_>>();
+
+        for (key, old_value) in old {
+            let entry_path = format!("{path}[{key}]");
+            match new_by_key.remove(key) {
+                Some(new_value) => self.diff_property(entry_path, old_value, new_value),
+                None => {
+                    let value = self.old.format_value(old_value);
+                    self.push(
+                        DiffTarget::Property,
+                        entry_path,
+                        DiffChange::Removed { value },
+                    );
+                }
+            }
+        }
+        for (key, new_value) in new {
+            if !old_keys.contains(key) {
+                let value = self.new.format_value(new_value);
+                self.push(
+                    DiffTarget::Property,
+                    format!("{path}[{key}]"),
+                    DiffChange::Added { value },
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! IoStore chunk ids
+
+use std::io::{self, Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, BE, LE};
+
+use unreal_asset_base::enums::EIoChunkType;
+
+/// Id of a chunk of data in an IoStore container
+///
+/// Chunks of packages use the package id as `id`, other chunks derive it from their content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IoChunkId {
+    /// Id of the package or other object this chunk belongs to
+    pub id: u64,
+    /// Index of this chunk within the object
+    pub index: u16,
+    /// Chunk type, the meaning of the values changed between engine versions
+    pub chunk_type: u8,
+}
+
+impl IoChunkId {
+    /// Create a new `IoChunkId`
+    pub fn new(id: u64, index: u16, chunk_type: u8) -> Self {
+        IoChunkId {
+            id,
+            index,
+            chunk_type,
+        }
+    }
+
+    /// Get the chunk type as a UE5 `EIoChunkType`, `None` for unknown types
+    pub fn get_chunk_type(&self) -> Option<EIoChunkType> {
+        EIoChunkType::try_from(self.chunk_type).ok()
+    }
+
+    /// Read an `IoChunkId` from a reader
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let id = reader.read_u64::<LE>()?;
+        let index = reader.read_u16::<BE>()?;
+        let _padding = reader.read_u8()?;
+        let chunk_type = reader.read_u8()?;
+
+        Ok(IoChunkId {
+            id,
+            index,
+            chunk_type,
+        })
+    }
+
+    /// Write an `IoChunkId` to a writer
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_u64::<LE>(self.id)?;
+        writer.write_u16::<BE>(self.index)?;
+        writer.write_u8(0)?;
+        writer.write_u8(self.chunk_type)?;
+        Ok(())
+    }
+}
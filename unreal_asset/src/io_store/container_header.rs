@@ -0,0 +1,604 @@
+//! IoStore container headers
+
+/*
+    container header (UE 5.1+), stored in the ContainerHeader chunk of every container:
+    - u32 signature 0x496f436e
+    - u32 version
+    - u64 container id
+    - array of u64 package ids
+    - array of u8 store entries
+        - entries (24 bytes each, 16 bytes since NoExportInfo)
+            - i32 export count (before NoExportInfo)
+            - i32 export bundle count (before NoExportInfo)
+            - array view of u64 imported package ids
+            - array view of 20 bytes shader map hashes
+        - array views are u32 count and u32 offset of the data relative to the view itself
+    - array of u64 optional segment package ids (version >= OptionalSegmentPackages)
+    - array of u8 optional segment store entries (version >= OptionalSegmentPackages)
+    - name batch of redirect names
+    - array of localized packages (version >= LocalizedPackages)
+    - array of package redirects
+
+    unversioned container header (UE 5.0):
+    - u64 container id
+    - array of u64 package ids
+    - array of u8 store entries, 24 bytes each like above
+    - map of culture names to arrays of source and localized package ids
+    - array of source and redirected package ids
+
+    container header (UE 4.26 and 4.27):
+    - u64 container id
+    - u32 package count
+    - array of u8 name data, the header and characters of each name
+    - array of u8 name hashes, u64 hash algorithm followed by the u64 hash of each name
+    - array of u64 package ids
+    - array of u8 store entries
+        - entries (48 bytes each)
+            - u64 export bundles size
+            - mapped name of the package
+            - mapped name of the source package
+            - i32 export count
+            - i32 export bundle count
+            - u32 load order
+            - u32 padding
+            - array view of u64 imported package ids
+        - mapped names are u32 index into the names, the top 2 bits are the name map type,
+          and u32 number
+    - map of culture names to arrays of source and localized package ids
+    - array of source and redirected package ids
+*/
+
+use std::io::{Cursor, Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, BE, LE};
+
+use unreal_asset_base::{
+    crc,
+    enums::{EIoContainerHeaderVersion, EIoStoreTocVersion, HASH_VERSION_CITYHASH64},
+    error::IoStoreError,
+    Error, ParseLimits,
+};
+use unreal_helpers::UnrealReadExt;
+
+/// Signature at the start of every versioned container header
+const CONTAINER_HEADER_SIGNATURE: u32 = 0x496f436e;
+/// Size of a shader map hash
+const SHADER_MAP_HASH_SIZE: usize = 20;
+/// Bits of a mapped name index used for the index, the rest is the name map type
+const MAPPED_NAME_INDEX_BITS: u32 = 30;
+/// Name map type of names stored in the container header
+const MAPPED_NAME_TYPE_CONTAINER: u32 = 1;
+
+/// Version of a container header, the engine only started versioning container headers in UE 5.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ContainerHeaderVersion {
+    /// `FContainerHeader` of UE 4.26 and 4.27, which also stores the names of its packages
+    Legacy,
+    /// Unversioned `FIoContainerHeader` of UE 5.0
+    Unversioned,
+    /// Versioned `FIoContainerHeader` of UE 5.1+
+    Versioned(EIoContainerHeaderVersion),
+}
+
+/// Store entry of a package in the container header
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageStoreEntry {
+    /// Size of the export bundles, only stored in UE4 containers
+    pub export_bundles_size: u64,
+    /// Name of the package, only stored in UE4 containers
+    pub package_name: String,
+    /// Name of the package this one was localized from, only stored in UE4 containers
+    pub source_package_name: Option<String>,
+    /// Amount of exports, not stored since `EIoContainerHeaderVersion::NoExportInfo`
+    pub export_count: i32,
+    /// Amount of export bundles, not stored since `EIoContainerHeaderVersion::NoExportInfo`
+    pub export_bundle_count: i32,
+    /// Load order, only stored in UE4 containers
+    pub load_order: u32,
+    /// Ids of the packages this package imports
+    pub imported_packages: Vec<u64>,
+    /// Hashes of the shader maps this package uses, not stored in UE4 containers
+    pub shader_map_hashes: Vec<[u8; SHADER_MAP_HASH_SIZE]>,
+}
+
+/// IoStore container header, lists the packages of a container.
+/// Localized packages and package redirects are neither read nor written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IoContainerHeader {
+    /// Container header version
+    pub version: ContainerHeaderVersion,
+    /// Id of the container
+    pub container_id: u64,
+    /// Package ids and store entries of all packages in the container
+    pub packages: Vec<(u64, PackageStoreEntry)>,
+}
+
+impl IoContainerHeader {
+    /// Create a new empty `IoContainerHeader`
+    pub fn new(version: ContainerHeaderVersion, container_id: u64) -> Self {
+        IoContainerHeader {
+            version,
+            container_id,
+            packages: Vec::new(),
+        }
+    }
+
+    /// Read an `IoContainerHeader` from the data of a ContainerHeader chunk of a container with
+    /// the given toc version
+    pub fn read(
+        data: &[u8],
+        toc_version: EIoStoreTocVersion,
+        limits: &ParseLimits,
+    ) -> Result<Self, Error> {
+        let mut reader = Cursor::new(data);
+
+        // UE 5.0 and 5.1 share toc versions, only versioned container headers start with the signature
+        let version = match toc_version {
+            version if version < EIoStoreTocVersion::PerfectHash => ContainerHeaderVersion::Legacy,
+            _ if data.get(..4) != Some(&CONTAINER_HEADER_SIGNATURE.to_le_bytes()) => {
+                ContainerHeaderVersion::Unversioned
+            }
+            _ => {
+                reader.read_u32::<LE>()?;
+                let version = EIoContainerHeaderVersion::try_from(reader.read_u32::<LE>()?)
+                    .map_err(IoStoreError::from)?;
+                ContainerHeaderVersion::Versioned(version)
+            }
+        };
+        let container_id = reader.read_u64::<LE>()?;
+
+        let mut names = Vec::new();
+        if version == ContainerHeaderVersion::Legacy {
+            let _package_count = reader.read_u32::<LE>()?;
+            let name_data = read_bytes(&mut reader, limits)?;
+            let name_hashes = read_bytes(&mut reader, limits)?;
+            names = read_names(&name_data, &name_hashes, limits)?;
+        }
+
+        let package_count = read_count(&mut reader, 8, limits)?;
+        let mut package_ids = Vec::with_capacity(package_count);
+        for _ in 0..package_count {
+            package_ids.push(reader.read_u64::<LE>()?);
+        }
+
+        let store_entries = read_bytes(&mut reader, limits)?;
+        let store_entries =
+            read_store_entries(&store_entries, package_count, version, &names, limits)?;
+
+        // optional segments, redirects and localized packages are not needed to load packages
+
+        Ok(IoContainerHeader {
+            version,
+            container_id,
+            packages: package_ids.into_iter().zip(store_entries).collect(),
+        })
+    }
+
+    /// Write an `IoContainerHeader`
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        let version = match self.version {
+            ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::Latest) => {
+                ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::NoExportInfo)
+            }
+            ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::LatestPlusOne) => {
+                return Err(IoStoreError::configuration_invalid(format!(
+                    "Can not write container header version {:?}",
+                    self.version
+                ))
+                .into())
+            }
+            version => version,
+        };
+
+        match version {
+            ContainerHeaderVersion::Versioned(version) => {
+                writer.write_u32::<LE>(CONTAINER_HEADER_SIGNATURE)?;
+                writer.write_u32::<LE>(version as u32)?;
+                writer.write_u64::<LE>(self.container_id)?;
+            }
+            ContainerHeaderVersion::Unversioned => writer.write_u64::<LE>(self.container_id)?,
+            ContainerHeaderVersion::Legacy => {
+                writer.write_u64::<LE>(self.container_id)?;
+                writer.write_u32::<LE>(self.packages.len() as u32)?;
+            }
+        }
+
+        // the names of UE4 store entries are stored in the container header
+        let mut names = Vec::new();
+        if version == ContainerHeaderVersion::Legacy {
+            for (_, entry) in &self.packages {
+                for name in [
+                    entry.package_name.as_str(),
+                    entry.source_package_name.as_deref().unwrap_or("None"),
+                ] {
+                    if !names.iter().any(|e| e == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+
+            let (name_data, name_hashes) = write_names(&names)?;
+            writer.write_u32::<LE>(name_data.len() as u32)?;
+            writer.write_all(&name_data)?;
+            writer.write_u32::<LE>(name_hashes.len() as u32)?;
+            writer.write_all(&name_hashes)?;
+        }
+
+        writer.write_u32::<LE>(self.packages.len() as u32)?;
+        for (package_id, _) in &self.packages {
+            writer.write_u64::<LE>(*package_id)?;
+        }
+
+        let store_entries = write_store_entries(&self.packages, version, &names)?;
+        writer.write_u32::<LE>(store_entries.len() as u32)?;
+        writer.write_all(&store_entries)?;
+
+        match version {
+            ContainerHeaderVersion::Versioned(version) => {
+                if version >= EIoContainerHeaderVersion::OptionalSegmentPackages {
+                    // optional segment package ids and store entries
+                    writer.write_u32::<LE>(0)?;
+                    writer.write_u32::<LE>(0)?;
+                }
+                // empty name batch of redirect names
+                writer.write_u32::<LE>(0)?;
+                if version >= EIoContainerHeaderVersion::LocalizedPackages {
+                    writer.write_u32::<LE>(0)?;
+                }
+            }
+            // culture package map
+            _ => writer.write_u32::<LE>(0)?,
+        }
+        // package redirects
+        writer.write_u32::<LE>(0)?;
+
+        Ok(())
+    }
+}
+
+/// Size of a store entry in the given version
+fn store_entry_size(version: ContainerHeaderVersion) -> usize {
+    match version {
+        ContainerHeaderVersion::Legacy => 48,
+        ContainerHeaderVersion::Versioned(version)
+            if version >= EIoContainerHeaderVersion::NoExportInfo =>
+        {
+            16
+        }
+        _ => 24,
+    }
+}
+
+/// Whether store entries of the given version start with the export counts
+fn has_export_info(version: ContainerHeaderVersion) -> bool {
+    match version {
+        ContainerHeaderVersion::Versioned(version) => {
+            version < EIoContainerHeaderVersion::NoExportInfo
+        }
+        _ => true,
+    }
+}
+
+fn read_store_entries(
+    data: &[u8],
+    count: usize,
+    version: ContainerHeaderVersion,
+    names: &[String],
+    limits: &ParseLimits,
+) -> Result<Vec<PackageStoreEntry>, Error> {
+    let entry_size = store_entry_size(version);
+    if count.saturating_mul(entry_size) > data.len() {
+        return Err(Error::invalid_file(format!(
+            "{count} store entries do not fit into {} bytes",
+            data.len()
+        )));
+    }
+
+    let read_u32 =
+        |position: usize| u32::from_le_bytes(data[position..position + 4].try_into().unwrap());
+
+    // returns the position and element count of the array view at the given position
+    let read_view = |position: usize, element_size: usize| -> Result<(usize, usize), Error> {
+        let count = read_u32(position);
+        let offset = read_u32(position + 4);
+        if count > limits.max_array_length {
+            return Err(Error::limit_exceeded(
+                "Array length",
+                count as u64,
+                limits.max_array_length as u64,
+            ));
+        }
+        if count == 0 {
+            return Ok((0, 0));
+        }
+
+        let start = position + offset as usize;
+        if start + count as usize * element_size > data.len() {
+            return Err(Error::invalid_file(
+                "Store entry array is outside of the store entries".to_string(),
+            ));
+        }
+        Ok((start, count as usize))
+    };
+
+    // returns the name of the mapped name at the given position
+    let read_name = |position: usize| -> Result<String, Error> {
+        let index = read_u32(position) & ((1 << MAPPED_NAME_INDEX_BITS) - 1);
+        let number = read_u32(position + 4);
+        let name = names.get(index as usize).ok_or_else(|| {
+            Error::invalid_file(format!("Store entry name index {index} is out of bounds"))
+        })?;
+        Ok(match number {
+            0 => name.clone(),
+            number => format!("{name}_{}", number - 1),
+        })
+    };
+
+    let mut entries = Vec::with_capacity(count);
+    for i in 0..count {
+        let mut position = i * entry_size;
+        let mut entry = PackageStoreEntry::default();
+
+        if version == ContainerHeaderVersion::Legacy {
+            entry.export_bundles_size =
+                u64::from_le_bytes(data[position..position + 8].try_into().unwrap());
+            entry.package_name = read_name(position + 8)?;
+            entry.source_package_name =
+                Some(read_name(position + 16)?).filter(|e| e.as_str() != "None");
+            position += 24;
+        }
+
+        if has_export_info(version) {
+            entry.export_count = read_u32(position) as i32;
+            entry.export_bundle_count = read_u32(position + 4) as i32;
+            position += 8;
+        }
+
+        if version == ContainerHeaderVersion::Legacy {
+            entry.load_order = read_u32(position);
+            // padding
+            position += 8;
+        }
+
+        let (start, imported_count) = read_view(position, 8)?;
+        entry.imported_packages = data[start..start + imported_count * 8]
+            .chunks_exact(8)
+            .map(|e| u64::from_le_bytes(e.try_into().unwrap()))
+            .collect();
+
+        if version != ContainerHeaderVersion::Legacy {
+            let (start, hash_count) = read_view(position + 8, SHADER_MAP_HASH_SIZE)?;
+            entry.shader_map_hashes = data[start..start + hash_count * SHADER_MAP_HASH_SIZE]
+                .chunks_exact(SHADER_MAP_HASH_SIZE)
+                .map(|e| e.try_into().unwrap())
+                .collect();
+        }
+
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn write_store_entries(
+    packages: &[(u64, PackageStoreEntry)],
+    version: ContainerHeaderVersion,
+    names: &[String],
+) -> Result<Vec<u8>, Error> {
+    let entry_size = store_entry_size(version);
+    let mut entries = vec![0u8; packages.len() * entry_size];
+
+    // imported package ids come first to keep them aligned
+    let mut imported_packages = Vec::new();
+    let mut shader_map_hashes = Vec::new();
+    let mut views = Vec::with_capacity(packages.len());
+    for (_, entry) in packages {
+        if version == ContainerHeaderVersion::Legacy && !entry.shader_map_hashes.is_empty() {
+            return Err(IoStoreError::configuration_invalid(
+                "UE4 container headers can not store shader map hashes".to_string(),
+            )
+            .into());
+        }
+
+        views.push((imported_packages.len(), shader_map_hashes.len()));
+        for package_id in &entry.imported_packages {
+            imported_packages.write_u64::<LE>(*package_id)?;
+        }
+        for hash in &entry.shader_map_hashes {
+            shader_map_hashes.write_all(hash)?;
+        }
+    }
+
+    // names are always added to the container names before the store entries are written
+    let mapped_name = |name: &str| {
+        let index = names.iter().position(|e| e == name).unwrap_or_default() as u32;
+        (MAPPED_NAME_TYPE_CONTAINER << MAPPED_NAME_INDEX_BITS | index) as u64
+    };
+
+    let imported_packages_start = entries.len();
+    let shader_map_hashes_start = imported_packages_start + imported_packages.len();
+    for (i, ((_, entry), (imported_offset, hash_offset))) in packages.iter().zip(views).enumerate()
+    {
+        let mut position = i * entry_size;
+        if version == ContainerHeaderVersion::Legacy {
+            let source_package_name = entry.source_package_name.as_deref().unwrap_or("None");
+            entries[position..position + 8]
+                .copy_from_slice(&entry.export_bundles_size.to_le_bytes());
+            entries[position + 8..position + 16]
+                .copy_from_slice(&mapped_name(&entry.package_name).to_le_bytes());
+            entries[position + 16..position + 24]
+                .copy_from_slice(&mapped_name(source_package_name).to_le_bytes());
+            position += 24;
+        }
+
+        if has_export_info(version) {
+            entries[position..position + 4].copy_from_slice(&entry.export_count.to_le_bytes());
+            entries[position + 4..position + 8]
+                .copy_from_slice(&entry.export_bundle_count.to_le_bytes());
+            position += 8;
+        }
+
+        if version == ContainerHeaderVersion::Legacy {
+            entries[position..position + 4].copy_from_slice(&entry.load_order.to_le_bytes());
+            position += 8;
+        }
+
+        write_view(
+            &mut entries,
+            position,
+            entry.imported_packages.len(),
+            imported_packages_start + imported_offset,
+        );
+        if version != ContainerHeaderVersion::Legacy {
+            write_view(
+                &mut entries,
+                position + 8,
+                entry.shader_map_hashes.len(),
+                shader_map_hashes_start + hash_offset,
+            );
+        }
+    }
+
+    entries.extend_from_slice(&imported_packages);
+    entries.extend_from_slice(&shader_map_hashes);
+    Ok(entries)
+}
+
+/// Write an array view at the given position pointing to the given target
+fn write_view(entries: &mut [u8], position: usize, count: usize, target: usize) {
+    let offset = match count {
+        0 => 0,
+        _ => target - position,
+    };
+    entries[position..position + 4].copy_from_slice(&(count as u32).to_le_bytes());
+    entries[position + 4..position + 8].copy_from_slice(&(offset as u32).to_le_bytes());
+}
+
+/// Read the names of a UE4 container header from its name data and name hashes
+fn read_names(
+    name_data: &[u8],
+    name_hashes: &[u8],
+    limits: &ParseLimits,
+) -> Result<Vec<String>, Error> {
+    if name_hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut hashes = Cursor::new(name_hashes);
+    let hash_version = hashes.read_u64::<LE>()?;
+    if hash_version != HASH_VERSION_CITYHASH64 {
+        return Err(Error::unimplemented(format!(
+            "Unimplemented name batch algorithm: {hash_version}"
+        )));
+    }
+    let count = name_hashes.len() / 8 - 1;
+    if count > limits.max_array_length as usize {
+        return Err(Error::limit_exceeded(
+            "Array length",
+            count as u64,
+            limits.max_array_length as u64,
+        ));
+    }
+
+    let mut reader = Cursor::new(name_data);
+    let mut names = Vec::with_capacity(count);
+    for _ in 0..count {
+        let header = reader.read_u16::<BE>()?;
+        let len = (header & 0x7fff) as usize;
+        let name = match header & 0x8000 != 0 {
+            true => {
+                let mut characters = vec![0u16; len];
+                reader.read_u16_into::<LE>(&mut characters)?;
+                String::from_utf16(&characters)
+                    .map_err(|_| Error::invalid_file("Name is not valid UTF-16".to_string()))?
+            }
+            false => {
+                let mut characters = vec![0u8; len];
+                reader.read_exact(&mut characters)?;
+                // names which are not wide only contain latin-1 characters
+                characters.into_iter().map(char::from).collect()
+            }
+        };
+
+        let hash = hashes.read_u64::<LE>()?;
+        if hash != crc::cityhash64_to_lower(&name) {
+            return Err(Error::name_batch_hash_mismatch(
+                hash,
+                crc::cityhash64_to_lower(&name),
+                name,
+            ));
+        }
+        names.push(name);
+    }
+
+    Ok(names)
+}
+
+/// Write the names of a UE4 container header, returning the name data and the name hashes
+fn write_names(names: &[String]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let mut name_data = Vec::new();
+    let mut name_hashes = Vec::with_capacity((names.len() + 1) * 8);
+    name_hashes.write_u64::<LE>(HASH_VERSION_CITYHASH64)?;
+
+    for name in names {
+        let (len, is_wide, characters) = match name.is_ascii() {
+            true => (name.len(), false, name.as_bytes().to_vec()),
+            false => {
+                let characters = name.encode_utf16().collect::<Vec<_>>();
+                (
+                    characters.len(),
+                    true,
+                    characters.into_iter().flat_map(u16::to_le_bytes).collect(),
+                )
+            }
+        };
+        if len > 0x7fff {
+            return Err(Error::invalid_file(format!(
+                "Name {name} is too long for a name batch"
+            )));
+        }
+
+        name_data.write_u16::<BE>((is_wide as u16) << 15 | len as u16)?;
+        name_data.write_all(&characters)?;
+        name_hashes.write_u64::<LE>(crc::cityhash64_to_lower(name))?;
+    }
+
+    Ok((name_data, name_hashes))
+}
+
+/// Read a byte array, checking its size against the limits
+fn read_bytes(reader: &mut Cursor<&[u8]>, limits: &ParseLimits) -> Result<Vec<u8>, Error> {
+    let size = reader.read_u32::<LE>()? as u64;
+    if size > limits.max_data_size {
+        return Err(Error::limit_exceeded(
+            "Container header data size",
+            size,
+            limits.max_data_size,
+        ));
+    }
+    Ok(reader.read_vec(size as usize)?)
+}
+
+/// Read an element count and check that the elements can fit into the remaining data
+fn read_count(
+    reader: &mut Cursor<&[u8]>,
+    element_size: u64,
+    limits: &ParseLimits,
+) -> Result<usize, Error> {
+    let count = reader.read_u32::<LE>()?;
+    if count > limits.max_array_length {
+        return Err(Error::limit_exceeded(
+            "Array length",
+            count as u64,
+            limits.max_array_length as u64,
+        ));
+    }
+
+    let remaining = (reader.get_ref().len() as u64).saturating_sub(reader.position());
+    if count as u64 * element_size > remaining {
+        return Err(Error::invalid_file(format!(
+            "Container header array of {count} elements is larger than the remaining data"
+        )));
+    }
+    Ok(count as usize)
+}
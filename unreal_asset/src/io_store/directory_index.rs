@@ -0,0 +1,263 @@
+/*
+    .utoc directory index:
+    - FString mount point
+    - directory entries (16 bytes each)
+        - u32 name, index into the string table
+        - u32 first child directory
+        - u32 next sibling directory
+        - u32 first file
+    - file entries (12 bytes each)
+        - u32 name, index into the string table
+        - u32 next file
+        - u32 user data, index of the chunk in the toc
+    - string table, array of FStrings
+
+    u32::MAX marks a missing index, the first directory is the root without a name.
+*/
+
+use std::io::Cursor;
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+
+use unreal_asset_base::{Error, ParseLimits};
+use unreal_helpers::{read_ext::read_fstring_len, UnrealWriteExt};
+
+/// Marks a missing directory, file or name
+pub(crate) const INVALID_INDEX: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DirectoryEntry {
+    pub name: u32,
+    pub first_child: u32,
+    pub next_sibling: u32,
+    pub first_file: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FileEntry {
+    pub name: u32,
+    pub next_file: u32,
+    pub user_data: u32,
+}
+
+/// Read a directory index, returning the mount point and the paths of all files with their chunk
+/// index relative to the mount point
+pub(crate) fn read_directory_index(
+    data: &[u8],
+    limits: &ParseLimits,
+) -> Result<(String, Vec<(String, u32)>), Error> {
+    let mut reader = Cursor::new(data);
+
+    let mount_point = read_string(&mut reader, limits)?;
+
+    let directory_count = read_count(&mut reader, 16, limits)?;
+    let mut directories = Vec::with_capacity(directory_count);
+    for _ in 0..directory_count {
+        directories.push(DirectoryEntry {
+            name: reader.read_u32::<LE>()?,
+            first_child: reader.read_u32::<LE>()?,
+            next_sibling: reader.read_u32::<LE>()?,
+            first_file: reader.read_u32::<LE>()?,
+        });
+    }
+
+    let file_count = read_count(&mut reader, 12, limits)?;
+    let mut files = Vec::with_capacity(file_count);
+    for _ in 0..file_count {
+        files.push(FileEntry {
+            name: reader.read_u32::<LE>()?,
+            next_file: reader.read_u32::<LE>()?,
+            user_data: reader.read_u32::<LE>()?,
+        });
+    }
+
+    let string_count = read_count(&mut reader, 4, limits)?;
+    let mut strings = Vec::with_capacity(string_count);
+    for _ in 0..string_count {
+        strings.push(read_string(&mut reader, limits)?);
+    }
+
+    let get_string = |index: u32| {
+        strings
+            .get(index as usize)
+            .ok_or_else(|| Error::invalid_file(format!("Invalid directory index name {index}")))
+    };
+
+    let mut entries = Vec::new();
+    if directories.is_empty() {
+        return Ok((mount_point, entries));
+    }
+
+    // every directory and file can only be visited once, which also protects against cycles
+    let mut visited_directories = vec![false; directories.len()];
+    let mut visited_files = vec![false; files.len()];
+    let mut stack = vec![(0u32, String::new())];
+
+    while let Some((mut directory_index, parent_path)) = stack.pop() {
+        while directory_index != INVALID_INDEX {
+            let directory = directories.get(directory_index as usize).ok_or_else(|| {
+                Error::invalid_file(format!("Invalid directory index {directory_index}"))
+            })?;
+            if std::mem::replace(&mut visited_directories[directory_index as usize], true) {
+                return Err(Error::invalid_file(
+                    "Directory index contains a cycle".to_string(),
+                ));
+            }
+
+            let path = match directory.name {
+                INVALID_INDEX => parent_path.clone(),
+                name => format!("{}{}/", parent_path, get_string(name)?),
+            };
+
+            let mut file_index = directory.first_file;
+            while file_index != INVALID_INDEX {
+                let file = files.get(file_index as usize).ok_or_else(|| {
+                    Error::invalid_file(format!("Invalid directory index file {file_index}"))
+                })?;
+                if std::mem::replace(&mut visited_files[file_index as usize], true) {
+                    return Err(Error::invalid_file(
+                        "Directory index contains a cycle".to_string(),
+                    ));
+                }
+
+                entries.push((
+                    format!("{}{}", path, get_string(file.name)?),
+                    file.user_data,
+                ));
+                file_index = file.next_file;
+            }
+
+            if directory.first_child != INVALID_INDEX {
+                stack.push((directory.first_child, path));
+            }
+            directory_index = directory.next_sibling;
+        }
+    }
+
+    Ok((mount_point, entries))
+}
+
+/// Write a directory index containing the given file paths relative to the mount point with
+/// their chunk index
+pub(crate) fn write_directory_index(
+    mount_point: &str,
+    files: &[(String, u32)],
+) -> Result<Vec<u8>, Error> {
+    let mut directories = vec![DirectoryEntry {
+        name: INVALID_INDEX,
+        first_child: INVALID_INDEX,
+        next_sibling: INVALID_INDEX,
+        first_file: INVALID_INDEX,
+    }];
+    let mut file_entries: Vec<FileEntry> = Vec::with_capacity(files.len());
+    let mut strings: Vec<&str> = Vec::new();
+    let mut string_indices = std::collections::HashMap::new();
+    let mut get_string_index = |string| {
+        *string_indices.entry(string).or_insert_with(|| {
+            strings.push(string);
+            strings.len() as u32 - 1
+        })
+    };
+
+    for (path, chunk_index) in files {
+        let (directory_names, file_name) = match path.rsplit_once('/') {
+            Some((directory_names, file_name)) => (Some(directory_names), file_name),
+            None => (None, path.as_str()),
+        };
+
+        let mut directory = 0;
+        for directory_name in directory_names.into_iter().flat_map(|e| e.split('/')) {
+            let name = get_string_index(directory_name);
+
+            let mut child = directories[directory].first_child;
+            while child != INVALID_INDEX && directories[child as usize].name != name {
+                child = directories[child as usize].next_sibling;
+            }
+            if child == INVALID_INDEX {
+                child = directories.len() as u32;
+                directories.push(DirectoryEntry {
+                    name,
+                    first_child: INVALID_INDEX,
+                    next_sibling: directories[directory].first_child,
+                    first_file: INVALID_INDEX,
+                });
+                directories[directory].first_child = child;
+            }
+            directory = child as usize;
+        }
+
+        file_entries.push(FileEntry {
+            name: get_string_index(file_name),
+            next_file: directories[directory].first_file,
+            user_data: *chunk_index,
+        });
+        directories[directory].first_file = file_entries.len() as u32 - 1;
+    }
+
+    let mut writer = Vec::new();
+    writer.write_fstring(Some(mount_point))?;
+
+    writer.write_u32::<LE>(directories.len() as u32)?;
+    for directory in &directories {
+        writer.write_u32::<LE>(directory.name)?;
+        writer.write_u32::<LE>(directory.first_child)?;
+        writer.write_u32::<LE>(directory.next_sibling)?;
+        writer.write_u32::<LE>(directory.first_file)?;
+    }
+
+    writer.write_u32::<LE>(file_entries.len() as u32)?;
+    for file in &file_entries {
+        writer.write_u32::<LE>(file.name)?;
+        writer.write_u32::<LE>(file.next_file)?;
+        writer.write_u32::<LE>(file.user_data)?;
+    }
+
+    writer.write_u32::<LE>(strings.len() as u32)?;
+    for string in strings {
+        writer.write_fstring(Some(string))?;
+    }
+
+    Ok(writer)
+}
+
+/// Read an element count and check that the elements can fit into the remaining data
+fn read_count(
+    reader: &mut Cursor<&[u8]>,
+    element_size: u64,
+    limits: &ParseLimits,
+) -> Result<usize, Error> {
+    let count = reader.read_u32::<LE>()?;
+    if count > limits.max_array_length {
+        return Err(Error::limit_exceeded(
+            "Array length",
+            count as u64,
+            limits.max_array_length as u64,
+        ));
+    }
+
+    let remaining = (reader.get_ref().len() as u64).saturating_sub(reader.position());
+    if count as u64 * element_size > remaining {
+        return Err(Error::invalid_file(format!(
+            "Directory index array of {count} elements is larger than the remaining data"
+        )));
+    }
+    Ok(count as usize)
+}
+
+fn read_string(reader: &mut Cursor<&[u8]>, limits: &ParseLimits) -> Result<String, Error> {
+    let len = reader.read_i32::<LE>()?;
+    let (len, is_wide) = match len < 0 {
+        true => (len.checked_neg().unwrap_or(i32::MAX), true),
+        false => (len, false),
+    };
+
+    if len as u32 > limits.max_string_length {
+        return Err(Error::limit_exceeded(
+            "String length",
+            len as u64,
+            limits.max_string_length as u64,
+        ));
+    }
+
+    Ok(read_fstring_len(reader, len, is_wide)?.unwrap_or_default())
+}
@@ -0,0 +1,63 @@
+//! IoStore containers
+//!
+//! UE5 and late UE4 games store their cooked packages in IoStore containers instead of pak files.
+//! A container consists of a `.utoc` table of contents and one or more `.ucas` partitions
+//! holding the possibly compressed and encrypted chunk data.
+
+use aes::cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::Aes256;
+
+use unreal_asset_base::{crc, error::IoStoreError, Error};
+
+pub mod chunk_id;
+pub mod container_header;
+mod directory_index;
+pub mod reader;
+pub mod script_objects;
+mod toc;
+pub mod writer;
+
+pub use chunk_id::IoChunkId;
+pub use container_header::{ContainerHeaderVersion, IoContainerHeader, PackageStoreEntry};
+pub use reader::IoStoreReader;
+pub use script_objects::ScriptObjects;
+pub use writer::IoStoreWriter;
+
+/// Size of one AES block, encrypted data is always aligned to this
+const AES_BLOCK_SIZE: usize = 16;
+
+/// Get the id of a package from its name, e.g. `/Game/Maps/Level`
+pub fn package_id(package_name: &str) -> u64 {
+    crc::cityhash64_to_lower(package_name)
+}
+
+/// Get the id of a container from its name, the file name of the .utoc without extension
+pub fn container_id(container_name: &str) -> u64 {
+    crc::cityhash64_to_lower(container_name)
+}
+
+/// Encrypt data in place with AES-256 in ECB mode, `data` must be aligned to the AES block size
+fn encrypt(key: &[u8; 32], data: &mut [u8]) {
+    let cipher = Aes256::new(GenericArray::from_slice(key));
+    for block in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+        cipher.encrypt_block(GenericArray::from_mut_slice(block));
+    }
+}
+
+/// Decrypt data in place with AES-256 in ECB mode
+fn decrypt(key: Option<&[u8; 32]>, data: &mut [u8]) -> Result<(), Error> {
+    let key = key.ok_or(IoStoreError::NoEncryptionKey)?;
+    if data.len().next_multiple_of(AES_BLOCK_SIZE) != data.len() {
+        return Err(Error::invalid_file(format!(
+            "Encrypted data of size {} is not aligned to the AES block size",
+            data.len()
+        )));
+    }
+
+    let cipher = Aes256::new(GenericArray::from_slice(key));
+    for block in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+        cipher.decrypt_block(GenericArray::from_mut_slice(block));
+    }
+
+    Ok(())
+}
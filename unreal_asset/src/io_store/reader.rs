@@ -0,0 +1,341 @@
+//! IoStore container reader
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use unreal_asset_base::{
+    compression::{self, CompressionMethod},
+    enums::EIoStoreTocVersion,
+    error::IoStoreError,
+    flags::EIoContainerFlags,
+    Error, Guid, ParseLimits,
+};
+use unreal_helpers::UnrealReadExt;
+
+use super::chunk_id::IoChunkId;
+use super::directory_index::read_directory_index;
+use super::toc::{CompressedBlock, Toc};
+use super::{decrypt, AES_BLOCK_SIZE};
+
+/// An IoStore container reader with its chunk data kept on disk and only read on demand.
+#[derive(Debug)]
+pub struct IoStoreReader<R>
+where
+    R: Read + Seek,
+{
+    /// mount point of the directory index, file names are relative to it
+    pub mount_point: String,
+    toc: Option<Toc>,
+    chunk_indices: HashMap<IoChunkId, usize>,
+    files: BTreeMap<String, IoChunkId>,
+    key: Option<[u8; 32]>,
+    limits: ParseLimits,
+    toc_reader: R,
+    partitions: Vec<R>,
+}
+
+impl<R> IoStoreReader<R>
+where
+    R: Read + Seek,
+{
+    /// Creates a new `IoStoreReader` that reads the table of contents from `toc` and the chunk
+    /// data from the `.ucas` `partitions` in order.
+    /// When using readers that use syscalls like a `File` it is recommended to wrap them in a
+    /// [`std::io::BufReader`] to avoid unnecessary syscalls.
+    pub fn new(toc: R, partitions: Vec<R>) -> Self {
+        Self {
+            mount_point: "".to_owned(),
+            toc: None,
+            chunk_indices: HashMap::new(),
+            files: BTreeMap::new(),
+            key: None,
+            limits: ParseLimits::default(),
+            toc_reader: toc,
+            partitions,
+        }
+    }
+
+    /// Creates a new `IoStoreReader` that decrypts the directory index and chunk data with the
+    /// given AES-256 key where needed.
+    pub fn new_encrypted(toc: R, partitions: Vec<R>, key: [u8; 32]) -> Self {
+        Self {
+            key: Some(key),
+            ..Self::new(toc, partitions)
+        }
+    }
+
+    /// Sets the limits used to reject malformed containers, this has to happen before the table
+    /// of contents is loaded.
+    pub fn set_limits(&mut self, limits: ParseLimits) {
+        self.limits = limits;
+    }
+
+    /// Load the table of contents and the directory index into memory to start reading chunks.
+    pub fn load_toc(&mut self) -> Result<(), Error> {
+        self.toc_reader.seek(SeekFrom::Start(0))?;
+        let toc = Toc::read(&mut self.toc_reader, &self.limits)?;
+
+        let mut chunk_indices = HashMap::with_capacity(toc.chunk_ids.len());
+        for (index, chunk_id) in toc.chunk_ids.iter().enumerate() {
+            chunk_indices.insert(*chunk_id, index);
+        }
+
+        let mut mount_point = String::new();
+        let mut files = BTreeMap::new();
+        if let Some(directory_index) = &toc.directory_index {
+            let mut directory_index = directory_index.clone();
+            if toc
+                .header
+                .container_flags
+                .contains(EIoContainerFlags::ENCRYPTED)
+            {
+                decrypt(self.key.as_ref(), &mut directory_index)?;
+            }
+
+            let (directory_mount_point, entries) =
+                read_directory_index(&directory_index, &self.limits)?;
+            mount_point = directory_mount_point;
+            for (name, index) in entries {
+                let chunk_id = toc.chunk_ids.get(index as usize).ok_or_else(|| {
+                    Error::invalid_file(format!("Invalid chunk index {index} for file {name}"))
+                })?;
+                files.insert(name, *chunk_id);
+            }
+        }
+
+        self.mount_point = mount_point;
+        self.chunk_indices = chunk_indices;
+        self.files = files;
+        self.toc = Some(toc);
+
+        Ok(())
+    }
+
+    /// Returns the version of the table of contents, only valid after it has been loaded.
+    pub fn get_toc_version(&self) -> EIoStoreTocVersion {
+        self.toc
+            .as_ref()
+            .map(|e| e.header.version)
+            .unwrap_or(EIoStoreTocVersion::Invalid)
+    }
+
+    /// Returns the id of the container, only valid after the table of contents has been loaded.
+    pub fn get_container_id(&self) -> u64 {
+        self.toc
+            .as_ref()
+            .map(|e| e.header.container_id)
+            .unwrap_or_default()
+    }
+
+    /// Returns the flags of the container, only valid after the table of contents has been
+    /// loaded.
+    pub fn get_container_flags(&self) -> EIoContainerFlags {
+        self.toc
+            .as_ref()
+            .map(|e| e.header.container_flags)
+            .unwrap_or_default()
+    }
+
+    /// Returns the guid of the key the container is encrypted with, only valid after the table of
+    /// contents has been loaded.
+    pub fn get_encryption_key_guid(&self) -> Guid {
+        self.toc
+            .as_ref()
+            .map(|e| e.header.encryption_key_guid)
+            .unwrap_or_default()
+    }
+
+    /// Returns the ids of all chunks in the order they are stored in the table of contents.
+    pub fn get_chunk_ids(&self) -> Vec<IoChunkId> {
+        self.toc
+            .as_ref()
+            .map(|e| e.chunk_ids.clone())
+            .unwrap_or_default()
+    }
+
+    /// Checks if the container contains a chunk with the given id
+    pub fn contains_chunk(&self, chunk_id: &IoChunkId) -> bool {
+        self.chunk_indices.contains_key(chunk_id)
+    }
+
+    /// Returns the size of the chunk with the given id after decompression
+    pub fn get_chunk_size(&self, chunk_id: &IoChunkId) -> Option<u64> {
+        let index = *self.chunk_indices.get(chunk_id)?;
+        Some(self.toc.as_ref()?.offsets[index].length)
+    }
+
+    /// Returns the paths of all files in the directory index, relative to the mount point.
+    pub fn get_file_names(&self) -> Vec<&String> {
+        self.files.keys().collect()
+    }
+
+    /// Checks if the directory index contains a file with the given path
+    pub fn contains_file(&self, name: &str) -> bool {
+        self.files.contains_key(name)
+    }
+
+    /// Returns the id of the chunk stored at the given path
+    pub fn get_chunk_id(&self, name: &str) -> Option<IoChunkId> {
+        self.files.get(name).copied()
+    }
+
+    /// Reads the data of the file at the given path, relative to the mount point
+    pub fn read_file(&mut self, name: &str) -> Result<Vec<u8>, Error> {
+        let chunk_id = self
+            .get_chunk_id(name)
+            .ok_or_else(|| IoStoreError::no_file(name.to_string()))?;
+        self.read_chunk(&chunk_id)
+    }
+
+    /// Reads the data of the chunk with the given id
+    pub fn read_chunk(&mut self, chunk_id: &IoChunkId) -> Result<Vec<u8>, Error> {
+        let (toc, index) = match (self.toc.as_ref(), self.chunk_indices.get(chunk_id)) {
+            (Some(toc), Some(index)) => (toc, *index),
+            _ => return Err(IoStoreError::no_file(format!("{chunk_id:?}")).into()),
+        };
+
+        let location = toc.offsets[index];
+        if location.length > self.limits.max_data_size {
+            return Err(Error::limit_exceeded(
+                "Chunk size",
+                location.length,
+                self.limits.max_data_size,
+            ));
+        }
+        if location.length == 0 {
+            return Ok(Vec::new());
+        }
+
+        let block_size = toc.header.compression_block_size as u64;
+        let end = location.offset + location.length;
+        let first_block = location.offset / block_size;
+        let last_block = (end - 1) / block_size;
+        let blocks = toc
+            .compressed_blocks
+            .get(first_block as usize..=last_block as usize)
+            .ok_or_else(|| {
+                Error::invalid_file(format!("Chunk {chunk_id:?} is outside of the container"))
+            })?
+            .to_vec();
+
+        let mut data = Vec::with_capacity(location.length.min(0x1000000) as usize);
+        for (block_index, block) in (first_block..).zip(blocks) {
+            let decompressed = self.read_block(block)?;
+
+            let block_start = block_index * block_size;
+            let start = location.offset.saturating_sub(block_start) as usize;
+            let end = (end - block_start).min(decompressed.len() as u64) as usize;
+            if start > end {
+                return Err(Error::invalid_file(format!(
+                    "Compression block {block_index} is too small"
+                )));
+            }
+            data.extend_from_slice(&decompressed[start..end]);
+        }
+
+        if data.len() as u64 != location.length {
+            return Err(Error::invalid_file(format!(
+                "Chunk {chunk_id:?} has size {}, expected {}",
+                data.len(),
+                location.length
+            )));
+        }
+
+        Ok(data)
+    }
+
+    /// Read and decompress a single compression block
+    fn read_block(&mut self, block: CompressedBlock) -> Result<Vec<u8>, Error> {
+        let toc = self
+            .toc
+            .as_ref()
+            .ok_or_else(|| Error::invalid_file("Table of contents is not loaded".to_string()))?;
+
+        let method = match block.compression_method {
+            0 => CompressionMethod::None,
+            index => toc
+                .compression_methods
+                .get(index as usize - 1)
+                .cloned()
+                .ok_or_else(|| {
+                    Error::invalid_file(format!("Invalid compression method index {index}"))
+                })?,
+        };
+
+        let encrypted = toc
+            .header
+            .container_flags
+            .contains(EIoContainerFlags::ENCRYPTED);
+        let size = match encrypted {
+            true => (block.compressed_size as usize).next_multiple_of(AES_BLOCK_SIZE),
+            false => block.compressed_size as usize,
+        };
+
+        let partition = block.offset / toc.header.partition_size;
+        if partition >= toc.header.partition_count as u64 {
+            return Err(Error::invalid_file(format!(
+                "Compression block in partition {partition} of {}",
+                toc.header.partition_count
+            )));
+        }
+        let reader = self
+            .partitions
+            .get_mut(partition as usize)
+            .ok_or_else(|| IoStoreError::no_file(format!("partition {partition}")))?;
+        reader.seek(SeekFrom::Start(block.offset % toc.header.partition_size))?;
+        let mut compressed = reader.read_vec(size)?;
+
+        if encrypted {
+            decrypt(self.key.as_ref(), &mut compressed)?;
+        }
+        compressed.truncate(block.compressed_size as usize);
+
+        let mut decompressed = vec![0u8; block.uncompressed_size as usize];
+        // uncompressed blocks are copied and must contain enough data
+        if method == CompressionMethod::None && compressed.len() < decompressed.len() {
+            return Err(Error::invalid_file(format!(
+                "Uncompressed block of size {} is smaller than {}",
+                compressed.len(),
+                decompressed.len()
+            )));
+        }
+        compression::decompress(method, &compressed, &mut decompressed)?;
+
+        Ok(decompressed)
+    }
+
+    /// Returns the table of contents reader and the partition readers
+    pub fn into_inner(self) -> (R, Vec<R>) {
+        (self.toc_reader, self.partitions)
+    }
+}
+
+impl IoStoreReader<BufReader<File>> {
+    /// Opens the container at the given `.utoc` path together with all of its `.ucas` partitions
+    /// next to it, `name.ucas`, `name_s1.ucas`, `name_s2.ucas` and so on.
+    /// The key is used to decrypt encrypted containers.
+    pub fn open<P: AsRef<Path>>(path: P, key: Option<[u8; 32]>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let toc = BufReader::new(File::open(path)?);
+
+        let mut partitions = vec![BufReader::new(File::open(path.with_extension("ucas"))?)];
+        let stem = path
+            .file_stem()
+            .map(|e| e.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        loop {
+            let partition_path = path.with_file_name(format!("{stem}_s{}.ucas", partitions.len()));
+            match partition_path.is_file() {
+                true => partitions.push(BufReader::new(File::open(partition_path)?)),
+                false => break,
+            }
+        }
+
+        Ok(Self {
+            key,
+            ..Self::new(toc, partitions)
+        })
+    }
+}
@@ -0,0 +1,136 @@
+//! IoStore script objects
+
+/*
+    script objects, stored in the ScriptObjects chunk of global.utoc:
+    - name batch of global names
+    - i32 script object count
+    - script objects, 32 bytes each
+        - u32 global name index, u32 name number
+        - u64 global package object index
+        - u64 outer package object index
+        - u64 cdo class package object index
+*/
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use byteorder::{ReadBytesExt, LE};
+
+use unreal_asset_base::{
+    containers::{Chain, NameMap},
+    object_version::{ObjectVersion, ObjectVersionUE5},
+    reader::{ArchiveReader, RawReader},
+    types::{FName, PackageObjectIndex},
+    Error, ParseLimits,
+};
+
+/// Script object of the global container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptObjectEntry {
+    /// Object name, e.g. `Actor` or `/Script/Engine` for script packages
+    pub object_name: String,
+    /// Outer object, null for script packages
+    pub outer_index: PackageObjectIndex,
+    /// Class of a class default object, null for other objects
+    pub cdo_class_index: PackageObjectIndex,
+}
+
+/// Script objects of the global container
+///
+/// Zen packages reference engine classes and other script objects by hash,
+/// these are needed to resolve the names of such imports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptObjects {
+    /// Script objects by their package object index
+    pub objects: HashMap<PackageObjectIndex, ScriptObjectEntry>,
+}
+
+impl ScriptObjects {
+    /// Create a new empty `ScriptObjects` table
+    pub fn new() -> Self {
+        ScriptObjects::default()
+    }
+
+    /// Read `ScriptObjects` from the data of the ScriptObjects chunk of global.utoc
+    pub fn read(data: &[u8], limits: &ParseLimits) -> Result<Self, Error> {
+        let mut reader = RawReader::<PackageObjectIndex, _>::new(
+            Chain::new(Cursor::new(data), None),
+            ObjectVersion::UNKNOWN,
+            ObjectVersionUE5::UNKNOWN,
+            false,
+            NameMap::new(),
+        );
+        reader.limits = *limits;
+
+        let (names, _) = reader.read_name_batch(false)?;
+        let count = reader.read_i32::<LE>()?;
+        let count = reader.check_array_length(count)?;
+
+        let mut objects = HashMap::with_capacity(count.min(0x10000));
+        for _ in 0..count {
+            let name_index = (reader.read_u32::<LE>()? & FName::INDEX_MASK) as usize;
+            let name_number = reader.read_u32::<LE>()?;
+            let global_index = PackageObjectIndex::new(reader.read_u64::<LE>()?);
+            let outer_index = PackageObjectIndex::new(reader.read_u64::<LE>()?);
+            let cdo_class_index = PackageObjectIndex::new(reader.read_u64::<LE>()?);
+
+            let name = names.get(name_index).ok_or_else(|| {
+                Error::invalid_file(format!(
+                    "Script object name index {name_index} is out of range"
+                ))
+            })?;
+            let object_name = match name_number {
+                0 => name.clone(),
+                number => format!("{}_{}", name, number - 1),
+            };
+
+            objects.insert(
+                global_index,
+                ScriptObjectEntry {
+                    object_name,
+                    outer_index,
+                    cdo_class_index,
+                },
+            );
+        }
+
+        Ok(ScriptObjects { objects })
+    }
+
+    /// Add a script object and its outers by its path, e.g. `/Script/Engine.Actor`
+    pub fn add_object(&mut self, path: &str) -> PackageObjectIndex {
+        let index = PackageObjectIndex::from_script_path(path);
+        if self.objects.contains_key(&index) {
+            return index;
+        }
+
+        let (outer_index, object_name) = match path.rfind(['.', ':']) {
+            Some(separator) => (
+                self.add_object(&path[..separator]),
+                path[separator + 1..].to_string(),
+            ),
+            None => (PackageObjectIndex::NULL, path.to_string()),
+        };
+        self.objects.insert(
+            index,
+            ScriptObjectEntry {
+                object_name,
+                outer_index,
+                cdo_class_index: PackageObjectIndex::NULL,
+            },
+        );
+        index
+    }
+
+    /// Get the name of a script object
+    pub fn get_object_name(&self, index: PackageObjectIndex) -> Option<FName> {
+        self.objects
+            .get(&index)
+            .map(|e| FName::new_dummy(e.object_name.clone(), 0))
+    }
+
+    /// Get the outer of a script object
+    pub fn get_outer_index(&self, index: PackageObjectIndex) -> Option<PackageObjectIndex> {
+        self.objects.get(&index).map(|e| e.outer_index)
+    }
+}
@@ -0,0 +1,405 @@
+/*
+    .utoc table of contents:
+    - header (144 bytes)
+        - 16 bytes magic "-==--==--==--==-"
+        - u8 version, u8 + u16 reserved
+        - u32 header size
+        - u32 entry count
+        - u32 compressed block entry count
+        - u32 compressed block entry size (12)
+        - u32 compression method name count
+        - u32 compression method name length (32)
+        - u32 compression block size
+        - u32 directory index size
+        - u32 partition count (version >= PartitionSize)
+        - u64 container id
+        - 16 bytes encryption key guid
+        - u8 container flags, u8 + u16 reserved
+        - u32 perfect hash seed count (version >= PerfectHash)
+        - u64 partition size (version >= PartitionSize)
+        - u32 chunks without perfect hash count (version >= PerfectHashWithOverflow)
+        - u32 + 5 * u64 reserved
+    - chunk ids (12 bytes each)
+    - offsets and lengths in the uncompressed data (10 bytes each)
+        - 5 bytes BE offset
+        - 5 bytes BE length
+    - i32 perfect hash seeds
+    - i32 indices of chunks without perfect hash
+    - compressed blocks (12 bytes each)
+        - 5 bytes LE offset in the .ucas files
+        - 3 bytes LE compressed size
+        - 3 bytes LE uncompressed size
+        - u8 compression method index, 0 is no compression
+    - compression method names (32 bytes each)
+    - signatures (only when signed)
+        - i32 signature size
+        - toc signature
+        - block signature
+        - 20 bytes sha1 hash of every compressed block
+    - directory index (only when indexed, encrypted when the container is)
+    - chunk metadata (33 bytes each, 24 bytes since ReplaceIoChunkHashWithIoHash)
+        - 32 bytes hash, 20 bytes since ReplaceIoChunkHashWithIoHash
+        - u8 flags
+        - 3 bytes padding (version >= ReplaceIoChunkHashWithIoHash)
+*/
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, BE, LE};
+
+use unreal_asset_base::{
+    compression::CompressionMethod, enums::EIoStoreTocVersion, error::IoStoreError,
+    flags::EIoContainerFlags, Error, Guid, ParseLimits,
+};
+use unreal_helpers::{UnrealReadExt, UnrealWriteExt};
+
+use super::chunk_id::IoChunkId;
+
+/// Magic at the start of every .utoc file
+pub(crate) const TOC_MAGIC: [u8; 16] = *b"-==--==--==--==-";
+/// Size of the .utoc header
+pub(crate) const TOC_HEADER_SIZE: u32 = 144;
+/// Size of a compressed block entry
+pub(crate) const COMPRESSED_BLOCK_ENTRY_SIZE: u32 = 12;
+/// Length of a compression method name
+pub(crate) const COMPRESSION_METHOD_NAME_LENGTH: u32 = 32;
+/// Chunk metadata flag for chunks with at least one compressed block
+const META_FLAG_COMPRESSED: u8 = 0x01;
+
+/// .utoc header
+#[derive(Debug, Clone)]
+pub(crate) struct TocHeader {
+    pub version: EIoStoreTocVersion,
+    pub entry_count: u32,
+    pub compressed_block_entry_count: u32,
+    pub compression_method_name_count: u32,
+    pub compression_method_name_length: u32,
+    pub compression_block_size: u32,
+    pub directory_index_size: u32,
+    pub partition_count: u32,
+    pub container_id: u64,
+    pub encryption_key_guid: Guid,
+    pub container_flags: EIoContainerFlags,
+    pub perfect_hash_seed_count: u32,
+    pub partition_size: u64,
+    pub chunks_without_perfect_hash_count: u32,
+}
+
+/// Location of a chunk in the uncompressed data of a container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OffsetAndLength {
+    pub offset: u64,
+    pub length: u64,
+}
+
+/// One compression block in the .ucas files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CompressedBlock {
+    /// offset in the .ucas files, partitions are laid out after each other
+    pub offset: u64,
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+    /// index into the compression methods, 0 is no compression
+    pub compression_method: u8,
+}
+
+/// Parsed .utoc file
+#[derive(Debug)]
+pub(crate) struct Toc {
+    pub header: TocHeader,
+    pub chunk_ids: Vec<IoChunkId>,
+    pub offsets: Vec<OffsetAndLength>,
+    pub compressed_blocks: Vec<CompressedBlock>,
+    /// compression methods, the first one is referenced by index 1
+    pub compression_methods: Vec<CompressionMethod>,
+    /// directory index as stored in the file, it might be encrypted
+    pub directory_index: Option<Vec<u8>>,
+}
+
+impl TocHeader {
+    fn read<R: Read + Seek>(reader: &mut R) -> Result<Self, Error> {
+        let mut magic = [0u8; 16];
+        reader.read_exact(&mut magic)?;
+        if magic != TOC_MAGIC {
+            return Err(IoStoreError::InvalidTocMagic(magic).into());
+        }
+
+        let version =
+            EIoStoreTocVersion::try_from(reader.read_u8()?).map_err(IoStoreError::from)?;
+        if matches!(
+            version,
+            EIoStoreTocVersion::Invalid
+                | EIoStoreTocVersion::Latest
+                | EIoStoreTocVersion::LatestPlusOne
+        ) {
+            return Err(IoStoreError::InvalidTocVersion(version).into());
+        }
+        let _reserved = reader.read_u8()?;
+        let _reserved = reader.read_u16::<LE>()?;
+
+        let header_size = reader.read_u32::<LE>()?;
+        if header_size != TOC_HEADER_SIZE {
+            return Err(IoStoreError::invalid_toc_header_size(TOC_HEADER_SIZE, header_size).into());
+        }
+
+        let entry_count = reader.read_u32::<LE>()?;
+        let compressed_block_entry_count = reader.read_u32::<LE>()?;
+        let compressed_block_entry_size = reader.read_u32::<LE>()?;
+        if compressed_block_entry_size != COMPRESSED_BLOCK_ENTRY_SIZE {
+            return Err(Error::invalid_file(format!(
+                "Invalid .utoc compressed block entry size {compressed_block_entry_size}"
+            )));
+        }
+
+        let compression_method_name_count = reader.read_u32::<LE>()?;
+        let compression_method_name_length = reader.read_u32::<LE>()?;
+        let compression_block_size = reader.read_u32::<LE>()?;
+        let directory_index_size = reader.read_u32::<LE>()?;
+        let mut partition_count = reader.read_u32::<LE>()?;
+        let container_id = reader.read_u64::<LE>()?;
+        let encryption_key_guid = reader.read_guid()?;
+        let container_flags = EIoContainerFlags::from_bits_retain(reader.read_u8()?);
+        let _reserved = reader.read_u8()?;
+        let _reserved = reader.read_u16::<LE>()?;
+        let mut perfect_hash_seed_count = reader.read_u32::<LE>()?;
+        let mut partition_size = reader.read_u64::<LE>()?;
+        let mut chunks_without_perfect_hash_count = reader.read_u32::<LE>()?;
+        let _reserved = reader.read_u32::<LE>()?;
+        for _ in 0..5 {
+            let _reserved = reader.read_u64::<LE>()?;
+        }
+
+        // fields which did not exist yet were reserved and might contain garbage
+        if version < EIoStoreTocVersion::PartitionSize {
+            partition_count = 1;
+            partition_size = u64::MAX;
+        }
+        if version < EIoStoreTocVersion::PerfectHash {
+            perfect_hash_seed_count = 0;
+        }
+        if version < EIoStoreTocVersion::PerfectHashWithOverflow {
+            chunks_without_perfect_hash_count = 0;
+        }
+
+        if compression_block_size == 0 || partition_count == 0 || partition_size == 0 {
+            return Err(Error::invalid_file("Invalid .utoc header".to_string()));
+        }
+
+        Ok(TocHeader {
+            version,
+            entry_count,
+            compressed_block_entry_count,
+            compression_method_name_count,
+            compression_method_name_length,
+            compression_block_size,
+            directory_index_size,
+            partition_count,
+            container_id,
+            encryption_key_guid,
+            container_flags,
+            perfect_hash_seed_count,
+            partition_size,
+            chunks_without_perfect_hash_count,
+        })
+    }
+}
+
+impl TocHeader {
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        writer.write_all(&TOC_MAGIC)?;
+        writer.write_u8(self.version as u8)?;
+        writer.write_u8(0)?;
+        writer.write_u16::<LE>(0)?;
+        writer.write_u32::<LE>(TOC_HEADER_SIZE)?;
+        writer.write_u32::<LE>(self.entry_count)?;
+        writer.write_u32::<LE>(self.compressed_block_entry_count)?;
+        writer.write_u32::<LE>(COMPRESSED_BLOCK_ENTRY_SIZE)?;
+        writer.write_u32::<LE>(self.compression_method_name_count)?;
+        writer.write_u32::<LE>(self.compression_method_name_length)?;
+        writer.write_u32::<LE>(self.compression_block_size)?;
+        writer.write_u32::<LE>(self.directory_index_size)?;
+
+        let has_partitions = self.version >= EIoStoreTocVersion::PartitionSize;
+        writer.write_u32::<LE>(match has_partitions {
+            true => self.partition_count,
+            false => 0,
+        })?;
+        writer.write_u64::<LE>(self.container_id)?;
+        writer.write_guid(&self.encryption_key_guid)?;
+        writer.write_u8(self.container_flags.bits())?;
+        writer.write_u8(0)?;
+        writer.write_u16::<LE>(0)?;
+        writer.write_u32::<LE>(self.perfect_hash_seed_count)?;
+        writer.write_u64::<LE>(match has_partitions {
+            true => self.partition_size,
+            false => 0,
+        })?;
+        writer.write_u32::<LE>(self.chunks_without_perfect_hash_count)?;
+        writer.write_u32::<LE>(0)?;
+        for _ in 0..5 {
+            writer.write_u64::<LE>(0)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Toc {
+    /// Read a .utoc file
+    pub(crate) fn read<R: Read + Seek>(
+        reader: &mut R,
+        limits: &ParseLimits,
+    ) -> Result<Self, Error> {
+        let header = TocHeader::read(reader)?;
+        reader.seek(SeekFrom::Start(TOC_HEADER_SIZE as u64))?;
+
+        let entry_count = check_count(header.entry_count, limits)?;
+
+        let mut chunk_ids = Vec::with_capacity(entry_count.min(0x10000));
+        for _ in 0..entry_count {
+            chunk_ids.push(IoChunkId::read(reader)?);
+        }
+
+        let mut offsets = Vec::with_capacity(entry_count.min(0x10000));
+        for _ in 0..entry_count {
+            offsets.push(OffsetAndLength {
+                offset: reader.read_uint::<BE>(5)?,
+                length: reader.read_uint::<BE>(5)?,
+            });
+        }
+
+        // perfect hashes are only needed to look up chunks without building a map
+        let perfect_hash_count = check_count(header.perfect_hash_seed_count, limits)?
+            + check_count(header.chunks_without_perfect_hash_count, limits)?;
+        reader.seek(SeekFrom::Current(perfect_hash_count as i64 * 4))?;
+
+        let block_count = check_count(header.compressed_block_entry_count, limits)?;
+        let mut compressed_blocks = Vec::with_capacity(block_count.min(0x10000));
+        for _ in 0..block_count {
+            compressed_blocks.push(CompressedBlock {
+                offset: reader.read_uint::<LE>(5)?,
+                compressed_size: reader.read_uint::<LE>(3)? as u32,
+                uncompressed_size: reader.read_uint::<LE>(3)? as u32,
+                compression_method: reader.read_u8()?,
+            });
+        }
+
+        let name_count = check_count(header.compression_method_name_count, limits)?;
+        let name_length = check_count(header.compression_method_name_length, limits)?;
+        let mut compression_methods = Vec::with_capacity(name_count.min(0x100));
+        for _ in 0..name_count {
+            let name = reader.read_vec(name_length)?;
+            let name = name.split(|e| *e == 0).next().unwrap_or_default();
+            compression_methods.push(CompressionMethod::new(&String::from_utf8(name.to_vec())?));
+        }
+
+        if header.container_flags.contains(EIoContainerFlags::SIGNED) {
+            let hash_size = reader.read_i32::<LE>()?;
+            if hash_size < 0 {
+                return Err(Error::invalid_file(format!(
+                    "Invalid .utoc signature size {hash_size}"
+                )));
+            }
+            // toc signature, block signature and the hashes of all blocks
+            reader.seek(SeekFrom::Current(
+                hash_size as i64 * 2 + block_count as i64 * 20,
+            ))?;
+        }
+
+        let directory_index = match header.version >= EIoStoreTocVersion::DirectoryIndex
+            && header.container_flags.contains(EIoContainerFlags::INDEXED)
+            && header.directory_index_size > 0
+        {
+            true => {
+                let size = header.directory_index_size as u64;
+                if size > limits.max_data_size {
+                    return Err(Error::limit_exceeded(
+                        "Directory index size",
+                        size,
+                        limits.max_data_size,
+                    ));
+                }
+                Some(reader.read_vec(size as usize)?)
+            }
+            false => None,
+        };
+
+        Ok(Toc {
+            header,
+            chunk_ids,
+            offsets,
+            compressed_blocks,
+            compression_methods,
+            directory_index,
+        })
+    }
+
+    /// Write a .utoc file, the directory index has to be encrypted already when the container is
+    pub(crate) fn write<W: Write>(&self, writer: &mut W) -> Result<(), Error> {
+        self.header.write(writer)?;
+
+        for chunk_id in &self.chunk_ids {
+            chunk_id.write(writer)?;
+        }
+        for offset in &self.offsets {
+            writer.write_uint::<BE>(offset.offset, 5)?;
+            writer.write_uint::<BE>(offset.length, 5)?;
+        }
+        for block in &self.compressed_blocks {
+            writer.write_uint::<LE>(block.offset, 5)?;
+            writer.write_uint::<LE>(block.compressed_size as u64, 3)?;
+            writer.write_uint::<LE>(block.uncompressed_size as u64, 3)?;
+            writer.write_u8(block.compression_method)?;
+        }
+        for method in &self.compression_methods {
+            let mut name = [0u8; COMPRESSION_METHOD_NAME_LENGTH as usize];
+            let method = method.to_string();
+            let len = method.len().min(name.len() - 1);
+            name[..len].copy_from_slice(&method.as_bytes()[..len]);
+            writer.write_all(&name)?;
+        }
+
+        if let Some(directory_index) = &self.directory_index {
+            writer.write_all(directory_index)?;
+        }
+
+        // chunk hashes are only checked when the engine validates containers, they stay empty
+        let (hash_size, padding) =
+            match self.header.version >= EIoStoreTocVersion::ReplaceIoChunkHashWithIoHash {
+                true => (20, 3),
+                false => (32, 0),
+            };
+        let block_size = self.header.compression_block_size as u64;
+        for offset in &self.offsets {
+            let first_block = (offset.offset / block_size) as usize;
+            let block_count = offset.length.div_ceil(block_size) as usize;
+            let compressed = self
+                .compressed_blocks
+                .iter()
+                .skip(first_block)
+                .take(block_count)
+                .any(|e| e.compression_method != 0);
+
+            writer.write_all(&[0u8; 32][..hash_size])?;
+            writer.write_u8(match compressed {
+                true => META_FLAG_COMPRESSED,
+                false => 0,
+            })?;
+            writer.write_all(&[0u8; 3][..padding])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Check an element count read from the file against the limits
+fn check_count(count: u32, limits: &ParseLimits) -> Result<usize, Error> {
+    if count > limits.max_array_length {
+        return Err(Error::limit_exceeded(
+            "Array length",
+            count as u64,
+            limits.max_array_length as u64,
+        ));
+    }
+    Ok(count as usize)
+}
@@ -0,0 +1,456 @@
+//! IoStore container writer
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, Write};
+use std::path::Path;
+
+use unreal_asset_base::{
+    compression::{self, CompressionMethod},
+    enums::{EIoChunkType, EIoChunkType4, EIoContainerHeaderVersion, EIoStoreTocVersion},
+    error::IoStoreError,
+    flags::EIoContainerFlags,
+    Error, Guid,
+};
+
+use super::chunk_id::IoChunkId;
+use super::container_header::{ContainerHeaderVersion, IoContainerHeader, PackageStoreEntry};
+use super::directory_index::write_directory_index;
+use super::toc::{
+    CompressedBlock, OffsetAndLength, Toc, TocHeader, COMPRESSION_METHOD_NAME_LENGTH,
+};
+use super::{encrypt, package_id, AES_BLOCK_SIZE};
+
+/// Largest compression block size, block sizes are stored in 3 bytes
+const MAX_COMPRESSION_BLOCK_SIZE: u32 = 0xff0000;
+
+/// Creates the writer of the partition with the given index
+type CreatePartition<W> = Box<dyn FnMut(usize) -> io::Result<W>>;
+
+/// An IoStore container writer which writes chunks to the `.ucas` partitions as they are added
+/// and the `.utoc` table of contents when finished.
+pub struct IoStoreWriter<W>
+where
+    W: Write + Seek,
+{
+    /// Version of the table of contents, `Latest` writes the newest supported version
+    pub toc_version: EIoStoreTocVersion,
+    /// Version of the container header.
+    /// UE4 containers always use `ContainerHeaderVersion::Legacy` and containers of toc version
+    /// `PerfectHash` `ContainerHeaderVersion::Unversioned`. Toc version `PerfectHashWithOverflow`
+    /// is used by both UE 5.0 and 5.1, so containers of that version need the container header
+    /// version of the game set explicitly.
+    pub container_header_version: ContainerHeaderVersion,
+    /// Mount point of the directory index. Typically `../../../`.
+    pub mount_point: String,
+    /// Compression block size
+    pub block_size: u32,
+    container_id: u64,
+    compression: CompressionMethod,
+    key: Option<[u8; 32]>,
+    encryption_key_guid: Guid,
+    partition_size: u64,
+    create_partition: Option<CreatePartition<W>>,
+    chunk_ids: Vec<IoChunkId>,
+    written_chunks: HashSet<IoChunkId>,
+    offsets: Vec<OffsetAndLength>,
+    compressed_blocks: Vec<CompressedBlock>,
+    files: Vec<(String, u32)>,
+    packages: Vec<(u64, PackageStoreEntry)>,
+    uncompressed_size: u64,
+    partition_position: u64,
+    toc: W,
+    partitions: Vec<W>,
+}
+
+impl<W> IoStoreWriter<W>
+where
+    W: Write + Seek,
+{
+    /// Creates a new `IoStoreWriter` that writes the table of contents to `toc` and the chunk
+    /// data to `ucas`. The container id is usually [`container_id`] of the container name.
+    /// When using writers that use syscalls like a `File` it is recommended to wrap them in a
+    /// [`std::io::BufWriter`] to avoid unnecessary syscalls.
+    ///
+    /// [`container_id`]: super::container_id
+    pub fn new(toc: W, ucas: W, toc_version: EIoStoreTocVersion, container_id: u64) -> Self {
+        Self {
+            toc_version,
+            container_header_version: ContainerHeaderVersion::Versioned(
+                EIoContainerHeaderVersion::Latest,
+            ),
+            mount_point: "../../../".to_owned(),
+            block_size: 0x010000,
+            container_id,
+            compression: CompressionMethod::Zlib,
+            key: None,
+            encryption_key_guid: Guid::default(),
+            partition_size: u64::MAX,
+            create_partition: None,
+            chunk_ids: Vec::new(),
+            written_chunks: HashSet::new(),
+            offsets: Vec::new(),
+            compressed_blocks: Vec::new(),
+            files: Vec::new(),
+            packages: Vec::new(),
+            uncompressed_size: 0,
+            partition_position: 0,
+            toc,
+            partitions: vec![ucas],
+        }
+    }
+
+    /// Creates a new `IoStoreWriter` which encrypts all chunks and the directory index with the
+    /// given key. The GUID of the key is stored in the table of contents, the engine uses it to
+    /// find the matching key.
+    pub fn new_encrypted(
+        toc: W,
+        ucas: W,
+        toc_version: EIoStoreTocVersion,
+        container_id: u64,
+        key: [u8; 32],
+        encryption_key_guid: Guid,
+    ) -> Self {
+        Self {
+            key: Some(key),
+            encryption_key_guid,
+            ..Self::new(toc, ucas, toc_version, container_id)
+        }
+    }
+
+    /// Sets the compression method used for all blocks, defaults to Zlib.
+    /// This has to happen before any chunks are written.
+    pub fn set_compression(&mut self, compression: CompressionMethod) -> Result<(), Error> {
+        self.check_empty()?;
+        self.compression = compression;
+        Ok(())
+    }
+
+    /// Splits the chunk data into partitions of at most `partition_size` bytes, `create_partition`
+    /// is called with the index of every additional partition to create its writer.
+    /// This needs at least `EIoStoreTocVersion::PartitionSize` and has to happen before any chunks
+    /// are written.
+    pub fn set_partitions(
+        &mut self,
+        partition_size: u64,
+        create_partition: impl FnMut(usize) -> io::Result<W> + 'static,
+    ) -> Result<(), Error> {
+        self.check_empty()?;
+        if partition_size == 0 {
+            return Err(IoStoreError::configuration_invalid(
+                "The partition size can not be 0".to_string(),
+            )
+            .into());
+        }
+
+        self.partition_size = partition_size;
+        self.create_partition = Some(Box::new(create_partition));
+        Ok(())
+    }
+
+    /// Returns the ids of all chunks which have been written.
+    pub fn get_chunk_ids(&self) -> Vec<IoChunkId> {
+        self.chunk_ids.clone()
+    }
+
+    /// Write a chunk, `path` is its path in the directory index relative to the mount point.
+    /// Chunks without a path like bulk data of packages can only be found by their id.
+    pub fn write_chunk(
+        &mut self,
+        chunk_id: IoChunkId,
+        path: Option<&str>,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let toc_version = self.get_toc_version()?;
+        if self.block_size == 0 || self.block_size > MAX_COMPRESSION_BLOCK_SIZE {
+            return Err(Error::limit_exceeded(
+                "Compression block size",
+                self.block_size as u64,
+                MAX_COMPRESSION_BLOCK_SIZE as u64,
+            ));
+        }
+        if self.partition_size != u64::MAX && toc_version < EIoStoreTocVersion::PartitionSize {
+            return Err(IoStoreError::configuration_invalid(format!(
+                "Partitions can not be written with toc version {toc_version:?}"
+            ))
+            .into());
+        }
+        if !self.written_chunks.insert(chunk_id) {
+            return Err(IoStoreError::duplicate_chunk(format!("{chunk_id:?}")).into());
+        }
+
+        let block_size = self.block_size as u64;
+        // every chunk starts at a new compression block
+        let offset = self.uncompressed_size.next_multiple_of(block_size);
+        self.offsets.push(OffsetAndLength {
+            offset,
+            length: data.len() as u64,
+        });
+        self.uncompressed_size = offset + data.len() as u64;
+
+        for block in data.chunks(block_size as usize) {
+            let compressed = match self.compression {
+                CompressionMethod::None => None,
+                _ => Some(compression::compress(self.compression.clone(), block)?)
+                    .filter(|e| e.len() < block.len()),
+            };
+
+            let (mut data, compression_method) = match compressed {
+                Some(compressed) => (compressed, 1),
+                None => (block.to_vec(), 0),
+            };
+            // the engine reads blocks aligned to the AES block size even when they aren't encrypted
+            let compressed_size = data.len() as u32;
+            data.resize(data.len().next_multiple_of(AES_BLOCK_SIZE), 0);
+            if let Some(key) = &self.key {
+                encrypt(key, &mut data);
+            }
+
+            let offset = self.write_block(&data)?;
+            self.compressed_blocks.push(CompressedBlock {
+                offset,
+                compressed_size,
+                uncompressed_size: block.len() as u32,
+                compression_method,
+            });
+        }
+
+        self.chunk_ids.push(chunk_id);
+        if let Some(path) = path {
+            self.files
+                .push((path.to_string(), self.chunk_ids.len() as u32 - 1));
+        }
+
+        Ok(())
+    }
+
+    /// Write the export data of a package and add it to the container header.
+    /// `package_name` is the name the engine loads it by, e.g. `/Game/Maps/Level` and `path` is
+    /// the file path relative to the mount point, e.g. `Game/Content/Maps/Level.umap`.
+    /// The package name and export bundles size of UE4 store entries are set from `package_name`
+    /// and `data`.
+    pub fn write_package(
+        &mut self,
+        package_name: &str,
+        path: &str,
+        data: &[u8],
+        mut store_entry: PackageStoreEntry,
+    ) -> Result<IoChunkId, Error> {
+        let container_header_version = self.get_container_header_version()?;
+
+        let package_id = package_id(package_name);
+        let chunk_type = match container_header_version {
+            ContainerHeaderVersion::Legacy => {
+                store_entry.package_name = package_name.to_string();
+                store_entry.export_bundles_size = data.len() as u64;
+                EIoChunkType4::ExportBundleData as u8
+            }
+            _ => EIoChunkType::ExportBundleData as u8,
+        };
+        let chunk_id = IoChunkId::new(package_id, 0, chunk_type);
+        self.write_chunk(chunk_id, Some(path), data)?;
+        self.packages.push((package_id, store_entry));
+
+        Ok(chunk_id)
+    }
+
+    /// Write the container header, the directory index and the table of contents, returning the
+    /// table of contents writer and the partition writers
+    pub fn finish_write(mut self) -> Result<(W, Vec<W>), Error> {
+        let toc_version = self.get_toc_version()?;
+
+        let container_header = IoContainerHeader {
+            version: self.get_container_header_version()?,
+            container_id: self.container_id,
+            packages: std::mem::take(&mut self.packages),
+        };
+        let mut data = Vec::new();
+        container_header.write(&mut data)?;
+
+        let chunk_type = match container_header.version {
+            ContainerHeaderVersion::Legacy => EIoChunkType4::ContainerHeader as u8,
+            _ => EIoChunkType::ContainerHeader as u8,
+        };
+        let chunk_id = IoChunkId::new(self.container_id, 0, chunk_type);
+        self.write_chunk(chunk_id, None, &data)?;
+
+        let mut container_flags = EIoContainerFlags::NONE;
+        if self.compression != CompressionMethod::None {
+            container_flags |= EIoContainerFlags::COMPRESSED;
+        }
+        if self.key.is_some() {
+            container_flags |= EIoContainerFlags::ENCRYPTED;
+        }
+
+        let directory_index = match toc_version >= EIoStoreTocVersion::DirectoryIndex {
+            true => {
+                container_flags |= EIoContainerFlags::INDEXED;
+                let mut directory_index = write_directory_index(&self.mount_point, &self.files)?;
+                if let Some(key) = &self.key {
+                    directory_index
+                        .resize(directory_index.len().next_multiple_of(AES_BLOCK_SIZE), 0);
+                    encrypt(key, &mut directory_index);
+                }
+                Some(directory_index)
+            }
+            false => None,
+        };
+
+        let compression_methods = match self.compression {
+            CompressionMethod::None => Vec::new(),
+            _ => vec![self.compression.clone()],
+        };
+
+        let toc = Toc {
+            header: TocHeader {
+                version: toc_version,
+                entry_count: self.chunk_ids.len() as u32,
+                compressed_block_entry_count: self.compressed_blocks.len() as u32,
+                compression_method_name_count: compression_methods.len() as u32,
+                compression_method_name_length: COMPRESSION_METHOD_NAME_LENGTH,
+                compression_block_size: self.block_size,
+                directory_index_size: directory_index.as_ref().map(|e| e.len()).unwrap_or(0) as u32,
+                partition_count: self.partitions.len() as u32,
+                container_id: self.container_id,
+                encryption_key_guid: self.encryption_key_guid,
+                container_flags,
+                // without perfect hash seeds the engine looks up chunks in a map
+                perfect_hash_seed_count: 0,
+                partition_size: self.partition_size,
+                chunks_without_perfect_hash_count: 0,
+            },
+            chunk_ids: std::mem::take(&mut self.chunk_ids),
+            offsets: std::mem::take(&mut self.offsets),
+            compressed_blocks: std::mem::take(&mut self.compressed_blocks),
+            compression_methods,
+            directory_index,
+        };
+        toc.write(&mut self.toc)?;
+
+        self.toc.flush()?;
+        for partition in &mut self.partitions {
+            partition.flush()?;
+        }
+
+        Ok((self.toc, self.partitions))
+    }
+
+    /// Resolve `Latest` and reject versions which can not be written
+    fn get_toc_version(&self) -> Result<EIoStoreTocVersion, Error> {
+        match self.toc_version {
+            EIoStoreTocVersion::Latest => Ok(EIoStoreTocVersion::ReplaceIoChunkHashWithIoHash),
+            EIoStoreTocVersion::Invalid | EIoStoreTocVersion::LatestPlusOne => {
+                Err(IoStoreError::configuration_invalid(format!(
+                    "Can not write toc version {:?}",
+                    self.toc_version
+                ))
+                .into())
+            }
+            version => Ok(version),
+        }
+    }
+
+    /// Get the container header version matching the toc version
+    fn get_container_header_version(&self) -> Result<ContainerHeaderVersion, Error> {
+        let toc_version = self.get_toc_version()?;
+        let version = self.container_header_version;
+        match toc_version {
+            EIoStoreTocVersion::Initial
+            | EIoStoreTocVersion::DirectoryIndex
+            | EIoStoreTocVersion::PartitionSize => Ok(ContainerHeaderVersion::Legacy),
+            EIoStoreTocVersion::PerfectHash => Ok(ContainerHeaderVersion::Unversioned),
+            EIoStoreTocVersion::PerfectHashWithOverflow => match version {
+                ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::Latest)
+                | ContainerHeaderVersion::Legacy => Err(IoStoreError::configuration_invalid(
+                    "Toc version PerfectHashWithOverflow is used by UE 5.0 and 5.1, it needs an \
+                    explicit container header version"
+                        .to_string(),
+                )
+                .into()),
+                version => Ok(version),
+            },
+            _ => match version {
+                ContainerHeaderVersion::Versioned(_) => Ok(version),
+                _ => Err(IoStoreError::configuration_invalid(format!(
+                    "Container header version {version:?} can not be written with toc version \
+                    {toc_version:?}"
+                ))
+                .into()),
+            },
+        }
+    }
+
+    /// Fail if chunks have been written already
+    fn check_empty(&self) -> Result<(), Error> {
+        if !self.chunk_ids.is_empty() {
+            return Err(IoStoreError::configuration_invalid(
+                "The container has to be configured before chunks are written".to_string(),
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Write a compression block to the current partition, returning its offset
+    fn write_block(&mut self, data: &[u8]) -> Result<u64, Error> {
+        let size = data.len() as u64;
+        if size > self.partition_size {
+            return Err(Error::limit_exceeded(
+                "Compression block size",
+                size,
+                self.partition_size,
+            ));
+        }
+
+        // blocks never cross partitions
+        if self.partition_position + size > self.partition_size {
+            let create_partition = self.create_partition.as_mut().ok_or_else(|| {
+                IoStoreError::configuration_invalid("No partition writer".to_string())
+            })?;
+            self.partitions
+                .push(create_partition(self.partitions.len())?);
+            self.partition_position = 0;
+        }
+
+        let partition = self.partitions.len() as u64 - 1;
+        let offset = partition * self.partition_size + self.partition_position;
+        self.partitions
+            .last_mut()
+            .expect("there is always at least one partition")
+            .write_all(data)?;
+        self.partition_position += size;
+
+        Ok(offset)
+    }
+}
+
+impl IoStoreWriter<BufWriter<File>> {
+    /// Creates a container at the given `.utoc` path together with its `.ucas` partitions next to
+    /// it, `name.ucas`, `name_s1.ucas`, `name_s2.ucas` and so on.
+    /// Additional partitions are only created when a `partition_size` is given.
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        toc_version: EIoStoreTocVersion,
+        container_id: u64,
+        partition_size: Option<u64>,
+    ) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let toc = BufWriter::new(File::create(path)?);
+        let ucas = BufWriter::new(File::create(path.with_extension("ucas"))?);
+        let mut writer = Self::new(toc, ucas, toc_version, container_id);
+
+        if let Some(partition_size) = partition_size {
+            let path = path.to_path_buf();
+            let stem = path
+                .file_stem()
+                .map(|e| e.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            writer.set_partitions(partition_size, move |index| {
+                let partition_path = path.with_file_name(format!("{stem}_s{index}.ucas"));
+                Ok(BufWriter::new(File::create(partition_path)?))
+            })?;
+        }
+
+        Ok(writer)
+    }
+}
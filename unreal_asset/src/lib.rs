@@ -51,6 +51,7 @@ pub use base::enums;
 pub use base::error;
 pub use base::flags;
 pub use base::import;
+pub use base::limits;
 pub use base::object_version;
 pub use base::reader;
 pub use base::types;
@@ -60,6 +61,7 @@ pub use base::cast;
 pub use base::Guid;
 pub use error::Error;
 pub use import::Import;
+pub use limits::ParseLimits;
 
 // properties
 pub use unreal_asset_properties as properties;
@@ -85,9 +87,14 @@ pub mod ac7;
 pub mod asset;
 pub mod asset_archive_writer;
 pub mod asset_data;
+pub mod diff;
 pub mod fengineversion;
+pub mod io_store;
 pub mod package_file_summary;
+pub mod zen_archive_writer;
+pub mod zen_asset;
 
 pub use asset::Asset;
+pub use zen_asset::ZenAsset;
 
 const UE4_ASSET_MAGIC: u32 = u32::from_be_bytes([0xc1, 0x83, 0x2a, 0x9e]);
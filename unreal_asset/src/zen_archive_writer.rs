@@ -0,0 +1,180 @@
+//! Archive that can be used to write a zen asset
+
+use std::io::{Seek, Write};
+
+use unreal_asset_base::{
+    cast,
+    containers::{IndexedMap, NameMap, SharedResource},
+    custom_version::{CustomVersion, CustomVersionTrait},
+    engine_version::EngineVersion,
+    flags::EPackageFlags,
+    object_version::{ObjectVersion, ObjectVersionUE5},
+    passthrough_archive_writer,
+    reader::{ArchiveTrait, ArchiveType, ArchiveWriter},
+    types::{FName, PackageIndex, PackageIndexTrait, PackageObjectIndex},
+    unversioned::Usmap,
+    Error,
+};
+use unreal_asset_exports::Export;
+
+use crate::asset_data::AssetData;
+use crate::io_store::ScriptObjects;
+
+/// Archive that can be used to write zen package data
+pub struct ZenArchiveWriter<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageObjectIndex>>
+{
+    /// Parent writer for this writer
+    writer: &'parent_writer mut ParentWriter,
+    /// Asset data
+    asset_data: &'asset AssetData<PackageObjectIndex>,
+    /// Asset import map
+    import_map: &'asset [PackageObjectIndex],
+    /// Script objects used to resolve script import names
+    script_objects: Option<&'asset ScriptObjects>,
+    /// Asset name map
+    name_map: SharedResource<NameMap>,
+}
+
+impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageObjectIndex>>
+    ZenArchiveWriter<'parent_writer, 'asset, ParentWriter>
+{
+    /// Create a new `ZenArchiveWriter` instance
+    pub fn new(
+        parent_writer: &'parent_writer mut ParentWriter,
+        asset_data: &'asset AssetData<PackageObjectIndex>,
+        import_map: &'asset [PackageObjectIndex],
+        script_objects: Option<&'asset ScriptObjects>,
+        name_map: SharedResource<NameMap>,
+    ) -> Self {
+        ZenArchiveWriter {
+            writer: parent_writer,
+            asset_data,
+            import_map,
+            script_objects,
+            name_map,
+        }
+    }
+
+    /// Get an import from this `ZenArchiveWriter`
+    pub fn get_import(&self, index: PackageIndex) -> Option<PackageObjectIndex> {
+        if !index.is_import() {
+            return None;
+        }
+
+        self.import_map
+            .get((-(index.index as i64) - 1) as usize)
+            .copied()
+    }
+}
+
+impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageObjectIndex>>
+    ArchiveTrait<PackageObjectIndex> for ZenArchiveWriter<'parent_writer, 'asset, ParentWriter>
+{
+    #[inline(always)]
+    fn get_archive_type(&self) -> ArchiveType {
+        ArchiveType::Zen
+    }
+
+    fn get_custom_version<T>(&self) -> CustomVersion
+    where
+        T: CustomVersionTrait + Into<i32>,
+    {
+        self.asset_data.get_custom_version::<T>()
+    }
+
+    fn has_unversioned_properties(&self) -> bool {
+        self.asset_data
+            .summary
+            .package_flags
+            .contains(EPackageFlags::PKG_UNVERSIONED_PROPERTIES)
+    }
+
+    fn use_event_driven_loader(&self) -> bool {
+        self.asset_data.use_event_driven_loader
+    }
+
+    fn position(&mut self) -> u64 {
+        self.writer.position()
+    }
+
+    fn set_position(&mut self, pos: u64) -> std::io::Result<()> {
+        self.writer.set_position(pos)
+    }
+
+    fn get_name_map(&self) -> SharedResource<NameMap> {
+        self.name_map.clone()
+    }
+
+    fn get_array_struct_type_override(&self) -> &IndexedMap<String, String> {
+        &self.asset_data.array_struct_type_override
+    }
+
+    fn get_map_key_override(&self) -> &IndexedMap<String, String> {
+        &self.asset_data.map_key_override
+    }
+
+    fn get_map_value_override(&self) -> &IndexedMap<String, String> {
+        &self.asset_data.map_value_override
+    }
+
+    fn get_engine_version(&self) -> EngineVersion {
+        self.asset_data.get_engine_version()
+    }
+
+    fn get_object_version(&self) -> ObjectVersion {
+        self.asset_data.object_version
+    }
+
+    fn get_object_version_ue5(&self) -> ObjectVersionUE5 {
+        self.asset_data.object_version_ue5
+    }
+
+    fn get_mappings(&self) -> Option<&Usmap> {
+        self.asset_data.mappings.as_ref()
+    }
+
+    fn get_parent_class_export_name(&self) -> Option<FName> {
+        let script_objects = self.script_objects?;
+        self.asset_data
+            .exports
+            .iter()
+            .find_map(|e| cast!(Export, ClassExport, e))
+            .and_then(|e| self.get_import(e.struct_export.super_struct))
+            .and_then(|e| script_objects.get_outer_index(e))
+            .and_then(|e| script_objects.get_object_name(e))
+    }
+
+    fn get_object_name(&self, index: PackageObjectIndex) -> Option<FName> {
+        self.script_objects?.get_object_name(index)
+    }
+
+    fn get_object_name_packageindex(&self, index: PackageIndex) -> Option<FName> {
+        self.get_import(index).and_then(|e| self.get_object_name(e))
+    }
+}
+
+impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageObjectIndex>>
+    ArchiveWriter<PackageObjectIndex> for ZenArchiveWriter<'parent_writer, 'asset, ParentWriter>
+{
+    passthrough_archive_writer!(writer);
+}
+
+impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageObjectIndex>> Write
+    for ZenArchiveWriter<'parent_writer, 'asset, ParentWriter>
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+impl<'parent_writer, 'asset, ParentWriter: ArchiveWriter<PackageObjectIndex>> Seek
+    for ZenArchiveWriter<'parent_writer, 'asset, ParentWriter>
+{
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        self.writer.seek(pos)
+    }
+}
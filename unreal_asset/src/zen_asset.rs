@@ -0,0 +1,1207 @@
+//! Zen package [`ZenAsset`] type
+//!
+//! Zen packages are the cooked packages stored in UE5 IoStore containers,
+//! their exports are read with the same readers as legacy .uasset exports.
+
+/*
+    zen package layout (UE 5.0 - 5.3):
+    - summary
+        - u32 has versioning info
+        - u32 header size, export data starts here
+        - mapped name of the package
+        - u32 package flags
+        - u32 cooked header size, size of the header of the legacy .uasset
+        - i32 imported public export hashes offset
+        - i32 import map offset
+        - i32 export map offset
+        - i32 export bundle entries offset
+        - i32 graph data offset (version < ImportedPackageNames)
+        - i32 dependency bundle headers offset (version >= ImportedPackageNames)
+        - i32 dependency bundle entries offset (version >= ImportedPackageNames)
+        - i32 imported package names offset (version >= ImportedPackageNames)
+    - versioning info (has versioning info)
+        - u32 zen package version
+        - i32 ue4 object version
+        - i32 ue5 object version
+        - i32 licensee version
+        - optimized custom version container
+    - name batch
+    - bulk data map (version >= DataResourceTable)
+        - i64 size
+        - entries, 32 bytes each
+    - u64 imported public export hashes
+    - u64 package object indices of the import map
+    - export map entries, 72 bytes each
+    - export bundle entries, 8 bytes each
+    - graph data (version < ImportedPackageNames)
+        - export bundle headers, 16 bytes each
+        - i32 internal arc count, arcs between export bundles
+        - for every imported package: i32 external arc count, arcs from imports to export bundles
+    - dependency bundle headers, 20 bytes for each export (version >= ImportedPackageNames)
+    - dependency bundle entries, i32 package index each (version >= ImportedPackageNames)
+    - imported package names (version >= ImportedPackageNames)
+        - name batch
+        - i32 number of each name
+    - export data, serialized in export bundle order, packages without graph data have a single
+      export bundle
+*/
+
+use std::fmt::{Debug, Formatter};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+
+use unreal_asset_base::{
+    cast,
+    containers::{Chain, IndexedMap, NameMap, SharedResource},
+    custom_version::{CustomVersion, CustomVersionTrait},
+    engine_version::EngineVersion,
+    enums::{ECustomVersionSerializationFormat, EExportCommandType, EZenPackageVersion},
+    error::{Error, ZenError},
+    flags::{EObjectFlags, EPackageFlags},
+    object_version::{ObjectVersion, ObjectVersionUE5},
+    passthrough_archive_reader,
+    reader::{ArchiveReader, ArchiveTrait, ArchiveType, ArchiveWriter, RawReader, RawWriter},
+    types::{
+        fname::{EMappedNameType, FNameContainer},
+        FName, PackageIndex, PackageIndexTrait, PackageObjectIndex,
+    },
+    unversioned::Usmap,
+    FNameContainer, ParseLimits,
+};
+use unreal_asset_exports::{
+    base_export::EExportFilterFlags, BaseExport, Export, ExportBaseTrait, ExportNormalTrait,
+    ExportTrait,
+};
+
+use crate::asset_data::{AssetData, AssetTrait, ExportReaderTrait};
+use crate::io_store::ScriptObjects;
+use crate::zen_archive_writer::ZenArchiveWriter;
+
+/// Size of the zen package summary
+const SUMMARY_SIZE: u64 = 44;
+/// Size of the zen package summary since `EZenPackageVersion::ImportedPackageNames`
+const SUMMARY_SIZE_IMPORTED_PACKAGE_NAMES: u64 = 52;
+/// Size of a bulk data map entry
+const BULK_DATA_MAP_ENTRY_SIZE: i64 = 32;
+/// Size of an export map entry
+const EXPORT_MAP_ENTRY_SIZE: i32 = 72;
+/// Size of an export bundle entry
+const EXPORT_BUNDLE_ENTRY_SIZE: i32 = 8;
+/// Size of an export bundle header
+const EXPORT_BUNDLE_HEADER_SIZE: u64 = 16;
+/// Size of a dependency bundle header
+const DEPENDENCY_BUNDLE_HEADER_SIZE: i32 = 20;
+/// Size of a dependency bundle entry
+const DEPENDENCY_BUNDLE_ENTRY_SIZE: i32 = 4;
+
+/// Zen bulk data map entry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ZenBulkDataMapEntry {
+    /// Serialized offset
+    pub serial_offset: i64,
+    /// Serialized offset of the duplicate in an optional package
+    pub duplicate_serial_offset: i64,
+    /// Serialized size
+    pub serial_size: i64,
+    /// Bulk data flags
+    pub flags: u32,
+}
+
+/// Zen export map entry
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ZenExportMapEntry {
+    /// Offset of the export in the legacy .uexp file, including the cooked header size
+    pub cooked_serial_offset: u64,
+    /// Serialized size
+    pub cooked_serial_size: u64,
+    /// Object name
+    pub object_name: FName,
+    /// Outer index
+    pub outer_index: PackageObjectIndex,
+    /// Class index
+    pub class_index: PackageObjectIndex,
+    /// Super index
+    pub super_index: PackageObjectIndex,
+    /// Template index
+    pub template_index: PackageObjectIndex,
+    /// Public export hash, used by other packages to import this export
+    pub public_export_hash: u64,
+    /// Object flags
+    pub object_flags: EObjectFlags,
+    /// Filter flags
+    pub filter_flags: EExportFilterFlags,
+}
+
+impl ZenExportMapEntry {
+    /// Read `ZenExportMapEntry` from an archive
+    pub fn read<R: ArchiveReader<PackageObjectIndex>>(archive: &mut R) -> Result<Self, Error> {
+        let cooked_serial_offset = archive.read_u64::<LE>()?;
+        let cooked_serial_size = archive.read_u64::<LE>()?;
+        let object_name = read_mapped_name(archive)?;
+        let outer_index = PackageObjectIndex::new(archive.read_u64::<LE>()?);
+        let class_index = PackageObjectIndex::new(archive.read_u64::<LE>()?);
+        let super_index = PackageObjectIndex::new(archive.read_u64::<LE>()?);
+        let template_index = PackageObjectIndex::new(archive.read_u64::<LE>()?);
+        let public_export_hash = archive.read_u64::<LE>()?;
+        let object_flags = EObjectFlags::from_bits(archive.read_u32::<LE>()?)
+            .ok_or_else(|| Error::invalid_file("Invalid object flags".to_string()))?;
+        let filter_flags = EExportFilterFlags::try_from(archive.read_u8()?)?;
+        archive.read_exact(&mut [0u8; 3])?;
+
+        Ok(ZenExportMapEntry {
+            cooked_serial_offset,
+            cooked_serial_size,
+            object_name,
+            outer_index,
+            class_index,
+            super_index,
+            template_index,
+            public_export_hash,
+            object_flags,
+            filter_flags,
+        })
+    }
+
+    /// Write `ZenExportMapEntry` to an archive
+    pub fn write<W: ArchiveWriter<PackageObjectIndex>>(
+        &self,
+        archive: &mut W,
+    ) -> Result<(), Error> {
+        archive.write_u64::<LE>(self.cooked_serial_offset)?;
+        archive.write_u64::<LE>(self.cooked_serial_size)?;
+        write_mapped_name(archive, &self.object_name)?;
+        archive.write_u64::<LE>(self.outer_index.index)?;
+        archive.write_u64::<LE>(self.class_index.index)?;
+        archive.write_u64::<LE>(self.super_index.index)?;
+        archive.write_u64::<LE>(self.template_index.index)?;
+        archive.write_u64::<LE>(self.public_export_hash)?;
+        archive.write_u32::<LE>(self.object_flags.bits())?;
+        archive.write_u8(self.filter_flags as u8)?;
+        archive.write_all(&[0u8; 3])?;
+        Ok(())
+    }
+
+    /// Convert `ZenExportMapEntry` to [`BaseExport`]
+    ///
+    /// The serial offset is left empty as it depends on the export bundles
+    pub fn to_base_export(self) -> BaseExport<PackageObjectIndex> {
+        BaseExport {
+            class_index: self.class_index,
+            super_index: self.super_index,
+            template_index: self.template_index,
+            outer_index: self.outer_index,
+            object_name: self.object_name,
+            object_flags: self.object_flags,
+            serial_size: self.cooked_serial_size as i64,
+            not_for_client: self.filter_flags == EExportFilterFlags::NotForClient,
+            not_for_server: self.filter_flags == EExportFilterFlags::NotForServer,
+            public_export_hash: self.public_export_hash,
+            ..Default::default()
+        }
+    }
+
+    /// Convert [`BaseExport`] to `ZenExportMapEntry`
+    pub fn from_base_export(
+        b: &BaseExport<PackageObjectIndex>,
+        cooked_serial_offset: u64,
+        cooked_serial_size: u64,
+    ) -> Self {
+        ZenExportMapEntry {
+            cooked_serial_offset,
+            cooked_serial_size,
+            object_name: b.object_name.clone(),
+            outer_index: b.outer_index,
+            class_index: b.class_index,
+            super_index: b.super_index,
+            template_index: b.template_index,
+            public_export_hash: b.public_export_hash,
+            object_flags: b.object_flags,
+            filter_flags: match (b.not_for_client, b.not_for_server) {
+                (true, _) => EExportFilterFlags::NotForClient,
+                (false, true) => EExportFilterFlags::NotForServer,
+                (false, false) => EExportFilterFlags::None,
+            },
+        }
+    }
+}
+
+/// Zen export bundle header
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ZenExportBundleHeader {
+    /// Offset of the bundle data relative to the end of the package header
+    pub serial_offset: u64,
+    /// Index of the first export bundle entry of this bundle
+    pub first_entry_index: u32,
+    /// Amount of export bundle entries in this bundle
+    pub entry_count: u32,
+}
+
+/// Zen export bundle entry
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ZenExportBundleEntry {
+    /// Export index
+    pub local_export_index: u32,
+    /// Command executed for the export
+    pub command_type: EExportCommandType,
+}
+
+/// Dependency arc between two export bundles of a zen package
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ZenInternalArc {
+    /// Export bundle which must be processed first
+    pub from_export_bundle_index: i32,
+    /// Dependent export bundle
+    pub to_export_bundle_index: i32,
+}
+
+/// Dependency arc from an import of a zen package to one of its export bundles
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ZenExternalArc {
+    /// Import which must be processed first
+    pub from_import_index: i32,
+    /// Command which must be executed for the import first
+    pub from_command_type: EExportCommandType,
+    /// Dependent export bundle
+    pub to_export_bundle_index: i32,
+}
+
+/// Dependencies of an export, replacing the graph data since `EZenPackageVersion::ImportedPackageNames`
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ZenDependencyBundleHeader {
+    /// Index of the first dependency bundle entry of this export
+    pub first_entry_index: i32,
+    /// Amount of dependency bundle entries by the command of the export and the command which
+    /// must be executed for the dependency first, `Create` is 0 and `Serialize` 1
+    pub entry_count: [[u32; 2]; 2],
+}
+
+/// Zen dependency bundle entry
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ZenDependencyBundleEntry {
+    /// Import or export which the export depends on
+    pub local_import_or_export_index: PackageIndex,
+}
+
+/// Unreal Engine 5 zen package
+///
+/// Packages of UE 5.0 to 5.3 are supported. Packages without versioning info are read with the
+/// zen version of the engine version, which can not be UE 5.3 yet.
+#[derive(FNameContainer)]
+pub struct ZenAsset<C: Read + Seek> {
+    /// Raw reader
+    #[container_ignore]
+    pub raw_reader: RawReader<PackageObjectIndex, C>,
+    /// Asset data
+    pub asset_data: AssetData<PackageObjectIndex>,
+    /// Zen package version
+    #[container_ignore]
+    pub zen_version: EZenPackageVersion,
+    /// Does the package store its versions, otherwise they are taken from the engine version
+    pub has_versioning_info: bool,
+    /// Package name
+    pub package_name: FName,
+    /// Size of the header of the legacy .uasset this package was cooked from
+    pub cooked_header_size: u32,
+    /// Bulk data map
+    #[container_ignore]
+    pub bulk_data_map: Vec<ZenBulkDataMapEntry>,
+    /// Hashes of the public exports imported from other packages
+    pub imported_public_export_hashes: Vec<u64>,
+    /// Import map
+    #[container_ignore]
+    pub import_map: Vec<PackageObjectIndex>,
+    /// Export bundle headers
+    #[container_ignore]
+    pub export_bundle_headers: Vec<ZenExportBundleHeader>,
+    /// Export bundle entries
+    #[container_ignore]
+    pub export_bundle_entries: Vec<ZenExportBundleEntry>,
+    /// Dependency arcs between export bundles
+    #[container_ignore]
+    pub internal_arcs: Vec<ZenInternalArc>,
+    /// Dependency arcs from the imports of each imported package
+    #[container_ignore]
+    pub external_arcs: Vec<Vec<ZenExternalArc>>,
+    /// Dependency bundle headers, one for each export
+    #[container_ignore]
+    pub dependency_bundle_headers: Vec<ZenDependencyBundleHeader>,
+    /// Dependency bundle entries
+    #[container_ignore]
+    pub dependency_bundle_entries: Vec<ZenDependencyBundleEntry>,
+    /// Names and numbers of the imported packages
+    #[container_ignore]
+    pub imported_package_names: Vec<(String, i32)>,
+
+    /// Script objects used to resolve script import names
+    #[container_ignore]
+    script_objects: Option<ScriptObjects>,
+    /// Name map
+    #[container_ignore]
+    name_map: SharedResource<NameMap>,
+}
+
+impl<'a, C: Read + Seek> ZenAsset<C> {
+    /// Create a zen asset from a binary file
+    ///
+    /// Script objects from global.utoc are needed to resolve the class names of exports,
+    /// exports with an unresolved class are read as raw exports.
+    pub fn new(
+        asset_data: C,
+        engine_version: EngineVersion,
+        mappings: Option<Usmap>,
+        script_objects: Option<ScriptObjects>,
+    ) -> Result<Self, Error> {
+        Self::new_with_limits(
+            asset_data,
+            engine_version,
+            mappings,
+            script_objects,
+            ParseLimits::default(),
+        )
+    }
+
+    /// Create a zen asset from a binary file, rejecting files which exceed the given limits
+    pub fn new_with_limits(
+        asset_data: C,
+        engine_version: EngineVersion,
+        mappings: Option<Usmap>,
+        script_objects: Option<ScriptObjects>,
+        limits: ParseLimits,
+    ) -> Result<Self, Error> {
+        let name_map = NameMap::new();
+        let mut raw_reader = RawReader::new(
+            Chain::new(asset_data, None),
+            ObjectVersion::UNKNOWN,
+            ObjectVersionUE5::UNKNOWN,
+            true,
+            name_map.clone(),
+        );
+        raw_reader.limits = limits;
+
+        let mut asset = ZenAsset {
+            raw_reader,
+            asset_data: AssetData {
+                use_event_driven_loader: true,
+                ..Default::default()
+            },
+            zen_version: EZenPackageVersion::Initial,
+            has_versioning_info: false,
+            package_name: FName::default(),
+            cooked_header_size: 0,
+            bulk_data_map: Vec::new(),
+            imported_public_export_hashes: Vec::new(),
+            import_map: Vec::new(),
+            export_bundle_headers: Vec::new(),
+            export_bundle_entries: Vec::new(),
+            internal_arcs: Vec::new(),
+            external_arcs: Vec::new(),
+            dependency_bundle_headers: Vec::new(),
+            dependency_bundle_entries: Vec::new(),
+            imported_package_names: Vec::new(),
+            script_objects,
+            name_map,
+        };
+        asset.set_engine_version(engine_version);
+        asset.asset_data.mappings = mappings;
+        asset.parse_data()?;
+        Ok(asset)
+    }
+
+    /// Set asset engine version
+    fn set_engine_version(&mut self, engine_version: EngineVersion) {
+        self.asset_data.set_engine_version(engine_version);
+        self.raw_reader.object_version = self.asset_data.object_version;
+        self.raw_reader.object_version_ue5 = self.asset_data.object_version_ue5;
+    }
+
+    /// Get the name map
+    pub fn get_name_map(&self) -> SharedResource<NameMap> {
+        self.name_map.clone()
+    }
+
+    /// Add an `FName`
+    pub fn add_fname(&mut self, slice: &str) -> FName {
+        self.name_map.get_mut().add_fname(slice)
+    }
+
+    /// Get an import by a `PackageIndex` used in export data
+    pub fn get_import(&self, index: PackageIndex) -> Option<PackageObjectIndex> {
+        if !index.is_import() {
+            return None;
+        }
+
+        self.import_map
+            .get((-(index.index as i64) - 1) as usize)
+            .copied()
+    }
+
+    /// Get an export
+    pub fn get_export(&'a self, index: PackageIndex) -> Option<&'a Export<PackageObjectIndex>> {
+        self.asset_data.get_export(index)
+    }
+
+    /// Get a mutable export reference
+    pub fn get_export_mut(
+        &'a mut self,
+        index: PackageIndex,
+    ) -> Option<&'a mut Export<PackageObjectIndex>> {
+        self.asset_data.get_export_mut(index)
+    }
+
+    /// Get the script objects used to resolve script import names
+    pub fn get_script_objects(&self) -> Option<&ScriptObjects> {
+        self.script_objects.as_ref()
+    }
+
+    /// Get the amount of elements of a header section, which are only delimited by their offsets
+    fn get_section_length(
+        &self,
+        start: i32,
+        end: i32,
+        element_size: i32,
+        header_size: u32,
+    ) -> Result<usize, Error> {
+        if start < 0 || end < start || end as u32 > header_size {
+            return Err(Error::invalid_file(format!(
+                "Invalid zen package header section from {start} to {end}"
+            )));
+        }
+        self.check_array_length((end - start) / element_size)
+    }
+
+    /// Read the export bundle headers and dependency arcs of packages before UE 5.3
+    fn read_graph_data(&mut self, graph_data_offset: i32, header_size: u32) -> Result<(), Error> {
+        // the export bundle count is not stored, the bundles cover all export bundle entries
+        self.seek(SeekFrom::Start(graph_data_offset as u64))?;
+        let mut covered_entries = 0u64;
+        while covered_entries < self.export_bundle_entries.len() as u64 {
+            if self.position() + EXPORT_BUNDLE_HEADER_SIZE > header_size as u64 {
+                return Err(Error::invalid_file(
+                    "Export bundle headers do not cover all export bundle entries".to_string(),
+                ));
+            }
+
+            let header = ZenExportBundleHeader {
+                serial_offset: self.read_u64::<LE>()?,
+                first_entry_index: self.read_u32::<LE>()?,
+                entry_count: self.read_u32::<LE>()?,
+            };
+            covered_entries += header.entry_count as u64;
+            self.export_bundle_headers.push(header);
+        }
+
+        let internal_arc_count = self.read_i32::<LE>()?;
+        self.internal_arcs = self.read_array_with_length(internal_arc_count, |e| {
+            Ok(ZenInternalArc {
+                from_export_bundle_index: e.read_i32::<LE>()?,
+                to_export_bundle_index: e.read_i32::<LE>()?,
+            })
+        })?;
+
+        // the imported package count is not stored, the external arcs fill the rest of the header
+        while self.position() + 4 <= header_size as u64 {
+            let external_arc_count = self.read_i32::<LE>()?;
+            let external_arcs = self.read_array_with_length(external_arc_count, |e| {
+                Ok(ZenExternalArc {
+                    from_import_index: e.read_i32::<LE>()?,
+                    from_command_type: EExportCommandType::try_from(e.read_u8()? as u32)?,
+                    to_export_bundle_index: e.read_i32::<LE>()?,
+                })
+            })?;
+            self.external_arcs.push(external_arcs);
+        }
+
+        Ok(())
+    }
+
+    /// Read the dependency bundles and imported package names of UE 5.3+ packages
+    fn read_dependency_bundles(
+        &mut self,
+        dependency_bundle_headers_offset: i32,
+        dependency_bundle_entries_offset: i32,
+        imported_package_names_offset: i32,
+        header_size: u32,
+    ) -> Result<(), Error> {
+        let header_count = self.get_section_length(
+            dependency_bundle_headers_offset,
+            dependency_bundle_entries_offset,
+            DEPENDENCY_BUNDLE_HEADER_SIZE,
+            header_size,
+        )?;
+        self.seek(SeekFrom::Start(dependency_bundle_headers_offset as u64))?;
+        self.dependency_bundle_headers = self.read_array_with_length(header_count as i32, |e| {
+            let mut header = ZenDependencyBundleHeader {
+                first_entry_index: e.read_i32::<LE>()?,
+                ..Default::default()
+            };
+            for counts in &mut header.entry_count {
+                e.read_u32_into::<LE>(counts)?;
+            }
+            Ok(header)
+        })?;
+
+        let entry_count = self.get_section_length(
+            dependency_bundle_entries_offset,
+            imported_package_names_offset,
+            DEPENDENCY_BUNDLE_ENTRY_SIZE,
+            header_size,
+        )?;
+        self.seek(SeekFrom::Start(dependency_bundle_entries_offset as u64))?;
+        self.dependency_bundle_entries = self.read_array_with_length(entry_count as i32, |e| {
+            Ok(ZenDependencyBundleEntry {
+                local_import_or_export_index: PackageIndex::new(e.read_i32::<LE>()?),
+            })
+        })?;
+
+        self.seek(SeekFrom::Start(imported_package_names_offset as u64))?;
+        let (names, _) = self.read_name_batch(false)?;
+        self.imported_package_names = Vec::with_capacity(names.len());
+        for name in names {
+            let number = self.read_i32::<LE>()?;
+            self.imported_package_names.push((name, number));
+        }
+
+        // without export bundle headers all exports are serialized in a single export bundle
+        self.export_bundle_headers = vec![ZenExportBundleHeader {
+            serial_offset: 0,
+            first_entry_index: 0,
+            entry_count: self.export_bundle_entries.len() as u32,
+        }];
+
+        Ok(())
+    }
+
+    /// Parse asset data
+    fn parse_data(&mut self) -> Result<(), Error> {
+        self.rewind()?;
+
+        self.has_versioning_info = self.read_u32::<LE>()? != 0;
+        let header_size = self.read_u32::<LE>()?;
+        let package_name_index = self.read_u32::<LE>()?;
+        let package_name_number = self.read_u32::<LE>()?;
+        self.asset_data.summary.package_flags = EPackageFlags::from_bits(self.read_u32::<LE>()?)
+            .ok_or_else(|| Error::invalid_file("Invalid package flags".to_string()))?;
+        self.cooked_header_size = self.read_u32::<LE>()?;
+        let imported_public_export_hashes_offset = self.read_i32::<LE>()?;
+        let import_map_offset = self.read_i32::<LE>()?;
+        let export_map_offset = self.read_i32::<LE>()?;
+        let export_bundle_entries_offset = self.read_i32::<LE>()?;
+        // the dependency bundle headers offset takes the place of the graph data offset
+        let graph_data_offset = self.read_i32::<LE>()?;
+        let mut dependency_bundle_entries_offset = 0;
+        let mut imported_package_names_offset = 0;
+
+        if header_size as u64 > self.data_length()? {
+            return Err(Error::invalid_file(format!(
+                "Zen package header size {header_size} is larger than the package"
+            )));
+        }
+
+        self.asset_data.summary.unversioned = !self.has_versioning_info;
+        if self.has_versioning_info {
+            // the summary grew in UE 5.3, the offset following the old summary is always larger
+            // than the zen version which followed it before
+            let zen_version = self.read_u32::<LE>()?;
+            self.zen_version = match zen_version < EZenPackageVersion::ImportedPackageNames as u32 {
+                true => EZenPackageVersion::try_from(zen_version)?,
+                false => {
+                    dependency_bundle_entries_offset = zen_version as i32;
+                    imported_package_names_offset = self.read_i32::<LE>()?;
+                    let zen_version = EZenPackageVersion::try_from(self.read_u32::<LE>()?)?;
+                    if zen_version < EZenPackageVersion::ImportedPackageNames {
+                        return Err(Error::invalid_file(format!(
+                            "Zen package version {zen_version:?} with the summary of version {:?}",
+                            EZenPackageVersion::ImportedPackageNames
+                        )));
+                    }
+                    zen_version
+                }
+            };
+            self.asset_data.object_version = self.read_i32::<LE>()?.try_into()?;
+            self.asset_data.object_version_ue5 = self.read_i32::<LE>()?.try_into()?;
+            self.asset_data.summary.file_licensee_version = self.read_i32::<LE>()?;
+
+            let old_container = self.asset_data.summary.custom_versions.clone();
+            self.asset_data.summary.custom_versions = self.read_custom_version_container(
+                ECustomVersionSerializationFormat::Optimized,
+                Some(&old_container),
+            )?;
+
+            self.raw_reader.object_version = self.asset_data.object_version;
+            self.raw_reader.object_version_ue5 = self.asset_data.object_version_ue5;
+        } else {
+            if self.asset_data.object_version == ObjectVersion::UNKNOWN {
+                return Err(ZenError::NoObjectVersion.into());
+            }
+            self.zen_version =
+                match self.asset_data.object_version_ue5 >= ObjectVersionUE5::DATA_RESOURCES {
+                    true => EZenPackageVersion::DataResourceTable,
+                    false => EZenPackageVersion::Initial,
+                };
+        }
+
+        let (name_batch, _) = self.read_name_batch(false)?;
+        for name in name_batch {
+            self.add_name_reference(name, true);
+        }
+        self.package_name =
+            self.create_mapped_name(package_name_index, package_name_number as i32)?;
+
+        if self.zen_version >= EZenPackageVersion::DataResourceTable {
+            let bulk_data_map_size = self.read_i64::<LE>()?;
+            let count = self.check_array_length(bulk_data_map_size / BULK_DATA_MAP_ENTRY_SIZE)?;
+
+            self.bulk_data_map = self.read_array_with_length(count as i32, |e| {
+                let entry = ZenBulkDataMapEntry {
+                    serial_offset: e.read_i64::<LE>()?,
+                    duplicate_serial_offset: e.read_i64::<LE>()?,
+                    serial_size: e.read_i64::<LE>()?,
+                    flags: e.read_u32::<LE>()?,
+                };
+                e.read_u32::<LE>()?;
+                Ok(entry)
+            })?;
+        }
+
+        let hash_count = self.get_section_length(
+            imported_public_export_hashes_offset,
+            import_map_offset,
+            8,
+            header_size,
+        )?;
+        self.seek(SeekFrom::Start(imported_public_export_hashes_offset as u64))?;
+        self.imported_public_export_hashes =
+            self.read_array_with_length(hash_count as i32, |e| Ok(e.read_u64::<LE>()?))?;
+
+        let import_count =
+            self.get_section_length(import_map_offset, export_map_offset, 8, header_size)?;
+        self.seek(SeekFrom::Start(import_map_offset as u64))?;
+        self.import_map = self.read_array_with_length(import_count as i32, |e| {
+            Ok(PackageObjectIndex::new(e.read_u64::<LE>()?))
+        })?;
+
+        let export_count = self.get_section_length(
+            export_map_offset,
+            export_bundle_entries_offset,
+            EXPORT_MAP_ENTRY_SIZE,
+            header_size,
+        )?;
+        self.seek(SeekFrom::Start(export_map_offset as u64))?;
+        let export_map =
+            self.read_array_with_length(export_count as i32, ZenExportMapEntry::read)?;
+
+        let export_bundle_entry_count = self.get_section_length(
+            export_bundle_entries_offset,
+            graph_data_offset,
+            EXPORT_BUNDLE_ENTRY_SIZE,
+            header_size,
+        )?;
+        self.seek(SeekFrom::Start(export_bundle_entries_offset as u64))?;
+        self.export_bundle_entries =
+            self.read_array_with_length(export_bundle_entry_count as i32, |e| {
+                Ok(ZenExportBundleEntry {
+                    local_export_index: e.read_u32::<LE>()?,
+                    command_type: EExportCommandType::try_from(e.read_u32::<LE>()?)?,
+                })
+            })?;
+
+        match self.zen_version >= EZenPackageVersion::ImportedPackageNames {
+            true => self.read_dependency_bundles(
+                graph_data_offset,
+                dependency_bundle_entries_offset,
+                imported_package_names_offset,
+                header_size,
+            )?,
+            false => self.read_graph_data(graph_data_offset, header_size)?,
+        }
+
+        // exports are serialized one after another in export bundle order
+        let mut serial_offsets = vec![None; export_map.len()];
+        for header in &self.export_bundle_headers {
+            let mut serial_offset = (header_size as u64).saturating_add(header.serial_offset);
+            for entry in get_bundle_entries(&self.export_bundle_entries, header)? {
+                if entry.command_type != EExportCommandType::Serialize {
+                    continue;
+                }
+
+                let index = entry.local_export_index as usize;
+                let export = export_map.get(index).ok_or_else(|| {
+                    Error::invalid_file(format!(
+                        "Export bundle entry references missing export {index}"
+                    ))
+                })?;
+                serial_offsets[index] = Some(serial_offset);
+                serial_offset = serial_offset.saturating_add(export.cooked_serial_size);
+            }
+        }
+
+        self.asset_data.exports.reserve(export_map.len());
+        for (i, entry) in export_map.into_iter().enumerate() {
+            let serial_offset = serial_offsets[i].ok_or_else(|| {
+                Error::invalid_file(format!("Export {i} is not serialized by any export bundle"))
+            })?;
+
+            let mut base_export = entry.to_base_export();
+            base_export.serial_offset = serial_offset as i64;
+            let next_starting = serial_offset.saturating_add(base_export.serial_size as u64);
+
+            let export = self.read_export(base_export, next_starting)?;
+            self.asset_data.exports.push(export);
+        }
+
+        Ok(())
+    }
+
+    /// Create an `FName` from a mapped name of the package name map
+    fn create_mapped_name(&self, index: u32, number: i32) -> Result<FName, Error> {
+        let ty = EMappedNameType::try_from((index >> FName::TYPE_SHIFT) as u16)?;
+        let index = (index & FName::INDEX_MASK) as i32;
+
+        let name_map_size = self.name_map.get_ref().get_name_map_index_list().len();
+        if ty != EMappedNameType::Package || index as usize >= name_map_size {
+            return Err(Error::invalid_file(format!(
+                "Invalid mapped name {index} of type {ty:?}"
+            )));
+        }
+
+        Ok(FName::new_with_type(
+            index,
+            number,
+            ty,
+            self.name_map.clone(),
+        ))
+    }
+
+    /// Rebuild the FName map
+    /// This can be used if the user forgot to add a name to the name map when performing modifications
+    pub fn rebuild_name_map(&mut self) {
+        let mut current_name_map = self.name_map.clone();
+        self.traverse_fnames(&mut |mut name| {
+            let content = name.get_owned_content();
+            let FName::Backed {
+                index, name_map, ..
+            } = &mut name
+            else {
+                return;
+            };
+
+            if *name_map != current_name_map {
+                let new_index = current_name_map
+                    .get_mut()
+                    .add_name_reference(content, false);
+
+                *index = new_index;
+                *name_map = current_name_map.clone();
+            }
+        });
+    }
+
+    /// Write asset data
+    ///
+    /// Exports are written in export bundle order, new exports must be added to an export bundle.
+    pub fn write_data<W: Read + Seek + Write>(&self, cursor: &mut W) -> Result<(), Error> {
+        let has_graph_data = self.zen_version < EZenPackageVersion::ImportedPackageNames;
+
+        // export data is written first as it can add names to the name map
+        let mut export_data = Cursor::new(Vec::new());
+        let mut export_sizes = vec![None; self.asset_data.exports.len()];
+        let mut export_bundle_headers = Vec::with_capacity(self.export_bundle_headers.len());
+        {
+            let mut raw_serializer = RawWriter::new(
+                &mut export_data,
+                self.asset_data.object_version,
+                self.asset_data.object_version_ue5,
+                self.asset_data.use_event_driven_loader,
+                self.name_map.clone(),
+            );
+            let mut serializer = ZenArchiveWriter::new(
+                &mut raw_serializer,
+                &self.asset_data,
+                &self.import_map,
+                self.script_objects.as_ref(),
+                self.name_map.clone(),
+            );
+
+            for header in &self.export_bundle_headers {
+                export_bundle_headers.push(ZenExportBundleHeader {
+                    serial_offset: serializer.position(),
+                    ..*header
+                });
+
+                for entry in get_bundle_entries(&self.export_bundle_entries, header)? {
+                    if entry.command_type != EExportCommandType::Serialize {
+                        continue;
+                    }
+
+                    let index = entry.local_export_index as usize;
+                    let export = self.asset_data.exports.get(index).ok_or_else(|| {
+                        Error::invalid_file(format!(
+                            "Export bundle entry references missing export {index}"
+                        ))
+                    })?;
+
+                    let start = serializer.position();
+                    export.write(&mut serializer)?;
+                    if let Some(normal_export) = export.get_normal_export() {
+                        serializer.write_all(&normal_export.extras)?;
+                    }
+                    export_sizes[index] = Some(serializer.position() - start);
+                }
+            }
+        }
+
+        let mut serializer = RawWriter::<PackageObjectIndex, _>::new(
+            cursor,
+            self.asset_data.object_version,
+            self.asset_data.object_version_ue5,
+            self.asset_data.use_event_driven_loader,
+            self.name_map.clone(),
+        );
+
+        // the summary is written last once all offsets are known
+        let summary_size = match has_graph_data {
+            true => SUMMARY_SIZE,
+            false => SUMMARY_SIZE_IMPORTED_PACKAGE_NAMES,
+        };
+        serializer.write_all(&vec![0u8; summary_size as usize])?;
+
+        if self.has_versioning_info {
+            serializer.write_u32::<LE>(self.zen_version as u32)?;
+            serializer.write_i32::<LE>(self.asset_data.object_version as i32)?;
+            serializer.write_i32::<LE>(self.asset_data.object_version_ue5 as i32)?;
+            serializer.write_i32::<LE>(self.asset_data.summary.file_licensee_version)?;
+            serializer.write_i32::<LE>(self.asset_data.summary.custom_versions.len() as i32)?;
+            for custom_version in &self.asset_data.summary.custom_versions {
+                serializer.write_guid(&custom_version.guid)?;
+                serializer.write_i32::<LE>(custom_version.version)?;
+            }
+        }
+
+        serializer.write_name_batch(self.name_map.get_ref().get_name_map_index_list())?;
+
+        if self.zen_version >= EZenPackageVersion::DataResourceTable {
+            serializer
+                .write_i64::<LE>(self.bulk_data_map.len() as i64 * BULK_DATA_MAP_ENTRY_SIZE)?;
+            for entry in &self.bulk_data_map {
+                serializer.write_i64::<LE>(entry.serial_offset)?;
+                serializer.write_i64::<LE>(entry.duplicate_serial_offset)?;
+                serializer.write_i64::<LE>(entry.serial_size)?;
+                serializer.write_u32::<LE>(entry.flags)?;
+                serializer.write_u32::<LE>(0)?;
+            }
+        }
+
+        let imported_public_export_hashes_offset = serializer.position() as i32;
+        for hash in &self.imported_public_export_hashes {
+            serializer.write_u64::<LE>(*hash)?;
+        }
+
+        let import_map_offset = serializer.position() as i32;
+        for import in &self.import_map {
+            serializer.write_u64::<LE>(import.index)?;
+        }
+
+        // cooked offsets follow the export order of the legacy .uexp
+        let export_map_offset = serializer.position() as i32;
+        let mut cooked_serial_offset = self.cooked_header_size as u64;
+        for (i, export) in self.asset_data.exports.iter().enumerate() {
+            let serial_size = export_sizes[i].ok_or_else(|| {
+                Error::invalid_file(format!("Export {i} is not serialized by any export bundle"))
+            })?;
+
+            ZenExportMapEntry::from_base_export(
+                export.get_base_export(),
+                cooked_serial_offset,
+                serial_size,
+            )
+            .write(&mut serializer)?;
+            cooked_serial_offset += serial_size;
+        }
+
+        let export_bundle_entries_offset = serializer.position() as i32;
+        for entry in &self.export_bundle_entries {
+            serializer.write_u32::<LE>(entry.local_export_index)?;
+            serializer.write_u32::<LE>(entry.command_type as u32)?;
+        }
+
+        // the dependency bundle headers take the place of the graph data
+        let graph_data_offset = serializer.position() as i32;
+        let mut dependency_bundle_entries_offset = 0;
+        let mut imported_package_names_offset = 0;
+        if has_graph_data {
+            for header in &export_bundle_headers {
+                serializer.write_u64::<LE>(header.serial_offset)?;
+                serializer.write_u32::<LE>(header.first_entry_index)?;
+                serializer.write_u32::<LE>(header.entry_count)?;
+            }
+
+            serializer.write_i32::<LE>(self.internal_arcs.len() as i32)?;
+            for arc in &self.internal_arcs {
+                serializer.write_i32::<LE>(arc.from_export_bundle_index)?;
+                serializer.write_i32::<LE>(arc.to_export_bundle_index)?;
+            }
+
+            for external_arcs in &self.external_arcs {
+                serializer.write_i32::<LE>(external_arcs.len() as i32)?;
+                for arc in external_arcs {
+                    serializer.write_i32::<LE>(arc.from_import_index)?;
+                    serializer.write_u8(arc.from_command_type as u8)?;
+                    serializer.write_i32::<LE>(arc.to_export_bundle_index)?;
+                }
+            }
+        } else {
+            for header in &self.dependency_bundle_headers {
+                serializer.write_i32::<LE>(header.first_entry_index)?;
+                for count in header.entry_count.iter().flatten() {
+                    serializer.write_u32::<LE>(*count)?;
+                }
+            }
+
+            dependency_bundle_entries_offset = serializer.position() as i32;
+            for entry in &self.dependency_bundle_entries {
+                serializer.write_i32::<LE>(entry.local_import_or_export_index.index)?;
+            }
+
+            imported_package_names_offset = serializer.position() as i32;
+            let names = self
+                .imported_package_names
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+            serializer.write_name_batch(&names)?;
+            for (_, number) in &self.imported_package_names {
+                serializer.write_i32::<LE>(*number)?;
+            }
+        }
+
+        let header_size = serializer.position() as u32;
+        serializer.write_all(export_data.get_ref())?;
+
+        serializer.rewind()?;
+        serializer.write_u32::<LE>(match self.has_versioning_info {
+            true => 1,
+            false => 0,
+        })?;
+        serializer.write_u32::<LE>(header_size)?;
+        write_mapped_name(&mut serializer, &self.package_name)?;
+        serializer.write_u32::<LE>(self.asset_data.summary.package_flags.bits())?;
+        serializer.write_u32::<LE>(self.cooked_header_size)?;
+        serializer.write_i32::<LE>(imported_public_export_hashes_offset)?;
+        serializer.write_i32::<LE>(import_map_offset)?;
+        serializer.write_i32::<LE>(export_map_offset)?;
+        serializer.write_i32::<LE>(export_bundle_entries_offset)?;
+        serializer.write_i32::<LE>(graph_data_offset)?;
+        if !has_graph_data {
+            serializer.write_i32::<LE>(dependency_bundle_entries_offset)?;
+            serializer.write_i32::<LE>(imported_package_names_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the export bundle entries of an export bundle
+fn get_bundle_entries<'entries>(
+    entries: &'entries [ZenExportBundleEntry],
+    header: &ZenExportBundleHeader,
+) -> Result<&'entries [ZenExportBundleEntry], Error> {
+    let start = header.first_entry_index as usize;
+    entries
+        .get(start..start.saturating_add(header.entry_count as usize))
+        .ok_or_else(|| {
+            Error::invalid_file(format!(
+                "Export bundle entries {} to {} are out of range",
+                start,
+                start.saturating_add(header.entry_count as usize)
+            ))
+        })
+}
+
+/// Read a mapped name of the package name map
+fn read_mapped_name<R: ArchiveReader<PackageObjectIndex>>(archive: &mut R) -> Result<FName, Error> {
+    let index = archive.read_u32::<LE>()?;
+    let number = archive.read_u32::<LE>()?;
+
+    let ty = EMappedNameType::try_from((index >> FName::TYPE_SHIFT) as u16)?;
+    let index = (index & FName::INDEX_MASK) as i32;
+
+    let name_map = archive.get_name_map();
+    let name_map_size = name_map.get_ref().get_name_map_index_list().len();
+    if ty != EMappedNameType::Package || index as usize >= name_map_size {
+        return Err(Error::invalid_file(format!(
+            "Invalid mapped name {index} of type {ty:?}"
+        )));
+    }
+
+    Ok(FName::new_with_type(index, number as i32, ty, name_map))
+}
+
+/// Write a mapped name
+fn write_mapped_name<W: ArchiveWriter<PackageObjectIndex>>(
+    archive: &mut W,
+    name: &FName,
+) -> Result<(), Error> {
+    match name {
+        FName::Backed {
+            index, number, ty, ..
+        } => {
+            let ty: u16 = (*ty).into();
+            archive.write_u32::<LE>(*index as u32 | (ty as u32) << FName::TYPE_SHIFT)?;
+            archive.write_u32::<LE>(*number as u32)?;
+            Ok(())
+        }
+        // dummy names can not be serialized
+        FName::Dummy { .. } => archive.write_fname(name),
+    }
+}
+
+impl<C: Read + Seek> AssetTrait<PackageObjectIndex> for ZenAsset<C> {
+    fn get_asset_data(&self) -> &AssetData<PackageObjectIndex> {
+        &self.asset_data
+    }
+
+    fn get_asset_data_mut(&mut self) -> &mut AssetData<PackageObjectIndex> {
+        &mut self.asset_data
+    }
+
+    fn get_name_map(&self) -> SharedResource<NameMap> {
+        self.name_map.clone()
+    }
+
+    fn search_name_reference(&self, name: &str) -> Option<i32> {
+        self.name_map.get_ref().search_name_reference(name)
+    }
+
+    fn add_name_reference(&mut self, name: String, force_add_duplicates: bool) -> i32 {
+        self.name_map
+            .get_mut()
+            .add_name_reference(name, force_add_duplicates)
+    }
+
+    fn get_name_reference<T>(&self, index: i32, func: impl FnOnce(&str) -> T) -> T {
+        func(self.name_map.get_ref().get_name_reference(index))
+    }
+
+    fn add_fname(&mut self, slice: &str) -> FName {
+        self.name_map.get_mut().add_fname(slice)
+    }
+}
+
+impl<C: Read + Seek> ArchiveTrait<PackageObjectIndex> for ZenAsset<C> {
+    fn get_archive_type(&self) -> ArchiveType {
+        ArchiveType::Zen
+    }
+
+    fn get_custom_version<T>(&self) -> CustomVersion
+    where
+        T: CustomVersionTrait + Into<i32>,
+    {
+        self.asset_data.get_custom_version::<T>()
+    }
+
+    fn has_unversioned_properties(&self) -> bool {
+        self.asset_data.has_unversioned_properties()
+    }
+
+    fn use_event_driven_loader(&self) -> bool {
+        self.asset_data.use_event_driven_loader
+    }
+
+    fn position(&mut self) -> u64 {
+        self.raw_reader.position()
+    }
+
+    fn get_name_map(&self) -> SharedResource<NameMap> {
+        self.name_map.clone()
+    }
+
+    fn get_array_struct_type_override(&self) -> &IndexedMap<String, String> {
+        &self.asset_data.array_struct_type_override
+    }
+
+    fn get_map_key_override(&self) -> &IndexedMap<String, String> {
+        &self.asset_data.map_key_override
+    }
+
+    fn get_map_value_override(&self) -> &IndexedMap<String, String> {
+        &self.asset_data.map_value_override
+    }
+
+    fn get_engine_version(&self) -> EngineVersion {
+        self.asset_data.get_engine_version()
+    }
+
+    fn get_object_version(&self) -> ObjectVersion {
+        self.asset_data.object_version
+    }
+
+    fn get_object_version_ue5(&self) -> ObjectVersionUE5 {
+        self.asset_data.object_version_ue5
+    }
+
+    fn get_mappings(&self) -> Option<&Usmap> {
+        self.asset_data.mappings.as_ref()
+    }
+
+    fn get_parent_class_export_name(&self) -> Option<FName> {
+        let script_objects = self.script_objects.as_ref()?;
+        self.asset_data
+            .exports
+            .iter()
+            .find_map(|e| cast!(Export, ClassExport, e))
+            .and_then(|e| self.get_import(e.struct_export.super_struct))
+            .and_then(|e| script_objects.get_outer_index(e))
+            .and_then(|e| script_objects.get_object_name(e))
+    }
+
+    fn get_object_name(&self, index: PackageObjectIndex) -> Option<FName> {
+        self.script_objects.as_ref()?.get_object_name(index)
+    }
+
+    fn get_object_name_packageindex(&self, index: PackageIndex) -> Option<FName> {
+        self.get_import(index).and_then(|e| self.get_object_name(e))
+    }
+}
+
+impl<C: Read + Seek> ArchiveReader<PackageObjectIndex> for ZenAsset<C> {
+    passthrough_archive_reader!(raw_reader);
+}
+
+impl<C: Read + Seek> Read for ZenAsset<C> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.raw_reader.read(buf)
+    }
+}
+
+impl<C: Read + Seek> Seek for ZenAsset<C> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.raw_reader.seek(pos)
+    }
+}
+
+// custom debug implementation to not print the whole data buffer
+impl<C: Read + Seek> Debug for ZenAsset<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        f.debug_struct("ZenAsset")
+            .field("asset_data", &self.asset_data)
+            .field("zen_version", &self.zen_version)
+            .field("has_versioning_info", &self.has_versioning_info)
+            .field("package_name", &self.package_name)
+            .field("cooked_header_size", &self.cooked_header_size)
+            .field("bulk_data_map", &self.bulk_data_map)
+            .field(
+                "imported_public_export_hashes",
+                &self.imported_public_export_hashes,
+            )
+            .field("import_map", &self.import_map)
+            .field("export_bundle_headers", &self.export_bundle_headers)
+            .field("export_bundle_entries", &self.export_bundle_entries)
+            .field("internal_arcs", &self.internal_arcs)
+            .field("external_arcs", &self.external_arcs)
+            .field("dependency_bundle_headers", &self.dependency_bundle_headers)
+            .field("dependency_bundle_entries", &self.dependency_bundle_entries)
+            .field("imported_package_names", &self.imported_package_names)
+            .finish()
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use unreal_asset::{
+    cast,
+    containers::{NameMap, SharedResource},
+    diff::{AssetDiff, DiffChange, DiffEntry, DiffTarget},
+    engine_version::EngineVersion,
+    exports::{data_table_export::DataTableExport, Export},
+    properties::{
+        delegate_property::{Delegate, MulticastInlineDelegateProperty},
+        int_property::IntProperty,
+        str_property::NameProperty,
+        Property, PropertyDataTrait,
+    },
+    types::{fname::FNameContainer, FName, PackageIndex},
+    unversioned::Ancestry,
+    Asset, Error, Import,
+};
+
+const TEST_ASSET: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/assets/general/BloodStained/PB_DT_ItemMaster.uasset"
+));
+
+fn parse() -> Result<Asset<Cursor<&'static [u8]>>, Error> {
+    Asset::new(
+        Cursor::new(TEST_ASSET),
+        None,
+        EngineVersion::VER_UE4_18,
+        None,
+    )
+}
+
+fn get_table<'a>(
+    asset: &'a mut Asset<Cursor<&'static [u8]>>,
+) -> &'a mut DataTableExport<PackageIndex> {
+    cast!(Export, DataTableExport, &mut asset.asset_data.exports[0])
+        .expect("First export is not a DataTableExport")
+}
+
+fn add_import(asset: &mut Asset<Cursor<&'static [u8]>>, object_name: &str) -> PackageIndex {
+    let import = Import::new(
+        asset.add_fname("/Script/CoreUObject"),
+        asset.add_fname("Package"),
+        PackageIndex::new(0),
+        asset.add_fname(object_name),
+        None,
+        false,
+    );
+    asset.add_import(import)
+}
+
+fn add_delegate(asset: &mut Asset<Cursor<&'static [u8]>>, object: PackageIndex) {
+    let name = asset.add_fname("OnUse");
+    let function = asset.add_fname("Use");
+    let potion = &mut get_table(asset).table.data[0];
+    potion.value.push(Property::MulticastInlineDelegateProperty(
+        MulticastInlineDelegateProperty {
+            name,
+            ancestry: Ancestry::default(),
+            property_guid: None,
+            duplication_index: 0,
+            value: vec![Delegate::new(object, function)],
+        },
+    ));
+}
+
+fn entry(path: &str, change: DiffChange) -> DiffEntry {
+    DiffEntry {
+        target: DiffTarget::Property,
+        path: path.to_string(),
+        change,
+    }
+}
+
+#[test]
+fn identical_assets() -> Result<(), Error> {
+    let old = parse()?;
+    let new = parse()?;
+
+    let diff = AssetDiff::new(&old, &new);
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "No differences\n");
+
+    Ok(())
+}
+
+#[test]
+fn property_changes() -> Result<(), Error> {
+    let old = parse()?;
+    let mut new = parse()?;
+
+    let new_row_name = new.add_fname("Potion_Diff");
+    let category = new.add_fname("SE_Diff");
+    let table = get_table(&mut new);
+
+    let potion = &mut table.table.data[0];
+    assert_eq!(potion.name.get_owned_content(), "Potion");
+    potion
+        .value
+        .retain(|e| e.get_name().get_owned_content() != "IsForBackersOnly");
+    for property in &mut potion.value {
+        if property.get_name().get_owned_content() == "buyPrice" {
+            let buy_price = cast!(Property, IntProperty, property).unwrap();
+            buy_price.value = 250;
+        } else if property.get_name().get_owned_content() == "SECategories" {
+            let categories = cast!(Property, ArrayProperty, property).unwrap();
+            categories.value.push(Property::NameProperty(NameProperty {
+                name: category.clone(),
+                ancestry: Ancestry::default(),
+                property_guid: None,
+                duplication_index: 0,
+                value: category.clone(),
+            }));
+        }
+    }
+
+    let mut new_row = table.table.data[1].clone();
+    new_row.name = new_row_name;
+    table.table.data.push(new_row);
+
+    let diff = AssetDiff::new(&old, &new);
+
+    let names = diff.get_entries(DiffTarget::Name).collect::<Vec<_>>();
+    assert_eq!(names.len(), 2);
+    assert!(names
+        .iter()
+        .all(|e| matches!(e.change, DiffChange::Added { .. })));
+
+    let properties = diff
+        .get_entries(DiffTarget::Property)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(properties.len(), 4);
+    assert!(properties.contains(&entry(
+        "PB_DT_ItemMaster.Potion.buyPrice",
+        DiffChange::Changed {
+            old: "200".to_string(),
+            new: "250".to_string(),
+        },
+    )));
+    assert!(properties.contains(&entry(
+        "PB_DT_ItemMaster.Potion.IsForBackersOnly",
+        DiffChange::Removed {
+            value: "false".to_string(),
+        },
+    )));
+    assert!(properties.contains(&entry(
+        "PB_DT_ItemMaster.Potion.SECategories[0]",
+        DiffChange::Added {
+            value: "SE_Diff".to_string(),
+        },
+    )));
+    assert!(properties
+        .iter()
+        .any(|e| e.path == "PB_DT_ItemMaster.Potion_Diff"
+            && matches!(e.change, DiffChange::Added { .. })));
+
+    let report = diff.to_string();
+    assert!(report.contains("Names:\n"));
+    assert!(report.contains("  + Potion_Diff\n"));
+    assert!(report.contains("  ~ PB_DT_ItemMaster.Potion.buyPrice: 200 -> 250\n"));
+
+    Ok(())
+}
+
+#[test]
+fn extended_name_map() -> Result<(), Error> {
+    let old = parse()?;
+
+    // every name references a copy appended to the name map
+    let mut extended = parse()?;
+    let mut name_map = extended.get_name_map();
+    let mut copies = HashMap::new();
+    extended.traverse_fnames(&mut |name| {
+        let content = name.get_owned_content();
+        if let FName::Backed { index, .. } = name {
+            *index = *copies
+                .entry(*index)
+                .or_insert_with(|| name_map.get_mut().add_name_reference(content, true));
+        }
+    });
+
+    let mut cursor = Cursor::new(Vec::new());
+    extended.write_data(&mut cursor, None)?;
+    let reparsed = Asset::new(
+        Cursor::new(cursor.into_inner()),
+        None,
+        EngineVersion::VER_UE4_18,
+        None,
+    )?;
+    let names =
+        |name_map: SharedResource<NameMap>| name_map.get_ref().get_name_map_index_list().len();
+    assert!(names(reparsed.get_name_map()) > names(old.get_name_map()));
+
+    // names are compared by content, not by their name map index
+    let diff = AssetDiff::new(&old, &reparsed);
+    assert!(diff.is_empty(), "{diff}");
+
+    Ok(())
+}
+
+#[test]
+fn package_index_changes() -> Result<(), Error> {
+    let mut old = parse()?;
+    let target = add_import(&mut old, "/Game/Target");
+    add_delegate(&mut old, target);
+
+    // the same object referenced by another import index is not a change
+    let mut new = parse()?;
+    let other = add_import(&mut new, "/Game/Other");
+    let new_target = add_import(&mut new, "/Game/Target");
+    assert_ne!(target, new_target);
+    add_delegate(&mut new, new_target);
+
+    let diff = AssetDiff::new(&old, &new);
+    assert_eq!(diff.get_entries(DiffTarget::Import).count(), 1);
+    assert_eq!(diff.get_entries(DiffTarget::Property).count(), 0);
+
+    let mut new = parse()?;
+    let other_index = add_import(&mut new, "/Game/Other");
+    assert_eq!(other_index, other);
+    add_import(&mut new, "/Game/Target");
+    add_delegate(&mut new, other_index);
+
+    let properties = AssetDiff::new(&old, &new)
+        .get_entries(DiffTarget::Property)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        properties,
+        [entry(
+            "PB_DT_ItemMaster.Potion.OnUse",
+            DiffChange::Changed {
+                old: "[/Game/Target.Use]".to_string(),
+                new: "[/Game/Other.Use]".to_string(),
+            },
+        )]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn duplicate_property_keys() -> Result<(), Error> {
+    let add_values = |asset: &mut Asset<Cursor<&'static [u8]>>, values: &[i32]| {
+        let name = asset.add_fname("Duplicate");
+        let potion = &mut get_table(asset).table.data[0];
+        for value in values {
+            potion.value.push(Property::IntProperty(IntProperty {
+                name: name.clone(),
+                ancestry: Ancestry::default(),
+                property_guid: None,
+                duplication_index: 0,
+                value: *value,
+            }));
+        }
+    };
+
+    let mut old = parse()?;
+    add_values(&mut old, &[1, 2]);
+    let mut new = parse()?;
+    add_values(&mut new, &[1, 3, 4]);
+
+    // properties with the same key are matched in order
+    let properties = AssetDiff::new(&old, &new)
+        .get_entries(DiffTarget::Property)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(
+        properties,
+        [
+            entry(
+                "PB_DT_ItemMaster.Potion.Duplicate",
+                DiffChange::Changed {
+                    old: "2".to_string(),
+                    new: "3".to_string(),
+                },
+            ),
+            entry(
+                "PB_DT_ItemMaster.Potion.Duplicate",
+                DiffChange::Added {
+                    value: "4".to_string(),
+                },
+            ),
+        ]
+    );
+
+    let properties = AssetDiff::new(&new, &old)
+        .get_entries(DiffTarget::Property)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(properties.len(), 2);
+    assert_eq!(
+        properties[1],
+        entry(
+            "PB_DT_ItemMaster.Potion.Duplicate",
+            DiffChange::Removed {
+                value: "4".to_string(),
+            },
+        )
+    );
+
+    Ok(())
+}
@@ -0,0 +1,353 @@
+use std::io::{Cursor, Write};
+
+use aes::cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit};
+use aes::Aes256;
+use byteorder::{WriteBytesExt, BE, LE};
+use flate2::{write::ZlibEncoder, Compression};
+
+use unreal_asset::{
+    enums::{EIoChunkType, EIoStoreTocVersion},
+    error::{Error, IoStoreError},
+    flags::EIoContainerFlags,
+    io_store::{IoChunkId, IoStoreReader},
+    ParseLimits,
+};
+use unreal_helpers::UnrealWriteExt;
+
+const MOUNT_POINT: &str = "../../../";
+const BLOCK_SIZE: usize = 0x100;
+const KEY: [u8; 32] = [0x5a; 32];
+
+struct TestChunk {
+    chunk_id: IoChunkId,
+    path: Option<&'static str>,
+    data: Vec<u8>,
+}
+
+fn test_chunks() -> Vec<TestChunk> {
+    vec![
+        TestChunk {
+            chunk_id: IoChunkId::new(0x1234, 0, EIoChunkType::ExportBundleData as u8),
+            path: Some("Game/Content/Data.uasset"),
+            data: (0..1000u32).map(|i| (i % 13) as u8).collect(),
+        },
+        TestChunk {
+            chunk_id: IoChunkId::new(0x1234, 0, EIoChunkType::BulkData as u8),
+            path: Some("Game/Content/Data.ubulk"),
+            data: (0..300u32).map(|i| (i * 7919 % 251) as u8).collect(),
+        },
+        TestChunk {
+            chunk_id: IoChunkId::new(0x5678, 0, EIoChunkType::ExportBundleData as u8),
+            path: Some("Game/Maps/Level.umap"),
+            data: b"small uncompressed chunk".to_vec(),
+        },
+        TestChunk {
+            chunk_id: IoChunkId::new(0x9abc, 0, EIoChunkType::ContainerHeader as u8),
+            path: None,
+            data: vec![0x42; 40],
+        },
+    ]
+}
+
+fn encrypt(data: &mut Vec<u8>) {
+    data.resize(data.len().next_multiple_of(16), 0);
+    let cipher = Aes256::new(GenericArray::from_slice(&KEY));
+    for block in data.chunks_exact_mut(16) {
+        cipher.encrypt_block(GenericArray::from_mut_slice(block));
+    }
+}
+
+/// Build the directory index from the paths of the chunks
+fn write_directory_index(chunks: &[TestChunk]) -> Vec<u8> {
+    const NONE: u32 = u32::MAX;
+    // name, first child, next sibling, first file
+    let mut directories = vec![[NONE; 4]];
+    // name, next file, chunk index
+    let mut files: Vec<[u32; 3]> = Vec::new();
+    let mut strings: Vec<&str> = Vec::new();
+    let mut intern = |name| match strings.iter().position(|e| *e == name) {
+        Some(index) => index as u32,
+        None => {
+            strings.push(name);
+            strings.len() as u32 - 1
+        }
+    };
+
+    for (chunk_index, chunk) in chunks.iter().enumerate() {
+        let Some(path) = chunk.path else {
+            continue;
+        };
+        let (directory_names, file_name) = path.rsplit_once('/').unwrap();
+
+        let mut directory = 0;
+        for directory_name in directory_names.split('/') {
+            let name = intern(directory_name);
+            let mut child = directories[directory][1];
+            while child != NONE && directories[child as usize][0] != name {
+                child = directories[child as usize][2];
+            }
+            if child == NONE {
+                child = directories.len() as u32;
+                directories.push([name, NONE, directories[directory][1], NONE]);
+                directories[directory][1] = child;
+            }
+            directory = child as usize;
+        }
+
+        files.push([
+            intern(file_name),
+            directories[directory][3],
+            chunk_index as u32,
+        ]);
+        directories[directory][3] = files.len() as u32 - 1;
+    }
+
+    let mut writer = Vec::new();
+    writer.write_fstring(Some(MOUNT_POINT)).unwrap();
+    writer.write_u32::<LE>(directories.len() as u32).unwrap();
+    for value in directories.iter().flatten() {
+        writer.write_u32::<LE>(*value).unwrap();
+    }
+    writer.write_u32::<LE>(files.len() as u32).unwrap();
+    for value in files.iter().flatten() {
+        writer.write_u32::<LE>(*value).unwrap();
+    }
+    writer.write_u32::<LE>(strings.len() as u32).unwrap();
+    for string in strings {
+        writer.write_fstring(Some(string)).unwrap();
+    }
+    writer
+}
+
+/// Build a container, returning the .utoc and the .ucas partitions
+fn write_container(encrypted: bool, partition_size: Option<u64>) -> (Vec<u8>, Vec<Vec<u8>>) {
+    let chunks = test_chunks();
+
+    let mut offsets = Vec::new();
+    let mut blocks = Vec::new();
+    let mut partitions = vec![Vec::new()];
+    let mut uncompressed_offset = 0;
+    for chunk in &chunks {
+        offsets.push((uncompressed_offset as u64, chunk.data.len() as u64));
+        uncompressed_offset += chunk.data.len().next_multiple_of(BLOCK_SIZE);
+
+        for block in chunk.data.chunks(BLOCK_SIZE) {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+            encoder.write_all(block).unwrap();
+            let compressed = encoder.finish().unwrap();
+
+            let (mut data, method) = match compressed.len() < block.len() {
+                true => (compressed, 1),
+                false => (block.to_vec(), 0),
+            };
+            let size = data.len();
+            if encrypted {
+                encrypt(&mut data);
+            }
+
+            // blocks never cross partitions
+            let partition_size = partition_size.unwrap_or(u64::MAX);
+            if partitions.last().unwrap().len() as u64 + data.len() as u64 > partition_size {
+                partitions.push(Vec::new());
+            }
+            let partition = partitions.len() - 1;
+            let offset = partition as u64 * partition_size + partitions[partition].len() as u64;
+            partitions[partition].extend_from_slice(&data);
+            blocks.push((offset, size as u32, block.len() as u32, method));
+        }
+    }
+
+    let mut directory_index = write_directory_index(&chunks);
+    if encrypted {
+        encrypt(&mut directory_index);
+    }
+
+    let mut flags = EIoContainerFlags::COMPRESSED | EIoContainerFlags::INDEXED;
+    if encrypted {
+        flags |= EIoContainerFlags::ENCRYPTED;
+    }
+
+    let mut toc = Vec::new();
+    toc.write_all(b"-==--==--==--==-").unwrap();
+    toc.write_u8(EIoStoreTocVersion::PerfectHashWithOverflow as u8)
+        .unwrap();
+    toc.write_all(&[0; 3]).unwrap();
+    toc.write_u32::<LE>(144).unwrap();
+    toc.write_u32::<LE>(chunks.len() as u32).unwrap();
+    toc.write_u32::<LE>(blocks.len() as u32).unwrap();
+    toc.write_u32::<LE>(12).unwrap();
+    toc.write_u32::<LE>(1).unwrap();
+    toc.write_u32::<LE>(32).unwrap();
+    toc.write_u32::<LE>(BLOCK_SIZE as u32).unwrap();
+    toc.write_u32::<LE>(directory_index.len() as u32).unwrap();
+    toc.write_u32::<LE>(partitions.len() as u32).unwrap();
+    toc.write_u64::<LE>(0xc0ffee).unwrap();
+    toc.write_all(&[0; 16]).unwrap();
+    toc.write_u8(flags.bits()).unwrap();
+    toc.write_all(&[0; 3]).unwrap();
+    toc.write_u32::<LE>(0).unwrap();
+    toc.write_u64::<LE>(partition_size.unwrap_or(u64::MAX))
+        .unwrap();
+    toc.write_u32::<LE>(0).unwrap();
+    toc.write_all(&[0; 44]).unwrap();
+    assert_eq!(toc.len(), 144);
+
+    for chunk in &chunks {
+        chunk.chunk_id.write(&mut toc).unwrap();
+    }
+    for (offset, length) in offsets {
+        toc.write_uint::<BE>(offset, 5).unwrap();
+        toc.write_uint::<BE>(length, 5).unwrap();
+    }
+    for (offset, compressed_size, uncompressed_size, method) in blocks {
+        toc.write_uint::<LE>(offset, 5).unwrap();
+        toc.write_uint::<LE>(compressed_size as u64, 3).unwrap();
+        toc.write_uint::<LE>(uncompressed_size as u64, 3).unwrap();
+        toc.write_u8(method).unwrap();
+    }
+    let mut method_name = [0u8; 32];
+    method_name[..4].copy_from_slice(b"Zlib");
+    toc.write_all(&method_name).unwrap();
+    toc.write_all(&directory_index).unwrap();
+
+    (toc, partitions)
+}
+
+fn check_container(reader: &mut IoStoreReader<Cursor<Vec<u8>>>) -> Result<(), Error> {
+    reader.load_toc()?;
+    assert_eq!(
+        reader.get_toc_version(),
+        EIoStoreTocVersion::PerfectHashWithOverflow
+    );
+    assert_eq!(reader.get_container_id(), 0xc0ffee);
+    assert_eq!(reader.mount_point, MOUNT_POINT);
+
+    let chunks = test_chunks();
+    assert_eq!(
+        reader.get_chunk_ids(),
+        chunks.iter().map(|e| e.chunk_id).collect::<Vec<_>>()
+    );
+    assert_eq!(reader.get_file_names().len(), 3);
+
+    for chunk in chunks {
+        assert!(reader.contains_chunk(&chunk.chunk_id));
+        assert_eq!(reader.read_chunk(&chunk.chunk_id)?, chunk.data);
+
+        if let Some(path) = chunk.path {
+            assert_eq!(reader.get_chunk_id(path), Some(chunk.chunk_id));
+            assert_eq!(reader.read_file(path)?, chunk.data);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn read_container() -> Result<(), Error> {
+    let (toc, partitions) = write_container(false, None);
+    let mut reader = IoStoreReader::new(
+        Cursor::new(toc),
+        partitions.into_iter().map(Cursor::new).collect(),
+    );
+    check_container(&mut reader)?;
+
+    assert!(reader
+        .get_container_flags()
+        .contains(EIoContainerFlags::INDEXED));
+    assert!(matches!(
+        reader.read_file("Game/Content/Missing.uasset"),
+        Err(Error::IoStore(IoStoreError::NoFile(_)))
+    ));
+    assert!(matches!(
+        reader.read_chunk(&IoChunkId::new(1, 0, 0)),
+        Err(Error::IoStore(IoStoreError::NoFile(_)))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn read_encrypted_partitioned_container() -> Result<(), Error> {
+    let (toc, partitions) = write_container(true, Some(0x110));
+    assert!(partitions.len() > 1);
+
+    let mut reader = IoStoreReader::new_encrypted(
+        Cursor::new(toc.clone()),
+        partitions.iter().cloned().map(Cursor::new).collect(),
+        KEY,
+    );
+    check_container(&mut reader)?;
+
+    let mut reader = IoStoreReader::new(
+        Cursor::new(toc),
+        partitions.into_iter().map(Cursor::new).collect(),
+    );
+    assert!(matches!(
+        reader.load_toc(),
+        Err(Error::IoStore(IoStoreError::NoEncryptionKey))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_toc_version() {
+    let (toc, partitions) = write_container(false, None);
+
+    // the version follows the magic, the placeholder versions are never written by the engine
+    for version in [
+        EIoStoreTocVersion::Invalid,
+        EIoStoreTocVersion::Latest,
+        EIoStoreTocVersion::LatestPlusOne,
+    ] {
+        let mut toc = toc.clone();
+        toc[16] = version as u8;
+
+        let mut reader = IoStoreReader::new(
+            Cursor::new(toc),
+            partitions.iter().cloned().map(Cursor::new).collect(),
+        );
+        assert!(matches!(
+            reader.load_toc(),
+            Err(Error::IoStore(IoStoreError::InvalidTocVersion(e))) if e == version
+        ));
+    }
+}
+
+#[test]
+fn malformed_container() {
+    let (toc, partitions) = write_container(false, None);
+
+    // corrupt every byte of the toc, reading must fail cleanly or still succeed
+    for position in 0..toc.len() {
+        for value in [0x00, 0x01, 0x7f, 0xff] {
+            let mut toc = toc.clone();
+            toc[position] = value;
+
+            let mut reader = IoStoreReader::new(
+                Cursor::new(toc),
+                partitions.iter().cloned().map(Cursor::new).collect(),
+            );
+            reader.set_limits(ParseLimits {
+                max_data_size: 0x100000,
+                ..Default::default()
+            });
+            if reader.load_toc().is_ok() {
+                for chunk_id in reader.get_chunk_ids() {
+                    let _ = reader.read_chunk(&chunk_id);
+                }
+            }
+        }
+    }
+
+    // truncated partitions
+    let mut reader = IoStoreReader::new(
+        Cursor::new(toc),
+        vec![Cursor::new(
+            partitions[0][..partitions[0].len() / 2].to_vec(),
+        )],
+    );
+    reader.load_toc().unwrap();
+    let chunk_ids = reader.get_chunk_ids();
+    assert!(chunk_ids.iter().any(|e| reader.read_chunk(e).is_err()));
+}
@@ -0,0 +1,497 @@
+use std::io::Cursor;
+
+use unreal_asset::{
+    compression::CompressionMethod,
+    crc::cityhash64_to_lower,
+    enums::{EIoChunkType, EIoChunkType4, EIoContainerHeaderVersion, EIoStoreTocVersion},
+    error::{Error, IoStoreError},
+    flags::EIoContainerFlags,
+    io_store::{
+        container_id, package_id, ContainerHeaderVersion, IoChunkId, IoContainerHeader,
+        IoStoreReader, IoStoreWriter, PackageStoreEntry,
+    },
+    Guid, ParseLimits,
+};
+
+const KEY: [u8; 32] = [0x3c; 32];
+
+type Container = (Cursor<Vec<u8>>, Vec<Cursor<Vec<u8>>>);
+
+/// Data which does not compress
+fn random_data(size: usize) -> Vec<u8> {
+    let mut state = 0x2545f491u32;
+    (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect()
+}
+
+fn test_packages() -> Vec<(&'static str, &'static str, Vec<u8>, PackageStoreEntry)> {
+    vec![
+        (
+            "/Game/Maps/Level",
+            "Game/Content/Maps/Level.umap",
+            (0..100_000u32).map(|i| (i % 17) as u8).collect(),
+            PackageStoreEntry {
+                export_count: 12,
+                export_bundle_count: 1,
+                load_order: 1,
+                imported_packages: vec![package_id("/Game/Data")],
+                shader_map_hashes: vec![[0x11; 20], [0x22; 20]],
+                ..Default::default()
+            },
+        ),
+        (
+            "/Game/Data",
+            "Game/Content/Data.uasset",
+            random_data(5000),
+            PackageStoreEntry {
+                export_count: 1,
+                export_bundle_count: 1,
+                ..Default::default()
+            },
+        ),
+    ]
+}
+
+fn bulk_data_chunk(toc_version: EIoStoreTocVersion) -> (IoChunkId, Vec<u8>) {
+    // UE4 used different chunk types
+    let chunk_type = match toc_version >= EIoStoreTocVersion::PerfectHash {
+        true => EIoChunkType::BulkData as u8,
+        false => EIoChunkType4::BulkData as u8,
+    };
+    (
+        IoChunkId::new(package_id("/Game/Data"), 0, chunk_type),
+        vec![0x42; 0x30000],
+    )
+}
+
+fn write_container(
+    toc_version: EIoStoreTocVersion,
+    key: Option<[u8; 32]>,
+    partition_size: Option<u64>,
+) -> Result<Container, Error> {
+    let (toc, ucas) = (Cursor::new(Vec::new()), Cursor::new(Vec::new()));
+    let container_id = container_id("Mod_P");
+    let mut writer = match key {
+        Some(key) => {
+            IoStoreWriter::new_encrypted(toc, ucas, toc_version, container_id, key, Guid([1; 16]))
+        }
+        None => IoStoreWriter::new(toc, ucas, toc_version, container_id),
+    };
+    if let Some(partition_size) = partition_size {
+        writer.set_partitions(partition_size, |_| Ok(Cursor::new(Vec::new())))?;
+    }
+    // UE 5.1 also uses this toc version
+    if toc_version == EIoStoreTocVersion::PerfectHashWithOverflow {
+        writer.container_header_version =
+            ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::OptionalSegmentPackages);
+    }
+
+    for (package_name, path, data, mut store_entry) in test_packages() {
+        // UE4 packages do not use shader maps
+        if toc_version < EIoStoreTocVersion::PerfectHash {
+            store_entry.shader_map_hashes.clear();
+        }
+        writer.write_package(package_name, path, &data, store_entry)?;
+    }
+    let (chunk_id, data) = bulk_data_chunk(toc_version);
+    writer.write_chunk(chunk_id, None, &data)?;
+
+    writer.finish_write()
+}
+
+fn read_container(container: Container, key: Option<[u8; 32]>) -> IoStoreReader<Cursor<Vec<u8>>> {
+    let (mut toc, mut partitions) = container;
+    toc.set_position(0);
+    for partition in &mut partitions {
+        partition.set_position(0);
+    }
+
+    match key {
+        Some(key) => IoStoreReader::new_encrypted(toc, partitions, key),
+        None => IoStoreReader::new(toc, partitions),
+    }
+}
+
+#[test]
+fn write_container_round_trip() -> Result<(), Error> {
+    for toc_version in [
+        EIoStoreTocVersion::Initial,
+        EIoStoreTocVersion::DirectoryIndex,
+        EIoStoreTocVersion::PartitionSize,
+        EIoStoreTocVersion::PerfectHash,
+        EIoStoreTocVersion::PerfectHashWithOverflow,
+        EIoStoreTocVersion::OnDemandMetaData,
+        EIoStoreTocVersion::RemovedOnDemandMetaData,
+        EIoStoreTocVersion::ReplaceIoChunkHashWithIoHash,
+        EIoStoreTocVersion::Latest,
+    ] {
+        let mut reader = read_container(write_container(toc_version, None, None)?, None);
+        reader.load_toc()?;
+
+        let expected_version = match toc_version {
+            EIoStoreTocVersion::Latest => EIoStoreTocVersion::ReplaceIoChunkHashWithIoHash,
+            version => version,
+        };
+        assert_eq!(reader.get_toc_version(), expected_version);
+        assert_eq!(reader.get_container_id(), container_id("Mod_P"));
+        assert!(reader
+            .get_container_flags()
+            .contains(EIoContainerFlags::COMPRESSED));
+
+        let (chunk_id, data) = bulk_data_chunk(toc_version);
+        assert_eq!(reader.read_chunk(&chunk_id)?, data);
+
+        // every container lists its packages in the container header
+        let header_chunk_type = match expected_version >= EIoStoreTocVersion::PerfectHash {
+            true => EIoChunkType::ContainerHeader as u8,
+            false => EIoChunkType4::ContainerHeader as u8,
+        };
+        let header_chunk_id = IoChunkId::new(container_id("Mod_P"), 0, header_chunk_type);
+        let header = IoContainerHeader::read(
+            &reader.read_chunk(&header_chunk_id)?,
+            expected_version,
+            &ParseLimits::default(),
+        )?;
+        let expected_header_version = match expected_version {
+            EIoStoreTocVersion::Initial
+            | EIoStoreTocVersion::DirectoryIndex
+            | EIoStoreTocVersion::PartitionSize => ContainerHeaderVersion::Legacy,
+            EIoStoreTocVersion::PerfectHash => ContainerHeaderVersion::Unversioned,
+            EIoStoreTocVersion::PerfectHashWithOverflow => ContainerHeaderVersion::Versioned(
+                EIoContainerHeaderVersion::OptionalSegmentPackages,
+            ),
+            _ => ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::NoExportInfo),
+        };
+        assert_eq!(header.version, expected_header_version);
+        assert_eq!(header.container_id, container_id("Mod_P"));
+        assert_eq!(header.packages.len(), 2);
+        for ((package_name, _, data, store_entry), (id, entry)) in
+            test_packages().into_iter().zip(header.packages)
+        {
+            assert_eq!(id, package_id(package_name));
+            assert_eq!(entry.imported_packages, store_entry.imported_packages);
+            if expected_header_version == ContainerHeaderVersion::Legacy {
+                assert_eq!(entry.package_name, package_name);
+                assert_eq!(entry.export_bundles_size, data.len() as u64);
+                assert_eq!(entry.load_order, store_entry.load_order);
+            } else {
+                assert_eq!(entry.shader_map_hashes, store_entry.shader_map_hashes);
+            }
+        }
+
+        // the directory index only exists since DirectoryIndex
+        if expected_version < EIoStoreTocVersion::DirectoryIndex {
+            assert!(reader.get_file_names().is_empty());
+            continue;
+        }
+        assert_eq!(reader.mount_point, "../../../");
+        for (_, path, data, _) in test_packages() {
+            assert_eq!(reader.read_file(path)?, data);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn write_encrypted_partitioned_container() -> Result<(), Error> {
+    let container = write_container(EIoStoreTocVersion::Latest, Some(KEY), Some(0x1400))?;
+    assert!(container.1.len() > 1);
+    assert!(container
+        .1
+        .iter()
+        .all(|e| e.get_ref().len() as u64 <= 0x1400));
+
+    let mut reader = read_container(container, Some(KEY));
+    reader.load_toc()?;
+    assert!(reader
+        .get_container_flags()
+        .contains(EIoContainerFlags::ENCRYPTED));
+    assert_eq!(reader.get_encryption_key_guid(), Guid([1; 16]));
+    for (_, path, data, _) in test_packages() {
+        assert_eq!(reader.read_file(path)?, data);
+    }
+    let (chunk_id, data) = bulk_data_chunk(EIoStoreTocVersion::Latest);
+    assert_eq!(reader.read_chunk(&chunk_id)?, data);
+
+    Ok(())
+}
+
+#[test]
+fn chunk_meta_layout() -> Result<(), Error> {
+    // hash, flags and padding
+    for (toc_version, hash_size, meta_size) in [
+        (EIoStoreTocVersion::RemovedOnDemandMetaData, 32, 33),
+        (EIoStoreTocVersion::ReplaceIoChunkHashWithIoHash, 20, 24),
+    ] {
+        let (toc, _) = write_container(toc_version, None, None)?;
+        let toc = toc.into_inner();
+        let read_u32 =
+            |offset: usize| u32::from_le_bytes(toc[offset..offset + 4].try_into().unwrap());
+
+        // chunk metadata is the last section of the table of contents
+        let entry_count = read_u32(24) as usize;
+        let block_count = read_u32(28) as usize;
+        let method_count = read_u32(36) as usize;
+        let directory_index_size = read_u32(48) as usize;
+        let metas_start =
+            144 + entry_count * 22 + block_count * 12 + method_count * 32 + directory_index_size;
+        assert_eq!(toc.len(), metas_start + entry_count * meta_size);
+
+        let metas = toc[metas_start..].chunks_exact(meta_size);
+        assert!(metas
+            .clone()
+            .all(|e| e[hash_size + 1..].iter().all(|e| *e == 0)));
+        // the packages are compressed
+        assert!(metas.clone().any(|e| e[hash_size] == 1));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn block_alignment() -> Result<(), Error> {
+    let (toc, partitions) = write_container(EIoStoreTocVersion::Latest, None, None)?;
+    let toc = toc.into_inner();
+    let entry_count = u32::from_le_bytes(toc[24..28].try_into().unwrap()) as usize;
+    let block_count = u32::from_le_bytes(toc[28..32].try_into().unwrap()) as usize;
+
+    // blocks are aligned to the AES block size even without encryption
+    let blocks_start = 144 + entry_count * 22;
+    for block in toc[blocks_start..blocks_start + block_count * 12].chunks_exact(12) {
+        let mut offset = [0u8; 8];
+        offset[..5].copy_from_slice(&block[..5]);
+        assert_eq!(u64::from_le_bytes(offset) % 16, 0);
+    }
+    assert_eq!(partitions[0].get_ref().len() % 16, 0);
+
+    let mut reader = read_container((Cursor::new(toc), partitions), None);
+    reader.load_toc()?;
+    for (_, path, data, _) in test_packages() {
+        assert_eq!(reader.read_file(path)?, data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn container_header_versions() -> Result<(), Error> {
+    for (version, toc_version) in [
+        (
+            ContainerHeaderVersion::Legacy,
+            EIoStoreTocVersion::PartitionSize,
+        ),
+        (
+            ContainerHeaderVersion::Unversioned,
+            EIoStoreTocVersion::PerfectHashWithOverflow,
+        ),
+        (
+            ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::Initial),
+            EIoStoreTocVersion::PerfectHashWithOverflow,
+        ),
+        (
+            ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::LocalizedPackages),
+            EIoStoreTocVersion::PerfectHashWithOverflow,
+        ),
+        (
+            ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::OptionalSegmentPackages),
+            EIoStoreTocVersion::PerfectHashWithOverflow,
+        ),
+        (
+            ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::NoExportInfo),
+            EIoStoreTocVersion::ReplaceIoChunkHashWithIoHash,
+        ),
+    ] {
+        let mut header = IoContainerHeader::new(version, 0x1234);
+        for (package_name, _, data, mut store_entry) in test_packages() {
+            if version == ContainerHeaderVersion::Legacy {
+                store_entry.package_name = package_name.to_string();
+                store_entry.export_bundles_size = data.len() as u64;
+                store_entry.shader_map_hashes.clear();
+            }
+            header
+                .packages
+                .push((package_id(package_name), store_entry));
+        }
+        if version == ContainerHeaderVersion::Legacy {
+            header.packages[0].1.source_package_name = Some("/Game/L10N/de/Maps/Level".to_string());
+        }
+
+        let mut data = Vec::new();
+        header.write(&mut data)?;
+        let read = IoContainerHeader::read(&data, toc_version, &ParseLimits::default())?;
+
+        // everything but the package ids and imports is only stored in UE4 containers
+        for (_, entry) in &mut header.packages {
+            if version != ContainerHeaderVersion::Legacy {
+                entry.load_order = 0;
+            }
+            // export counts were removed in NoExportInfo
+            if version >= ContainerHeaderVersion::Versioned(EIoContainerHeaderVersion::NoExportInfo)
+            {
+                entry.export_count = 0;
+                entry.export_bundle_count = 0;
+            }
+        }
+        assert_eq!(read, header);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn legacy_container_header_layout() -> Result<(), Error> {
+    let mut header = IoContainerHeader::new(ContainerHeaderVersion::Legacy, 0x1234);
+    header.packages.push((
+        package_id("/Game/Data"),
+        PackageStoreEntry {
+            export_bundles_size: 0x500,
+            package_name: "/Game/Data".to_string(),
+            export_count: 3,
+            export_bundle_count: 1,
+            load_order: 7,
+            imported_packages: vec![0xabcd],
+            ..Default::default()
+        },
+    ));
+    let mut data = Vec::new();
+    header.write(&mut data)?;
+
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&0x1234u64.to_le_bytes());
+    expected.extend_from_slice(&1u32.to_le_bytes());
+    // names with big endian headers and their hashes
+    expected.extend_from_slice(&18u32.to_le_bytes());
+    expected.extend_from_slice(&[0, 10]);
+    expected.extend_from_slice(b"/Game/Data");
+    expected.extend_from_slice(&[0, 4]);
+    expected.extend_from_slice(b"None");
+    expected.extend_from_slice(&24u32.to_le_bytes());
+    expected.extend_from_slice(&0xC1640000u64.to_le_bytes());
+    expected.extend_from_slice(&cityhash64_to_lower("/Game/Data").to_le_bytes());
+    expected.extend_from_slice(&cityhash64_to_lower("None").to_le_bytes());
+    // package ids
+    expected.extend_from_slice(&1u32.to_le_bytes());
+    expected.extend_from_slice(&package_id("/Game/Data").to_le_bytes());
+    // store entry followed by the imported package ids
+    expected.extend_from_slice(&56u32.to_le_bytes());
+    expected.extend_from_slice(&0x500u64.to_le_bytes());
+    expected.extend_from_slice(&[0, 0, 0, 0x40, 0, 0, 0, 0]);
+    expected.extend_from_slice(&[1, 0, 0, 0x40, 0, 0, 0, 0]);
+    for value in [3u32, 1, 7, 0, 1, 8] {
+        expected.extend_from_slice(&value.to_le_bytes());
+    }
+    expected.extend_from_slice(&0xabcdu64.to_le_bytes());
+    // culture package map and package redirects
+    expected.extend_from_slice(&[0; 8]);
+    assert_eq!(data, expected);
+
+    Ok(())
+}
+
+#[test]
+fn unversioned_container_header_layout() -> Result<(), Error> {
+    let mut header = IoContainerHeader::new(ContainerHeaderVersion::Unversioned, 0x1234);
+    header.packages.push((
+        package_id("/Game/Data"),
+        PackageStoreEntry {
+            export_count: 3,
+            export_bundle_count: 1,
+            ..Default::default()
+        },
+    ));
+    let mut data = Vec::new();
+    header.write(&mut data)?;
+
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&0x1234u64.to_le_bytes());
+    expected.extend_from_slice(&1u32.to_le_bytes());
+    expected.extend_from_slice(&package_id("/Game/Data").to_le_bytes());
+    expected.extend_from_slice(&24u32.to_le_bytes());
+    for value in [3u32, 1, 0, 0, 0, 0] {
+        expected.extend_from_slice(&value.to_le_bytes());
+    }
+    expected.extend_from_slice(&[0; 8]);
+    assert_eq!(data, expected);
+
+    Ok(())
+}
+
+#[test]
+fn invalid_writes() -> Result<(), Error> {
+    let new_writer = |toc_version| {
+        IoStoreWriter::new(
+            Cursor::new(Vec::new()),
+            Cursor::new(Vec::new()),
+            toc_version,
+            1,
+        )
+    };
+
+    let mut writer = new_writer(EIoStoreTocVersion::Latest);
+    writer.write_chunk(IoChunkId::new(1, 0, 2), None, b"data")?;
+    assert!(matches!(
+        writer.write_chunk(IoChunkId::new(1, 0, 2), None, b"data"),
+        Err(Error::IoStore(IoStoreError::DuplicateChunk(_)))
+    ));
+    assert!(matches!(
+        writer.set_compression(CompressionMethod::Lz4),
+        Err(Error::IoStore(IoStoreError::ConfigurationInvalid(_)))
+    ));
+
+    // UE4 store entries have no shader map hashes
+    let mut writer = new_writer(EIoStoreTocVersion::PartitionSize);
+    writer.write_package(
+        "/Game/Data",
+        "Game/Content/Data.uasset",
+        b"data",
+        PackageStoreEntry {
+            shader_map_hashes: vec![[0x11; 20]],
+            ..Default::default()
+        },
+    )?;
+    assert!(matches!(
+        writer.finish_write(),
+        Err(Error::IoStore(IoStoreError::ConfigurationInvalid(_)))
+    ));
+
+    // UE 5.0 and 5.1 share this toc version
+    let mut writer = new_writer(EIoStoreTocVersion::PerfectHashWithOverflow);
+    writer.write_chunk(IoChunkId::new(1, 0, 2), None, b"data")?;
+    assert!(matches!(
+        writer.finish_write(),
+        Err(Error::IoStore(IoStoreError::ConfigurationInvalid(_)))
+    ));
+
+    let mut writer = new_writer(EIoStoreTocVersion::Latest);
+    writer.container_header_version = ContainerHeaderVersion::Unversioned;
+    assert!(matches!(
+        writer.write_package(
+            "/Game/Data",
+            "Game/Content/Data.uasset",
+            b"data",
+            Default::default()
+        ),
+        Err(Error::IoStore(IoStoreError::ConfigurationInvalid(_)))
+    ));
+
+    let mut writer = new_writer(EIoStoreTocVersion::DirectoryIndex);
+    writer.set_partitions(0x1000, |_| Ok(Cursor::new(Vec::new())))?;
+    assert!(matches!(
+        writer.write_chunk(IoChunkId::new(1, 0, 2), None, b"data"),
+        Err(Error::IoStore(IoStoreError::ConfigurationInvalid(_)))
+    ));
+
+    let mut writer = new_writer(EIoStoreTocVersion::LatestPlusOne);
+    assert!(writer
+        .write_chunk(IoChunkId::new(1, 0, 2), None, b"data")
+        .is_err());
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use std::io::Cursor;
+
+use unreal_asset::{engine_version::EngineVersion, error::Error, Asset, ParseLimits};
+
+mod shared;
+use shared::Mutator;
+
+macro_rules! assets_folder {
+    () => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets/general/")
+    };
+}
+
+/// Asset data, bulk data and the engine version of each asset
+type TestAsset = (&'static [u8], Option<&'static [u8]>, EngineVersion);
+
+const TEST_ASSETS: [TestAsset; 3] = [
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "Misc_426/MainChar_BellySlice_BR.uasset"
+        )),
+        Some(include_bytes!(concat!(
+            assets_folder!(),
+            "Misc_426/MainChar_BellySlice_BR.uexp"
+        ))),
+        EngineVersion::VER_UE4_26,
+    ),
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "Misc_426/RaceSimDataAsset.uasset"
+        )),
+        Some(include_bytes!(concat!(
+            assets_folder!(),
+            "Misc_426/RaceSimDataAsset.uexp"
+        ))),
+        EngineVersion::VER_UE4_26,
+    ),
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "Versioned/Assault_M1A1Thompson_WW2_DrumSuppressor.uasset"
+        )),
+        None,
+        EngineVersion::UNKNOWN,
+    ),
+];
+
+fn parse(
+    asset_data: &[u8],
+    bulk_data: Option<&[u8]>,
+    engine_version: EngineVersion,
+    limits: ParseLimits,
+) -> Result<Asset<Cursor<Vec<u8>>>, Error> {
+    Asset::new_with_limits(
+        Cursor::new(asset_data.to_vec()),
+        bulk_data.map(|e| Cursor::new(e.to_vec())),
+        engine_version,
+        None,
+        limits,
+    )
+}
+
+#[test]
+fn malformed_assets_do_not_panic() {
+    for (asset_data, bulk_data, engine_version) in TEST_ASSETS {
+        // truncated files
+        for len in (0..asset_data.len()).step_by(61) {
+            let _ = parse(
+                &asset_data[..len],
+                bulk_data,
+                engine_version,
+                ParseLimits::default(),
+            );
+        }
+
+        // single corrupted bytes, deterministic so failures can be reproduced
+        let mut mutator = Mutator::new(0x2545F4914F6CDD1D);
+        for _ in 0..1000 {
+            let mut asset_data = asset_data.to_vec();
+            let mut bulk_data = bulk_data.map(|e| e.to_vec());
+            let data = match (&mut bulk_data, mutator.next_u64() % 3) {
+                (Some(bulk_data), 0) => bulk_data,
+                _ => &mut asset_data,
+            };
+            mutator.mutate(data);
+
+            let _ = parse(
+                &asset_data,
+                bulk_data.as_deref(),
+                engine_version,
+                ParseLimits::default(),
+            );
+        }
+    }
+}
+
+#[test]
+fn limits_are_enforced() -> Result<(), Error> {
+    let (asset_data, bulk_data, engine_version) = TEST_ASSETS[1];
+    parse(
+        asset_data,
+        bulk_data,
+        engine_version,
+        ParseLimits::default(),
+    )?;
+    parse(
+        asset_data,
+        bulk_data,
+        engine_version,
+        ParseLimits::unlimited(),
+    )?;
+
+    let err = parse(
+        asset_data,
+        bulk_data,
+        engine_version,
+        ParseLimits {
+            max_string_length: 4,
+            ..Default::default()
+        },
+    )
+    .expect_err("Expected err");
+    assert!(matches!(err, Error::LimitExceeded(..)));
+
+    let err = parse(
+        asset_data,
+        bulk_data,
+        engine_version,
+        ParseLimits {
+            max_array_length: 0,
+            ..Default::default()
+        },
+    )
+    .expect_err("Expected err");
+    assert!(matches!(err, Error::LimitExceeded(..)));
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+#![cfg(feature = "serde")]
+
+use std::io::Cursor;
+
+use unreal_asset::{
+    engine_version::EngineVersion,
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    properties::{Property, PropertyDataTrait},
+    types::PackageIndex,
+    Asset, Error,
+};
+
+macro_rules! assets_folder {
+    () => {
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets/")
+    };
+}
+
+/// Asset data, bulk data and engine version
+type TestAsset = (&'static [u8], Option<&'static [u8]>, EngineVersion);
+
+const TEST_ASSETS: [TestAsset; 6] = [
+    // kismet
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "general/npc_onop/NPC_Onop_IO_Bech.uasset"
+        )),
+        Some(include_bytes!(concat!(
+            assets_folder!(),
+            "general/npc_onop/NPC_Onop_IO_Bech.uexp"
+        ))),
+        EngineVersion::VER_UE4_25,
+    ),
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "general/Tekken/BP_TekkenPlayer_Modular.uasset"
+        )),
+        None,
+        EngineVersion::VER_UE4_14,
+    ),
+    // data table
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "general/BloodStained/PB_DT_ItemMaster.uasset"
+        )),
+        None,
+        EngineVersion::VER_UE4_18,
+    ),
+    // level
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "general/Astroneer_prebulk/Staging_T2.umap"
+        )),
+        None,
+        EngineVersion::VER_UE4_23,
+    ),
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "general/Misc_426/MainChar_BellySlice_BR.uasset"
+        )),
+        Some(include_bytes!(concat!(
+            assets_folder!(),
+            "general/Misc_426/MainChar_BellySlice_BR.uexp"
+        ))),
+        EngineVersion::VER_UE4_26,
+    ),
+    // names referenced by index
+    (
+        include_bytes!(concat!(
+            assets_folder!(),
+            "duplicate_name_map_entries/BIOME_AzureWeald.uasset"
+        )),
+        Some(include_bytes!(concat!(
+            assets_folder!(),
+            "duplicate_name_map_entries/BIOME_AzureWeald.uexp"
+        ))),
+        EngineVersion::VER_UE4_25,
+    ),
+];
+
+fn write<C: std::io::Read + std::io::Seek>(
+    asset: &Asset<C>,
+) -> Result<(Vec<u8>, Option<Vec<u8>>), Error> {
+    let mut cursor = Cursor::new(Vec::new());
+    let mut bulk_cursor = match asset.asset_data.use_event_driven_loader {
+        true => Some(Cursor::new(Vec::new())),
+        false => None,
+    };
+    asset.write_data(&mut cursor, bulk_cursor.as_mut())?;
+    Ok((cursor.into_inner(), bulk_cursor.map(|e| e.into_inner())))
+}
+
+#[test]
+fn json_round_trip() -> Result<(), Error> {
+    for (test_asset, asset_bulk, engine_version) in TEST_ASSETS {
+        let asset = Asset::new(
+            Cursor::new(test_asset),
+            asset_bulk.map(Cursor::new),
+            engine_version,
+            None,
+        )?;
+
+        let json = serde_json::to_string(&asset).unwrap();
+        let deserialized: Asset<Cursor<Vec<u8>>> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(
+            deserialized
+                .get_name_map()
+                .get_ref()
+                .get_name_map_index_list(),
+            asset.get_name_map().get_ref().get_name_map_index_list()
+        );
+        assert_eq!(write(&deserialized)?, write(&asset)?);
+        // serializing again gives the same json
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn json_modification() -> Result<(), Error> {
+    let (test_asset, asset_bulk, engine_version) = TEST_ASSETS[2];
+    let asset = Asset::new(
+        Cursor::new(test_asset),
+        asset_bulk.map(Cursor::new),
+        engine_version,
+        None,
+    )?;
+
+    let mut json = serde_json::to_value(&asset).unwrap();
+    let export = &mut json["asset_data"]["exports"][0];
+    assert_eq!(export["$type"], "DataTableExport");
+    assert_eq!(
+        export["normal_export"]["base_export"]["object_name"],
+        "PB_DT_ItemMaster"
+    );
+    export["normal_export"]["base_export"]["object_name"] = "PB_DT_Renamed".into();
+
+    let mut deserialized: Asset<Cursor<Vec<u8>>> = serde_json::from_value(json).unwrap();
+    let name_count = asset
+        .get_name_map()
+        .get_ref()
+        .get_name_map_index_list()
+        .len();
+    assert_eq!(
+        deserialized
+            .get_name_map()
+            .get_ref()
+            .get_name_map_index_list()
+            .len(),
+        name_count + 1
+    );
+
+    let (data, _) = write(&deserialized)?;
+    let reparsed = Asset::new(Cursor::new(data), None, engine_version, None)?;
+    let export = reparsed.get_export(PackageIndex::new(1)).unwrap();
+    assert_eq!(
+        export.get_base_export().object_name.get_owned_content(),
+        "PB_DT_Renamed"
+    );
+
+    // properties are tagged with their type
+    let normal_export = deserialized
+        .get_export_mut(PackageIndex::new(1))
+        .and_then(|e| e.get_normal_export_mut())
+        .unwrap();
+    let property = serde_json::to_value(&normal_export.properties[0]).unwrap();
+    assert!(property["$type"].is_string());
+
+    Ok(())
+}
+
+#[test]
+fn standalone_property() -> Result<(), Error> {
+    let (test_asset, asset_bulk, engine_version) = TEST_ASSETS[3];
+    let asset = Asset::new(
+        Cursor::new(test_asset),
+        asset_bulk.map(Cursor::new),
+        engine_version,
+        None,
+    )?;
+    let properties = asset
+        .asset_data
+        .exports
+        .iter()
+        .filter_map(|e| e.get_normal_export())
+        .find(|e| e.properties.len() > 1)
+        .map(|e| &e.properties)
+        .unwrap();
+
+    // names deserialized outside of an asset keep their content
+    let json = serde_json::to_string(&properties[0]).unwrap();
+    let deserialized: Property = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        deserialized.get_name().get_owned_content(),
+        properties[0].get_name().get_owned_content()
+    );
+    assert_eq!(&deserialized, &properties[0]);
+
+    let other_json = serde_json::to_string(&properties[1]).unwrap();
+    let other: Property = serde_json::from_str(&other_json).unwrap();
+    assert_ne!(deserialized.get_name(), other.get_name());
+
+    Ok(())
+}
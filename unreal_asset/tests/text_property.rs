@@ -0,0 +1,456 @@
+use std::io::Cursor;
+
+use unreal_asset::{
+    custom_version::FEditorObjectVersion,
+    engine_version::EngineVersion,
+    exports::ExportNormalTrait,
+    properties::{
+        str_property::{
+            DateTimeStyle, DateTimeTextHistory, FText, FormatArgumentData, FormatArgumentValue,
+            FormatTextHistory, NumberFormattingOptions, NumberTextHistory,
+            OrderedFormatTextHistory, RoundingMode, TextGender, TextGeneratorHistory, TextHistory,
+            TextHistoryType, TextProperty, TextTransformType, TransformTextHistory,
+        },
+        unknown_property::UnknownProperty,
+        Property,
+    },
+    reader::ArchiveTrait,
+    types::PackageIndex,
+    Asset, Error,
+};
+
+mod shared;
+
+const TEST_ASSET: &[u8] = include_bytes!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/assets/general/Astroneer_prebulk/Augment_BroadBrush.uasset"
+));
+
+/// Export and property index of the `Name` text property
+const TEXT_EXPORT: i32 = 2;
+const TEXT_PROPERTY: usize = 6;
+
+fn parse(data: &[u8]) -> Result<Asset<Cursor<&[u8]>>, Error> {
+    Asset::new(Cursor::new(data), None, EngineVersion::VER_UE4_23, None)
+}
+
+fn get_text<'a>(asset: &'a mut Asset<Cursor<&[u8]>>) -> &'a mut TextProperty {
+    let normal_export = asset
+        .get_export_mut(PackageIndex::new(TEXT_EXPORT))
+        .and_then(|e| e.get_normal_export_mut())
+        .unwrap();
+    match &mut normal_export.properties[TEXT_PROPERTY] {
+        Property::TextProperty(property) => property,
+        _ => panic!("Expected a TextProperty"),
+    }
+}
+
+fn write(asset: &Asset<Cursor<&[u8]>>) -> Result<Vec<u8>, Error> {
+    let mut cursor = Cursor::new(Vec::new());
+    asset.write_data(&mut cursor, None)?;
+    Ok(cursor.into_inner())
+}
+
+fn base_text(key: &str, source_string: &str) -> FText {
+    FText {
+        history_type: TextHistoryType::Base,
+        namespace: Some(String::new()),
+        value: Some(key.to_string()),
+        culture_invariant_string: Some(source_string.to_string()),
+        ..Default::default()
+    }
+}
+
+fn number_format_options() -> NumberFormattingOptions {
+    NumberFormattingOptions {
+        always_sign: true,
+        use_grouping: false,
+        rounding_mode: RoundingMode::HalfFromZero,
+        minimum_integral_digits: 1,
+        maximum_integral_digits: 324,
+        minimum_fractional_digits: 0,
+        maximum_fractional_digits: 3,
+    }
+}
+
+fn histories(asset: &mut Asset<Cursor<&[u8]>>) -> Vec<(TextHistoryType, TextHistory)> {
+    let generator_type_id = asset.add_fname("TextGenerator");
+
+    let arguments = vec![
+        FormatArgumentData {
+            name: Some("Count".to_string()),
+            value: FormatArgumentValue::Int(-7),
+        },
+        FormatArgumentData {
+            name: Some("Item".to_string()),
+            value: FormatArgumentValue::Text(Box::new(base_text("Item", "Paver"))),
+        },
+        FormatArgumentData {
+            name: Some("Gender".to_string()),
+            value: FormatArgumentValue::Gender(TextGender::Feminine),
+        },
+    ];
+
+    vec![
+        (
+            TextHistoryType::NamedFormat,
+            TextHistory::NamedFormat(FormatTextHistory {
+                source_format: Box::new(base_text("Fmt", "{Count} {Item}")),
+                arguments: arguments.clone(),
+            }),
+        ),
+        (
+            TextHistoryType::OrderedFormat,
+            TextHistory::OrderedFormat(OrderedFormatTextHistory {
+                source_format: Box::new(base_text("Fmt", "{0} {1} {2} {3}")),
+                arguments: vec![
+                    FormatArgumentValue::UInt(u64::MAX),
+                    FormatArgumentValue::Float(1.5.into()),
+                    FormatArgumentValue::Double(0.1.into()),
+                    FormatArgumentValue::Int(i64::MIN),
+                ],
+            }),
+        ),
+        (
+            TextHistoryType::ArgumentFormat,
+            TextHistory::ArgumentFormat(FormatTextHistory {
+                source_format: Box::new(base_text("Fmt", "{Count} {Item}")),
+                arguments,
+            }),
+        ),
+        (
+            TextHistoryType::AsNumber,
+            TextHistory::Number(NumberTextHistory {
+                currency_code: None,
+                source_value: FormatArgumentValue::Double(1234.5.into()),
+                format_options: Some(number_format_options()),
+                target_culture: Some("en".to_string()),
+            }),
+        ),
+        (
+            TextHistoryType::AsPercent,
+            TextHistory::Number(NumberTextHistory {
+                currency_code: None,
+                source_value: FormatArgumentValue::Float(0.25.into()),
+                format_options: None,
+                target_culture: None,
+            }),
+        ),
+        (
+            TextHistoryType::AsCurrency,
+            TextHistory::Number(NumberTextHistory {
+                currency_code: Some("EUR".to_string()),
+                source_value: FormatArgumentValue::Int(1999),
+                format_options: Some(number_format_options()),
+                target_culture: Some("de-DE".to_string()),
+            }),
+        ),
+        (
+            TextHistoryType::AsDate,
+            TextHistory::DateTime(DateTimeTextHistory {
+                source_date_time: 637_134_336_000_000_000,
+                date_style: DateTimeStyle::Long,
+                time_style: DateTimeStyle::Default,
+                time_zone: Some("UTC".to_string()),
+                target_culture: None,
+            }),
+        ),
+        (
+            TextHistoryType::AsTime,
+            TextHistory::DateTime(DateTimeTextHistory {
+                source_date_time: 637_134_336_000_000_000,
+                date_style: DateTimeStyle::Default,
+                time_style: DateTimeStyle::Short,
+                time_zone: None,
+                target_culture: Some("en-US".to_string()),
+            }),
+        ),
+        (
+            TextHistoryType::AsDateTime,
+            TextHistory::DateTime(DateTimeTextHistory {
+                source_date_time: 637_134_336_000_000_000,
+                date_style: DateTimeStyle::Medium,
+                time_style: DateTimeStyle::Full,
+                time_zone: Some("Europe/Berlin".to_string()),
+                target_culture: Some("en-GB".to_string()),
+            }),
+        ),
+        (
+            TextHistoryType::Transform,
+            TextHistory::Transform(TransformTextHistory {
+                source_text: Box::new(FText {
+                    history_type: TextHistoryType::OrderedFormat,
+                    history: Some(TextHistory::OrderedFormat(OrderedFormatTextHistory {
+                        source_format: Box::new(base_text("Fmt", "{0}")),
+                        arguments: vec![FormatArgumentValue::Int(3)],
+                    })),
+                    ..Default::default()
+                }),
+                transform_type: TextTransformType::ToUpper,
+            }),
+        ),
+        (
+            TextHistoryType::TextGenerator,
+            TextHistory::TextGenerator(TextGeneratorHistory {
+                generator_type_id,
+                generator_contents: Some(vec![1, 2, 3, 4]),
+            }),
+        ),
+    ]
+}
+
+#[test]
+fn text_histories() -> Result<(), Error> {
+    let mut asset = parse(TEST_ASSET)?;
+
+    for (history_type, history) in histories(&mut asset) {
+        let text = get_text(&mut asset);
+        text.flags = 2;
+        text.history_type = history_type;
+        text.table_id = None;
+        text.value = None;
+        text.history = Some(history);
+        let expected = text.clone();
+
+        let data = write(&asset)?;
+        let mut reparsed = parse(&data)?;
+        assert!(shared::verify_all_exports_parsed(&reparsed));
+        assert_eq!(get_text(&mut reparsed), &expected);
+        assert_eq!(write(&reparsed)?, data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn text_generator_without_generator() -> Result<(), Error> {
+    let mut asset = parse(TEST_ASSET)?;
+    let generator_type_id = asset.add_fname("None");
+
+    let text = get_text(&mut asset);
+    text.history_type = TextHistoryType::TextGenerator;
+    text.table_id = None;
+    text.value = None;
+    text.history = Some(TextHistory::TextGenerator(TextGeneratorHistory {
+        generator_type_id,
+        generator_contents: None,
+    }));
+    let expected = text.clone();
+
+    let data = write(&asset)?;
+    let mut reparsed = parse(&data)?;
+    assert_eq!(get_text(&mut reparsed), &expected);
+
+    Ok(())
+}
+
+#[test]
+fn missing_text_history() -> Result<(), Error> {
+    let mut asset = parse(TEST_ASSET)?;
+
+    let text = get_text(&mut asset);
+    text.history_type = TextHistoryType::AsNumber;
+    text.history = None;
+    assert!(write(&asset).is_err());
+
+    // history data has to match the history type
+    let text = get_text(&mut asset);
+    text.history = Some(TextHistory::Transform(TransformTextHistory {
+        source_text: Box::default(),
+        transform_type: TextTransformType::ToLower,
+    }));
+    assert!(write(&asset).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn nested_text_depth() -> Result<(), Error> {
+    let nested_text = |depth: usize| {
+        let mut text = base_text("Key", "Text");
+        for _ in 0..depth {
+            text = FText {
+                history_type: TextHistoryType::Transform,
+                history: Some(TextHistory::Transform(TransformTextHistory {
+                    source_text: Box::new(text),
+                    transform_type: TextTransformType::ToUpper,
+                })),
+                ..Default::default()
+            };
+        }
+        text
+    };
+
+    for (depth, parses) in [(32, true), (100, false)] {
+        let mut asset = parse(TEST_ASSET)?;
+        let text = get_text(&mut asset);
+        let nested = nested_text(depth);
+        text.history_type = nested.history_type;
+        text.table_id = None;
+        text.value = None;
+        text.history = nested.history;
+        let expected = text.clone();
+
+        // too deeply nested texts are rejected instead of overflowing the stack
+        let data = write(&asset)?;
+        let mut reparsed = parse(&data)?;
+        assert_eq!(shared::verify_all_exports_parsed(&reparsed), parses);
+        if parses {
+            assert_eq!(get_text(&mut reparsed), &expected);
+        }
+    }
+
+    Ok(())
+}
+
+/// Serialize an FString the way the engine does, empty strings only store their length
+fn engine_fstring(value: &str) -> Vec<u8> {
+    let mut data = Vec::new();
+    if !value.is_empty() {
+        data.extend_from_slice(&(value.len() as i32 + 1).to_le_bytes());
+        data.extend_from_slice(value.as_bytes());
+        data.push(0);
+    } else {
+        data.extend_from_slice(&0i32.to_le_bytes());
+    }
+    data
+}
+
+/// Text data laid out byte by byte after `FText::SerializeText` and the `FTextHistory`
+/// serializers of the engine, so it doesn't depend on how this crate writes texts
+fn engine_texts(always_sign: bool) -> Vec<(Vec<u8>, TextHistoryType, TextHistory)> {
+    let base_text = |namespace: &str, key: &str, source_string: &str| {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.push(TextHistoryType::Base as i8 as u8);
+        data.extend(engine_fstring(namespace));
+        data.extend(engine_fstring(key));
+        data.extend(engine_fstring(source_string));
+        data
+    };
+    let text_header = |history_type: TextHistoryType| {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.push(history_type as i8 as u8);
+        data
+    };
+
+    // FTextHistory_NamedFormat: source format and a TMap<FString, FFormatArgumentValue>
+    let mut named_format = text_header(TextHistoryType::NamedFormat);
+    named_format.extend(base_text("UI", "Fmt", "{Count} items"));
+    named_format.extend_from_slice(&1i32.to_le_bytes());
+    named_format.extend(engine_fstring("Count"));
+    named_format.push(0); // EFormatArgumentType::Int
+    named_format.extend_from_slice(&3i64.to_le_bytes());
+
+    // FTextHistory_AsNumber: source value, optional FNumberFormattingOptions and culture
+    let mut as_number = text_header(TextHistoryType::AsNumber);
+    as_number.push(3); // EFormatArgumentType::Double
+    as_number.extend_from_slice(&1234.5f64.to_le_bytes());
+    as_number.extend_from_slice(&1i32.to_le_bytes());
+    if always_sign {
+        as_number.extend_from_slice(&0i32.to_le_bytes());
+    }
+    as_number.extend_from_slice(&1i32.to_le_bytes());
+    as_number.push(0); // ERoundingMode::HalfToEven
+    for digits in [1i32, 324, 0, 3] {
+        as_number.extend_from_slice(&digits.to_le_bytes());
+    }
+    as_number.extend(engine_fstring("en"));
+
+    // FTextHistory_AsDateTime: ticks, date and time style, time zone and culture
+    let mut as_date_time = text_header(TextHistoryType::AsDateTime);
+    as_date_time.extend_from_slice(&637_134_336_000_000_000i64.to_le_bytes());
+    as_date_time.push(DateTimeStyle::Long as i8 as u8);
+    as_date_time.push(DateTimeStyle::Short as i8 as u8);
+    as_date_time.extend(engine_fstring("UTC"));
+    as_date_time.extend(engine_fstring("en-US"));
+
+    vec![
+        (
+            named_format,
+            TextHistoryType::NamedFormat,
+            TextHistory::NamedFormat(FormatTextHistory {
+                source_format: Box::new(FText {
+                    history_type: TextHistoryType::Base,
+                    namespace: Some("UI".to_string()),
+                    value: Some("Fmt".to_string()),
+                    culture_invariant_string: Some("{Count} items".to_string()),
+                    ..Default::default()
+                }),
+                arguments: vec![FormatArgumentData {
+                    name: Some("Count".to_string()),
+                    value: FormatArgumentValue::Int(3),
+                }],
+            }),
+        ),
+        (
+            as_number,
+            TextHistoryType::AsNumber,
+            TextHistory::Number(NumberTextHistory {
+                currency_code: None,
+                source_value: FormatArgumentValue::Double(1234.5.into()),
+                format_options: Some(NumberFormattingOptions {
+                    always_sign: false,
+                    use_grouping: true,
+                    rounding_mode: RoundingMode::HalfToEven,
+                    minimum_integral_digits: 1,
+                    maximum_integral_digits: 324,
+                    minimum_fractional_digits: 0,
+                    maximum_fractional_digits: 3,
+                }),
+                target_culture: Some("en".to_string()),
+            }),
+        ),
+        (
+            as_date_time,
+            TextHistoryType::AsDateTime,
+            TextHistory::DateTime(DateTimeTextHistory {
+                source_date_time: 637_134_336_000_000_000,
+                date_style: DateTimeStyle::Long,
+                time_style: DateTimeStyle::Short,
+                time_zone: Some("UTC".to_string()),
+                target_culture: Some("en-US".to_string()),
+            }),
+        ),
+    ]
+}
+
+#[test]
+fn engine_text_layouts() -> Result<(), Error> {
+    let asset = parse(TEST_ASSET)?;
+    let always_sign = asset.get_custom_version::<FEditorObjectVersion>().version
+        >= FEditorObjectVersion::AddedAlwaysSignNumberFormattingOption as i32;
+
+    for (text_data, history_type, history) in engine_texts(always_sign) {
+        let mut asset = parse(TEST_ASSET)?;
+        let serialized_type = asset.add_fname("TextProperty");
+        let text = get_text(&mut asset).clone();
+
+        // store the engine layout as the raw data of the text property
+        let normal_export = asset
+            .get_export_mut(PackageIndex::new(TEXT_EXPORT))
+            .and_then(|e| e.get_normal_export_mut())
+            .unwrap();
+        normal_export.properties[TEXT_PROPERTY] = Property::UnknownProperty(UnknownProperty {
+            name: text.name.clone(),
+            ancestry: text.ancestry.clone(),
+            property_guid: text.property_guid,
+            duplication_index: text.duplication_index,
+            value: text_data,
+            serialized_type,
+        });
+
+        let data = write(&asset)?;
+        let mut reparsed = parse(&data)?;
+        assert!(shared::verify_all_exports_parsed(&reparsed));
+        let parsed = get_text(&mut reparsed);
+        assert_eq!(parsed.flags, 2);
+        assert_eq!(parsed.history_type, history_type);
+        assert_eq!(parsed.history.as_ref(), Some(&history));
+
+        // writing the parsed text gives back the engine layout
+        assert_eq!(write(&reparsed)?, data);
+    }
+
+    Ok(())
+}
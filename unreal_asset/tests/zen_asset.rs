@@ -0,0 +1,394 @@
+use std::io::{Cursor, Write};
+
+use byteorder::{WriteBytesExt, LE};
+
+use unreal_asset::{
+    cast,
+    crc::cityhash64_to_lower,
+    engine_version::EngineVersion,
+    enums::{
+        EExportCommandType, EPackageObjectIndexType, EZenPackageVersion, HASH_VERSION_CITYHASH64,
+    },
+    error::{Error, ZenError},
+    exports::{ExportBaseTrait, ExportNormalTrait},
+    flags::EObjectFlags,
+    io_store::ScriptObjects,
+    properties::Property,
+    types::{PackageIndex, PackageObjectIndex},
+    zen_asset::{ZenDependencyBundleEntry, ZenDependencyBundleHeader},
+    Export, ParseLimits, ZenAsset,
+};
+
+mod shared;
+use shared::Mutator;
+
+const NAMES: [&str; 5] = ["/Game/Zen", "Zen", "Count", "IntProperty", "None"];
+const CLASS_PATH: &str = "/Script/CoreUObject.Object";
+const COOKED_HEADER_SIZE: u32 = 0x200;
+
+fn write_name_batch(data: &mut Vec<u8>, names: &[&str]) {
+    data.write_u32::<LE>(names.len() as u32).unwrap();
+    data.write_u32::<LE>(names.iter().map(|e| e.len() as u32).sum())
+        .unwrap();
+    data.write_u64::<LE>(HASH_VERSION_CITYHASH64).unwrap();
+    for name in names {
+        data.write_u64::<LE>(cityhash64_to_lower(name)).unwrap();
+    }
+    for name in names {
+        data.write_u16::<byteorder::BE>(name.len() as u16).unwrap();
+    }
+    for name in names {
+        data.write_all(name.as_bytes()).unwrap();
+    }
+}
+
+/// Export data of an object with a single IntProperty
+fn export_data(value: i32) -> Vec<u8> {
+    let mut data = Vec::new();
+    for name in [2, 3] {
+        data.write_i32::<LE>(name).unwrap();
+        data.write_i32::<LE>(0).unwrap();
+    }
+    data.write_i32::<LE>(4).unwrap();
+    data.write_i32::<LE>(0).unwrap();
+    data.write_u8(0).unwrap();
+    data.write_i32::<LE>(value).unwrap();
+    data.write_i32::<LE>(4).unwrap();
+    data.write_i32::<LE>(0).unwrap();
+    // extras
+    data.write_u32::<LE>(0).unwrap();
+    data
+}
+
+/// Build a zen package with a single export
+fn build_package(zen_version: EZenPackageVersion, versioning_info: bool) -> Vec<u8> {
+    let has_graph_data = zen_version < EZenPackageVersion::ImportedPackageNames;
+    let summary_size = match has_graph_data {
+        true => 44,
+        false => 52,
+    };
+
+    let mut header = Vec::new();
+    if versioning_info {
+        header.write_u32::<LE>(zen_version as u32).unwrap();
+        header.write_i32::<LE>(522).unwrap();
+        header.write_i32::<LE>(1008).unwrap();
+        header.write_i32::<LE>(0).unwrap();
+        header.write_i32::<LE>(0).unwrap();
+    }
+    write_name_batch(&mut header, &NAMES);
+    if zen_version >= EZenPackageVersion::DataResourceTable {
+        header.write_i64::<LE>(32).unwrap();
+        for value in [0x10i64, -1, 0x20] {
+            header.write_i64::<LE>(value).unwrap();
+        }
+        header.write_u32::<LE>(1).unwrap();
+        header.write_u32::<LE>(0).unwrap();
+    }
+
+    let imported_public_export_hashes_offset = summary_size + header.len() as i32;
+    header.write_u64::<LE>(0x1122334455667788).unwrap();
+
+    let import_map_offset = summary_size + header.len() as i32;
+    header
+        .write_u64::<LE>(PackageObjectIndex::from_script_path(CLASS_PATH).index)
+        .unwrap();
+
+    let export_data = export_data(42);
+    let export_map_offset = summary_size + header.len() as i32;
+    header.write_u64::<LE>(COOKED_HEADER_SIZE as u64).unwrap();
+    header.write_u64::<LE>(export_data.len() as u64).unwrap();
+    header.write_u32::<LE>(1).unwrap();
+    header.write_u32::<LE>(0).unwrap();
+    header
+        .write_u64::<LE>(PackageObjectIndex::NULL.index)
+        .unwrap();
+    header
+        .write_u64::<LE>(PackageObjectIndex::from_script_path(CLASS_PATH).index)
+        .unwrap();
+    header
+        .write_u64::<LE>(PackageObjectIndex::NULL.index)
+        .unwrap();
+    header
+        .write_u64::<LE>(PackageObjectIndex::NULL.index)
+        .unwrap();
+    header.write_u64::<LE>(0xabcdef).unwrap();
+    header
+        .write_u32::<LE>(EObjectFlags::RF_PUBLIC.bits())
+        .unwrap();
+    header.write_all(&[0; 4]).unwrap();
+
+    let export_bundle_entries_offset = summary_size + header.len() as i32;
+    for command_type in [EExportCommandType::Create, EExportCommandType::Serialize] {
+        header.write_u32::<LE>(0).unwrap();
+        header.write_u32::<LE>(command_type as u32).unwrap();
+    }
+
+    let graph_data_offset = summary_size + header.len() as i32;
+    let mut offsets = Vec::new();
+    if has_graph_data {
+        header.write_u64::<LE>(0).unwrap();
+        header.write_u32::<LE>(0).unwrap();
+        header.write_u32::<LE>(2).unwrap();
+        header.write_i32::<LE>(0).unwrap();
+        // one imported package with a single arc
+        header.write_i32::<LE>(1).unwrap();
+        header.write_i32::<LE>(0).unwrap();
+        header
+            .write_u8(EExportCommandType::Serialize as u8)
+            .unwrap();
+        header.write_i32::<LE>(0).unwrap();
+    } else {
+        // the export is serialized after its class import is created
+        header.write_i32::<LE>(0).unwrap();
+        for count in [0u32, 0, 1, 0] {
+            header.write_u32::<LE>(count).unwrap();
+        }
+
+        offsets.push(summary_size + header.len() as i32);
+        header.write_i32::<LE>(-1).unwrap();
+
+        offsets.push(summary_size + header.len() as i32);
+        write_name_batch(&mut header, &["/Script/CoreUObject"]);
+        header.write_i32::<LE>(0).unwrap();
+    }
+
+    let mut data = Vec::new();
+    data.write_u32::<LE>(versioning_info as u32).unwrap();
+    data.write_u32::<LE>(summary_size as u32 + header.len() as u32)
+        .unwrap();
+    data.write_u32::<LE>(0).unwrap();
+    data.write_u32::<LE>(0).unwrap();
+    data.write_u32::<LE>(0).unwrap();
+    data.write_u32::<LE>(COOKED_HEADER_SIZE).unwrap();
+    for offset in [
+        imported_public_export_hashes_offset,
+        import_map_offset,
+        export_map_offset,
+        export_bundle_entries_offset,
+        graph_data_offset,
+    ]
+    .into_iter()
+    .chain(offsets)
+    {
+        data.write_i32::<LE>(offset).unwrap();
+    }
+    data.extend(header);
+    data.extend(export_data);
+    data
+}
+
+fn script_objects() -> ScriptObjects {
+    let mut script_objects = ScriptObjects::new();
+    script_objects.add_object(CLASS_PATH);
+    script_objects
+}
+
+fn parse(data: &[u8], engine_version: EngineVersion) -> Result<ZenAsset<Cursor<Vec<u8>>>, Error> {
+    ZenAsset::new(
+        Cursor::new(data.to_vec()),
+        engine_version,
+        None,
+        Some(script_objects()),
+    )
+}
+
+fn write(asset: &ZenAsset<Cursor<Vec<u8>>>) -> Result<Vec<u8>, Error> {
+    let mut cursor = Cursor::new(Vec::new());
+    asset.write_data(&mut cursor)?;
+    Ok(cursor.into_inner())
+}
+
+fn get_count(asset: &ZenAsset<Cursor<Vec<u8>>>) -> Option<i32> {
+    let export = asset.get_export(PackageIndex::new(1))?;
+    let normal_export = export.get_normal_export()?;
+    cast!(Property, IntProperty, &normal_export.properties[0]).map(|e| e.value)
+}
+
+#[test]
+fn zen_package_round_trip() -> Result<(), Error> {
+    for (engine_version, zen_version, versioning_info) in [
+        (EngineVersion::VER_UE5_1, EZenPackageVersion::Initial, false),
+        (
+            EngineVersion::VER_UE5_2,
+            EZenPackageVersion::DataResourceTable,
+            false,
+        ),
+        (EngineVersion::UNKNOWN, EZenPackageVersion::Initial, true),
+        (
+            EngineVersion::UNKNOWN,
+            EZenPackageVersion::ImportedPackageNames,
+            true,
+        ),
+    ] {
+        let data = build_package(zen_version, versioning_info);
+        let asset = parse(&data, engine_version)?;
+
+        assert_eq!(asset.zen_version, zen_version);
+        assert_eq!(asset.has_versioning_info, versioning_info);
+        assert_eq!(asset.package_name.get_owned_content(), "/Game/Zen");
+        assert_eq!(
+            asset.imported_public_export_hashes,
+            vec![0x1122334455667788]
+        );
+        assert_eq!(
+            asset.external_arcs.len(),
+            (zen_version < EZenPackageVersion::ImportedPackageNames) as usize
+        );
+        assert_eq!(
+            asset.bulk_data_map.len(),
+            (zen_version >= EZenPackageVersion::DataResourceTable) as usize
+        );
+
+        let export = asset.get_export(PackageIndex::new(1)).unwrap();
+        assert!(matches!(export, Export::NormalExport(_)));
+        let base_export = export.get_base_export();
+        assert_eq!(base_export.object_name.get_owned_content(), "Zen");
+        assert_eq!(base_export.public_export_hash, 0xabcdef);
+        assert_eq!(
+            base_export.class_index.get_type(),
+            EPackageObjectIndexType::ScriptImport
+        );
+        assert_eq!(get_count(&asset), Some(42));
+
+        assert_eq!(write(&asset)?, data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn zen_package_modification() -> Result<(), Error> {
+    let data = build_package(EZenPackageVersion::DataResourceTable, false);
+    let mut asset = parse(&data, EngineVersion::VER_UE5_2)?;
+
+    let export = asset.get_export_mut(PackageIndex::new(1)).unwrap();
+    let normal_export = export.get_normal_export_mut().unwrap();
+    match &mut normal_export.properties[0] {
+        Property::IntProperty(property) => property.value = 1337,
+        _ => panic!("Expected an IntProperty"),
+    }
+
+    let asset = parse(&write(&asset)?, EngineVersion::VER_UE5_2)?;
+    assert_eq!(get_count(&asset), Some(1337));
+
+    Ok(())
+}
+
+#[test]
+fn zen_package_without_version() {
+    let data = build_package(EZenPackageVersion::Initial, false);
+    assert!(matches!(
+        parse(&data, EngineVersion::UNKNOWN),
+        Err(Error::Zen(ZenError::NoObjectVersion))
+    ));
+}
+
+#[test]
+fn zen_package_imported_package_names() -> Result<(), Error> {
+    // UE 5.3 packages store dependency bundles and imported package names instead of graph data
+    let data = build_package(EZenPackageVersion::ImportedPackageNames, true);
+    let mut asset = parse(&data, EngineVersion::UNKNOWN)?;
+
+    assert!(asset.internal_arcs.is_empty());
+    assert_eq!(asset.export_bundle_headers.len(), 1);
+    assert_eq!(asset.export_bundle_headers[0].entry_count, 2);
+    assert_eq!(
+        asset.dependency_bundle_headers,
+        vec![ZenDependencyBundleHeader {
+            first_entry_index: 0,
+            entry_count: [[0, 0], [1, 0]],
+        }]
+    );
+    assert_eq!(
+        asset.dependency_bundle_entries,
+        vec![ZenDependencyBundleEntry {
+            local_import_or_export_index: PackageIndex::new(-1),
+        }]
+    );
+    assert_eq!(
+        asset.imported_package_names,
+        vec![("/Script/CoreUObject".to_string(), 0)]
+    );
+
+    asset
+        .imported_package_names
+        .push(("/Game/Other".to_string(), 2));
+    let asset = parse(&write(&asset)?, EngineVersion::UNKNOWN)?;
+    assert_eq!(
+        asset.imported_package_names[1],
+        ("/Game/Other".to_string(), 2)
+    );
+    assert_eq!(get_count(&asset), Some(42));
+
+    Ok(())
+}
+
+#[test]
+fn read_script_objects() -> Result<(), Error> {
+    let names = ["/Script/CoreUObject", "Object", "Default__Object"];
+    let package = PackageObjectIndex::from_script_path("/Script/CoreUObject");
+    let class = PackageObjectIndex::from_script_path(CLASS_PATH);
+    let cdo = PackageObjectIndex::from_script_path("/Script/CoreUObject.Default__Object");
+
+    let mut data = Vec::new();
+    write_name_batch(&mut data, &names);
+    data.write_i32::<LE>(3).unwrap();
+    for (name, index, outer, cdo_class) in [
+        (
+            0,
+            package,
+            PackageObjectIndex::NULL,
+            PackageObjectIndex::NULL,
+        ),
+        (1, class, package, PackageObjectIndex::NULL),
+        (2, cdo, package, class),
+    ] {
+        data.write_u32::<LE>(name).unwrap();
+        data.write_u32::<LE>(0).unwrap();
+        for index in [index, outer, cdo_class] {
+            data.write_u64::<LE>(index.index).unwrap();
+        }
+    }
+
+    let script_objects = ScriptObjects::read(&data, &ParseLimits::default())?;
+    assert_eq!(script_objects.objects.len(), 3);
+    assert_eq!(script_objects.objects[&cdo].cdo_class_index, class);
+    assert_eq!(script_objects.get_outer_index(class), Some(package));
+    assert_eq!(
+        script_objects
+            .get_object_name(class)
+            .map(|e| e.get_owned_content()),
+        Some("Object".to_string())
+    );
+
+    // objects added by path resolve to the same indices
+    let mut added = ScriptObjects::new();
+    assert_eq!(added.add_object(CLASS_PATH), class);
+    assert_eq!(added.get_outer_index(class), Some(package));
+
+    Ok(())
+}
+
+#[test]
+fn malformed_zen_packages() {
+    let mut mutator = Mutator::new(0x9e3779b9);
+    for zen_version in [
+        EZenPackageVersion::DataResourceTable,
+        EZenPackageVersion::ImportedPackageNames,
+    ] {
+        let data = build_package(zen_version, true);
+
+        for len in 0..data.len() {
+            let _ = parse(&data[..len], EngineVersion::VER_UE5_2);
+        }
+
+        for _ in 0..2000 {
+            let mut corrupted = data.clone();
+            for _ in 0..4 {
+                mutator.mutate(&mut corrupted);
+            }
+            let _ = parse(&corrupted, EngineVersion::VER_UE5_2);
+        }
+    }
+}
@@ -1,8 +1,9 @@
-//! Unreal decompression
+//! Unreal compression and decompression
 
-use std::io::Read;
+use std::io::{Read, Write};
 
 use flate2::bufread::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
 
 use crate::Error;
 
@@ -67,3 +68,22 @@ pub fn decompress(
         CompressionMethod::Unknown(name) => Err(Error::UnknownCompressionMethod(name)),
     }
 }
+
+/// Compress data with the given compression method
+pub fn compress(method: CompressionMethod, data: &[u8]) -> Result<Vec<u8>, Error> {
+    match method {
+        CompressionMethod::None => Ok(data.to_vec()),
+        CompressionMethod::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?)
+        }
+        CompressionMethod::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            Ok(encoder.finish()?)
+        }
+        CompressionMethod::Lz4 => Ok(lz4_flex::block::compress(data)),
+        CompressionMethod::Unknown(name) => Err(Error::UnknownCompressionMethod(name)),
+    }
+}
@@ -69,6 +69,39 @@ impl NameMap {
         (self.name_map_index_list.len() - 1) as i32
     }
 
+    /// Get which duplicate of its name an FName map index points to
+    ///
+    /// Returns `None` for the entry found by [`NameMap::search_name_reference`]
+    pub fn get_duplicate(&self, index: i32) -> Option<usize> {
+        let name = self.name_map_index_list.get(usize::try_from(index).ok()?)?;
+        if self.search_name_reference(name) == Some(index) {
+            return None;
+        }
+
+        Some(
+            self.name_map_index_list[..index as usize]
+                .iter()
+                .filter(|e| *e == name)
+                .count(),
+        )
+    }
+
+    /// Search a duplicate entry of an FName reference
+    pub fn search_duplicate(&self, name: &str, duplicate: usize) -> Option<i32> {
+        let index = self
+            .name_map_index_list
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| *e == name)
+            .nth(duplicate)
+            .map(|(index, _)| index as i32)?;
+
+        match self.search_name_reference(name) == Some(index) {
+            true => None,
+            false => Some(index),
+        }
+    }
+
     /// Get all FNames
     pub fn get_name_map_index_list(&self) -> &[String] {
         &self.name_map_index_list
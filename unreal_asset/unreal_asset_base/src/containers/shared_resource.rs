@@ -57,6 +57,18 @@ impl<T> SharedResource<T> {
         SharedResource { resource }
     }
 
+    /// Returns `true` if both `SharedResource` instances point to the same value
+    #[cfg(not(feature = "threading"))]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        std::rc::Rc::ptr_eq(&this.resource, &other.resource)
+    }
+
+    /// Returns `true` if both `SharedResource` instances point to the same value
+    #[cfg(feature = "threading")]
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        std::sync::Arc::ptr_eq(&this.resource, &other.resource)
+    }
+
     /// Get a reference to the value inside of this `SharedResource`
     ///
     /// # Panics
@@ -104,6 +116,18 @@ impl<T> SharedResource<T> {
     pub fn get_mut(&mut self) -> std::sync::RwLockWriteGuard<T> {
         self.resource.write().unwrap()
     }
+
+    /// Create a weak reference to this `SharedResource`
+    #[cfg(not(feature = "threading"))]
+    pub fn downgrade(&self) -> SharedResourceWeakRef<T> {
+        SharedResourceWeakRef::new(std::rc::Rc::downgrade(&self.resource))
+    }
+
+    /// Create a weak reference to this `SharedResource`
+    #[cfg(feature = "threading")]
+    pub fn downgrade(&self) -> SharedResourceWeakRef<T> {
+        SharedResourceWeakRef::new(std::sync::Arc::downgrade(&self.resource))
+    }
 }
 
 impl<T: CyclicSharedResource<T> + Clone> SharedResource<T> {
@@ -82,6 +82,45 @@ lazy_static! {
     ]);
 }
 
+/// Serialized form of a [`CustomVersion`], the friendly name and version mappings are looked up by guid
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SerializedCustomVersion<'a> {
+    guid: Guid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    friendly_name: Option<std::borrow::Cow<'a, str>>,
+    version: i32,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CustomVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        SerializedCustomVersion {
+            guid: self.guid,
+            friendly_name: self
+                .friendly_name
+                .as_deref()
+                .map(std::borrow::Cow::Borrowed),
+            version: self.version,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CustomVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let serialized = SerializedCustomVersion::deserialize(deserializer)?;
+        Ok(CustomVersion::new(serialized.guid, serialized.version))
+    }
+}
+
 impl CustomVersion {
     /// Create a new custom version
     pub fn new(guid: Guid, version: i32) -> Self {
@@ -205,6 +244,7 @@ macro_rules! impl_custom_version_trait {
 
 /// Custom serialization version for changes made in the //Fortnite/Main stream
 #[derive(IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FFortniteMainBranchObjectVersion {
     /// Before any version changes were made
@@ -469,6 +509,7 @@ impl_custom_version_trait!(
 
 /// Custom serialization version for changes made in Dev-Framework stream.
 #[derive(IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FFrameworkObjectVersion {
     /// Before any version changes were made
@@ -652,6 +693,7 @@ impl_custom_version_trait!(
 
 /// Custom serialization version for changes made in Dev-Core stream.
 #[derive(IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FCoreObjectVersion {
     /// Before any version changes were made
@@ -691,6 +733,7 @@ impl_custom_version_trait!(
 
 /// Custom serialization version for changes made in Dev-Editor stream.
 #[derive(IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FEditorObjectVersion {
     /// Before any version changes were made
@@ -888,6 +931,7 @@ impl_custom_version_trait!(
 
 /// Custom serialization version for changes made in Dev-AnimPhys stream
 #[derive(IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FAnimPhysObjectVersion {
     /// Before any version changes were made
@@ -984,6 +1028,7 @@ impl_custom_version_trait!(
 
 /// Custom serialization version for changes made in Release streams.
 #[derive(IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FReleaseObjectVersion {
     /// Before any version changes were made
@@ -1197,6 +1242,7 @@ impl_custom_version_trait!(
 
 /// Custom serialization version for changes made in Dev-Sequencer stream
 #[derive(IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FSequencerObjectVersion {
     /// Before any version changes were made
@@ -1280,6 +1326,7 @@ impl_custom_version_trait!(
 
 /// Asset registry version
 #[derive(IntoPrimitive, TryFromPrimitive, PartialEq, Eq, PartialOrd, Ord, Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum FAssetRegistryVersionType {
     /// From before file versioning was implemented
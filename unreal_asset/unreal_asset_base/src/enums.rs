@@ -7,6 +7,7 @@ pub const HASH_VERSION_CITYHASH64: u64 = 0x00000000C1640000;
 
 /// Array dimension
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 pub enum EArrayDim {
     /// Not an array
@@ -20,6 +21,7 @@ pub enum EArrayDim {
 
 /// Property lifetime conditions
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ELifetimeCondition {
     /// This property has no condition, and will send anytime it changes
@@ -59,6 +61,7 @@ pub enum ELifetimeCondition {
 
 /// Custom version serialization format
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ECustomVersionSerializationFormat {
     /// Unknown
     Unknown,
@@ -72,7 +75,10 @@ pub enum ECustomVersionSerializationFormat {
 }
 
 /// Zen package version
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum EZenPackageVersion {
     /// Initial
@@ -88,10 +94,133 @@ pub enum EZenPackageVersion {
     LatestPlusOne,
 }
 
+/// Zen package object index type
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum EPackageObjectIndexType {
+    /// Export of the package itself
+    Export,
+    /// Object from a script package, e.g. `/Script/Engine`
+    ScriptImport,
+    /// Public export of another package
+    PackageImport,
+    /// Null reference
+    Null,
+}
+
+/// Zen export bundle command type
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)]
+pub enum EExportCommandType {
+    /// Create the export object
+    Create,
+    /// Serialize the export data
+    Serialize,
+}
+
+/// IoStore .utoc version
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum EIoStoreTocVersion {
+    /// Invalid
+    Invalid = 0,
+    /// Initial
+    Initial,
+    /// Directory index
+    DirectoryIndex,
+    /// Partition size
+    PartitionSize,
+    /// Perfect hash
+    PerfectHash,
+    /// Perfect hash with overflow
+    PerfectHashWithOverflow,
+    /// On demand metadata
+    OnDemandMetaData,
+    /// Removed on demand metadata
+    RemovedOnDemandMetaData,
+    /// Replace IoChunkHash with IoHash
+    ReplaceIoChunkHashWithIoHash,
+
+    /// Latest
+    Latest,
+    /// Latest plus one
+    LatestPlusOne,
+}
+
+/// IoStore chunk type (UE5)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum EIoChunkType {
+    /// Invalid
+    Invalid = 0,
+    /// Export bundle data
+    ExportBundleData,
+    /// Bulk data
+    BulkData,
+    /// Optional bulk data
+    OptionalBulkData,
+    /// Memory mapped bulk data
+    MemoryMappedBulkData,
+    /// Script objects
+    ScriptObjects,
+    /// Container header
+    ContainerHeader,
+    /// External file
+    ExternalFile,
+    /// Shader code library
+    ShaderCodeLibrary,
+    /// Shader code
+    ShaderCode,
+    /// Package store entry
+    PackageStoreEntry,
+    /// Derived data
+    DerivedData,
+    /// Editor derived data
+    EditorDerivedData,
+    /// Package resource
+    PackageResource,
+}
+
+/// IoStore chunk type (UE 4.26 and 4.27)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum EIoChunkType4 {
+    /// Invalid
+    Invalid = 0,
+    /// Install manifest
+    InstallManifest,
+    /// Export bundle data
+    ExportBundleData,
+    /// Bulk data
+    BulkData,
+    /// Optional bulk data
+    OptionalBulkData,
+    /// Memory mapped bulk data
+    MemoryMappedBulkData,
+    /// Loader global meta
+    LoaderGlobalMeta,
+    /// Loader initial load meta
+    LoaderInitialLoadMeta,
+    /// Loader global names
+    LoaderGlobalNames,
+    /// Loader global name hashes
+    LoaderGlobalNameHashes,
+    /// Container header
+    ContainerHeader,
+}
+
 /// IoStore container header version
 #[derive(
     Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, TryFromPrimitive, IntoPrimitive,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u32)]
 pub enum EIoContainerHeaderVersion {
     /// Initial
@@ -8,6 +8,7 @@ use thiserror::Error;
 use unreal_helpers::error::FStringError;
 
 use crate::custom_version::FAssetRegistryVersionType;
+use crate::enums::EIoStoreTocVersion;
 use crate::reader::ArchiveType;
 use crate::unversioned::Ancestry;
 
@@ -255,12 +256,21 @@ pub enum IoStoreError {
     /// Invalid header size
     #[error("Invalid .utoc header size, expected: {0}, got: {1}")]
     InvalidTocHeaderSize(u32, u32),
+    /// Invalid toc version
+    #[error("Invalid .utoc version {0:?}")]
+    InvalidTocVersion(EIoStoreTocVersion),
     /// Invalid enum value
     #[error("{0}")]
     InvalidEnumValue(Box<str>),
     /// Tried to get a non-existent file from an `IoStoreFileProvider`
     #[error("Tried to get a non-existent file {0}")]
     NoFile(Box<str>),
+    /// Tried to write a chunk with the same id twice
+    #[error("Tried to write chunk {0} twice")]
+    DuplicateChunk(Box<str>),
+    /// The container can not be written with the current configuration
+    #[error("Invalid container configuration: {0}")]
+    ConfigurationInvalid(Box<str>),
 
     /// No encryption key was provided for an encrypted file
     #[error("No encryption key was provided for an encrypted file")]
@@ -285,6 +295,21 @@ impl IoStoreError {
     pub fn invalid_toc_header_size(expected: u32, got: u32) -> Self {
         IoStoreError::InvalidTocHeaderSize(expected, got)
     }
+
+    /// Create a new `NoFile` error
+    pub fn no_file(name: String) -> Self {
+        IoStoreError::NoFile(name.into_boxed_str())
+    }
+
+    /// Create a new `DuplicateChunk` error
+    pub fn duplicate_chunk(chunk: String) -> Self {
+        IoStoreError::DuplicateChunk(chunk.into_boxed_str())
+    }
+
+    /// Create a new `ConfigurationInvalid` error
+    pub fn configuration_invalid(msg: String) -> Self {
+        IoStoreError::ConfigurationInvalid(msg.into_boxed_str())
+    }
 }
 
 impl<T: TryFromPrimitive> From<TryFromPrimitiveError<T>> for IoStoreError {
@@ -326,6 +351,9 @@ pub enum Error {
     /// The file is invalid
     #[error("{0}")]
     InvalidFile(Box<str>),
+    /// A value read from the file exceeds the configured limits
+    #[error("{0} of {1} exceeds the limit of {2}")]
+    LimitExceeded(&'static str, u64, u64),
     /// A package index is invalid
     #[error("{0}")]
     InvalidPackageIndex(Box<str>),
@@ -380,6 +408,11 @@ impl Error {
         Error::InvalidFile(msg.into_boxed_str())
     }
 
+    /// Create an `Error` when a value read from the file exceeds the configured limits
+    pub fn limit_exceeded(what: &'static str, value: u64, limit: u64) -> Self {
+        Error::LimitExceeded(what, value, limit)
+    }
+
     /// Create an `Error` when a package index is invalid
     pub fn invalid_package_index(msg: String) -> Self {
         Error::InvalidPackageIndex(msg.into_boxed_str())
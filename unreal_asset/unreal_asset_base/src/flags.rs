@@ -5,6 +5,7 @@ use bitflags::bitflags;
 bitflags! {
     /// Object instance flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EObjectFlags : u32
     {
         /// No flags
@@ -71,6 +72,7 @@ bitflags! {
 
     /// Package flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EPackageFlags : u32
     {
         /// No flags
@@ -123,6 +125,7 @@ bitflags! {
 
     /// Property flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EPropertyFlags : u64
     {
         /// None
@@ -230,6 +233,7 @@ bitflags! {
 
     /// Class flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EClassFlags : u32
     {
         /// No Flags
@@ -302,6 +306,7 @@ bitflags! {
 
     /// Function flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EFunctionFlags : u32 {
         /// None
         const FUNC_NONE = 0x00000000;
@@ -371,6 +376,7 @@ bitflags! {
 
     /// Asset registry dependency propety
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EDependencyProperty : u32 {
         /// None
         const NONE = 0;
@@ -399,6 +405,7 @@ bitflags! {
 
     /// User defined struct flags
     #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct EStructFlags: u32{
         /// No flags
         const NO_FLAGS = 0x00000000;
@@ -480,6 +487,27 @@ impl Default for EObjectFlags {
     }
 }
 
+bitflags! {
+    /// IoStore container flags
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct EIoContainerFlags : u8
+    {
+        /// No flags
+        const NONE = 0x00;
+        /// Compressed
+        const COMPRESSED = 0x01;
+        /// Encrypted
+        const ENCRYPTED = 0x02;
+        /// Signed
+        const SIGNED = 0x04;
+        /// Indexed
+        const INDEXED = 0x08;
+        /// On demand
+        const ON_DEMAND = 0x10;
+    }
+}
+
 impl Default for EPackageFlags {
     fn default() -> Self {
         Self::PKG_NONE
@@ -509,3 +537,9 @@ impl Default for EStructFlags {
         Self::NO_FLAGS
     }
 }
+
+impl Default for EIoContainerFlags {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
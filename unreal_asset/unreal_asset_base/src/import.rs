@@ -8,6 +8,7 @@ use crate::types::{FName, PackageIndex};
 ///
 /// This is used for referencing other assets
 #[derive(FNameContainer, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Import {
     /// Class package
     pub class_package: FName,
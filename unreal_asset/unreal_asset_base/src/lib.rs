@@ -14,6 +14,8 @@ pub use error::Error;
 pub mod flags;
 pub mod import;
 pub use import::Import;
+pub mod limits;
+pub use limits::ParseLimits;
 pub mod object_version;
 pub mod reader;
 pub mod types;
@@ -0,0 +1,38 @@
+//! Limits applied while parsing assets
+
+/// Limits checked against the lengths read from an asset before an array, string or data
+/// block of that length is allocated.
+///
+/// The defaults allow about a million elements per array, strings of 64 Ki characters and
+/// 1 GiB of raw data, so a corrupted length fails with an error instead of an allocation abort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Maximum amount of elements in a single array, map or set
+    pub max_array_length: u32,
+    /// Maximum length of a single string in characters
+    pub max_string_length: u32,
+    /// Maximum size of a single block of raw data in bytes, e.g. an export which can not be parsed
+    pub max_data_size: u64,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_array_length: 0x100000,
+            max_string_length: 0x10000,
+            max_data_size: 0x40000000,
+        }
+    }
+}
+
+impl ParseLimits {
+    /// Limits which accept every length stored in the asset, for trusted assets with larger
+    /// arrays or data blocks than the defaults allow
+    pub fn unlimited() -> Self {
+        Self {
+            max_array_length: u32::MAX,
+            max_string_length: u32::MAX,
+            max_data_size: u64::MAX,
+        }
+    }
+}
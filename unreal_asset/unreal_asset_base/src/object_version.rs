@@ -6,6 +6,7 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 #[derive(
     Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, IntoPrimitive, TryFromPrimitive,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 #[allow(non_camel_case_types)]
 pub enum ObjectVersion {
@@ -660,6 +661,7 @@ pub enum ObjectVersion {
 #[derive(
     Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, IntoPrimitive, TryFromPrimitive,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i32)]
 #[allow(non_camel_case_types)]
 pub enum ObjectVersionUE5 {
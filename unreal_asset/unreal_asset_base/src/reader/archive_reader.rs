@@ -10,6 +10,7 @@ use crate::custom_version::CustomVersion;
 use crate::enums;
 use crate::enums::ECustomVersionSerializationFormat;
 use crate::error::{Error, FNameError};
+use crate::limits::ParseLimits;
 use crate::object_version::ObjectVersion;
 use crate::reader::ArchiveTrait;
 use crate::types::{FName, PackageIndexTrait, SerializedNameHeader};
@@ -122,16 +123,17 @@ pub trait ArchiveReader<Index: PackageIndexTrait>: ArchiveTrait<Index> + Read {
     /// Read `FName` name batch
     fn read_name_batch(&mut self, verify_hashes: bool) -> Result<(Vec<String>, u64), Error> {
         let num_strings = self.read_i32::<LE>()?;
+        let num_strings = self.check_array_length(num_strings)?;
         if num_strings == 0 {
             return Ok((Vec::new(), 0));
         }
 
-        let _strings_length = self.read_u64::<LE>()?;
+        let _strings_length = self.read_u32::<LE>()?;
         let hash_version = self.read_u64::<LE>()?;
 
         let hashes = match hash_version {
             hash if hash == enums::HASH_VERSION_CITYHASH64 => {
-                let mut hashes = Vec::with_capacity(num_strings as usize);
+                let mut hashes = Vec::with_capacity(num_strings);
                 for _ in 0..num_strings {
                     hashes.push(self.read_u64::<LE>()?); // cityhash64 of crc::to_lower_string
                 }
@@ -143,15 +145,19 @@ pub trait ArchiveReader<Index: PackageIndexTrait>: ArchiveTrait<Index> + Read {
             ))),
         }?;
 
-        let mut name_headers = Vec::with_capacity(num_strings as usize);
+        let mut name_headers = Vec::with_capacity(num_strings);
         for _ in 0..num_strings {
             name_headers.push(SerializedNameHeader::read(self)?);
         }
 
-        let mut name_batch = Vec::with_capacity(num_strings as usize);
+        let mut name_batch = Vec::with_capacity(num_strings);
 
+        // name batch strings are neither null terminated nor followed by a hash
         for name_header in name_headers {
-            name_batch.push(self.read_name_map_string(Some(name_header)).map(|e| e.0)?);
+            name_batch.push(
+                self.read_fstring_name_header(name_header)?
+                    .unwrap_or_default(),
+            );
         }
 
         if verify_hashes {
@@ -179,6 +185,58 @@ pub trait ArchiveReader<Index: PackageIndexTrait>: ArchiveTrait<Index> + Read {
         Ok((name_batch, hash_version))
     }
 
+    /// Get the limits applied while parsing this archive
+    fn get_limits(&self) -> ParseLimits {
+        ParseLimits::default()
+    }
+
+    /// Check an array length read from this archive against the limits, returning it as a `usize`
+    fn check_array_length(&self, length: impl Into<i64>) -> Result<usize, Error> {
+        let length = length.into();
+        if length < 0 {
+            return Err(Error::invalid_file(format!(
+                "Negative array length {}",
+                length
+            )));
+        }
+
+        let limit = self.get_limits().max_array_length;
+        if length as u64 > limit as u64 {
+            return Err(Error::limit_exceeded(
+                "Array length",
+                length as u64,
+                limit as u64,
+            ));
+        }
+
+        Ok(length as usize)
+    }
+
+    /// Read a block of raw data, checking its size against the limits
+    ///
+    /// Memory is only allocated for data which is actually present in the archive
+    fn read_data(&mut self, size: impl Into<i64>) -> Result<Vec<u8>, Error>
+    where
+        Self: Sized,
+    {
+        let size = size.into();
+        if size < 0 {
+            return Err(Error::invalid_file(format!("Negative data size {}", size)));
+        }
+
+        let limit = self.get_limits().max_data_size;
+        if size as u64 > limit {
+            return Err(Error::limit_exceeded("Data size", size as u64, limit));
+        }
+
+        let mut data = Vec::with_capacity((size as usize).min(0x100000));
+        self.by_ref().take(size as u64).read_to_end(&mut data)?;
+        if data.len() as i64 != size {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(data)
+    }
+
     /// Read an array with specified length
     ///
     /// # Examples
@@ -196,7 +254,8 @@ pub trait ArchiveReader<Index: PackageIndexTrait>: ArchiveTrait<Index> + Read {
         length: i32,
         getter: impl Fn(&mut Self) -> Result<T, Error>,
     ) -> Result<Vec<T>, Error> {
-        let mut array = Vec::with_capacity(length as usize);
+        let length = self.check_array_length(length)?;
+        let mut array = Vec::with_capacity(length.min(0x10000));
         for _ in 0..length {
             array.push(getter(self)?);
         }
@@ -245,6 +304,11 @@ macro_rules! passthrough_archive_reader {
                 .read_fstring_name_header(serialized_name_header)
         }
 
+        #[inline(always)]
+        fn get_limits(&self) -> $crate::limits::ParseLimits {
+            self.$passthrough.get_limits()
+        }
+
         #[inline(always)]
         fn read_guid(&mut self) -> std::io::Result<unreal_helpers::Guid> {
             self.$passthrough.read_guid()
@@ -4,10 +4,12 @@ use std::io::{self, Write};
 
 use byteorder::{WriteBytesExt, LE};
 
+use crate::crc;
+use crate::enums;
 use crate::error::{Error, FNameError};
 use crate::object_version::ObjectVersion;
 use crate::reader::ArchiveTrait;
-use crate::types::{FName, PackageIndexTrait};
+use crate::types::{FName, PackageIndexTrait, SerializedNameHeader};
 use crate::Guid;
 
 /// A trait that allows for writing to an archive in an asset-specific way
@@ -42,6 +44,56 @@ pub trait ArchiveWriter<Index: PackageIndexTrait>: ArchiveTrait<Index> + Write {
         }
     }
 
+    /// Write `FName` name batch
+    fn write_name_batch(&mut self, name_batch: &[String]) -> Result<(), Error> {
+        self.write_u32::<LE>(name_batch.len() as u32)?;
+        if name_batch.is_empty() {
+            return Ok(());
+        }
+
+        // name batch strings are neither null terminated nor followed by a hash
+        let mut headers = Vec::with_capacity(name_batch.len());
+        let mut strings = Vec::new();
+        for name in name_batch {
+            let header = match name.is_ascii() {
+                true => {
+                    strings.extend_from_slice(name.as_bytes());
+                    SerializedNameHeader {
+                        is_wide: false,
+                        len: name.len() as i32,
+                    }
+                }
+                false => {
+                    let mut len = 0;
+                    for character in name.encode_utf16() {
+                        strings.extend_from_slice(&character.to_le_bytes());
+                        len += 1;
+                    }
+                    SerializedNameHeader { is_wide: true, len }
+                }
+            };
+
+            if header.len > 0x7fff {
+                return Err(Error::invalid_file(format!(
+                    "Name {name} is too long for a name batch"
+                )));
+            }
+            headers.push(header);
+        }
+
+        self.write_u32::<LE>(strings.len() as u32)?;
+        self.write_u64::<LE>(enums::HASH_VERSION_CITYHASH64)?;
+        for name in name_batch {
+            self.write_u64::<LE>(crc::cityhash64_to_lower(name))?;
+        }
+        for header in headers {
+            header.write(self)?;
+        }
+        self.write_all(&strings)?;
+
+        Ok(())
+    }
+
     /// Write an FString
     fn write_fstring(&mut self, value: Option<&str>) -> Result<usize, Error>;
     /// Write a guid.
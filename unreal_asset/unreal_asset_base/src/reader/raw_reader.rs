@@ -3,11 +3,16 @@
 use std::io::{self, Read, Seek};
 use std::marker::PhantomData;
 
-use unreal_helpers::{read_ext::read_fstring_len, Guid, UnrealReadExt};
+use byteorder::{ReadBytesExt, LE};
+use unreal_helpers::{
+    read_ext::{read_fstring_len, read_fstring_len_noterm},
+    Guid, UnrealReadExt,
+};
 
 use crate::containers::{Chain, IndexedMap, NameMap, SharedResource};
 use crate::custom_version::{CustomVersion, CustomVersionTrait};
 use crate::engine_version::{guess_engine_version, EngineVersion};
+use crate::limits::ParseLimits;
 use crate::object_version::{ObjectVersion, ObjectVersionUE5};
 use crate::reader::{
     archive_trait::{ArchiveTrait, ArchiveType},
@@ -17,7 +22,6 @@ use crate::types::{FName, PackageIndex, PackageIndexTrait, SerializedNameHeader}
 use crate::unversioned::Usmap;
 use crate::Error;
 
-
 /// A binary reader
 pub struct RawReader<Index: PackageIndexTrait, C: Read + Seek> {
     /// Reader cursor
@@ -30,6 +34,8 @@ pub struct RawReader<Index: PackageIndexTrait, C: Read + Seek> {
     pub use_event_driven_loader: bool,
     /// Name map
     pub name_map: SharedResource<NameMap>,
+    /// Limits applied while parsing
+    pub limits: ParseLimits,
     /// Empty map
     empty_map: IndexedMap<String, String>,
 
@@ -52,6 +58,7 @@ impl<Index: PackageIndexTrait, C: Read + Seek> RawReader<Index, C> {
             object_version_ue5,
             use_event_driven_loader,
             name_map,
+            limits: ParseLimits::default(),
             empty_map: IndexedMap::new(),
             _marker: PhantomData,
         }
@@ -129,8 +136,26 @@ impl<Index: PackageIndexTrait, C: Read + Seek> ArchiveTrait<Index> for RawReader
 }
 
 impl<Index: PackageIndexTrait, C: Read + Seek> ArchiveReader<Index> for RawReader<Index, C> {
+    fn get_limits(&self) -> ParseLimits {
+        self.limits
+    }
+
     fn read_fstring(&mut self) -> Result<Option<String>, Error> {
-        Ok(self.cursor.read_fstring()?)
+        let len = self.cursor.read_i32::<LE>()?;
+        let (len, is_wide) = match len < 0 {
+            true => (len.checked_neg().unwrap_or(i32::MAX), true),
+            false => (len, false),
+        };
+
+        if len as u32 > self.limits.max_string_length {
+            return Err(Error::limit_exceeded(
+                "String length",
+                len as u64,
+                self.limits.max_string_length as u64,
+            ));
+        }
+
+        Ok(read_fstring_len(&mut self.cursor, len, is_wide)?)
     }
 
     fn read_fstring_name_header(
@@ -141,7 +166,15 @@ impl<Index: PackageIndexTrait, C: Read + Seek> ArchiveReader<Index> for RawReade
             return Ok(None);
         }
 
-        Ok(read_fstring_len(
+        if serialized_name_header.len as u32 > self.limits.max_string_length {
+            return Err(Error::limit_exceeded(
+                "String length",
+                serialized_name_header.len as u64,
+                self.limits.max_string_length as u64,
+            ));
+        }
+
+        Ok(read_fstring_len_noterm(
             &mut self.cursor,
             serialized_name_header.len,
             serialized_name_header.is_wide,
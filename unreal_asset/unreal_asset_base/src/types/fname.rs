@@ -10,6 +10,7 @@ use crate::containers::{IndexedMap, NameMap, SharedResource};
 
 /// FName name type
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum EMappedNameType {
     /// Package-level name table
@@ -166,6 +167,38 @@ impl FName {
     pub fn eq_content(&self, other: &Self) -> bool {
         self.get_content(|this| other == this)
     }
+
+    /// Move a backed `FName` to another name map
+    ///
+    /// The name is looked up by its content and added to the name map if it's missing,
+    /// references to a duplicate name map entry are moved to the same duplicate if it exists.
+    pub fn rebind(&mut self, target: &mut SharedResource<NameMap>) {
+        let FName::Backed {
+            index, name_map, ..
+        } = self
+        else {
+            return;
+        };
+        if SharedResource::ptr_eq(name_map, target) {
+            return;
+        }
+
+        let (content, duplicate) = {
+            let name_map = name_map.get_ref();
+            (
+                name_map.get_owned_name(*index),
+                name_map.get_duplicate(*index),
+            )
+        };
+        let duplicate_index =
+            duplicate.and_then(|duplicate| target.get_ref().search_duplicate(&content, duplicate));
+
+        *index = match duplicate_index {
+            Some(duplicate_index) => duplicate_index,
+            None => target.get_mut().add_name_reference(content, false),
+        };
+        *name_map = target.clone();
+    }
 }
 
 impl PartialEq for FName {
@@ -176,15 +209,19 @@ impl PartialEq for FName {
                     index: a_index,
                     number: a_number,
                     ty: _,
-                    name_map: _,
+                    name_map: a_name_map,
                 },
                 FName::Backed {
                     index: b_index,
                     number: b_number,
                     ty: _,
-                    name_map: _,
+                    name_map: b_name_map,
                 },
-            ) => a_index == b_index && a_number == b_number,
+            ) => match SharedResource::ptr_eq(a_name_map, b_name_map) {
+                true => a_index == b_index && a_number == b_number,
+                // names from different name maps can only be compared by their content
+                false => a_number == b_number && self.eq_content(other),
+            },
             (
                 FName::Dummy {
                     value: a_value,
@@ -206,12 +243,13 @@ impl Hash for FName {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         match self {
             FName::Backed {
-                index,
+                index: _,
                 number,
                 ty: _,
                 name_map: _,
             } => {
-                index.hash(state);
+                // names from different name maps can be equal, so they are hashed by content
+                self.get_content(|name| name.hash(state));
                 number.hash(state);
             }
             FName::Dummy { value, number } => {
@@ -248,6 +286,99 @@ impl std::cmp::PartialEq<String> for FName {
         self == other
     }
 }
+
+/// Serialized form of an `FName`
+///
+/// Backed names are stored by their content, `"Name"` or `["Name", number]`, references to
+/// duplicate name map entries also store which duplicate of the name they point to.
+/// Dummy names are stored as `{"value": "Name", "number": 0}`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum SerializedFName {
+    Name(String),
+    Numbered(String, i32),
+    Object {
+        value: String,
+        number: i32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        duplicate: Option<u16>,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let serialized = match self {
+            FName::Backed {
+                index,
+                number,
+                name_map,
+                ..
+            } => {
+                let name_map = name_map.get_ref();
+                let value = name_map.get_owned_name(*index);
+                match (name_map.get_duplicate(*index), number) {
+                    (None, 0) => SerializedFName::Name(value),
+                    (None, _) => SerializedFName::Numbered(value, *number),
+                    (Some(duplicate), _) => SerializedFName::Object {
+                        value,
+                        number: *number,
+                        duplicate: Some(u16::try_from(duplicate).map_err(|_| {
+                            serde::ser::Error::custom("Too many duplicate name map entries")
+                        })?),
+                    },
+                }
+            }
+            FName::Dummy { value, number } => SerializedFName::Object {
+                value: value.clone(),
+                number: *number,
+                duplicate: None,
+            },
+        };
+        serialized.serialize(serializer)
+    }
+}
+
+/// Deserialized backed names get a name map of their own holding their content,
+/// they can be moved to the name map of an asset with [`FName::rebind`]
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(match SerializedFName::deserialize(deserializer)? {
+            SerializedFName::Name(value) => FName::new(0, 0, NameMap::from_name_batch(&[value])),
+            SerializedFName::Numbered(value, number) => {
+                FName::new(0, number, NameMap::from_name_batch(&[value]))
+            }
+            SerializedFName::Object {
+                value,
+                number,
+                duplicate: None,
+            } => FName::Dummy { value, number },
+            SerializedFName::Object {
+                value,
+                number,
+                duplicate: Some(duplicate),
+            } => {
+                // the last entry is the one found by name lookups,
+                // so the name keeps pointing to a duplicate
+                let mut name_map = NameMap::new();
+                for _ in 0..=duplicate {
+                    name_map.get_mut().add_name_reference(value.clone(), true);
+                }
+                name_map.get_mut().add_name_reference(value, true);
+                FName::new(duplicate as i32, number, name_map)
+            }
+        })
+    }
+}
+
 /// A trait that can be implemented for structs that contain an FName
 ///
 /// This trait will be typically used to traverse the whole asset FName tree
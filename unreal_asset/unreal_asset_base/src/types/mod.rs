@@ -9,6 +9,8 @@ pub mod vector;
 
 use std::hash::Hash;
 
+use crate::crc;
+use crate::enums::EPackageObjectIndexType;
 use crate::reader::{ArchiveReader, ArchiveWriter};
 use crate::Error;
 use crate::Guid;
@@ -37,7 +39,7 @@ impl SerializedNameHeader {
     }
 
     /// Write a `SerializedNameHeader` to an archive
-    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait> + ?Sized>(
         &self,
         writer: &mut Writer,
     ) -> Result<(), Error> {
@@ -74,6 +76,8 @@ pub trait PackageIndexTrait: std::fmt::Debug + Copy + Clone + PartialEq + Eq + T
 ///
 /// When PackageIndex is 0 it makes for a non-existent link.
 #[derive(Debug, Hash, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct PackageIndex {
     /// Index
     pub index: i32,
@@ -122,6 +126,106 @@ impl ToString for PackageIndex {
     }
 }
 
+/// PackageObjectIndex is used to reference objects in UE5 zen packages
+///
+/// The upper two bits store an [`EPackageObjectIndexType`], the remaining bits
+/// store an export index, a script object hash or a reference to a public export of an imported package.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct PackageObjectIndex {
+    /// Type and value
+    pub index: u64,
+}
+
+impl PackageObjectIndex {
+    /// Amount of bits used for the value
+    pub const INDEX_BITS: u64 = 62;
+    /// Value mask
+    pub const INDEX_MASK: u64 = (1u64 << Self::INDEX_BITS) - 1;
+    /// Null index
+    pub const NULL: PackageObjectIndex = PackageObjectIndex { index: u64::MAX };
+
+    /// Create a new `PackageObjectIndex` from its serialized value
+    pub fn new(index: u64) -> Self {
+        PackageObjectIndex { index }
+    }
+
+    /// Create a `PackageObjectIndex` from a type and a value
+    pub fn from_type(ty: EPackageObjectIndexType, value: u64) -> Self {
+        match ty {
+            EPackageObjectIndexType::Null => Self::NULL,
+            ty => PackageObjectIndex::new(
+                ((ty as u64) << Self::INDEX_BITS) | (value & Self::INDEX_MASK),
+            ),
+        }
+    }
+
+    /// Create a `PackageObjectIndex` from an export index
+    pub fn from_export(export_index: u32) -> Self {
+        Self::from_type(EPackageObjectIndexType::Export, export_index as u64)
+    }
+
+    /// Create a script import `PackageObjectIndex` from an object path, e.g. `/Script/Engine.Actor`
+    pub fn from_script_path(path: &str) -> Self {
+        let path = path.replace(['.', ':'], "/");
+        Self::from_type(
+            EPackageObjectIndexType::ScriptImport,
+            crc::cityhash64_to_lower(&path),
+        )
+    }
+
+    /// Get the type of this index
+    pub fn get_type(&self) -> EPackageObjectIndexType {
+        // the type is two bits wide, so every value is valid
+        EPackageObjectIndexType::try_from((self.index >> Self::INDEX_BITS) as u8)
+            .unwrap_or(EPackageObjectIndexType::Null)
+    }
+
+    /// Get the value of this index without the type
+    pub fn get_value(&self) -> u64 {
+        self.index & Self::INDEX_MASK
+    }
+
+    /// Check if this index is null
+    pub fn is_null(&self) -> bool {
+        self.get_type() == EPackageObjectIndexType::Null
+    }
+
+    /// Get the export index if this index references an export
+    pub fn get_export_index(&self) -> Option<usize> {
+        match self.get_type() {
+            EPackageObjectIndexType::Export => Some(self.get_value() as usize),
+            _ => None,
+        }
+    }
+}
+
+impl Default for PackageObjectIndex {
+    fn default() -> Self {
+        Self::NULL
+    }
+}
+
+impl PackageIndexTrait for PackageObjectIndex {
+    fn is_import(&self) -> bool {
+        matches!(
+            self.get_type(),
+            EPackageObjectIndexType::ScriptImport | EPackageObjectIndexType::PackageImport
+        )
+    }
+
+    fn is_export(&self) -> bool {
+        self.get_type() == EPackageObjectIndexType::Export
+    }
+}
+
+impl ToString for PackageObjectIndex {
+    fn to_string(&self) -> String {
+        self.index.to_string()
+    }
+}
+
 /// Create a Guid from 4 u32 values
 // #[rustfmt::skip]
 // pub const fn new_guid(a: u32, b: u32, c: u32, d: u32) -> Guid {
@@ -134,7 +238,8 @@ impl ToString for PackageIndex {
 // }
 
 /// Asset generation info
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenerationInfo {
     /// Export count
     pub export_count: i32,
@@ -12,6 +12,7 @@ use super::PackageIndexTrait;
 
 /// Frame number
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrameNumber {
     /// Value
     pub value: i32,
@@ -26,6 +27,7 @@ impl FrameNumber {
 
 /// Frame rate
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FrameRate {
     /// Numerator
     pub numerator: i32,
@@ -54,6 +56,7 @@ impl FrameRate {
 
 /// Enum CoreUObject.ERangeBoundTypes
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i8)]
 pub enum ERangeBoundTypes {
     /// Exclusive range
@@ -69,6 +72,7 @@ pub enum ERangeBoundTypes {
 
 /// Frame number bound by range
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FFrameNumberRangeBound {
     /// Binding range
     pub ty: ERangeBoundTypes,
@@ -100,6 +104,7 @@ impl FFrameNumberRangeBound {
 
 /// Frame number range
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FFrameNumberRange {
     /// Lower bound
     pub lower_bound: FFrameNumberRangeBound,
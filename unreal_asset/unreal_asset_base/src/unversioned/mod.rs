@@ -271,8 +271,16 @@ impl Usmap {
         let compressed_size = reader.read_u32::<LE>()?;
         let decompressed_size = reader.read_u32::<LE>()?;
 
-        let mut compressed_data = vec![0u8; compressed_size as usize];
-        reader.read_exact(&mut compressed_data)?;
+        let compressed_data = reader.read_data(compressed_size)?;
+
+        let max_data_size = reader.get_limits().max_data_size;
+        if decompressed_size as u64 > max_data_size {
+            return Err(Error::limit_exceeded(
+                "Decompressed size",
+                decompressed_size as u64,
+                max_data_size,
+            ));
+        }
 
         let data = match self.compression_method {
             EUsmapCompressionMethod::None => {
@@ -308,10 +316,8 @@ impl Usmap {
 
                 #[cfg(feature = "oodle")]
                 {
-                    let compressed = vec![0u8; compressed_size as usize];
-
                     let decompressed = oodle::decompress(
-                        &compressed,
+                        &compressed_data,
                         compressed_size as u64,
                         decompressed_size as u64,
                     )
@@ -337,13 +343,16 @@ impl Usmap {
 
         self.name_map = reader.read_array(|reader| {
             let name_length = reader.read_u8()?;
-            let mut buf = vec![0u8; name_length as usize - 1];
+            let name_length = name_length
+                .checked_sub(1)
+                .ok_or_else(|| Error::invalid_file("Usmap name with a length of 0".to_string()))?;
+            let mut buf = vec![0u8; name_length as usize];
             reader.read_exact(&mut buf)?;
             Ok(String::from_utf8(buf)?)
         })?;
 
         let enum_len = reader.read_u32::<LE>()?;
-        self.enum_map = IndexedMap::with_capacity(enum_len as usize);
+        self.enum_map = IndexedMap::with_capacity(reader.check_array_length(enum_len)?);
 
         let mut reader = UsmapReader::new(&mut reader, &self.name_map, &self.custom_versions);
 
@@ -361,7 +370,7 @@ impl Usmap {
         }
 
         let schemas_len = reader.read_u32::<LE>()?;
-        self.schemas = IndexedMap::with_capacity(schemas_len as usize);
+        self.schemas = IndexedMap::with_capacity(reader.check_array_length(schemas_len)?);
 
         for _ in 0..schemas_len {
             let schema = UsmapSchema::read(&mut reader)?;
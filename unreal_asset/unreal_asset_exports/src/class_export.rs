@@ -19,6 +19,7 @@ use crate::{ExportBaseTrait, ExportNormalTrait, ExportTrait};
 
 /// Serialized interface reference
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SerializedInterfaceReference {
     /// Class
     pub class: PackageIndex,
@@ -41,6 +42,7 @@ impl SerializedInterfaceReference {
 
 /// Class export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClassExport<Index: PackageIndexTrait> {
     /// Base struct export
     pub struct_export: StructExport<Index>,
@@ -78,7 +80,8 @@ impl<Index: PackageIndexTrait> ClassExport<Index> {
     ) -> Result<Self, Error> {
         let struct_export = StructExport::from_base(base, asset)?;
 
-        let num_func_index_entries = asset.read_i32::<LE>()? as usize;
+        let num_func_index_entries = asset.read_i32::<LE>()?;
+        let num_func_index_entries = asset.check_array_length(num_func_index_entries)?;
         let mut func_map = IndexedMap::with_capacity(num_func_index_entries);
         for _i in 0..num_func_index_entries {
             let name = asset.read_fname()?;
@@ -102,6 +105,7 @@ impl<Index: PackageIndexTrait> ClassExport<Index> {
         {
             interfaces_start = Some(asset.position());
             let num_interfaces = asset.read_i32::<LE>()?;
+            let num_interfaces = asset.check_array_length(num_interfaces)?;
             asset.seek(SeekFrom::Start(
                 interfaces_start.unwrap()
                     + size_of::<i32>() as u64
@@ -117,7 +121,8 @@ impl<Index: PackageIndexTrait> ClassExport<Index> {
         {
             asset.seek(SeekFrom::Start(interfaces_start.unwrap()))?;
         }
-        let num_interfaces = asset.read_i32::<LE>()? as usize;
+        let num_interfaces = asset.read_i32::<LE>()?;
+        let num_interfaces = asset.check_array_length(num_interfaces)?;
         let mut interfaces = Vec::with_capacity(num_interfaces);
         for _i in 0..num_interfaces {
             interfaces.push(SerializedInterfaceReference::new(
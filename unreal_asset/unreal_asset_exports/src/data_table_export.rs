@@ -17,6 +17,7 @@ use crate::{BaseExport, NormalExport};
 
 /// Data table
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataTable {
     /// Data
     pub data: Vec<StructProperty>,
@@ -31,6 +32,7 @@ impl DataTable {
 
 /// Data table export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataTableExport<Index: PackageIndexTrait> {
     /// Base normal export
     pub normal_export: NormalExport<Index>,
@@ -60,7 +62,8 @@ impl<Index: PackageIndexTrait> DataTableExport<Index> {
         }
 
         asset.read_i32::<LE>()?;
-        let num_entries = asset.read_i32::<LE>()? as usize;
+        let num_entries = asset.read_i32::<LE>()?;
+        let num_entries = asset.check_array_length(num_entries)?;
         let mut data = Vec::with_capacity(num_entries);
 
         let ancestry = Ancestry::new(base.get_class_type_for_ancestry(asset));
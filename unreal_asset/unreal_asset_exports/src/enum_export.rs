@@ -19,6 +19,7 @@ use crate::{BaseExport, NormalExport};
 
 /// Enum cpp form
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ECppForm {
     /// Regular
@@ -32,6 +33,7 @@ pub enum ECppForm {
 
 /// Enum
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UEnum {
     /// Enum names
     pub names: Vec<(FName, i64)>,
@@ -132,6 +134,7 @@ impl UEnum {
 
 /// Enum export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnumExport<Index: PackageIndexTrait> {
     /// Base normal export
     pub normal_export: NormalExport<Index>,
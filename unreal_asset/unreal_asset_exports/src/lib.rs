@@ -87,6 +87,8 @@ pub trait ExportTrait<Index: PackageIndexTrait>: Debug + Clone + PartialEq + Eq
 
 /// Export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "$type"))]
 #[container_nobounds]
 pub enum Export<Index: PackageIndexTrait> {
     /// Base export
@@ -15,6 +15,7 @@ use crate::{ExportBaseTrait, ExportNormalTrait, ExportTrait};
 ///
 /// This export is usually the base export for all other exports
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NormalExport<Index: PackageIndexTrait> {
     /// Base export
     pub base_export: BaseExport<Index>,
@@ -19,6 +19,7 @@ macro_rules! parse_simple_property {
     ($prop_name:ident) => {
         /// $prop_name
         #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $prop_name {
             /// Generic property
             pub generic_property: UGenericProperty
@@ -51,6 +52,7 @@ macro_rules! parse_simple_property {
     ) => {
         /// $prop_name
         #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $prop_name {
             /// Generic property
             pub generic_property: UGenericProperty,
@@ -98,6 +100,7 @@ pub trait UPropertyTrait: Debug + Clone + PartialEq + Eq + Hash {
 /// UProperty
 #[enum_dispatch(UPropertyTrait)]
 #[derive(FNameContainer, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[container_nobounds]
 pub enum UProperty {
     /// Generic UProperty
@@ -206,6 +209,7 @@ impl UProperty {
 
 /// UField
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UField {
     /// Next field package index
     pub next: Option<PackageIndex>,
@@ -213,6 +217,7 @@ pub struct UField {
 
 /// Generic UProperty
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UGenericProperty {
     /// UField
     #[container_ignore]
@@ -232,6 +237,7 @@ pub struct UGenericProperty {
 
 /// Boolean UProperty
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UBoolProperty {
     /// Generic property
     pub generic_property: UGenericProperty,
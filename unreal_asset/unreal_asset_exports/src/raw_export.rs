@@ -11,6 +11,7 @@ use crate::{ExportBaseTrait, ExportNormalTrait, ExportTrait};
 
 /// An export that failed to deserialize is stored as `Vec<u8>`
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawExport<Index: PackageIndexTrait> {
     /// Base export
     pub base_export: BaseExport<Index>,
@@ -46,8 +47,7 @@ impl<Index: PackageIndexTrait> RawExport<Index> {
         base: BaseExport<Index>,
         asset: &mut Reader,
     ) -> Result<Self, Error> {
-        let mut data = vec![0u8; base.serial_size as usize];
-        asset.read_exact(&mut data)?;
+        let data = asset.read_data(base.serial_size)?;
 
         Ok(RawExport {
             base_export: base,
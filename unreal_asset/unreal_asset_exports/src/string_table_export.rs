@@ -15,6 +15,7 @@ use crate::{BaseExport, NormalExport};
 
 /// String table export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StringTableExport<Index: PackageIndexTrait> {
     /// Base normal export
     pub normal_export: NormalExport<Index>,
@@ -20,6 +20,7 @@ use crate::{BaseExport, NormalExport};
 
 /// Struct export
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StructExport<Index: PackageIndexTrait> {
     /// Base normal export
     pub normal_export: NormalExport<Index>,
@@ -56,8 +57,8 @@ impl<Index: PackageIndexTrait> StructExport<Index> {
         let super_struct = PackageIndex::new(asset.read_i32::<LE>()?);
 
         let num_index_entries = asset.read_i32::<LE>()?;
-        let mut children = Vec::with_capacity(num_index_entries as usize);
-        for _i in 0..num_index_entries as usize {
+        let mut children = Vec::with_capacity(asset.check_array_length(num_index_entries)?);
+        for _i in 0..num_index_entries {
             children.push(PackageIndex::new(asset.read_i32::<LE>()?));
         }
 
@@ -66,8 +67,8 @@ impl<Index: PackageIndexTrait> StructExport<Index> {
         {
             true => {
                 let num_props = asset.read_i32::<LE>()?;
-                let mut props = Vec::with_capacity(num_props as usize);
-                for _i in 0..num_props as usize {
+                let mut props = Vec::with_capacity(asset.check_array_length(num_props)?);
+                for _i in 0..num_props {
                     props.push(FProperty::new(asset)?);
                 }
                 props
@@ -89,9 +90,7 @@ impl<Index: PackageIndexTrait> StructExport<Index> {
             Some(_) => None,
             None => {
                 asset.seek(SeekFrom::Start(start_offset))?;
-                let mut data = vec![0u8; script_storage_size as usize];
-                asset.read_exact(&mut data)?;
-                Some(data)
+                Some(asset.read_data(script_storage_size)?)
             }
         };
 
@@ -115,7 +114,7 @@ impl<Index: PackageIndexTrait> StructExport<Index> {
         storage_size: i32,
     ) -> Result<Vec<KismetExpression>, Error> {
         let mut code = Vec::new();
-        while (asset.position() - start_offset) < storage_size as u64 {
+        while asset.position().saturating_sub(start_offset) < storage_size as u64 {
             code.push(KismetExpression::new(asset)?);
         }
         Ok(code)
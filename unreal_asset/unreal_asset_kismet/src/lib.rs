@@ -28,6 +28,7 @@ use unreal_asset_base::{
 
 /// Kismet expression token
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EExprToken {
     /// A local variable.
@@ -224,6 +225,7 @@ pub enum EExprToken {
 
 /// Kismet cast token
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ECastToken {
     /// Cast object to interface
@@ -238,6 +240,7 @@ pub enum ECastToken {
 
 /// Kismet instrumentation type
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EScriptInstrumentationType {
     /// Class
@@ -278,6 +281,7 @@ pub enum EScriptInstrumentationType {
 
 /// Kismet text literal type
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EBlueprintTextLiteralType {
     /// Text is an empty string. The bytecode contains no strings, and you should use FText::GetEmpty() to initialize the FText instance.
@@ -294,6 +298,7 @@ pub enum EBlueprintTextLiteralType {
 
 /// Kismet field path
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FieldPath {
     /// Path
     pub path: Vec<FName>,
@@ -382,6 +387,7 @@ macro_rules! declare_expression {
     ) => {
         /// $name
         #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $name {
             /// Kismet token
             #[container_ignore]
@@ -412,6 +418,7 @@ macro_rules! implement_expression {
         $(
             $(#[$inner $($args)*])*
             #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
             pub struct $name {
                 /// Kismet token
                 #[container_ignore]
@@ -502,6 +509,7 @@ macro_rules! implement_value_expression {
 
 /// Kismet script text
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FScriptText {
     /// Literal type
     #[container_ignore]
@@ -671,6 +679,7 @@ impl FScriptText {
 // todo: replace with an enum with 2 variants
 /// Represents a Kismet bytecode pointer to an FProperty or FField.
 #[derive(FNameContainer, Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KismetPropertyPointer {
     /// Pointer serialized as PackageIndex. Used in versions older than [`KismetPropertyPointer::XFER_PROP_POINTER_SWITCH_TO_SERIALIZING_AS_FIELD_PATH_VERSION`]
     #[container_ignore]
@@ -707,8 +716,9 @@ impl KismetPropertyPointer {
             >= KismetPropertyPointer::XFER_PROP_POINTER_SWITCH_TO_SERIALIZING_AS_FIELD_PATH_VERSION
         {
             let num_entries = asset.read_i32::<LE>()?;
-            let mut names = Vec::with_capacity(num_entries as usize);
-            for _i in 0..num_entries as usize {
+            let num_entries = asset.check_array_length(num_entries)?;
+            let mut names = Vec::with_capacity(num_entries);
+            for _i in 0..num_entries {
                 names.push(asset.read_fname()?);
             }
             let owner = PackageIndex::new(asset.read_i32::<LE>()?);
@@ -753,6 +763,7 @@ impl KismetPropertyPointer {
 
 /// Kismet switch case
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KismetSwitchCase {
     /// Case value index
     pub case_index_value_term: KismetExpression,
@@ -808,6 +819,8 @@ pub trait KismetExpressionEnumEqTrait {
     KismetExpressionDataTrait
 )]
 #[derive(FNameContainer, Debug, Clone, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "$type"))]
 #[container_nobounds]
 pub enum KismetExpression {
     /// A local variable.
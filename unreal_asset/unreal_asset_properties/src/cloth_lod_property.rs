@@ -7,6 +7,7 @@ use crate::vector_property::Vector4Property;
 
 /// Mesh to mesh vertex data
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MeshToMeshVertData {
     /// Position barycentric coords and distance
     pub position_bary_coords_and_dist: Vector4Property,
@@ -104,6 +105,7 @@ impl MeshToMeshVertData {
 
 /// Cloth lod data property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClothLodDataProperty {
     /// Base struct property
     pub struct_property: StructProperty,
@@ -135,14 +137,15 @@ impl ClothLodDataProperty {
         )?;
 
         let transition_up_skin_data_len = asset.read_i32::<LE>()?;
-        let mut transition_up_skin_data = Vec::with_capacity(transition_up_skin_data_len as usize);
+        let mut transition_up_skin_data =
+            Vec::with_capacity(asset.check_array_length(transition_up_skin_data_len)?);
         for _ in 0..transition_up_skin_data_len {
             transition_up_skin_data.push(MeshToMeshVertData::new(asset)?);
         }
 
         let transition_down_skin_data_len = asset.read_i32::<LE>()?;
         let mut transition_down_skin_data =
-            Vec::with_capacity(transition_down_skin_data_len as usize);
+            Vec::with_capacity(asset.check_array_length(transition_down_skin_data_len)?);
         for _ in 0..transition_down_skin_data_len {
             transition_down_skin_data.push(MeshToMeshVertData::new(asset)?);
         }
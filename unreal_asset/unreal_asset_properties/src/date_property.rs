@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Time span property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeSpanProperty {
     /// Name
     pub name: FName,
@@ -22,6 +23,7 @@ impl_property_data_trait!(TimeSpanProperty);
 
 /// Date time property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DateTimeProperty {
     /// Name
     pub name: FName,
@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Delegate
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Delegate {
     /// Delegate object
     #[container_ignore]
@@ -23,6 +24,7 @@ impl Delegate {
 
 /// Delegate property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DelegateProperty {
     /// Name
     pub name: FName,
@@ -82,6 +84,7 @@ macro_rules! impl_multicast {
     ($property_name:ident) => {
         /// $property_name
         #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $property_name {
             /// Name
             pub name: FName,
@@ -108,7 +111,7 @@ macro_rules! impl_multicast {
                 let property_guid = optional_guid!(asset, include_header);
 
                 let length = asset.read_i32::<LE>()?;
-                let mut value = Vec::with_capacity(length as usize);
+                let mut value = Vec::with_capacity(asset.check_array_length(length)?);
                 for _ in 0..length {
                     value.push(Delegate::new(
                         PackageIndex::new(asset.read_i32::<LE>()?),
@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Enum property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnumProperty {
     /// Name
     pub name: FName,
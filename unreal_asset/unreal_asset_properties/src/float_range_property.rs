@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Float range property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FloatRangeProperty {
     /// Name
     pub name: FName,
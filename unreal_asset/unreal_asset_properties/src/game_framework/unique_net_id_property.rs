@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Unique network id
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UniqueNetId {
     /// Type
     pub ty: FName,
@@ -15,6 +16,7 @@ pub struct UniqueNetId {
 
 /// Unique network id property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UniqueNetIdProperty {
     /// Name
     pub name: FName,
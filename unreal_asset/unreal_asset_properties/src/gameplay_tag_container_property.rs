@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Gameplay tag container property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameplayTagContainerProperty {
     /// Name
     pub name: FName,
@@ -33,7 +34,7 @@ impl GameplayTagContainerProperty {
         let property_guid = optional_guid!(asset, include_header);
 
         let length = asset.read_i32::<LE>()?;
-        let mut value = Vec::with_capacity(length as usize);
+        let mut value = Vec::with_capacity(asset.check_array_length(length)?);
         for _i in 0..length as usize {
             value.push(asset.read_fname()?);
         }
@@ -35,6 +35,7 @@ macro_rules! impl_int_property {
 
 /// Int8 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Int8Property {
     /// Name
     pub name: FName,
@@ -51,6 +52,7 @@ impl_property_data_trait!(Int8Property);
 
 /// Byte property value
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BytePropertyValue {
     /// Byte variant
     Byte(u8),
@@ -66,6 +68,7 @@ impl Default for BytePropertyValue {
 
 /// Byte property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ByteProperty {
     /// Name
     pub name: FName,
@@ -84,6 +87,7 @@ impl_property_data_trait!(ByteProperty);
 
 /// Bool property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoolProperty {
     /// Name
     pub name: FName,
@@ -100,6 +104,7 @@ impl_property_data_trait!(BoolProperty);
 
 /// Int32 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntProperty {
     /// Name
     pub name: FName,
@@ -116,6 +121,7 @@ impl_property_data_trait!(IntProperty);
 
 /// Int16 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Int16Property {
     /// Name
     pub name: FName,
@@ -132,6 +138,7 @@ impl_property_data_trait!(Int16Property);
 
 /// Int64 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Int64Property {
     /// Name
     pub name: FName,
@@ -148,6 +155,7 @@ impl_property_data_trait!(Int64Property);
 
 /// UInt16 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UInt16Property {
     /// Name
     pub name: FName,
@@ -164,6 +172,7 @@ impl_property_data_trait!(UInt16Property);
 
 /// UInt32 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UInt32Property {
     /// Name
     pub name: FName,
@@ -180,6 +189,7 @@ impl_property_data_trait!(UInt32Property);
 
 /// UInt64 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UInt64Property {
     /// Name
     pub name: FName,
@@ -196,6 +206,7 @@ impl_property_data_trait!(UInt64Property);
 
 /// Float property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FloatProperty {
     /// Name
     pub name: FName,
@@ -212,6 +223,7 @@ impl_property_data_trait!(FloatProperty);
 
 /// Double property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DoubleProperty {
     /// Name
     pub name: FName,
@@ -8,6 +8,7 @@ use crate::property_prelude::*;
 
 /// Map property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MapProperty {
     /// Name
     pub name: FName,
@@ -150,13 +151,14 @@ impl MapProperty {
         }
 
         let num_keys_to_remove = asset.read_i32::<LE>()?;
+        let num_keys_to_remove = asset.check_array_length(num_keys_to_remove)?;
         let mut keys_to_remove = None;
 
         let type_1 = type_1.ok_or_else(|| Error::invalid_file("No type1".to_string()))?;
         let type_2 = type_2.ok_or_else(|| Error::invalid_file("No type2".to_string()))?;
 
-        for _ in 0..num_keys_to_remove as usize {
-            let mut vec = Vec::with_capacity(num_keys_to_remove as usize);
+        for _ in 0..num_keys_to_remove {
+            let mut vec = Vec::with_capacity(num_keys_to_remove);
             vec.push(MapProperty::map_type_to_class(
                 asset,
                 type_1.clone(),
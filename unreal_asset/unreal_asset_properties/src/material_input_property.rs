@@ -8,6 +8,7 @@ use crate::vector_property::{Vector2DProperty, VectorProperty};
 
 /// Material expression
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaterialExpression {
     /// Name
     pub name: FName,
@@ -23,6 +24,7 @@ pub struct MaterialExpression {
 
 /// Color material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorMaterialInputProperty {
     /// Name
     pub name: FName,
@@ -41,6 +43,7 @@ impl_property_data_trait!(ColorMaterialInputProperty);
 
 /// Scalar material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScalarMaterialInputProperty {
     /// Name
     pub name: FName,
@@ -59,6 +62,7 @@ impl_property_data_trait!(ScalarMaterialInputProperty);
 
 /// Shading model material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShadingModelMaterialInputProperty {
     /// Name
     pub name: FName,
@@ -77,6 +81,7 @@ impl_property_data_trait!(ShadingModelMaterialInputProperty);
 
 /// Vector material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VectorMaterialInputProperty {
     /// Name
     pub name: FName,
@@ -95,6 +100,7 @@ impl_property_data_trait!(VectorMaterialInputProperty);
 
 /// Vector2 material input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector2MaterialInputProperty {
     /// Name
     pub name: FName,
@@ -113,6 +119,7 @@ impl_property_data_trait!(Vector2MaterialInputProperty);
 
 /// Expression input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExpressionInputProperty {
     /// Name
     pub name: FName,
@@ -129,6 +136,7 @@ impl_property_data_trait!(ExpressionInputProperty);
 
 /// Material attributes input property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaterialAttributesInputProperty {
     /// Name
     pub name: FName,
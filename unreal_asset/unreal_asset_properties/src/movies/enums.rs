@@ -6,6 +6,7 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EMovieSceneKeyInterpolation {
     /// Auto
     Auto = 0,
@@ -25,6 +26,7 @@ pub enum EMovieSceneKeyInterpolation {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EMovieSceneBlendType {
     /// Invalid
     Invalid = 0,
@@ -42,6 +44,7 @@ pub enum EMovieSceneBlendType {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EMovieSceneBuiltInEasing {
     /// Linear
     Linear = 0,
@@ -95,6 +98,7 @@ pub enum EMovieSceneBuiltInEasing {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EEvaluationMethod {
     /// Static
     Static = 0,
@@ -108,6 +112,7 @@ pub enum EEvaluationMethod {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EUpdateClockSource {
     /// Tick
     Tick = 0,
@@ -129,6 +134,7 @@ pub enum EUpdateClockSource {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EMovieSceneEvaluationType {
     /// Frame locked
     FrameLocked = 0,
@@ -142,6 +148,7 @@ pub enum EMovieSceneEvaluationType {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EMovieScenePlayerStatus {
     /// Stopped
     Stopped = 0,
@@ -165,6 +172,7 @@ pub enum EMovieScenePlayerStatus {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EMovieSceneObjectBindingSpace {
     /// Local
     Local = 0,
@@ -178,6 +186,7 @@ pub enum EMovieSceneObjectBindingSpace {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EMovieSceneCompletionMode {
     /// Keep state
     KeepState = 0,
@@ -193,6 +202,7 @@ pub enum EMovieSceneCompletionMode {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ESectionEvaluationFlags {
     /// None
     #[default]
@@ -209,6 +219,7 @@ pub enum ESectionEvaluationFlags {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EUpdatePositionMethod {
     /// Play
     Play = 0,
@@ -224,6 +235,7 @@ pub enum EUpdatePositionMethod {
 #[repr(u8)]
 #[allow(non_camel_case_types)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ESpawnOwnership {
     /// Inner sequence
     InnerSequence = 0,
@@ -25,6 +25,7 @@ pub mod section_evaluation_data_tree_property;
 
 /// Movie scene tangent data
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneTangentData {
     /// Arrive tangent
     pub arrive_tangent: OrderedFloat<f32>,
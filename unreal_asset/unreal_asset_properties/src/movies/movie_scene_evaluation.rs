@@ -10,6 +10,7 @@ use crate::property_prelude::*;
 
 /// Movie scene evaluation entry
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FEntry {
     /// Start index
     pub start_index: i32,
@@ -49,6 +50,7 @@ impl FEntry {
 
 /// Evaluation tree entry handle
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EvaluationTreeEntryHandle {
     /// Entry index
     pub entry_index: i32,
@@ -76,6 +78,7 @@ impl EvaluationTreeEntryHandle {
 
 /// Movie scene evaluation tree node handle
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationTreeNodeHandle {
     /// Children handle
     pub children_handle: EvaluationTreeEntryHandle,
@@ -111,6 +114,7 @@ impl MovieSceneEvaluationTreeNodeHandle {
 
 /// Generic evaluation tree entry container
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TEvaluationTreeEntryContainer<T>
 where
     T: Debug + Clone + PartialEq + Eq + Hash,
@@ -137,14 +141,14 @@ where
         item_reader: fn(&mut Reader) -> Result<T, Error>,
     ) -> Result<Self, Error> {
         let entries_amount = asset.read_i32::<LE>()?;
-        let mut entries = Vec::with_capacity(entries_amount as usize);
+        let mut entries = Vec::with_capacity(asset.check_array_length(entries_amount)?);
 
         for _ in 0..entries_amount {
             entries.push(FEntry::new(asset)?);
         }
 
         let items_amount = asset.read_i32::<LE>()?;
-        let mut items = Vec::with_capacity(items_amount as usize);
+        let mut items = Vec::with_capacity(asset.check_array_length(items_amount)?);
 
         for _ in 0..entries_amount {
             items.push(item_reader(asset)?);
@@ -175,6 +179,7 @@ where
 
 /// Generic movie scene evaluation tree
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TMovieSceneEvaluationTree<T>
 where
     T: Debug + Clone + PartialEq + Eq + Hash,
@@ -246,6 +251,7 @@ where
 
 /// Movie scene evaluation tree node
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationTreeNode {
     /// Frame number range
     pub range: FFrameNumberRange,
@@ -291,6 +297,7 @@ impl MovieSceneEvaluationTreeNode {
 
 /// Movie entity and metadata index
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FEntityAndMetaDataIndex {
     /// Entity index
     pub entity_index: i32,
@@ -326,6 +333,7 @@ impl FEntityAndMetaDataIndex {
 
 /// Movie scene evaluation field entity tree
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationFieldEntityTree {
     /// Serialized data
     pub serialized_data: TMovieSceneEvaluationTree<FEntityAndMetaDataIndex>,
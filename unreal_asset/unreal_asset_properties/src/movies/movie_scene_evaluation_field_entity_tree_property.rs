@@ -8,6 +8,7 @@ use super::movie_scene_evaluation::MovieSceneEvaluationFieldEntityTree;
 
 /// Movie scene evaluation field entity tree property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEvaluationFieldEntityTreeProperty {
     /// Name
     pub name: FName,
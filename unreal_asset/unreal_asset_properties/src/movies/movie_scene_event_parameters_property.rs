@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Movie scene event parameters
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEventParameters {
     /// Struct type
     pub struct_type: SoftObjectPath,
@@ -19,8 +20,7 @@ impl MovieSceneEventParameters {
         let struct_type = SoftObjectPath::new(asset)?;
 
         let struct_bytes_length = asset.read_i32::<LE>()?;
-        let mut struct_bytes = vec![0u8; struct_bytes_length as usize];
-        asset.read_exact(&mut struct_bytes)?;
+        let struct_bytes = asset.read_data(struct_bytes_length)?;
 
         Ok(MovieSceneEventParameters {
             struct_type,
@@ -44,6 +44,7 @@ impl MovieSceneEventParameters {
 
 /// Movie scene event parameters property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneEventParametersProperty {
     /// Name
     pub name: FName,
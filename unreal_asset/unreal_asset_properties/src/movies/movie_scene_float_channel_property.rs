@@ -9,6 +9,7 @@ use super::movie_scene_float_value_property::MovieSceneFloatValue;
 
 /// Movie scene float channel
 #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneFloatChannel {
     /// Pre infinity extrapolation
     pub pre_infinity_extrap: RichCurveExtrapolation,
@@ -46,7 +47,7 @@ impl MovieSceneFloatChannel {
         let times_struct_length = asset.read_i32::<LE>()?;
         let times_length = asset.read_i32::<LE>()?;
 
-        let mut times = Vec::with_capacity(times_length as usize);
+        let mut times = Vec::with_capacity(asset.check_array_length(times_length)?);
         for _ in 0..times_length {
             times.push(FrameNumber::new(asset.read_i32::<LE>()?));
         }
@@ -54,7 +55,7 @@ impl MovieSceneFloatChannel {
         let values_struct_length = asset.read_i32::<LE>()?;
         let values_length = asset.read_i32::<LE>()?;
 
-        let mut values = Vec::with_capacity(values_length as usize);
+        let mut values = Vec::with_capacity(asset.check_array_length(values_length)?);
         for _ in 0..values_length {
             //todo: clangwin64 is always false?
             values.push(MovieSceneFloatValue::new(asset, false)?);
@@ -115,6 +116,7 @@ impl MovieSceneFloatChannel {
 
 /// Movie scene float channel property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneFloatChannelProperty {
     /// Name
     pub name: FName,
@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Movie scene sequence identifier
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneSequenceId {
     /// Value
     pub value: u32,
@@ -31,6 +32,7 @@ impl MovieSceneSequenceId {
 
 /// Movie scene sequence identifier property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MovieSceneSequenceIdProperty {
     /// Name
     pub name: FName,
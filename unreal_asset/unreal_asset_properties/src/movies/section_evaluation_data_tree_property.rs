@@ -6,6 +6,7 @@ use super::movie_scene_evaluation::TMovieSceneEvaluationTree;
 
 /// Section evaluation tree
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SectionEvaluationTree {
     /// Evaluation tree
     pub tree: TMovieSceneEvaluationTree<Vec<Property>>,
@@ -72,6 +73,7 @@ impl SectionEvaluationTree {
 
 /// Section evaluation data tree property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SectionEvaluationDataTreeProperty {
     /// Name
     pub name: FName,
@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Niagara variable property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NiagaraVariableProperty {
     /// Base struct property
     pub struct_property: StructProperty,
@@ -123,6 +124,7 @@ impl PropertyTrait for NiagaraVariableProperty {
 
 /// Niagara variable with offset property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NiagaraVariableWithOffsetProperty {
     /// Variable
     pub niagara_variable: NiagaraVariableProperty,
@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Object property
 #[derive(FNameContainer, Debug, Clone, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ObjectProperty {
     /// Name
     pub name: FName,
@@ -23,6 +24,7 @@ impl_property_data_trait!(ObjectProperty);
 
 /// Asset object property
 #[derive(FNameContainer, Debug, Clone, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AssetObjectProperty {
     /// Name
     pub name: FName,
@@ -39,6 +41,7 @@ impl_property_data_trait!(AssetObjectProperty);
 
 /// Top level asset path
 #[derive(FNameContainer, Debug, Clone, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TopLevelAssetPath {
     /// Package name that contains the asset e.g. /Some/Path/Package
     /// Only present in 5.1 and higher
@@ -98,6 +101,7 @@ impl TopLevelAssetPath {
 
 /// Soft object path
 #[derive(FNameContainer, Debug, Clone, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SoftObjectPath {
     /// Asset path
     pub asset_path: TopLevelAssetPath,
@@ -133,6 +137,7 @@ impl SoftObjectPath {
 
 /// Soft object property
 #[derive(FNameContainer, Debug, Clone, Default, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SoftObjectProperty {
     /// Name
     pub name: FName,
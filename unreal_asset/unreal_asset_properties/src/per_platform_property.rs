@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Per platform bool property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerPlatformBoolProperty {
     /// Name
     pub name: FName,
@@ -20,6 +21,7 @@ impl_property_data_trait!(PerPlatformBoolProperty);
 
 /// Per platform int property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerPlatformIntProperty {
     /// Name
     pub name: FName,
@@ -36,6 +38,7 @@ impl_property_data_trait!(PerPlatformIntProperty);
 
 /// Per platform float property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PerPlatformFloatProperty {
     /// Name
     pub name: FName,
@@ -63,7 +66,7 @@ impl PerPlatformBoolProperty {
         let property_guid = optional_guid!(asset, include_header);
 
         let num_entries = asset.read_i32::<LE>()?;
-        let mut value = Vec::with_capacity(num_entries as usize);
+        let mut value = Vec::with_capacity(asset.check_array_length(num_entries)?);
 
         for _i in 0..num_entries as usize {
             value.push(asset.read_bool()?);
@@ -107,7 +110,7 @@ impl PerPlatformIntProperty {
         let property_guid = optional_guid!(asset, include_header);
 
         let num_entries = asset.read_i32::<LE>()?;
-        let mut value = Vec::with_capacity(num_entries as usize);
+        let mut value = Vec::with_capacity(asset.check_array_length(num_entries)?);
 
         for _i in 0..num_entries as usize {
             value.push(asset.read_i32::<LE>()?);
@@ -151,7 +154,7 @@ impl PerPlatformFloatProperty {
         let property_guid = optional_guid!(asset, include_header);
 
         let num_entries = asset.read_i32::<LE>()?;
-        let mut value = Vec::with_capacity(num_entries as usize);
+        let mut value = Vec::with_capacity(asset.check_array_length(num_entries)?);
 
         for _i in 0..num_entries as usize {
             value.push(OrderedFloat(asset.read_f32::<LE>()?));
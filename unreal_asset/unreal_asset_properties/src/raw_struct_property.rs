@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Raw struct property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RawStructProperty {
     /// Name
     pub name: FName,
@@ -30,8 +31,7 @@ impl RawStructProperty {
     ) -> Result<Self, Error> {
         let property_guid = optional_guid!(asset, include_header);
 
-        let mut value = vec![0u8; length as usize];
-        asset.read_exact(&mut value)?;
+        let value = asset.read_data(length)?;
 
         Ok(RawStructProperty {
             name,
@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Rich curve extrapolation
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum RichCurveExtrapolation {
     /// Cycle
@@ -25,6 +26,7 @@ pub enum RichCurveExtrapolation {
 
 /// Rich curve interpolation mode
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i8)]
 pub enum RichCurveInterpMode {
     /// Linear
@@ -40,6 +42,7 @@ pub enum RichCurveInterpMode {
 
 /// Rich curve tangent mode
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i8)]
 pub enum RichCurveTangentMode {
     /// Auto
@@ -55,6 +58,7 @@ pub enum RichCurveTangentMode {
 
 /// Rich curve tangent weight mode
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i8)]
 pub enum RichCurveTangentWeightMode {
     /// None
@@ -70,6 +74,7 @@ pub enum RichCurveTangentWeightMode {
 
 /// Rich curve key property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RichCurveKeyProperty {
     /// Name
     pub name: FName,
@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Weighted random sampler property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WeightedRandomSamplerProperty {
     /// Name
     pub name: FName,
@@ -24,6 +25,7 @@ impl_property_data_trait!(WeightedRandomSamplerProperty);
 
 /// Skeletal mesh area weighted triangle sampler
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SkeletalMeshAreaWeightedTriangleSampler {
     /// Name
     pub name: FName,
@@ -44,6 +46,7 @@ impl_property_data_trait!(SkeletalMeshAreaWeightedTriangleSampler);
 
 /// Skeleetal mesh sampling lod built data property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SkeletalMeshSamplingLODBuiltDataProperty {
     /// Name
     pub name: FName,
@@ -71,13 +74,13 @@ impl WeightedRandomSamplerProperty {
         let property_guid = optional_guid!(asset, include_header);
 
         let size = asset.read_i32::<LE>()?;
-        let mut prob = Vec::with_capacity(size as usize);
+        let mut prob = Vec::with_capacity(asset.check_array_length(size)?);
         for _i in 0..size as usize {
             prob.push(OrderedFloat(asset.read_f32::<LE>()?));
         }
 
         let size = asset.read_i32::<LE>()?;
-        let mut alias = Vec::with_capacity(size as usize);
+        let mut alias = Vec::with_capacity(asset.check_array_length(size)?);
         for _i in 0..size as usize {
             alias.push(asset.read_i32::<LE>()?);
         }
@@ -135,13 +138,13 @@ impl SkeletalMeshAreaWeightedTriangleSampler {
         let property_guid = optional_guid!(asset, include_header);
 
         let size = asset.read_i32::<LE>()?;
-        let mut prob = Vec::with_capacity(size as usize);
+        let mut prob = Vec::with_capacity(asset.check_array_length(size)?);
         for _i in 0..size as usize {
             prob.push(OrderedFloat(asset.read_f32::<LE>()?));
         }
 
         let size = asset.read_i32::<LE>()?;
-        let mut alias = Vec::with_capacity(size as usize);
+        let mut alias = Vec::with_capacity(asset.check_array_length(size)?);
         for _i in 0..size as usize {
             alias.push(asset.read_i32::<LE>()?);
         }
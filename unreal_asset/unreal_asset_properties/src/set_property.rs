@@ -5,6 +5,7 @@ use crate::property_prelude::*;
 
 /// Set property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetProperty {
     /// Name
     pub name: FName,
@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Font hinting
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EFontHinting {
     /// Use the default hinting specified in the font.
@@ -23,6 +24,7 @@ pub enum EFontHinting {
 
 /// Font loading policy
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EFontLoadingPolicy {
     /// Lazy load the entire font into memory. This will consume more memory than Streaming, however there will be zero file-IO when rendering glyphs within the font, although the initial load may cause a hitch.
@@ -36,6 +38,7 @@ pub enum EFontLoadingPolicy {
 
 /// Font data
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontData {
     /// UObject
     #[container_ignore]
@@ -130,6 +133,7 @@ impl FontData {
 
 /// Font data property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FontDataProperty {
     /// Name
     pub name: FName,
@@ -2,6 +2,9 @@
 
 use crate::property_prelude::*;
 
+/// Maximum nesting depth of texts referencing other texts
+const MAX_TEXT_DEPTH: u32 = 64;
+
 /// Text history type
 #[derive(
     FNameContainer,
@@ -15,6 +18,7 @@ use crate::property_prelude::*;
     TryFromPrimitive,
     Hash,
 )]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(i8)]
 pub enum TextHistoryType {
     /// None
@@ -50,8 +54,264 @@ pub enum TextHistoryType {
     RawText,
 }
 
+/// Type of a format argument value
+#[derive(Debug, Copy, Clone, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i8)]
+pub enum FormatArgumentType {
+    /// Int
+    Int = 0,
+    /// UInt
+    UInt,
+    /// Float
+    Float,
+    /// Double
+    Double,
+    /// Text
+    Text,
+    /// Gender
+    Gender,
+}
+
+/// Grammatical gender of a text
+#[derive(
+    FNameContainer, Debug, Copy, Clone, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum TextGender {
+    /// Masculine
+    Masculine = 0,
+    /// Feminine
+    Feminine,
+    /// Neuter
+    Neuter,
+}
+
+/// Rounding mode used when formatting numbers
+#[derive(
+    FNameContainer, Debug, Copy, Clone, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i8)]
+pub enum RoundingMode {
+    /// Rounds to the nearest place, equidistant ties go to the value which is closest to an even value
+    HalfToEven = 0,
+    /// Rounds to nearest place, equidistant ties go to the value which is further from zero
+    HalfFromZero,
+    /// Rounds to nearest place, equidistant ties go to the value which is closer to zero
+    HalfToZero,
+    /// Rounds to the value which is further from zero
+    FromZero,
+    /// Rounds to the value which is closer to zero
+    ToZero,
+    /// Rounds to the value which is more negative
+    ToNegativeInfinity,
+    /// Rounds to the value which is more positive
+    ToPositiveInfinity,
+}
+
+/// Style of a formatted date or time
+#[derive(
+    FNameContainer, Debug, Copy, Clone, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(i8)]
+pub enum DateTimeStyle {
+    /// Default
+    Default = 0,
+    /// Short
+    Short,
+    /// Medium
+    Medium,
+    /// Long
+    Long,
+    /// Full
+    Full,
+}
+
+/// Transform applied to a text
+#[derive(
+    FNameContainer, Debug, Copy, Clone, PartialEq, Eq, IntoPrimitive, TryFromPrimitive, Hash,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum TextTransformType {
+    /// To lowercase
+    ToLower = 0,
+    /// To uppercase
+    ToUpper,
+}
+
+/// Format argument value
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[container_nobounds]
+pub enum FormatArgumentValue {
+    /// Int
+    Int(i64),
+    /// UInt
+    UInt(u64),
+    /// Float
+    Float(OrderedFloat<f32>),
+    /// Double
+    Double(OrderedFloat<f64>),
+    /// Text
+    Text(Box<FText>),
+    /// Gender
+    Gender(TextGender),
+}
+
+/// Named format argument
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[container_nobounds]
+pub struct FormatArgumentData {
+    /// Argument name
+    pub name: Option<String>,
+    /// Argument value
+    pub value: FormatArgumentValue,
+}
+
+/// Number formatting options
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumberFormattingOptions {
+    /// Always include the sign, only serialized since `FEditorObjectVersion::AddedAlwaysSignNumberFormattingOption`
+    pub always_sign: bool,
+    /// Use digit grouping
+    pub use_grouping: bool,
+    /// Rounding mode
+    pub rounding_mode: RoundingMode,
+    /// Minimum amount of integral digits
+    pub minimum_integral_digits: i32,
+    /// Maximum amount of integral digits
+    pub maximum_integral_digits: i32,
+    /// Minimum amount of fractional digits
+    pub minimum_fractional_digits: i32,
+    /// Maximum amount of fractional digits
+    pub maximum_fractional_digits: i32,
+}
+
+/// Format pattern with named arguments, used by named and argument formats
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[container_nobounds]
+pub struct FormatTextHistory {
+    /// Format pattern, e.g. `{Count} apples`
+    pub source_format: Box<FText>,
+    /// Arguments
+    pub arguments: Vec<FormatArgumentData>,
+}
+
+/// Format pattern with ordered arguments
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[container_nobounds]
+pub struct OrderedFormatTextHistory {
+    /// Format pattern, e.g. `{0} apples`
+    pub source_format: Box<FText>,
+    /// Arguments by index
+    pub arguments: Vec<FormatArgumentValue>,
+}
+
+/// Formatted number, percentage or currency
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[container_nobounds]
+pub struct NumberTextHistory {
+    /// Currency code, only used by `AsCurrency` since `VER_UE4_ADDED_CURRENCY_CODE_TO_FTEXT`
+    pub currency_code: Option<String>,
+    /// Source value
+    pub source_value: FormatArgumentValue,
+    /// Formatting options
+    pub format_options: Option<NumberFormattingOptions>,
+    /// Culture to format the number for
+    pub target_culture: Option<String>,
+}
+
+/// Formatted date, time or date and time
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DateTimeTextHistory {
+    /// Date time ticks
+    pub source_date_time: i64,
+    /// Date style, not used by `AsTime`
+    pub date_style: DateTimeStyle,
+    /// Time style, not used by `AsDate`
+    pub time_style: DateTimeStyle,
+    /// Time zone, only used by `AsDate` since `VER_UE4_FTEXT_HISTORY_DATE_TIMEZONE`
+    pub time_zone: Option<String>,
+    /// Culture to format the date for
+    pub target_culture: Option<String>,
+}
+
+/// Transformed text
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[container_nobounds]
+pub struct TransformTextHistory {
+    /// Source text
+    pub source_text: Box<FText>,
+    /// Transform
+    pub transform_type: TextTransformType,
+}
+
+/// Text generated by a registered text generator
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextGeneratorHistory {
+    /// Generator type, `None` if there is no generator
+    pub generator_type_id: FName,
+    /// Serialized generator, not present if there is no generator
+    pub generator_contents: Option<Vec<u8>>,
+}
+
+/// Data of text histories which is not stored in the base text fields
+#[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[container_nobounds]
+pub enum TextHistory {
+    /// Named format
+    NamedFormat(FormatTextHistory),
+    /// Ordered format
+    OrderedFormat(OrderedFormatTextHistory),
+    /// Argument format, a named format created by blueprints
+    ArgumentFormat(FormatTextHistory),
+    /// `AsNumber`, `AsPercent` and `AsCurrency`
+    Number(NumberTextHistory),
+    /// `AsDate`, `AsTime` and `AsDateTime`
+    DateTime(DateTimeTextHistory),
+    /// Transform
+    Transform(TransformTextHistory),
+    /// Text generator
+    TextGenerator(TextGeneratorHistory),
+}
+
+/// Text value, used by text properties and nested inside of text histories
+#[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[container_nobounds]
+pub struct FText {
+    /// Culture invariant string
+    pub culture_invariant_string: Option<String>,
+    /// Namespace
+    pub namespace: Option<String>,
+    /// String table id
+    pub table_id: Option<FName>,
+    /// Flags
+    pub flags: u32,
+    /// History type
+    pub history_type: TextHistoryType,
+    /// FString value
+    pub value: Option<String>,
+    /// History data for formatted and generated texts
+    pub history: Option<TextHistory>,
+}
+
 /// String property
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StrProperty {
     /// Name
     pub name: FName,
@@ -68,6 +328,7 @@ impl_property_data_trait!(StrProperty);
 
 /// Text property
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextProperty {
     /// Name
     pub name: FName,
@@ -89,11 +350,14 @@ pub struct TextProperty {
     pub history_type: TextHistoryType,
     /// FString value
     pub value: Option<String>,
+    /// History data for formatted and generated texts
+    pub history: Option<TextHistory>,
 }
 impl_property_data_trait!(TextProperty);
 
 /// Name property
 #[derive(FNameContainer, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NameProperty {
     /// Name
     pub name: FName,
@@ -142,16 +406,400 @@ impl PropertyTrait for StrProperty {
     }
 }
 
-impl TextProperty {
-    /// Read a `TextProperty` from an asset
+impl FormatArgumentValue {
+    /// Read a `FormatArgumentValue` from an asset
+    ///
+    /// Values of [`FormatArgumentData`] used by argument formats store integers with 32 bits before UE5.
     pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
         asset: &mut Reader,
-        name: FName,
-        ancestry: Ancestry,
-        include_header: bool,
-        duplication_index: i32,
+        is_argument_data: bool,
     ) -> Result<Self, Error> {
-        let property_guid = optional_guid!(asset, include_header);
+        Self::read(asset, is_argument_data, 0)
+    }
+
+    /// Read a `FormatArgumentValue` of a text nested `depth` levels deep
+    fn read<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+        is_argument_data: bool,
+        depth: u32,
+    ) -> Result<Self, Error> {
+        let wide_integers = !is_argument_data
+            || asset.get_object_version_ue5() >= ObjectVersionUE5::INITIAL_VERSION;
+
+        Ok(match FormatArgumentType::try_from(asset.read_i8()?)? {
+            FormatArgumentType::Int => match wide_integers {
+                true => FormatArgumentValue::Int(asset.read_i64::<LE>()?),
+                false => FormatArgumentValue::Int(asset.read_i32::<LE>()? as i64),
+            },
+            FormatArgumentType::UInt => match wide_integers {
+                true => FormatArgumentValue::UInt(asset.read_u64::<LE>()?),
+                false => FormatArgumentValue::UInt(asset.read_u32::<LE>()? as u64),
+            },
+            FormatArgumentType::Float => {
+                FormatArgumentValue::Float(OrderedFloat(asset.read_f32::<LE>()?))
+            }
+            FormatArgumentType::Double => {
+                FormatArgumentValue::Double(OrderedFloat(asset.read_f64::<LE>()?))
+            }
+            FormatArgumentType::Text => {
+                FormatArgumentValue::Text(Box::new(FText::read(asset, depth)?))
+            }
+            FormatArgumentType::Gender => {
+                FormatArgumentValue::Gender(TextGender::try_from(asset.read_u8()?)?)
+            }
+        })
+    }
+
+    /// Write a `FormatArgumentValue` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+        is_argument_data: bool,
+    ) -> Result<(), Error> {
+        let wide_integers = !is_argument_data
+            || asset.get_object_version_ue5() >= ObjectVersionUE5::INITIAL_VERSION;
+
+        asset.write_i8(self.get_type().into())?;
+        match self {
+            FormatArgumentValue::Int(value) => match wide_integers {
+                true => asset.write_i64::<LE>(*value)?,
+                false => asset.write_i32::<LE>(*value as i32)?,
+            },
+            FormatArgumentValue::UInt(value) => match wide_integers {
+                true => asset.write_u64::<LE>(*value)?,
+                false => asset.write_u32::<LE>(*value as u32)?,
+            },
+            FormatArgumentValue::Float(value) => asset.write_f32::<LE>(value.0)?,
+            FormatArgumentValue::Double(value) => asset.write_f64::<LE>(value.0)?,
+            FormatArgumentValue::Text(value) => value.write(asset)?,
+            FormatArgumentValue::Gender(value) => asset.write_u8((*value).into())?,
+        }
+        Ok(())
+    }
+
+    /// Get the type of this value
+    pub fn get_type(&self) -> FormatArgumentType {
+        match self {
+            FormatArgumentValue::Int(_) => FormatArgumentType::Int,
+            FormatArgumentValue::UInt(_) => FormatArgumentType::UInt,
+            FormatArgumentValue::Float(_) => FormatArgumentType::Float,
+            FormatArgumentValue::Double(_) => FormatArgumentType::Double,
+            FormatArgumentValue::Text(_) => FormatArgumentType::Text,
+            FormatArgumentValue::Gender(_) => FormatArgumentType::Gender,
+        }
+    }
+}
+
+impl FormatArgumentData {
+    /// Read a `FormatArgumentData` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+        is_argument_data: bool,
+    ) -> Result<Self, Error> {
+        Self::read(asset, is_argument_data, 0)
+    }
+
+    /// Read a `FormatArgumentData` of a text nested `depth` levels deep
+    fn read<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+        is_argument_data: bool,
+        depth: u32,
+    ) -> Result<Self, Error> {
+        let name = asset.read_fstring()?;
+        let value = FormatArgumentValue::read(asset, is_argument_data, depth)?;
+        Ok(FormatArgumentData { name, value })
+    }
+
+    /// Write a `FormatArgumentData` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+        is_argument_data: bool,
+    ) -> Result<(), Error> {
+        asset.write_fstring(self.name.as_deref())?;
+        self.value.write(asset, is_argument_data)
+    }
+}
+
+impl NumberFormattingOptions {
+    /// Read `NumberFormattingOptions` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        let mut always_sign = false;
+        if asset.get_custom_version::<FEditorObjectVersion>().version
+            >= FEditorObjectVersion::AddedAlwaysSignNumberFormattingOption as i32
+        {
+            always_sign = asset.read_i32::<LE>()? != 0;
+        }
+
+        Ok(NumberFormattingOptions {
+            always_sign,
+            use_grouping: asset.read_i32::<LE>()? != 0,
+            rounding_mode: RoundingMode::try_from(asset.read_i8()?)?,
+            minimum_integral_digits: asset.read_i32::<LE>()?,
+            maximum_integral_digits: asset.read_i32::<LE>()?,
+            minimum_fractional_digits: asset.read_i32::<LE>()?,
+            maximum_fractional_digits: asset.read_i32::<LE>()?,
+        })
+    }
+
+    /// Write `NumberFormattingOptions` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        if asset.get_custom_version::<FEditorObjectVersion>().version
+            >= FEditorObjectVersion::AddedAlwaysSignNumberFormattingOption as i32
+        {
+            asset.write_i32::<LE>(self.always_sign as i32)?;
+        }
+        asset.write_i32::<LE>(self.use_grouping as i32)?;
+        asset.write_i8(self.rounding_mode.into())?;
+        asset.write_i32::<LE>(self.minimum_integral_digits)?;
+        asset.write_i32::<LE>(self.maximum_integral_digits)?;
+        asset.write_i32::<LE>(self.minimum_fractional_digits)?;
+        asset.write_i32::<LE>(self.maximum_fractional_digits)?;
+        Ok(())
+    }
+}
+
+impl TextHistory {
+    /// Read the history data of a text from an asset
+    ///
+    /// Returns `None` for histories which only use the base text fields.
+    /// `depth` is the nesting depth of the texts referenced by this history.
+    fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+        history_type: TextHistoryType,
+        depth: u32,
+    ) -> Result<Option<Self>, Error> {
+        let history = match history_type {
+            TextHistoryType::NamedFormat => TextHistory::NamedFormat(FormatTextHistory {
+                source_format: Box::new(FText::read(asset, depth)?),
+                arguments: asset
+                    .read_array(|asset| FormatArgumentData::read(asset, false, depth))?,
+            }),
+            TextHistoryType::OrderedFormat => {
+                TextHistory::OrderedFormat(OrderedFormatTextHistory {
+                    source_format: Box::new(FText::read(asset, depth)?),
+                    arguments: asset
+                        .read_array(|asset| FormatArgumentValue::read(asset, false, depth))?,
+                })
+            }
+            TextHistoryType::ArgumentFormat => {
+                if asset.get_custom_version::<FEditorObjectVersion>().version
+                    < FEditorObjectVersion::TextFormatArgumentDataIsVariant as i32
+                {
+                    return Err(Error::unimplemented(
+                        "Argument format texts from before TextFormatArgumentDataIsVariant are not supported"
+                            .to_string(),
+                    ));
+                }
+                TextHistory::ArgumentFormat(FormatTextHistory {
+                    source_format: Box::new(FText::read(asset, depth)?),
+                    arguments: asset
+                        .read_array(|asset| FormatArgumentData::read(asset, true, depth))?,
+                })
+            }
+            TextHistoryType::AsNumber
+            | TextHistoryType::AsPercent
+            | TextHistoryType::AsCurrency => {
+                let mut currency_code = None;
+                if history_type == TextHistoryType::AsCurrency
+                    && asset.get_object_version()
+                        >= ObjectVersion::VER_UE4_ADDED_CURRENCY_CODE_TO_FTEXT
+                {
+                    currency_code = asset.read_fstring()?;
+                }
+                let source_value = FormatArgumentValue::read(asset, false, depth)?;
+                let has_format_options = asset.read_i32::<LE>()? != 0;
+                let format_options = match has_format_options {
+                    true => Some(NumberFormattingOptions::new(asset)?),
+                    false => None,
+                };
+
+                TextHistory::Number(NumberTextHistory {
+                    currency_code,
+                    source_value,
+                    format_options,
+                    target_culture: asset.read_fstring()?,
+                })
+            }
+            TextHistoryType::AsDate | TextHistoryType::AsTime | TextHistoryType::AsDateTime => {
+                let source_date_time = asset.read_i64::<LE>()?;
+
+                let mut date_style = DateTimeStyle::Default;
+                if history_type != TextHistoryType::AsTime {
+                    date_style = DateTimeStyle::try_from(asset.read_i8()?)?;
+                }
+                let mut time_style = DateTimeStyle::Default;
+                if history_type != TextHistoryType::AsDate {
+                    time_style = DateTimeStyle::try_from(asset.read_i8()?)?;
+                }
+
+                let mut time_zone = None;
+                if history_type != TextHistoryType::AsDate
+                    || asset.get_object_version()
+                        >= ObjectVersion::VER_UE4_FTEXT_HISTORY_DATE_TIMEZONE
+                {
+                    time_zone = asset.read_fstring()?;
+                }
+
+                TextHistory::DateTime(DateTimeTextHistory {
+                    source_date_time,
+                    date_style,
+                    time_style,
+                    time_zone,
+                    target_culture: asset.read_fstring()?,
+                })
+            }
+            TextHistoryType::Transform => TextHistory::Transform(TransformTextHistory {
+                source_text: Box::new(FText::read(asset, depth)?),
+                transform_type: TextTransformType::try_from(asset.read_u8()?)?,
+            }),
+            TextHistoryType::TextGenerator => {
+                let generator_type_id = asset.read_fname()?;
+                let mut generator_contents = None;
+                if generator_type_id != "None" {
+                    let length = asset.read_i32::<LE>()?;
+                    let mut contents = vec![0u8; asset.check_array_length(length)?];
+                    asset.read_exact(&mut contents)?;
+                    generator_contents = Some(contents);
+                }
+                TextHistory::TextGenerator(TextGeneratorHistory {
+                    generator_type_id,
+                    generator_contents,
+                })
+            }
+            TextHistoryType::None | TextHistoryType::Base | TextHistoryType::StringTableEntry => {
+                return Ok(None)
+            }
+            TextHistoryType::RawText => {
+                return Err(Error::unimplemented(format!(
+                    "Unimplemented reader for {history_type:?}"
+                )));
+            }
+        };
+        Ok(Some(history))
+    }
+
+    /// Write the history data of a text to an asset
+    fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+        history_type: TextHistoryType,
+    ) -> Result<(), Error> {
+        match (history_type, self) {
+            (TextHistoryType::NamedFormat, TextHistory::NamedFormat(history))
+            | (TextHistoryType::ArgumentFormat, TextHistory::ArgumentFormat(history)) => {
+                history.source_format.write(asset)?;
+                asset.write_i32::<LE>(history.arguments.len() as i32)?;
+                for argument in &history.arguments {
+                    argument.write(asset, history_type == TextHistoryType::ArgumentFormat)?;
+                }
+            }
+            (TextHistoryType::OrderedFormat, TextHistory::OrderedFormat(history)) => {
+                history.source_format.write(asset)?;
+                asset.write_i32::<LE>(history.arguments.len() as i32)?;
+                for argument in &history.arguments {
+                    argument.write(asset, false)?;
+                }
+            }
+            (
+                TextHistoryType::AsNumber
+                | TextHistoryType::AsPercent
+                | TextHistoryType::AsCurrency,
+                TextHistory::Number(history),
+            ) => {
+                if history_type == TextHistoryType::AsCurrency
+                    && asset.get_object_version()
+                        >= ObjectVersion::VER_UE4_ADDED_CURRENCY_CODE_TO_FTEXT
+                {
+                    asset.write_fstring(history.currency_code.as_deref())?;
+                }
+                history.source_value.write(asset, false)?;
+                asset.write_i32::<LE>(history.format_options.is_some() as i32)?;
+                if let Some(format_options) = &history.format_options {
+                    format_options.write(asset)?;
+                }
+                asset.write_fstring(history.target_culture.as_deref())?;
+            }
+            (
+                TextHistoryType::AsDate | TextHistoryType::AsTime | TextHistoryType::AsDateTime,
+                TextHistory::DateTime(history),
+            ) => {
+                asset.write_i64::<LE>(history.source_date_time)?;
+                if history_type != TextHistoryType::AsTime {
+                    asset.write_i8(history.date_style.into())?;
+                }
+                if history_type != TextHistoryType::AsDate {
+                    asset.write_i8(history.time_style.into())?;
+                }
+                if history_type != TextHistoryType::AsDate
+                    || asset.get_object_version()
+                        >= ObjectVersion::VER_UE4_FTEXT_HISTORY_DATE_TIMEZONE
+                {
+                    asset.write_fstring(history.time_zone.as_deref())?;
+                }
+                asset.write_fstring(history.target_culture.as_deref())?;
+            }
+            (TextHistoryType::Transform, TextHistory::Transform(history)) => {
+                history.source_text.write(asset)?;
+                asset.write_u8(history.transform_type.into())?;
+            }
+            (TextHistoryType::TextGenerator, TextHistory::TextGenerator(history)) => {
+                asset.write_fname(&history.generator_type_id)?;
+                if history.generator_type_id != "None" {
+                    let contents = history.generator_contents.as_ref().ok_or_else(|| {
+                        PropertyError::property_field_none("generator_contents", "Vec<u8>")
+                    })?;
+                    asset.write_i32::<LE>(contents.len() as i32)?;
+                    asset.write_all(contents)?;
+                }
+            }
+            _ => {
+                return Err(PropertyError::property_field_none(
+                    "history",
+                    &format!("TextHistory for {history_type:?}"),
+                )
+                .into())
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Borrowed fields of a text, shared by [`FText`] and [`TextProperty`]
+struct TextRef<'a> {
+    culture_invariant_string: &'a Option<String>,
+    namespace: &'a Option<String>,
+    table_id: &'a Option<FName>,
+    flags: u32,
+    history_type: TextHistoryType,
+    value: &'a Option<String>,
+    history: &'a Option<TextHistory>,
+}
+
+impl FText {
+    /// Read an `FText` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+    ) -> Result<Self, Error> {
+        Self::read(asset, 0)
+    }
+
+    /// Read an `FText` nested `depth` levels deep in other texts
+    fn read<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+        depth: u32,
+    ) -> Result<Self, Error> {
+        // texts can reference other texts, limit the depth so malformed assets can't overflow the stack
+        if depth > MAX_TEXT_DEPTH {
+            return Err(Error::invalid_file(format!(
+                "Texts are nested deeper than {MAX_TEXT_DEPTH} levels"
+            )));
+        }
 
         let mut culture_invariant_string = None;
         let mut namespace = None;
@@ -173,6 +821,7 @@ impl TextProperty {
         let flags = asset.read_u32::<LE>()?;
         let mut history_type = TextHistoryType::Base;
         let mut table_id = None;
+        let mut history = None;
         if asset.get_object_version() >= ObjectVersion::VER_UE4_FTEXT_HISTORY {
             history_type = TextHistoryType::try_from(asset.read_i8()?)?;
 
@@ -199,38 +848,45 @@ impl TextProperty {
                     table_id = Some(asset.read_fname()?);
                     value = asset.read_fstring()?;
                 }
-                _ => {
-                    return Err(Error::unimplemented(format!(
-                        "Unimplemented reader for {history_type:?}"
-                    )));
-                }
+                _ => history = TextHistory::new(asset, history_type, depth + 1)?,
             }
         }
 
-        Ok(TextProperty {
-            name,
-            ancestry,
-            property_guid,
-            duplication_index,
+        Ok(FText {
             culture_invariant_string,
             namespace,
             table_id,
             flags,
             history_type,
             value,
+            history,
         })
     }
+
+    /// Write an `FText` to an asset
+    pub fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+    ) -> Result<(), Error> {
+        TextRef {
+            culture_invariant_string: &self.culture_invariant_string,
+            namespace: &self.namespace,
+            table_id: &self.table_id,
+            flags: self.flags,
+            history_type: self.history_type,
+            value: &self.value,
+            history: &self.history,
+        }
+        .write(asset)
+    }
 }
 
-impl PropertyTrait for TextProperty {
+impl TextRef<'_> {
+    /// Write the text to an asset
     fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
         &self,
         asset: &mut Writer,
-        include_header: bool,
-    ) -> Result<usize, Error> {
-        optional_guid_write!(self, asset, include_header);
-        let begin = asset.position();
-
+    ) -> Result<(), Error> {
         if asset.get_object_version() < ObjectVersion::VER_UE4_FTEXT_HISTORY {
             asset.write_fstring(self.culture_invariant_string.as_deref())?;
             if asset.get_object_version()
@@ -280,12 +936,70 @@ impl PropertyTrait for TextProperty {
                     asset.write_fstring(self.value.as_deref())?;
                     Ok(())
                 }
-                _ => Err(Error::unimplemented(format!(
+                TextHistoryType::RawText => Err(Error::unimplemented(format!(
                     "Unimplemented writer for {}",
                     history_type as i8
                 ))),
+                _ => match self.history {
+                    Some(history) => history.write(asset, history_type),
+                    None => Err(PropertyError::property_field_none(
+                        "history",
+                        &format!("TextHistory for {history_type:?}"),
+                    )
+                    .into()),
+                },
             }?;
         }
+        Ok(())
+    }
+}
+
+impl TextProperty {
+    /// Read a `TextProperty` from an asset
+    pub fn new<Reader: ArchiveReader<impl PackageIndexTrait>>(
+        asset: &mut Reader,
+        name: FName,
+        ancestry: Ancestry,
+        include_header: bool,
+        duplication_index: i32,
+    ) -> Result<Self, Error> {
+        let property_guid = optional_guid!(asset, include_header);
+        let text = FText::new(asset)?;
+
+        Ok(TextProperty {
+            name,
+            ancestry,
+            property_guid,
+            duplication_index,
+            culture_invariant_string: text.culture_invariant_string,
+            namespace: text.namespace,
+            table_id: text.table_id,
+            flags: text.flags,
+            history_type: text.history_type,
+            value: text.value,
+            history: text.history,
+        })
+    }
+}
+
+impl PropertyTrait for TextProperty {
+    fn write<Writer: ArchiveWriter<impl PackageIndexTrait>>(
+        &self,
+        asset: &mut Writer,
+        include_header: bool,
+    ) -> Result<usize, Error> {
+        optional_guid_write!(self, asset, include_header);
+        let begin = asset.position();
+        TextRef {
+            culture_invariant_string: &self.culture_invariant_string,
+            namespace: &self.namespace,
+            table_id: &self.table_id,
+            flags: self.flags,
+            history_type: self.history_type,
+            value: &self.value,
+            history: &self.history,
+        }
+        .write(asset)?;
         Ok((asset.position() - begin) as usize)
     }
 }
@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// Struct property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StructProperty {
     /// Name
     pub name: FName,
@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 ///
 /// This gets created when an unknown property was encountered while deserializing
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnknownProperty {
     /// Name
     pub name: FName,
@@ -34,8 +35,7 @@ impl UnknownProperty {
         serialized_type: FName,
     ) -> Result<Self, Error> {
         let property_guid = optional_guid!(asset, include_header);
-        let mut value = vec![0u8; length as usize];
-        asset.read_exact(&mut value)?;
+        let value = asset.read_data(length)?;
 
         Ok(UnknownProperty {
             name,
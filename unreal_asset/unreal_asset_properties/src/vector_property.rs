@@ -6,6 +6,7 @@ use crate::property_prelude::*;
 
 /// Vector property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VectorProperty {
     /// Name
     pub name: FName,
@@ -23,6 +24,7 @@ impl_property_data_trait!(VectorProperty);
 
 /// Int point property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntPointProperty {
     /// Name
     pub name: FName,
@@ -40,6 +42,7 @@ impl_property_data_trait!(IntPointProperty);
 
 /// Vector4 property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector4Property {
     /// Name
     pub name: FName,
@@ -57,6 +60,7 @@ impl_property_data_trait!(Vector4Property);
 
 /// Vector2D property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector2DProperty {
     /// Name
     pub name: FName,
@@ -74,6 +78,7 @@ impl_property_data_trait!(Vector2DProperty);
 
 /// Quaternion property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QuatProperty {
     /// Name
     pub name: FName,
@@ -91,6 +96,7 @@ impl_property_data_trait!(QuatProperty);
 
 /// Rotator property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RotatorProperty {
     /// Name
     pub name: FName,
@@ -108,6 +114,7 @@ impl_property_data_trait!(RotatorProperty);
 
 /// Box property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoxProperty {
     /// Name
     pub name: FName,
@@ -128,6 +135,7 @@ impl_property_data_trait!(BoxProperty);
 
 /// Box2D property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Box2DProperty {
     /// Name
     pub name: FName,
@@ -148,6 +156,7 @@ impl_property_data_trait!(Box2DProperty);
 
 /// Plane property
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlaneProperty {
     /// Name
     pub name: FName,
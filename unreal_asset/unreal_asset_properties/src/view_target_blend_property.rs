@@ -4,6 +4,7 @@ use crate::property_prelude::*;
 
 /// View target blend function
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, IntoPrimitive, TryFromPrimitive)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ViewTargetBlendFunction {
     /// Camera does a simple linear interpolation.
@@ -23,6 +24,7 @@ pub enum ViewTargetBlendFunction {
 
 /// View target blend params property
 #[derive(FNameContainer, Debug, Hash, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ViewTargetBlendParamsProperty {
     /// Name
     pub name: FName,
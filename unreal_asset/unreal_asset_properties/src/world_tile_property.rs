@@ -8,6 +8,7 @@ use crate::vector_property::{BoxProperty, IntPointProperty};
 //todo: what is this file even doing in properties?
 /// World tile layer
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FWorldTileLayer {
     /// Name
     pub name: Option<String>,
@@ -91,6 +92,7 @@ impl FWorldTileLayer {
 
 /// World tile lod info
 #[derive(FNameContainer, Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FWorldTileLODInfo {
     /// Relative streaming distance
     pub relative_streaming_distance: i32,
@@ -134,6 +136,7 @@ impl FWorldTileLODInfo {
 
 /// World tile ifno
 #[derive(FNameContainer, Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FWorldTileInfo {
     /// Position
     #[container_ignore]
@@ -185,7 +188,8 @@ impl FWorldTileInfo {
 
         let mut lod_list = None;
         if object_version >= ObjectVersion::VER_UE4_WORLD_LEVEL_INFO_LOD_LIST {
-            let num_entries = asset.read_i32::<LE>()? as usize;
+            let num_entries = asset.read_i32::<LE>()?;
+            let num_entries = asset.check_array_length(num_entries)?;
             let mut list = Vec::with_capacity(num_entries);
             for _i in 0..num_entries {
                 list.push(FWorldTileLODInfo::new(asset)?);
@@ -72,7 +72,7 @@ impl AssetRegistryState {
 
         if version < FAssetRegistryVersionType::AddedDependencyFlags {
             let local_num_depends_nodes = asset.read_i32::<LE>()?;
-            *depends_nodes = Vec::with_capacity(local_num_depends_nodes as usize);
+            *depends_nodes = Vec::with_capacity(asset.check_array_length(local_num_depends_nodes)?);
 
             for i in 0..local_num_depends_nodes {
                 depends_nodes.push(DependsNode::new(i, version));
@@ -89,7 +89,7 @@ impl AssetRegistryState {
             let dependency_section_end = asset.position() + dependency_section_size as u64;
             let local_num_depends_nodes = asset.read_i32::<LE>()?;
 
-            *depends_nodes = Vec::with_capacity(local_num_depends_nodes as usize);
+            *depends_nodes = Vec::with_capacity(asset.check_array_length(local_num_depends_nodes)?);
             for i in 0..local_num_depends_nodes {
                 depends_nodes.push(DependsNode::new(i, version));
             }
@@ -489,8 +489,8 @@ impl DependsNode {
         };
         let num_referencers = asset.read_i32::<LE>()?;
 
-        let mut name_dependencies = Vec::with_capacity(num_name as usize);
-        let mut referencers = Vec::with_capacity(num_referencers as usize);
+        let mut name_dependencies = Vec::with_capacity(asset.check_array_length(num_name)?);
+        let mut referencers = Vec::with_capacity(asset.check_array_length(num_referencers)?);
 
         Self::read_node_array(
             asset,
@@ -252,12 +252,13 @@ impl Store {
                 Ok(asset.read_i32::<LE>()?)
             })?;
 
-        let mut ansi_strings_buf = vec![0u8; ansi_strings_size as usize];
-        asset.read_exact(&mut ansi_strings_buf)?;
+        let ansi_strings_buf = asset.read_data(ansi_strings_size)?;
 
         let mut ansi_strings = Vec::new();
 
         for ansi_string_offset in ansi_string_offsets {
+            // widened so offsets from malformed files can not overflow
+            let ansi_string_offset = ansi_string_offset as i64;
             let mut length = 0;
             while ansi_strings_buf
                 .get((ansi_string_offset + length) as usize)
@@ -268,18 +269,21 @@ impl Store {
             }
 
             let ansi_string = String::from_utf8(
-                ansi_strings_buf[(ansi_string_offset + length + 1) as usize..].to_vec(),
+                ansi_strings_buf
+                    .get((ansi_string_offset + length + 1) as usize..)
+                    .ok_or_else(|| Error::invalid_file("Invalid ANSI string".to_string()))?
+                    .to_vec(),
             )
             .map_err(|_| Error::invalid_file("Invalid ANSI string".to_string()))?;
             ansi_strings.push(ansi_string);
         }
 
-        let mut wide_strings_buf = vec![0u8; (wide_strings_size * 2) as usize];
-        asset.read_exact(&mut wide_strings_buf)?;
+        let wide_strings_buf = asset.read_data(wide_strings_size as i64 * 2)?;
 
         let mut wide_strings = Vec::new();
 
         for wide_string_offset in wide_string_offsets {
+            let wide_string_offset = wide_string_offset as i64;
             let mut length = 0;
             while wide_strings_buf
                 .get((wide_string_offset + length) as usize)
@@ -294,8 +298,10 @@ impl Store {
             }
 
             let wide_string = String::from_utf16(
-                &wide_strings_buf[(wide_string_offset + length) as usize..]
-                    .chunks(2)
+                &wide_strings_buf
+                    .get((wide_string_offset + length) as usize..)
+                    .ok_or_else(|| Error::invalid_file("Invalid wide string".to_string()))?
+                    .chunks_exact(2)
                     .map(|e| u16::from_le_bytes([e[0], e[1]]))
                     .collect::<Vec<_>>(),
             )?;
@@ -7,6 +7,9 @@ use byteorder::{ReadBytesExt, LE};
 
 use crate::error::FStringError;
 
+/// Maximum amount of elements allocated up front for lengths read from untrusted data
+const MAX_PREALLOCATION: usize = 0x10000;
+
 /// Extension for anything that implements `Read` to more easily read Unreal data formats.
 pub trait UnrealReadExt {
     /// Read u8 as bool.
@@ -34,8 +37,12 @@ impl<R: Read + Seek> UnrealReadExt for R {
     }
 
     fn read_vec(&mut self, len: usize) -> io::Result<Vec<u8>> {
-        let mut buf = vec![0; len];
-        self.read_exact(&mut buf)?;
+        // only allocate as much as can actually be read, the length might be garbage
+        let mut buf = Vec::with_capacity(len.min(MAX_PREALLOCATION));
+        self.by_ref().take(len as u64).read_to_end(&mut buf)?;
+        if buf.len() != len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
         Ok(buf)
     }
 
@@ -43,8 +50,9 @@ impl<R: Read + Seek> UnrealReadExt for R {
         &mut self,
         mut f: impl FnMut(&mut Self) -> io::Result<T>,
     ) -> io::Result<Vec<T>> {
-        let mut buf = Vec::with_capacity(self.read_u32::<LE>()? as usize);
-        for _ in 0..buf.capacity() {
+        let len = self.read_u32::<LE>()? as usize;
+        let mut buf = Vec::with_capacity(len.min(MAX_PREALLOCATION));
+        for _ in 0..len {
             buf.push(f(self)?);
         }
         Ok(buf)
@@ -54,7 +62,8 @@ impl<R: Read + Seek> UnrealReadExt for R {
         let len = self.read_i32::<LE>()?;
 
         let (len, is_wide) = match len < 0 {
-            true => (-len, true),
+            // i32::MIN can not be negated, it is rejected as too long anyways
+            true => (len.checked_neg().unwrap_or(i32::MAX), true),
             false => (len, false),
         };
         read_fstring_len(self, len, is_wide)
@@ -109,7 +118,7 @@ pub fn read_fstring_len_noterm<R: Read + Seek>(
     len: i32,
     is_wide: bool,
 ) -> Result<Option<String>, FStringError> {
-    if !(-131072..=131072).contains(&len) {
+    if !(0..=131072).contains(&len) {
         return Err(FStringError::InvalidStringSize(
             len,
             reader.stream_position()?,
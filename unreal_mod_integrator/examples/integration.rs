@@ -6,7 +6,7 @@ use std::path::PathBuf;
 
 use unreal_asset::engine_version::EngineVersion;
 use unreal_mod_integrator::{HandlerFn, IntegratorConfig};
-use unreal_pak::{PakMemory, PakReader};
+use unreal_pak::{PakMemory, PakVfs};
 
 pub struct Config;
 
@@ -14,8 +14,8 @@ pub struct Config;
 fn handle_linked_actor_components(
     _data: &(),
     _integrated_pak: &mut PakMemory,
-    _game_paks: &mut Vec<PakReader<BufReader<File>>>,
-    _mod_paks: &mut Vec<PakReader<BufReader<File>>>,
+    _game_paks: &mut PakVfs<BufReader<File>>,
+    _mod_paks: &mut PakVfs<BufReader<File>>,
     actors: &Vec<serde_json::Value>,
 ) -> Result<(), io::Error> {
     println!("Example linked actors: {actors:?}");
@@ -1,7 +1,7 @@
 use std::fs::File;
 use std::io::BufReader;
 
-use unreal_pak::{PakMemory, PakReader};
+use unreal_pak::{PakMemory, PakVfs};
 
 use crate::Error;
 
@@ -14,8 +14,8 @@ pub fn handle_persistent_actors(
     game_name: &'static str,
     map_paths: &[&str],
     integrated_pak: &mut PakMemory,
-    game_paks: &mut Vec<PakReader<BufReader<File>>>,
-    mod_paks: &mut Vec<PakReader<BufReader<File>>>,
+    game_paks: &mut PakVfs<BufReader<File>>,
+    mod_paks: &mut PakVfs<BufReader<File>>,
     persistent_actor_arrays: &Vec<serde_json::Value>,
 ) -> Result<(), Error> {
     #[cfg(feature = "ue4_23")]
@@ -16,7 +16,7 @@ use unreal_asset::{
     types::{PackageIndex, PackageIndexTrait},
     Asset, Import,
 };
-use unreal_pak::{PakMemory, PakReader};
+use unreal_pak::{PakMemory, PakVfs};
 
 use crate::helpers::{get_asset, write_asset};
 use crate::Error;
@@ -36,8 +36,8 @@ pub fn handle_persistent_actors(
     game_name: &'static str,
     map_paths: &[&str],
     integrated_pak: &mut PakMemory,
-    game_paks: &mut Vec<PakReader<BufReader<File>>>,
-    mod_paks: &mut Vec<PakReader<BufReader<File>>>,
+    game_paks: &mut PakVfs<BufReader<File>>,
+    mod_paks: &mut PakVfs<BufReader<File>>,
     persistent_actor_arrays: &Vec<serde_json::Value>,
 ) -> Result<(), Error> {
     let level_asset = Asset::new(
@@ -3,14 +3,14 @@ use std::io::{BufReader, Cursor};
 use std::path::Path;
 
 use unreal_asset::{engine_version::EngineVersion, reader::ArchiveTrait, Asset};
-use unreal_pak::{PakMemory, PakReader};
+use unreal_pak::{PakMemory, PakVfs};
 
 use crate::{error::IntegrationError, Error};
 
 pub fn get_asset(
     integrated_pak: &PakMemory,
-    game_paks: &mut [PakReader<BufReader<File>>],
-    mod_paks: &mut [PakReader<BufReader<File>>],
+    game_paks: &mut PakVfs<BufReader<File>>,
+    mod_paks: &mut PakVfs<BufReader<File>>,
     name: &String,
     version: EngineVersion,
 ) -> Result<Asset<Cursor<Vec<u8>>>, Error> {
@@ -22,53 +22,32 @@ pub fn get_asset(
         return Ok(asset);
     }
 
-    if let Some(mod_pak_index) = find_asset(mod_paks, name) {
-        return read_asset(
-            |name| {
-                mod_paks[mod_pak_index].read_entry(name).map_or_else(
-                    |err| {
-                        if matches!(err.kind, unreal_pak::error::PakErrorKind::EntryNotFound(_)) {
-                            Ok(None)
-                        } else {
-                            Err(err.into())
-                        }
-                    },
-                    |data| Ok(Some(data)),
-                )
-            },
-            version,
-            name,
-        );
+    if mod_paks.contains(name) {
+        return read_asset(|name| read_entry(mod_paks, name), version, name);
     }
 
-    let game_pak_index = find_asset(game_paks, name)
-        .ok_or_else(|| IntegrationError::asset_not_found(name.clone()))?;
+    if !game_paks.contains(name) {
+        return Err(IntegrationError::asset_not_found(name.clone()).into());
+    }
 
-    read_asset(
-        |name| {
-            game_paks[game_pak_index].read_entry(name).map_or_else(
-                |err| {
-                    if matches!(err.kind, unreal_pak::error::PakErrorKind::EntryNotFound(_)) {
-                        Ok(None)
-                    } else {
-                        Err(err.into())
-                    }
-                },
-                |data| Ok(Some(data)),
-            )
-        },
-        version,
-        name,
-    )
+    read_asset(|name| read_entry(game_paks, name), version, name)
 }
 
-pub fn find_asset(paks: &[PakReader<BufReader<File>>], name: &String) -> Option<usize> {
-    for (i, pak) in paks.iter().enumerate() {
-        if pak.contains_entry(name) {
-            return Some(i);
-        }
-    }
-    None
+/// Read a file from the pak the game would load it from, `None` if no pak contains it
+pub fn read_entry(
+    paks: &mut PakVfs<BufReader<File>>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, Error> {
+    paks.read(name).map_or_else(
+        |err| {
+            if matches!(err.kind, unreal_pak::error::PakErrorKind::EntryNotFound(_)) {
+                Ok(None)
+            } else {
+                Err(err.into())
+            }
+        },
+        |data| Ok(Some(data)),
+    )
 }
 
 pub fn read_asset<F>(
@@ -1,3 +1,4 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufReader, BufWriter, Cursor, Write};
@@ -22,7 +23,7 @@ use unreal_asset::{
     Asset,
 };
 use unreal_mod_metadata::{Metadata, SyncMode};
-use unreal_pak::{pakversion::PakVersion, PakMemory, PakReader};
+use unreal_pak::{pakversion::PakVersion, vfs, PakMemory, PakReader, PakVfs};
 
 mod assets;
 pub mod error;
@@ -149,16 +150,16 @@ pub trait DynamicMod<E: std::error::Error>: IntegratorModInfo {
     fn integrate(
         &self,
         integrated_pak: &mut PakMemory,
-        game_paks: &mut Vec<PakReader<BufReader<File>>>,
-        mod_paks: &mut Vec<PakReader<BufReader<File>>>,
+        game_paks: &mut PakVfs<BufReader<File>>,
+        mod_paks: &mut PakVfs<BufReader<File>>,
     ) -> Result<(), E>;
 }
 
 pub type HandlerFn<D, E> = dyn FnMut(
     &D,
     &mut PakMemory,
-    &mut Vec<PakReader<BufReader<File>>>,
-    &mut Vec<PakReader<BufReader<File>>>,
+    &mut PakVfs<BufReader<File>>,
+    &mut PakVfs<BufReader<File>>,
     &Vec<Value>,
 ) -> Result<(), E>;
 
@@ -386,12 +387,19 @@ pub fn integrate_mods<
             .any(|provided_mod| provided_mod.get_mod_id() == e.get_mod_id())
     });
 
+    // paks are mounted in descending order of their file names like the engine does
     let game_dir = fs::read_dir(game_path)?;
-    let game_files: Vec<File> = game_dir
+    let mut game_files: Vec<(String, File)> = game_dir
         .filter_map(|e| e.ok())
         .filter(|e| e.path().extension().map(|e| e == "pak").unwrap_or(false))
-        .filter_map(|e| File::open(e.path()).ok())
+        .filter_map(|e| {
+            Some((
+                e.file_name().to_string_lossy().into_owned(),
+                File::open(e.path()).ok()?,
+            ))
+        })
         .collect();
+    game_files.sort_by_key(|(file_name, _)| Reverse(file_name.to_lowercase()));
     if game_files.is_empty() {
         return Err(IntegrationError::game_not_found().into());
     }
@@ -401,17 +409,23 @@ pub fn integrate_mods<
         .chain(core_mods)
         .chain(enabled_baked_mods)
         .filter_map(|e| match e {
-            IntegratorMod::File(file_mod) => File::open(&file_mod.path).ok(),
-            IntegratorMod::Baked(baked_mod) => baked_mod.write(paks_path).ok(),
+            IntegratorMod::File(file_mod) => Some((
+                file_mod.path.file_name()?.to_string_lossy().into_owned(),
+                File::open(&file_mod.path).ok()?,
+            )),
+            IntegratorMod::Baked(baked_mod) => Some((
+                baked_mod.filename.to_owned(),
+                baked_mod.write(paks_path).ok()?,
+            )),
             _ => None,
         })
         .collect::<Vec<_>>();
 
-    let mut mod_paks = Vec::new();
+    let mut mod_paks = PakVfs::new();
     let mut read_mods = Vec::new();
     let mut optional_mods_data = HashMap::new();
 
-    for mod_file in mod_files {
+    for (file_name, mod_file) in mod_files {
         let mut pak = PakReader::new(BufReader::new(mod_file));
         pak.load_index()?;
 
@@ -431,11 +445,24 @@ pub fn integrate_mods<
                 .push(data.clone());
         }
 
-        mod_paks.push(pak);
+        mod_paks.mount(&file_name, vfs::PROJECT_SAVED_ORDER, pak);
     }
 
     if !mods.is_empty() {
-        let mut generated_pak = PakMemory::new(PakVersion::FnameBasedCompressionMethod);
+        let mut game_paks = PakVfs::new();
+        for (file_name, game_file) in game_files {
+            let mut pak = PakReader::new(BufReader::new(game_file));
+            pak.load_index()?;
+            game_paks.mount(&file_name, vfs::PROJECT_PAKS_ORDER, pak);
+        }
+
+        // write the generated pak in the same version as the game pak which wins
+        let pak_version = (0..game_paks.get_pak_count())
+            .max_by_key(|&pak| game_paks.get_pak_priority(pak))
+            .and_then(|pak| game_paks.get_pak(pak))
+            .map(|pak| pak.get_pak_version())
+            .unwrap_or(PakVersion::FnameBasedCompressionMethod);
+        let mut generated_pak = PakMemory::new(pak_version);
 
         #[cfg(not(feature = "no_bulk_data"))]
         let list_of_mods_bulk = Some(LIST_OF_MODS_BULK);
@@ -487,13 +514,6 @@ pub fn integrate_mods<
             );
         }
 
-        let mut game_paks = Vec::new();
-        for game_file in game_files {
-            let mut pak = PakReader::new(BufReader::new(game_file));
-            pak.load_index()?;
-            game_paks.push(pak);
-        }
-
         let empty_vec: Vec<Value> = Vec::new();
 
         let persistent_actor_maps: Vec<&str> = optional_mods_data
@@ -1,16 +1,176 @@
 //! Compression abstraction
-//! Currently supportted compressions (in addition to no compression):
+//! Built in compressions (in addition to no compression):
 //! - Zlib
+//! - Gzip
+//! - LZ4
+//! - Zstd
+//!
+//! Other methods like Oodle or game specific ones can be supported by implementing
+//! [`CompressionCodec`] and registering it with [`register_codec`].
 
 //* Note: when adding more compressions you should only have to update stuff in this file, but in a few places.
 
+use std::fmt;
 use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, PoisonError, RwLock};
 
-use flate2::{read::ZlibDecoder, write::ZlibEncoder};
+use flate2::{
+    read::{GzDecoder, ZlibDecoder},
+    write::{GzEncoder, ZlibEncoder},
+};
 
 use crate::error::PakError;
 use crate::pakversion::PakVersion;
 
+/// A compression method which can compress and decompress single compression blocks
+///
+/// Codecs are identified by the name which is written into the compression methods of the pak
+/// footer, names are compared case insensitively just like the engine compares FNames.
+pub trait CompressionCodec: Send + Sync {
+    /// Name of the compression method, e.g. `Oodle`
+    fn name(&self) -> &str;
+
+    /// Compress one block of data
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>>;
+
+    /// Decompress one block of data and append it to `buf`.
+    /// `decompressed_size` is the size the block is expected to have after decompression.
+    fn decompress(
+        &self,
+        data: &[u8],
+        decompressed_size: usize,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()>;
+}
+
+/// Codecs registered with [`register_codec`], these take precedence over the built in ones
+static CODECS: RwLock<Vec<Arc<dyn CompressionCodec>>> = RwLock::new(Vec::new());
+
+static BUILT_IN_CODECS: [&dyn CompressionCodec; 4] =
+    [&ZlibCodec, &GzipCodec, &Lz4Codec, &ZstdCodec];
+
+/// Register a codec which is used for all compression methods with the name of the codec.
+/// Codecs registered later take precedence and can also replace the built in ones.
+pub fn register_codec<C: CompressionCodec + 'static>(codec: C) {
+    CODECS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(0, Arc::new(codec));
+}
+
+fn find_codec(name: &str) -> Option<Arc<dyn CompressionCodec>> {
+    CODECS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|codec| codec.name().eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+struct ZlibCodec;
+
+impl CompressionCodec for ZlibCodec {
+    fn name(&self) -> &str {
+        "Zlib"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+
+    fn decompress(
+        &self,
+        data: &[u8],
+        decompressed_size: usize,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        ZlibDecoder::new(data)
+            .take(decompressed_size as u64)
+            .read_to_end(buf)?;
+        Ok(())
+    }
+}
+
+struct GzipCodec;
+
+impl CompressionCodec for GzipCodec {
+    fn name(&self) -> &str {
+        "Gzip"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data)?;
+        encoder.finish()
+    }
+
+    fn decompress(
+        &self,
+        data: &[u8],
+        decompressed_size: usize,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        GzDecoder::new(data)
+            .take(decompressed_size as u64)
+            .read_to_end(buf)?;
+        Ok(())
+    }
+}
+
+struct Lz4Codec;
+
+impl CompressionCodec for Lz4Codec {
+    fn name(&self) -> &str {
+        "LZ4"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        Ok(lz4_flex::block::compress(data))
+    }
+
+    fn decompress(
+        &self,
+        data: &[u8],
+        decompressed_size: usize,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        // lz4 can not compress better than 255:1, so a larger size can only come from bad data
+        let decompressed_size = decompressed_size.min(data.len().saturating_mul(255));
+        let start = buf.len();
+        buf.resize(start + decompressed_size, 0);
+        let written = lz4_flex::block::decompress_into(data, &mut buf[start..])
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        buf.truncate(start + written);
+        Ok(())
+    }
+}
+
+struct ZstdCodec;
+
+impl CompressionCodec for ZstdCodec {
+    fn name(&self) -> &str {
+        "Zstd"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        zstd::bulk::compress(data, zstd::DEFAULT_COMPRESSION_LEVEL)
+    }
+
+    fn decompress(
+        &self,
+        data: &[u8],
+        decompressed_size: usize,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        zstd::stream::read::Decoder::with_buffer(data)?
+            .take(decompressed_size as u64)
+            .read_to_end(buf)?;
+        Ok(())
+    }
+}
+
 /// Enum representing which compression method is being used for an entry
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Compression {
@@ -29,16 +189,62 @@ impl Compression {
         Self::Known("Zlib")
     }
 
+    /// Create Gzip Compression configuration
+    pub fn gzip() -> Self {
+        Self::Known("Gzip")
+    }
+
+    /// Create LZ4 Compression configuration
+    pub fn lz4() -> Self {
+        Self::Known("LZ4")
+    }
+
+    /// Create Zstd Compression configuration
+    pub fn zstd() -> Self {
+        Self::Known("Zstd")
+    }
+
+    /// Create Compression configuration from the name of a method.
+    /// Methods which are not built in need a [`CompressionCodec`] registered to be used.
+    pub fn from_name(name: &str) -> Result<Self, PakError> {
+        if name.is_empty() || name.eq_ignore_ascii_case("None") {
+            return Ok(Self::None);
+        }
+
+        if let Some(codec) = BUILT_IN_CODECS
+            .iter()
+            .find(|codec| codec.name().eq_ignore_ascii_case(name))
+        {
+            return Ok(Self::Known(codec.name()));
+        }
+
+        if name.len() >= 0x20 || name.contains('\0') {
+            return Err(PakError::configuration_invalid());
+        }
+        Ok(Self::Unknown(pad_zeroes(name.as_bytes())))
+    }
+
+    /// Get the name of the compression method, `None` for no compression or invalid names
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Self::None => None,
+            Self::Known(method) => Some(method),
+            Self::Unknown(method) => {
+                let len = method.iter().position(|b| *b == 0).unwrap_or(method.len());
+                std::str::from_utf8(&method[..len]).ok()
+            }
+        }
+    }
+
     pub(crate) fn from_reader<R: Read + Seek>(reader: &mut R) -> io::Result<Self> {
         let mut buf = [0; 0x20];
         reader.read_exact(&mut buf)?;
 
-        Ok(if buf == [0; 0x20] {
-            Self::None
-        } else if buf == pad_zeroes("Zlib".as_bytes()) {
-            Self::zlib()
-        } else {
-            Self::Unknown(buf)
+        let method = Self::Unknown(buf);
+        Ok(match method.name() {
+            Some(name) => Self::from_name(name).unwrap_or(method),
+            None if buf == [0; 0x20] => Self::None,
+            None => method,
         })
     }
 
@@ -60,6 +266,7 @@ impl Compression {
         } else {
             match compression_method_num {
                 0x01 | 0x10 | 0x20 => Compression::zlib(),
+                0x02 => Compression::gzip(),
                 _ => Compression::None,
             }
         }
@@ -71,26 +278,21 @@ impl Compression {
         compression: &CompressionMethods,
     ) -> Result<u32, PakError> {
         match self {
-            Self::Known(method) => {
-                if pak_version >= PakVersion::FnameBasedCompressionMethod {
-                    match compression
-                        .0
-                        .iter()
-                        .enumerate()
-                        .find(|(_, method)| *method == self)
-                    {
-                        Some((i, _)) => Ok((i + 1) as u32),
-                        None => Err(PakError::compression_unsupported_unknown()),
-                    }
-                } else {
-                    match *method {
-                        "Zlib" => Ok(1),
-                        _ => Err(PakError::compression_unsupported_unknown()),
-                    }
+            Self::None => Ok(0),
+            _ if pak_version >= PakVersion::FnameBasedCompressionMethod => {
+                match compression
+                    .0
+                    .iter()
+                    .enumerate()
+                    .find(|(_, method)| *method == self)
+                {
+                    Some((i, _)) => Ok((i + 1) as u32),
+                    None => Err(PakError::compression_unsupported(*self)),
                 }
             }
-            Self::None => Ok(0),
-            _ => Err(PakError::compression_unsupported_unknown()),
+            Self::Known("Zlib") => Ok(0x01),
+            Self::Known("Gzip") => Ok(0x02),
+            _ => Err(PakError::compression_unsupported(*self)),
         }
     }
 
@@ -102,37 +304,71 @@ impl Compression {
         }
     }
 
-    // These are panics becasue they should hard fail during developement.
+    /// Decompress one compression block and append it to `buf`
+    pub(crate) fn decompress(
+        &self,
+        buf: &mut Vec<u8>,
+        data: &[u8],
+        decompressed_size: usize,
+    ) -> Result<(), PakError> {
+        let name = self
+            .name()
+            .ok_or_else(|| PakError::compression_unsupported(*self))?;
 
-    pub(crate) fn decompress(&self, buf: &mut Vec<u8>, data: &[u8]) -> io::Result<()> {
-        match self {
-            Self::Known(method) => match *method {
-                "Zlib" => {
-                    let mut decoder = ZlibDecoder::new(data);
-                    decoder.read_to_end(buf)?;
-                    Ok(())
-                }
-                _ => panic!("Found Compression::Known with unknown compression."),
-            },
-            _ => panic!("Attempted to decompress with Compression type that can't decompress."),
+        match find_codec(name) {
+            Some(codec) => codec.decompress(data, decompressed_size, buf)?,
+            None => self
+                .built_in_codec()?
+                .decompress(data, decompressed_size, buf)?,
         }
+
+        Ok(())
     }
 
-    pub(crate) fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+    /// Compress one compression block
+    pub(crate) fn compress(&self, data: &[u8]) -> Result<Vec<u8>, PakError> {
+        let name = self
+            .name()
+            .ok_or_else(|| PakError::compression_unsupported(*self))?;
+
+        Ok(match find_codec(name) {
+            Some(codec) => codec.compress(data)?,
+            None => self.built_in_codec()?.compress(data)?,
+        })
+    }
+
+    fn built_in_codec(&self) -> Result<&'static dyn CompressionCodec, PakError> {
+        let name = self.name().unwrap_or_default();
+        BUILT_IN_CODECS
+            .iter()
+            .find(|codec| codec.name().eq_ignore_ascii_case(name))
+            .copied()
+            .ok_or_else(|| PakError::compression_unsupported(*self))
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Known(method) => match *method {
-                "Zlib" => {
-                    let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
-                    encoder.write_all(data)?;
-                    Ok(encoder.finish()?)
-                }
-                _ => panic!("Found Compression::Known with unknown compression."),
+            Self::None => f.write_str("None"),
+            _ => match self.name() {
+                Some(name) => f.write_str(name),
+                None => write!(f, "{:?}", self.as_bytes()),
             },
-            _ => panic!("Attempted to compress with Compression type that can't compress."),
         }
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Compression {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
 fn pad_zeroes(slice: &[u8]) -> [u8; 0x20] {
     let mut arr = [0; 0x20];
     arr[..slice.len()].copy_from_slice(slice);
@@ -144,8 +380,12 @@ pub(crate) struct CompressionMethods(pub [Compression; 5]);
 
 impl CompressionMethods {
     pub fn zlib() -> Self {
+        Self::single(Compression::zlib())
+    }
+
+    pub fn single(compression: Compression) -> Self {
         let mut methods = Self::default();
-        methods.0[0] = Compression::zlib();
+        methods.0[0] = compression;
         methods
     }
 
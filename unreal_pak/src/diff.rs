@@ -0,0 +1,110 @@
+//! Comparing the contents of two pak files
+
+use std::io::{Read, Seek};
+
+use crate::compression::Compression;
+use crate::error::PakError;
+use crate::pakreader::PakReader;
+
+/// Changes between two pak files, see [`diff`]
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PakDiff {
+    /// entries which only exist in the new pak
+    pub added: Vec<String>,
+    /// entries which only exist in the old pak
+    pub removed: Vec<String>,
+    /// entries which exist in both paks but differ
+    pub modified: Vec<EntryDiff>,
+}
+
+impl PakDiff {
+    /// Whether both paks contain the same entries
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Changes of a single entry, fields which did not change are `None`
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EntryDiff {
+    /// name of the entry
+    pub name: String,
+    /// whether the decompressed data of the entry changed
+    pub content_changed: bool,
+    /// old and new compression method
+    pub compression: Option<(Compression, Compression)>,
+    /// old and new size as stored in the pak
+    pub compressed_size: Option<(u64, u64)>,
+    /// old and new size after decompression
+    pub decompressed_size: Option<(u64, u64)>,
+}
+
+/// Compare the entries of two pak files, both need to have their index loaded.
+///
+/// The stored SHA-1 hashes cover the data as it is stored, so matching hashes of entries stored
+/// alike (same compression method, block size and encryption) mean the contents are equal.
+/// Otherwise the data is read and compared, compressors can produce different data for the
+/// same contents.
+pub fn diff<A, B>(old: &mut PakReader<A>, new: &mut PakReader<B>) -> Result<PakDiff, PakError>
+where
+    A: Read + Seek,
+    B: Read + Seek,
+{
+    let mut pak_diff = PakDiff::default();
+
+    for name in old.get_entry_names() {
+        if !new.contains_entry(name) {
+            pak_diff.removed.push(name.clone());
+        }
+    }
+
+    let new_names = new
+        .get_entry_names()
+        .into_iter()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for name in new_names {
+        if !old.contains_entry(&name) {
+            pak_diff.added.push(name);
+            continue;
+        }
+
+        let old_header = old.read_entry_header(&name)?;
+        let new_header = new.read_entry_header(&name)?;
+
+        let stored_alike = old_header.compression_method == new_header.compression_method
+            && old_header.compression_block_size == new_header.compression_block_size
+            && old_header.is_encrypted() == new_header.is_encrypted();
+        let hashes_present = old_header.hash != [0u8; 20] && new_header.hash != [0u8; 20];
+
+        let content_changed = if old_header.decompressed_size != new_header.decompressed_size {
+            true
+        } else if stored_alike && hashes_present && old_header.hash == new_header.hash {
+            false
+        } else {
+            old.read_entry(&name)? != new.read_entry(&name)?
+        };
+
+        let changed = |old, new| (old != new).then_some((old, new));
+        let entry_diff = EntryDiff {
+            content_changed,
+            compression: (old_header.compression_method != new_header.compression_method)
+                .then_some((old_header.compression_method, new_header.compression_method)),
+            compressed_size: changed(old_header.compressed_size, new_header.compressed_size),
+            decompressed_size: changed(old_header.decompressed_size, new_header.decompressed_size),
+            name,
+        };
+
+        if entry_diff.content_changed
+            || entry_diff.compression.is_some()
+            || entry_diff.compressed_size.is_some()
+        {
+            pak_diff.modified.push(entry_diff);
+        }
+    }
+
+    Ok(pak_diff)
+}
@@ -0,0 +1,121 @@
+//! AES-256 encryption used for pak entries and indexes
+//!
+//! Unreal encrypts data with AES-256 in ECB mode, so every encrypted region
+//! is padded to a multiple of the 16 byte AES block size.
+
+use std::fmt;
+use std::str::FromStr;
+
+use aes::cipher::{generic_array::GenericArray, BlockDecrypt, BlockEncrypt, KeyInit};
+use aes::Aes256;
+use base64::Engine;
+
+use crate::error::PakError;
+
+/// Size of one AES block, encrypted data is always aligned to this
+pub const AES_BLOCK_SIZE: u64 = 16;
+
+/// An AES-256 key used to encrypt and decrypt pak files
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    /// Create a key from raw bytes
+    pub fn new(key: [u8; 32]) -> Self {
+        Self(key)
+    }
+
+    /// Parse a key from a hex string with an optional `0x` prefix, as printed by most key finders
+    pub fn from_hex(key: &str) -> Result<Self, PakError> {
+        let key = key.trim();
+        let key = key
+            .strip_prefix("0x")
+            .or_else(|| key.strip_prefix("0X"))
+            .unwrap_or(key);
+
+        if key.len() != 64 || !key.is_ascii() {
+            return Err(PakError::encryption_key_invalid());
+        }
+
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&key[i * 2..i * 2 + 2], 16)
+                .map_err(|_| PakError::encryption_key_invalid())?;
+        }
+
+        Ok(Self(bytes))
+    }
+
+    /// Parse a key from a base64 string, as stored in `Crypto.json` of an Unreal project
+    pub fn from_base64(key: &str) -> Result<Self, PakError> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(key.trim())
+            .map_err(|_| PakError::encryption_key_invalid())?;
+
+        Ok(Self(
+            bytes
+                .try_into()
+                .map_err(|_| PakError::encryption_key_invalid())?,
+        ))
+    }
+
+    /// Get the raw bytes of this key
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Decrypt data in place, `data` must be aligned to [`AES_BLOCK_SIZE`]
+    pub(crate) fn decrypt(&self, data: &mut [u8]) -> Result<(), PakError> {
+        if align(data.len() as u64) != data.len() as u64 {
+            return Err(PakError::entry_invalid());
+        }
+
+        let cipher = Aes256::new(GenericArray::from_slice(&self.0));
+        for block in data.chunks_exact_mut(AES_BLOCK_SIZE as usize) {
+            cipher.decrypt_block(GenericArray::from_mut_slice(block));
+        }
+
+        Ok(())
+    }
+
+    /// Encrypt data in place, `data` must be aligned to [`AES_BLOCK_SIZE`]
+    pub(crate) fn encrypt(&self, data: &mut [u8]) -> Result<(), PakError> {
+        if align(data.len() as u64) != data.len() as u64 {
+            return Err(PakError::configuration_invalid());
+        }
+
+        let cipher = Aes256::new(GenericArray::from_slice(&self.0));
+        for block in data.chunks_exact_mut(AES_BLOCK_SIZE as usize) {
+            cipher.encrypt_block(GenericArray::from_mut_slice(block));
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for EncryptionKey {
+    type Err = PakError;
+
+    /// Parse a key from either hex or base64
+    fn from_str(key: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(key).or_else(|_| Self::from_base64(key))
+    }
+}
+
+// don't leak keys into logs
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(..)")
+    }
+}
+
+/// Round a size up to the next multiple of [`AES_BLOCK_SIZE`]
+pub(crate) fn align(size: u64) -> u64 {
+    size.saturating_add(AES_BLOCK_SIZE - 1) & !(AES_BLOCK_SIZE - 1)
+}
+
+/// Decrypt data in place with a key which is required to be present
+pub(crate) fn decrypt(key: Option<&EncryptionKey>, data: &mut [u8]) -> Result<(), PakError> {
+    key.ok_or_else(PakError::encryption_key_missing)?
+        .decrypt(data)
+}
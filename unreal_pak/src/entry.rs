@@ -1,11 +1,14 @@
+use std::borrow::Cow;
 use std::io::{Read, Seek, SeekFrom, Write};
 
 use crate::compression::CompressionMethods;
+use crate::encryption::{self, EncryptionKey};
 use crate::error::PakError;
-use crate::hash;
-use crate::header::{Block, Header};
+use crate::header::{Block, Header, FLAG_ENCRYPTED};
+use crate::limits::PakLimits;
 use crate::pakversion::PakVersion;
-use crate::Compression;
+use crate::workers::Workers;
+use crate::{hash, read_data, Compression};
 
 /// Read a pak entry at the given offset in the reader
 ///
@@ -14,11 +17,15 @@ use crate::Compression;
 /// * `reader` - Anything that implements Read + Seek
 /// * `pak_version` - Version of the pak format used
 /// * `offset` - The offset of the start of the header of the file
+/// * `key` - Key used to decrypt the entry if it is encrypted
+/// * `limits` - Limits the entry is checked against before allocating memory for it
 pub(crate) fn read_entry<R>(
     reader: &mut R,
     pak_version: PakVersion,
     compression: &CompressionMethods,
     offset: u64,
+    key: Option<&EncryptionKey>,
+    limits: &PakLimits,
 ) -> Result<Vec<u8>, PakError>
 where
     R: Read + Seek,
@@ -26,15 +33,27 @@ where
     reader.seek(SeekFrom::Start(offset))?;
 
     let header = Header::read(reader, pak_version, compression)?;
+    check_entry_size(&header, limits)?;
+    let encrypted = header.is_encrypted();
 
     match header.compression_method {
         Compression::None => {
-            let mut data = vec![0u8; header.decompressed_size as usize];
-            reader.read_exact(data.as_mut_slice())?;
-            Ok(data)
+            if encrypted {
+                // encrypted data is padded to the AES block size
+                let mut data = read_data(reader, encryption::align(header.decompressed_size))?;
+                encryption::decrypt(key, &mut data)?;
+                data.truncate(header.decompressed_size as usize);
+                return Ok(data);
+            }
+
+            Ok(read_data(reader, header.decompressed_size)?)
         }
-        Compression::Known(_) => {
-            let mut data = Vec::with_capacity(header.decompressed_size as usize);
+        _ => {
+            let mut data = Vec::with_capacity(header.decompressed_size.min(0x100000) as usize);
+            let block_size = match header.compression_block_size {
+                Some(block_size) if block_size != 0 => block_size as u64,
+                _ => header.decompressed_size,
+            };
 
             let compression_blocks = header
                 .compression_blocks
@@ -42,19 +61,99 @@ where
                 .ok_or_else(PakError::entry_invalid)?;
             for block in compression_blocks {
                 // we do not need to seek here because the reader is at the end of the header and compression blocks are continuous
-                let mut compressed_data = vec![0u8; block.size as usize];
-                reader.read_exact(&mut compressed_data)?;
-                header
-                    .compression_method
-                    .decompress(&mut data, compressed_data.as_slice())?;
+                // when encrypted every block is padded to the AES block size on its own
+                let stored_size = if encrypted {
+                    encryption::align(block.size)
+                } else {
+                    block.size
+                };
+                let mut compressed_data = read_data(reader, stored_size)?;
+                if encrypted {
+                    encryption::decrypt(key, &mut compressed_data)?;
+                }
+                let block_decompressed_size =
+                    block_size.min(header.decompressed_size.saturating_sub(data.len() as u64));
+                header.compression_method.decompress(
+                    &mut data,
+                    &compressed_data[..block.size as usize],
+                    block_decompressed_size as usize,
+                )?;
+
+                // codecs registered by users might not stop at the expected size
+                if data.len() as u64 > header.decompressed_size {
+                    return Err(PakError::entry_invalid());
+                }
             }
 
             Ok(data)
         }
-        _ => Err(PakError::compression_unsupported(header.compression_method)),
     }
 }
 
+/// Fail if the decompressed size of an entry is larger than the limit
+pub(crate) fn check_entry_size(header: &Header, limits: &PakLimits) -> Result<(), PakError> {
+    if header.decompressed_size > limits.max_entry_size {
+        return Err(PakError::limit_exceeded(
+            "Entry size",
+            header.decompressed_size,
+            limits.max_entry_size,
+        ));
+    }
+    Ok(())
+}
+
+/// Copy an entry as it is stored from one pak into another, without decompressing it
+///
+/// # Arguments
+///
+/// * `reader` - Pak to copy the entry from
+/// * `writer` - Pak to copy the entry to, at the position the writer is at
+/// * `pak_version` - Version of the pak format used, has to be the same for both paks
+/// * `offset` - The offset of the start of the header of the file in the reader
+pub(crate) fn copy_entry<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    pak_version: PakVersion,
+    compression: &CompressionMethods,
+    offset: u64,
+) -> Result<Header, PakError>
+where
+    R: Read + Seek,
+    W: Write + Seek,
+{
+    reader.seek(SeekFrom::Start(offset))?;
+    let mut header = Header::read(reader, pak_version, compression)?;
+
+    // the offset in front of the data is 0x00, so old absolute block offsets were not converted
+    if pak_version < PakVersion::RelativeChunkOffsets {
+        for block in header.compression_blocks.iter_mut().flatten() {
+            block.start = block
+                .start
+                .checked_sub(offset)
+                .ok_or_else(PakError::entry_invalid)?;
+        }
+    }
+
+    let stored_size = if header.is_encrypted() {
+        encryption::align(header.compressed_size)
+    } else {
+        header.compressed_size
+    };
+    let data = read_data(reader, stored_size)?;
+
+    let entry = PreparedEntry {
+        header,
+        data: Cow::Owned(data),
+    };
+    write_prepared_entry(writer, pak_version, compression, entry)
+}
+
+/// An entry which has been compressed and encrypted in memory but not written yet
+pub(crate) struct PreparedEntry<'data> {
+    header: Header,
+    data: Cow<'data, [u8]>,
+}
+
 /// Write an entry with Header at the position the write is at
 ///
 /// # Arguments
@@ -64,18 +163,45 @@ where
 /// * `data` - Uncompressed data to be written
 /// * `compression_method` - What compression to use
 /// * `block_size` - size of the used compression blocks
+/// * `key` - Key used to encrypt the entry, if it should be encrypted
+/// * `workers` - Workers used to compress the blocks of the entry
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn write_entry<W>(
     writer: &mut W,
     pak_version: PakVersion,
-    data: &Vec<u8>,
+    data: &[u8],
     compress: bool,
     compression: &CompressionMethods,
     block_size: u32,
+    key: Option<&EncryptionKey>,
+    workers: &Workers,
 ) -> Result<Header, PakError>
 where
     W: Write + Seek,
 {
-    let offset = writer.stream_position()?;
+    let entry = prepare_entry(
+        pak_version,
+        data,
+        compress,
+        compression,
+        block_size,
+        key,
+        workers,
+    )?;
+    write_prepared_entry(writer, pak_version, compression, entry)
+}
+
+/// Compress and encrypt an entry in memory, this does not depend on where the entry is written
+/// so it can happen in parallel for multiple entries.
+pub(crate) fn prepare_entry<'data>(
+    pak_version: PakVersion,
+    data: &'data [u8],
+    compress: bool,
+    compression: &CompressionMethods,
+    block_size: u32,
+    key: Option<&EncryptionKey>,
+    workers: &Workers,
+) -> Result<PreparedEntry<'data>, PakError> {
     let decompressed_size = data.len() as u64;
 
     let compress = compress && decompressed_size >= 32;
@@ -85,41 +211,57 @@ where
         Compression::None
     };
 
-    // compress data in memory
-    let mut compressed_data = if compress {
-        Vec::with_capacity(data.len())
-    } else {
-        // this will actually never be used
-        Vec::new()
-    };
     let mut compression_blocks = None;
     let data = match compression_method {
-        Compression::Known(_) => {
+        Compression::None => Cow::Borrowed(data),
+        _ => {
             if pak_version < PakVersion::CompressionEncryption {
                 return Err(PakError::configuration_invalid());
             }
 
-            let block_count = (data.len() as f64 / block_size as f64).ceil() as usize;
-            let mut compression_blocks_inner = Vec::with_capacity(block_count);
-            let header_len = Header::calculate_header_len(pak_version, Some(block_count as u32));
+            let chunks = data.chunks(block_size as usize).collect::<Vec<_>>();
+            let compressed_chunks =
+                workers.map(&chunks, |chunk| compression_method.compress(chunk))?;
+
+            // compress data in memory
+            let mut compressed_data = Vec::with_capacity(data.len());
+            let mut compression_blocks_inner = Vec::with_capacity(chunks.len());
+            let header_len = Header::calculate_header_len(pak_version, Some(chunks.len() as u32));
 
-            for chunk in data.chunks(block_size as usize) {
+            for block_compressed_data in compressed_chunks {
                 let begin = compressed_data.len() as u64;
 
-                let block_compressed_data = compression_method.compress(chunk)?;
                 compressed_data.extend_from_slice(&block_compressed_data);
-
                 compression_blocks_inner.push(Block {
                     start: begin + header_len,
                     size: block_compressed_data.len() as u64,
                 });
+
+                // encrypted blocks are padded to the AES block size individually, like the
+                // engine the stored block size does not include the padding
+                if key.is_some() {
+                    let aligned_len = encryption::align(compressed_data.len() as u64);
+                    compressed_data.resize(aligned_len as usize, 0);
+                }
             }
 
             compression_blocks = Some(compression_blocks_inner);
-            &compressed_data
+            Cow::Owned(compressed_data)
+        }
+    };
+
+    let data = match key {
+        Some(key) => {
+            if pak_version < PakVersion::CompressionEncryption {
+                return Err(PakError::configuration_invalid());
+            }
+
+            let mut encrypted_data = data.into_owned();
+            encrypted_data.resize(encryption::align(encrypted_data.len() as u64) as usize, 0);
+            key.encrypt(&mut encrypted_data)?;
+            Cow::Owned(encrypted_data)
         }
-        Compression::None => data,
-        _ => return Err(PakError::compression_unsupported(compression_method)),
+        None => data,
     };
 
     let compression_block_size = if pak_version >= PakVersion::CompressionEncryption {
@@ -134,19 +276,51 @@ where
         None
     };
 
-    let mut header = Header {
+    // like the engine the size of uncompressed entries does not include the encryption padding
+    let compressed_size = match compression_method {
+        Compression::None => decompressed_size,
+        _ => data.len() as u64,
+    };
+
+    let header = Header {
         offset: 0x00,
-        compressed_size: data.len() as u64,
+        compressed_size,
         decompressed_size,
         compression_method,
-        hash: hash(data),
+        hash: hash(&data),
         compression_blocks,
         compression_block_size,
-        flags: Some(0x00),
+        flags: Some(if key.is_some() { FLAG_ENCRYPTED } else { 0x00 }),
     };
 
-    Header::write(writer, pak_version, compression, &header)?;
-    writer.write_all(data)?;
+    Ok(PreparedEntry { header, data })
+}
+
+/// Write a prepared entry with Header at the position the write is at
+pub(crate) fn write_prepared_entry<W>(
+    writer: &mut W,
+    pak_version: PakVersion,
+    compression: &CompressionMethods,
+    entry: PreparedEntry,
+) -> Result<Header, PakError>
+where
+    W: Write + Seek,
+{
+    let offset = writer.stream_position()?;
+    let PreparedEntry { mut header, data } = entry;
+
+    if pak_version < PakVersion::RelativeChunkOffsets {
+        // the offset in the header right before the data is 0x00 but compression blocks still
+        // need to point to absolute offsets
+        let mut data_header = header.clone();
+        for block in data_header.compression_blocks.iter_mut().flatten() {
+            block.start += offset;
+        }
+        Header::write(writer, pak_version, compression, &data_header)?;
+    } else {
+        Header::write(writer, pak_version, compression, &header)?;
+    }
+    writer.write_all(&data)?;
 
     // the offset in the header right before the data is always 0x00, so only set here
     header.offset = offset;
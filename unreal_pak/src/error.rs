@@ -39,6 +39,18 @@ impl PakError {
             kind: PakErrorKind::EncryptionUnsupported,
         }
     }
+    /// construct EncryptionKeyMissing error
+    pub fn encryption_key_missing() -> Self {
+        PakError {
+            kind: PakErrorKind::EncryptionKeyMissing,
+        }
+    }
+    /// construct EncryptionKeyInvalid error
+    pub fn encryption_key_invalid() -> Self {
+        PakError {
+            kind: PakErrorKind::EncryptionKeyInvalid,
+        }
+    }
     /// construct InvalidConfiguration error
     pub fn configuration_invalid() -> Self {
         PakError {
@@ -70,6 +82,24 @@ impl PakError {
             kind: PakErrorKind::EntryInvalid,
         }
     }
+    /// construct SignatureInvalid error
+    pub fn signature_invalid() -> Self {
+        PakError {
+            kind: PakErrorKind::SignatureInvalid,
+        }
+    }
+    /// construct SigningKeyInvalid error
+    pub fn signing_key_invalid() -> Self {
+        PakError {
+            kind: PakErrorKind::SigningKeyInvalid,
+        }
+    }
+    /// construct LimitExceeded error
+    pub fn limit_exceeded(what: &'static str, value: u64, limit: u64) -> Self {
+        PakError {
+            kind: PakErrorKind::LimitExceeded(what, value, limit),
+        }
+    }
 }
 
 impl fmt::Display for PakError {
@@ -79,9 +109,15 @@ impl fmt::Display for PakError {
                 format!("Unsupported pak version: {}", *version as u32)
             }
             PakErrorKind::CompressionUnsupported(ref method) => {
-                format!("Unsupported compression method: {method:?}")
+                format!("Unsupported compression method: {method}")
             }
             PakErrorKind::EncryptionUnsupported => "Encryption is not supported".to_string(),
+            PakErrorKind::EncryptionKeyMissing => {
+                "Encrypted data found but no encryption key was provided".to_string()
+            }
+            PakErrorKind::EncryptionKeyInvalid => {
+                "Invalid encryption key, expected 32 bytes as hex or base64".to_string()
+            }
             PakErrorKind::ConfigurationInvalid => "Invalid configuration".to_string(),
             PakErrorKind::DoubleWrite(ref name) => {
                 format!("Attempted to write a file twice into the same PakFile, name: {name}")
@@ -92,6 +128,13 @@ impl fmt::Display for PakError {
                 format!("File not found: {file_name}")
             }
             PakErrorKind::EntryInvalid => "Invalid file".to_string(),
+            PakErrorKind::SignatureInvalid => "Invalid pak signature file".to_string(),
+            PakErrorKind::SigningKeyInvalid => {
+                "The signing key is too small to sign a pak".to_string()
+            }
+            PakErrorKind::LimitExceeded(what, value, limit) => {
+                format!("{what} of {value} exceeds the limit of {limit}")
+            }
 
             PakErrorKind::IoError(ref err) => {
                 format!("IO error: {err}")
@@ -121,6 +164,15 @@ impl From<unreal_helpers::error::FStringError> for PakError {
     }
 }
 
+impl From<PakError> for io::Error {
+    fn from(error: PakError) -> Self {
+        match error.kind {
+            PakErrorKind::IoError(err) => err,
+            kind => io::Error::new(io::ErrorKind::InvalidData, PakError { kind }),
+        }
+    }
+}
+
 impl error::Error for PakError {}
 
 /// Error representation of PakError
@@ -132,6 +184,10 @@ pub enum PakErrorKind {
     CompressionUnsupported(Compression),
     /// encryption is not supported
     EncryptionUnsupported,
+    /// data is encrypted but no key was provided
+    EncryptionKeyMissing,
+    /// an encryption key could not be parsed
+    EncryptionKeyInvalid,
     /// the state of a struct is invalid
     ConfigurationInvalid,
     /// Attempted to write a file twice into the same PakFile
@@ -143,6 +199,15 @@ pub enum PakErrorKind {
     EntryNotFound(String),
     /// a (compressed) file is corrupted or similar
     EntryInvalid,
+    /// a pak signature (.sig) file is not correctly formatted
+    SignatureInvalid,
+    /// a pak could not be signed with the given key
+    SigningKeyInvalid,
+    /// a value read from the pak file exceeds the configured [`PakLimits`],
+    /// contains what was limited, the value and the limit
+    ///
+    /// [`PakLimits`]: crate::limits::PakLimits
+    LimitExceeded(&'static str, u64, u64),
 
     /// something went wrong during reading
     IoError(io::Error),
@@ -0,0 +1,242 @@
+/*
+    frozen index (version 9, UE 4.25), a memory image of FPakFileData:
+    - pointers are i64, (offset relative to the pointer itself << 1) | 1
+    - arrays (16 bytes)
+        - pointer to the elements
+        - i32 element count
+        - i32 allocated element count
+    - strings are arrays of null terminated UTF-16
+    - maps (56 bytes)
+        - array of elements, unused ones are in a free list
+        - allocated bits (16 bytes)
+            - pointer to u32 words
+            - i32 bit count
+            - i32 allocated bit count
+        - i32 first free index, i32 free count
+        - hash pointer, i32 hash size, padding
+        - elements are the key, the value and 8 bytes of hash links, padded to 8 bytes
+
+    FPakFileData:
+    - string mount point
+    - array of entries (80 bytes each)
+        - i64 offset
+        - i64 size
+        - i64 size decompressed
+        - 20 bytes sha1 hash, 4 bytes padding
+        - array of compression blocks
+            - i64 block start
+            - i64 block end
+        - u32 block size
+        - u32 compression method
+        - u8 flags
+        - u8 verified, 6 bytes padding
+    - map of directory names to maps of file names to indices into the entries
+*/
+
+use crate::compression::{Compression, CompressionMethods};
+use crate::error::PakError;
+use crate::header::{Block, Header};
+use crate::limits::PakLimits;
+use crate::pakversion::PakVersion;
+
+/// Size of a frozen array or string
+const ARRAY_SIZE: usize = 16;
+/// Size of a frozen entry
+const ENTRY_SIZE: usize = 80;
+/// Size of a frozen compression block
+const BLOCK_SIZE: usize = 16;
+/// Size of an element of a directory, file name and index
+const FILE_ELEMENT_SIZE: usize = 32;
+/// Size of an element of the directory map, directory name and map of files
+const DIRECTORY_ELEMENT_SIZE: usize = 80;
+
+/// Read the mount point and entries of a frozen index
+pub(crate) fn read_frozen_index(
+    data: &[u8],
+    pak_version: PakVersion,
+    compression: &CompressionMethods,
+    limits: &PakLimits,
+) -> Result<(String, Vec<(String, Header)>), PakError> {
+    let image = MemoryImage { data, limits };
+
+    let mount_point = image.read_string(0)?;
+
+    let (files_start, file_count) = image.read_array(ARRAY_SIZE, ENTRY_SIZE)?;
+    check_entry_count(file_count, limits)?;
+    let headers = (0..file_count)
+        .map(|i| image.read_entry(files_start + i * ENTRY_SIZE, pak_version, compression))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut entries = Vec::new();
+    for directory in image.read_map(ARRAY_SIZE * 2, DIRECTORY_ELEMENT_SIZE)? {
+        let directory_name = image.read_string(directory)?;
+        for file in image.read_map(directory + ARRAY_SIZE, FILE_ELEMENT_SIZE)? {
+            let file_name = image.read_string(file)?;
+            let index = image.read_u32(file + ARRAY_SIZE)? as usize;
+
+            let mut path = directory_name
+                .strip_prefix('/')
+                .unwrap_or(&directory_name)
+                .to_owned();
+            path.push_str(&file_name);
+
+            let header = headers.get(index).ok_or_else(PakError::pak_invalid)?;
+            entries.push((path, header.clone()));
+            check_entry_count(entries.len(), limits)?;
+        }
+    }
+
+    Ok((mount_point, entries))
+}
+
+/// Fail if there are more entries than the limit
+fn check_entry_count(count: usize, limits: &PakLimits) -> Result<(), PakError> {
+    if count > limits.max_entry_count as usize {
+        return Err(PakError::limit_exceeded(
+            "Entry count",
+            count as u64,
+            limits.max_entry_count as u64,
+        ));
+    }
+    Ok(())
+}
+
+/// Bounds checked reads from a memory image
+struct MemoryImage<'a> {
+    data: &'a [u8],
+    limits: &'a PakLimits,
+}
+
+impl<'a> MemoryImage<'a> {
+    fn read_bytes<const N: usize>(&self, position: usize) -> Result<[u8; N], PakError> {
+        position
+            .checked_add(N)
+            .and_then(|end| self.data.get(position..end))
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(PakError::pak_invalid)
+    }
+
+    fn read_u32(&self, position: usize) -> Result<u32, PakError> {
+        Ok(u32::from_le_bytes(self.read_bytes(position)?))
+    }
+
+    fn read_u64(&self, position: usize) -> Result<u64, PakError> {
+        Ok(u64::from_le_bytes(self.read_bytes(position)?))
+    }
+
+    /// Resolve the pointer at the given position to the position it points to
+    fn read_pointer(&self, position: usize) -> Result<usize, PakError> {
+        let pointer = self.read_u64(position)? as i64;
+        // an unfrozen pointer can not be resolved
+        if pointer & 1 == 0 {
+            return Err(PakError::pak_invalid());
+        }
+
+        (position as i64)
+            .checked_add(pointer >> 1)
+            .and_then(|target| usize::try_from(target).ok())
+            .ok_or_else(PakError::pak_invalid)
+    }
+
+    /// Read the array at the given position, returning the position of the first element and
+    /// the element count after checking that all elements are inside of the image
+    fn read_array(&self, position: usize, element_size: usize) -> Result<(usize, usize), PakError> {
+        let count = self.read_u32(position + 8)? as usize;
+        if count == 0 {
+            return Ok((0, 0));
+        }
+
+        let start = self.read_pointer(position)?;
+        let end = count
+            .checked_mul(element_size)
+            .and_then(|size| start.checked_add(size))
+            .ok_or_else(PakError::pak_invalid)?;
+        if end > self.data.len() {
+            return Err(PakError::pak_invalid());
+        }
+
+        Ok((start, count))
+    }
+
+    fn read_string(&self, position: usize) -> Result<String, PakError> {
+        let (start, len) = self.read_array(position, 2)?;
+        if len * 2 > self.limits.max_string_len as usize {
+            return Err(PakError::limit_exceeded(
+                "String length",
+                len as u64 * 2,
+                self.limits.max_string_len as u64,
+            ));
+        }
+
+        let chars = self.data[start..start + len * 2]
+            .chunks_exact(2)
+            .map(|e| u16::from_le_bytes([e[0], e[1]]))
+            // the length includes the null terminator
+            .take_while(|e| *e != 0)
+            .collect::<Vec<_>>();
+        String::from_utf16(&chars).map_err(|_| PakError::pak_invalid())
+    }
+
+    /// Positions of all allocated elements of the map at the given position
+    fn read_map(&self, position: usize, element_size: usize) -> Result<Vec<usize>, PakError> {
+        let (start, count) = self.read_array(position, element_size)?;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        // one bit per element marks whether it is allocated
+        let bit_count = self.read_u32(position + ARRAY_SIZE + 8)? as usize;
+        if bit_count < count {
+            return Err(PakError::pak_invalid());
+        }
+        let bits = self.read_pointer(position + ARRAY_SIZE)?;
+
+        let mut elements = Vec::new();
+        for i in 0..count {
+            let word = self.read_u32(bits + i / 32 * 4)?;
+            if word & (1 << (i % 32)) != 0 {
+                elements.push(start + i * element_size);
+            }
+        }
+
+        Ok(elements)
+    }
+
+    fn read_entry(
+        &self,
+        position: usize,
+        pak_version: PakVersion,
+        compression: &CompressionMethods,
+    ) -> Result<Header, PakError> {
+        let (blocks_start, block_count) = self.read_array(position + 48, BLOCK_SIZE)?;
+        let compression_method =
+            Compression::from_u32(self.read_u32(position + 68)?, pak_version, compression);
+
+        let compression_blocks = match compression_method {
+            Compression::None => None,
+            _ => Some(
+                (0..block_count)
+                    .map(|i| {
+                        let start = self.read_u64(blocks_start + i * BLOCK_SIZE)?;
+                        let end = self.read_u64(blocks_start + i * BLOCK_SIZE + 8)?;
+                        Ok(Block {
+                            start,
+                            size: end.checked_sub(start).ok_or_else(PakError::entry_invalid)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, PakError>>()?,
+            ),
+        };
+
+        Ok(Header {
+            offset: self.read_u64(position)?,
+            compressed_size: self.read_u64(position + 8)?,
+            decompressed_size: self.read_u64(position + 16)?,
+            compression_method,
+            hash: self.read_bytes(position + 24)?,
+            compression_blocks,
+            flags: Some(self.read_bytes::<1>(position + 72)?[0]),
+            compression_block_size: Some(self.read_u32(position + 64)?),
+        })
+    }
+}
@@ -12,6 +12,21 @@
             - u64 block end
     - u8 is encrypted flag
     - u32 block size
+
+    encoded header (version >= 10, only in the index):
+    - u32 bitfield
+        - bits 0-5 compression block size >> 11 (0x3f if stored as extra u32)
+        - bits 6-21 compression block count
+        - bit 22 is encrypted flag
+        - bits 23-28 compression method
+        - bit 29 size fits u32
+        - bit 30 size decompressed fits u32
+        - bit 31 offset fits u32
+    - u32 block size (only when bits 0-5 are 0x3f)
+    - u32/u64 offset
+    - u32/u64 size decompressed
+    - u32/u64 size (only when compression method is not 0)
+    - u32 compressed block sizes (only when encrypted or more than one block)
 */
 
 use std::io::{self, Read, Seek, Write};
@@ -23,7 +38,12 @@ use crate::compression::{Compression, CompressionMethods};
 use crate::error::PakError;
 use crate::pakversion::PakVersion;
 
-#[derive(Debug)]
+/// Entry flag marking the entry data as encrypted
+pub(crate) const FLAG_ENCRYPTED: u8 = 0x01;
+/// Entry flag marking the entry as a delete record, which has no data
+pub(crate) const FLAG_DELETED: u8 = 0x02;
+
+#[derive(Debug, Clone)]
 pub(crate) struct Header {
     /// This may incorrectly be 0x00
     pub offset: u64,
@@ -75,20 +95,25 @@ impl Header {
         if pak_version >= PakVersion::CompressionEncryption {
             if !matches!(compression_method, Compression::None) {
                 let block_count = reader.read_u32::<LE>()? as usize;
-                let mut compression_blocks_inner = Vec::with_capacity(block_count);
+                let mut compression_blocks_inner = Vec::with_capacity(block_count.min(0x1000));
+
+                // convert old absolute to relative offsets
+                let base = if pak_version < PakVersion::RelativeChunkOffsets {
+                    offset
+                } else {
+                    0
+                };
 
                 for _ in 0..block_count {
-                    // convert old absolute to relative offsets
-                    let start_offset = reader.read_u64::<LE>()?
-                        - if pak_version < PakVersion::RelativeChunkOffsets {
-                            offset
-                        } else {
-                            0
-                        };
+                    let start_offset = reader.read_u64::<LE>()?;
                     let end_offset = reader.read_u64::<LE>()?;
                     compression_blocks_inner.push(Block {
-                        start: start_offset,
-                        size: end_offset - start_offset,
+                        start: start_offset
+                            .checked_sub(base)
+                            .ok_or_else(PakError::entry_invalid)?,
+                        size: end_offset
+                            .checked_sub(start_offset)
+                            .ok_or_else(PakError::entry_invalid)?,
                     });
                 }
                 compression_blocks = Some(compression_blocks_inner);
@@ -164,10 +189,90 @@ impl Header {
             hash: [0; 20],
             compression_blocks: None,
             compression_block_size: Some(block_size),
-            flags: Some(if is_encrypted { 1 } else { 0 }),
+            flags: Some(if is_encrypted { FLAG_ENCRYPTED } else { 0 }),
         })
     }
 
+    /// Whether this header can be bit encoded, otherwise it has to be stored in full in the index
+    pub(crate) fn is_encodable(
+        &self,
+        pak_version: PakVersion,
+        compression: &CompressionMethods,
+    ) -> bool {
+        let block_size = self.compression_block_size.unwrap_or(0);
+        let block_count = self
+            .compression_blocks
+            .as_ref()
+            .map_or(0, |blocks| blocks.len());
+
+        let compression_method = match self.compression_method.as_u32(pak_version, compression) {
+            Ok(method) => method,
+            Err(_) => return false,
+        };
+
+        // the flags of delete records can not be encoded
+        !self.is_deleted()
+            && block_size & 0x7ff == 0
+            && block_size >> 11 < 0x3f
+            && block_count <= 0xffff
+            && compression_method <= 0x3f
+            && self
+                .compression_blocks
+                .iter()
+                .flatten()
+                .all(|block| block.size <= u32::MAX as u64)
+    }
+
+    /// Write (bit)encoded header, check [`Header::is_encodable`] before calling this
+    pub(crate) fn write_encoded<W: Write>(
+        writer: &mut W,
+        pak_version: PakVersion,
+        compression: &CompressionMethods,
+        header: &Self,
+    ) -> Result<(), PakError> {
+        let mut header_bits = [0u8; 4];
+        let bits = header_bits.view_bits_mut::<Lsb0>();
+
+        let compression_blocks = header.compression_blocks.as_deref().unwrap_or_default();
+        let compression_method = header.compression_method.as_u32(pak_version, compression)?;
+
+        bits[0..=5].store_le(header.compression_block_size.unwrap_or(0) >> 11);
+        bits[6..=21].store_le(compression_blocks.len() as u32);
+        bits.set(22, header.is_encrypted());
+        bits[23..=28].store_le(compression_method);
+
+        let size_fits = header.compressed_size <= u32::MAX as u64;
+        let decompressed_size_fits = header.decompressed_size <= u32::MAX as u64;
+        let offset_fits = header.offset <= u32::MAX as u64;
+        bits.set(29, size_fits);
+        bits.set(30, decompressed_size_fits);
+        bits.set(31, offset_fits);
+
+        writer.write_all(&header_bits)?;
+
+        let mut write_size = |size: u64, fits: bool| -> io::Result<()> {
+            if fits {
+                writer.write_u32::<LE>(size as u32)
+            } else {
+                writer.write_u64::<LE>(size)
+            }
+        };
+
+        write_size(header.offset, offset_fits)?;
+        write_size(header.decompressed_size, decompressed_size_fits)?;
+        if !matches!(header.compression_method, Compression::None) {
+            write_size(header.compressed_size, size_fits)?;
+        }
+
+        if header.is_encrypted() || compression_blocks.len() > 1 {
+            for block in compression_blocks {
+                writer.write_u32::<LE>(block.size as u32)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Write data from a Header into the writer, writer needs to be set where the header is supposed to be written
     pub(crate) fn write<W: Write>(
         writer: &mut W,
@@ -180,15 +285,27 @@ impl Header {
         writer.write_u64::<LE>(header.decompressed_size)?;
         writer.write_u32::<LE>(header.compression_method.as_u32(pak_version, compression)?)?;
 
+        if pak_version <= PakVersion::Initial {
+            // the timestamp field is required by v1 paks, write 0 to keep the output reproducible
+            writer.write_u64::<LE>(0)?;
+        }
+
         writer.write_all(&header.hash)?;
 
         if pak_version >= PakVersion::CompressionEncryption {
             if !matches!(header.compression_method, Compression::None) {
                 if let Some(compression_blocks) = &header.compression_blocks {
+                    // convert relative to old absolute offsets
+                    let base = if pak_version < PakVersion::RelativeChunkOffsets {
+                        header.offset
+                    } else {
+                        0
+                    };
+
                     writer.write_u32::<LE>(compression_blocks.len() as u32)?;
                     for block in compression_blocks {
-                        writer.write_u64::<LE>(block.start)?;
-                        writer.write_u64::<LE>(block.start + block.size)?;
+                        writer.write_u64::<LE>(base + block.start)?;
+                        writer.write_u64::<LE>(base + block.start + block.size)?;
                     }
                 }
             }
@@ -200,6 +317,30 @@ impl Header {
         Ok(())
     }
 
+    /// Whether the data of this entry is encrypted
+    pub(crate) fn is_encrypted(&self) -> bool {
+        self.flags.unwrap_or(0) & FLAG_ENCRYPTED != 0
+    }
+
+    /// Whether this entry is a delete record, hiding the file in lower priority paks
+    pub(crate) fn is_deleted(&self) -> bool {
+        self.flags.unwrap_or(0) & FLAG_DELETED != 0
+    }
+
+    /// Create the header of a delete record, these are only stored in the index
+    pub(crate) fn delete_record() -> Self {
+        Header {
+            offset: 0,
+            compressed_size: 0,
+            decompressed_size: 0,
+            compression_method: Compression::None,
+            hash: [0; 20],
+            compression_blocks: None,
+            flags: Some(FLAG_DELETED),
+            compression_block_size: Some(0),
+        }
+    }
+
     pub(crate) fn calculate_header_len(pak_version: PakVersion, block_count: Option<u32>) -> u64 {
         let mut len = 0;
 
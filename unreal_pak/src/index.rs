@@ -1,97 +1,197 @@
+use std::collections::BTreeMap;
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
 use byteorder::{ReadBytesExt, WriteBytesExt, BE, LE};
 
-use unreal_helpers::{UnrealReadExt, UnrealWriteExt};
+use unreal_helpers::{read_ext::read_fstring_len, UnrealWriteExt};
 
 use crate::compression::CompressionMethods;
+use crate::encryption::{self, EncryptionKey};
 use crate::error::PakError;
+use crate::frozen_index::read_frozen_index;
 use crate::header::Header;
+use crate::limits::PakLimits;
 use crate::pakversion::PakVersion;
-use crate::{hash, PAK_MAGIC};
+use crate::verify::IndexPart;
+use crate::{hash, read_data, PAK_MAGIC};
 
 #[derive(Debug)]
 pub(crate) struct Index {
     pub mount_point: String,
     pub path_hash_seed: Option<u64>,
     pub entries: Vec<(String, Header)>,
+    /// Path hash and full directory index (v10+), only filled when reading
+    pub directory_indexes: Vec<IndexPartLocation>,
     pub footer: Footer,
 }
 
-impl Index {
-    pub(crate) fn read<R: Read + Seek>(mut reader: &mut R) -> Result<Self, PakError> {
-        let footer = Footer::read(&mut reader)?;
+/// Where a part of the index is stored and the hash it should have
+#[derive(Debug, Clone)]
+pub(crate) struct IndexPartLocation {
+    pub part: IndexPart,
+    pub offset: u64,
+    pub size: u64,
+    pub hash: [u8; 20],
+    pub encrypted: bool,
+}
+
+impl IndexPartLocation {
+    fn read<R: Read>(reader: &mut R, part: IndexPart, encrypted: bool) -> Result<Self, PakError> {
+        let offset = reader.read_u64::<LE>()?;
+        let size = reader.read_u64::<LE>()?;
+        let mut hash = [0u8; 20];
+        reader.read_exact(&mut hash)?;
+
+        Ok(IndexPartLocation {
+            part,
+            offset,
+            size,
+            hash,
+            encrypted,
+        })
+    }
+}
 
-        reader.seek(SeekFrom::Start(footer.index_offset))?;
+impl Index {
+    pub(crate) fn read<R: Read + Seek>(
+        reader: &mut R,
+        key: Option<&EncryptionKey>,
+        limits: &PakLimits,
+    ) -> Result<Self, PakError> {
+        let footer = Footer::read(reader)?;
+        let index_encrypted = footer.index_encrypted.unwrap_or(false);
+
+        check_index_size(footer.index_size, limits)?;
+        let mut index = Cursor::new(read_index_data(
+            reader,
+            footer.index_offset,
+            footer.index_size,
+            index_encrypted,
+            key,
+        )?);
+
+        if footer.index_frozen {
+            let (mount_point, entries) = read_frozen_index(
+                index.get_ref(),
+                footer.pak_version,
+                &footer.compression_methods,
+                limits,
+            )?;
+            return Ok(Index {
+                mount_point,
+                path_hash_seed: None,
+                entries,
+                directory_indexes: Vec::new(),
+                footer,
+            });
+        }
 
-        let mount_point = reader.read_fstring()?.unwrap_or_default();
+        let mount_point = read_string(&mut index, limits)?;
         let mut path_hash_seed = None;
+        let mut directory_indexes = Vec::new();
 
-        let entry_count = reader.read_u32::<LE>()?;
-        let mut entries = Vec::with_capacity(entry_count as usize);
+        let entry_count = index.read_u32::<LE>()?;
+        check_entry_count(entry_count as u64, limits)?;
+        // every entry takes up at least a few bytes, so this can not exceed the index size
+        let mut entries = Vec::with_capacity((entry_count as usize).min(index.get_ref().len() / 4));
 
         if footer.pak_version < PakVersion::PathHashIndex {
             for _ in 0..entry_count {
-                let file_name = reader.read_fstring()?.unwrap_or_default();
+                let file_name = read_string(&mut index, limits)?;
 
                 entries.push((
                     file_name,
-                    Header::read(reader, footer.pak_version, &footer.compression_methods)?,
+                    Header::read(&mut index, footer.pak_version, &footer.compression_methods)?,
                 ));
             }
         } else {
-            path_hash_seed = Some(reader.read_u64::<LE>()?);
+            path_hash_seed = Some(index.read_u64::<LE>()?);
 
             // path hash index
-            if reader.read_u32::<LE>()? != 0 {
-                let _path_hash_index_offset = reader.read_u64::<LE>()?;
-                let _path_hash_index_size = reader.read_u64::<LE>()?;
-                // skip hash
-                reader.seek(SeekFrom::Current(20))?;
+            if index.read_u32::<LE>()? != 0 {
+                directory_indexes.push(IndexPartLocation::read(
+                    &mut index,
+                    IndexPart::PathHashIndex,
+                    index_encrypted,
+                )?);
             }
 
-            let full_directory_index = if reader.read_u32::<LE>()? != 0 {
-                let full_directory_index_offset = reader.read_u64::<LE>()?;
-                let _full_directory_index_size = reader.read_u64::<LE>()?;
-                // skip hash
-                reader.seek(SeekFrom::Current(20))?;
-
-                let previous_pos = reader.stream_position()?;
-                reader.seek(SeekFrom::Start(full_directory_index_offset))?;
+            let full_directory_index = if index.read_u32::<LE>()? != 0 {
+                let location = IndexPartLocation::read(
+                    &mut index,
+                    IndexPart::FullDirectoryIndex,
+                    index_encrypted,
+                )?;
 
-                let directory_count = reader.read_u32::<LE>()? as usize;
+                check_index_size(location.size, limits)?;
+                let mut directory_index = Cursor::new(read_index_data(
+                    reader,
+                    location.offset,
+                    location.size,
+                    index_encrypted,
+                    key,
+                )?);
+                directory_indexes.push(location);
+
+                let directory_count = directory_index.read_u32::<LE>()? as usize;
                 let mut directories = Vec::new();
+                let mut total_file_count = 0u64;
                 for _ in 0..directory_count {
-                    let directory_name = reader.read_fstring()?.unwrap_or_default();
-                    let file_count = reader.read_u32::<LE>()? as usize;
+                    let directory_name = read_string(&mut directory_index, limits)?;
+                    let file_count = directory_index.read_u32::<LE>()? as usize;
+                    total_file_count += file_count as u64;
+                    check_entry_count(total_file_count, limits)?;
+
                     let mut files = Vec::new();
                     for _ in 0..file_count {
-                        let file_name = reader.read_fstring()?.unwrap_or_default();
-                        files.push((file_name, reader.read_u32::<LE>()?));
+                        let file_name = read_string(&mut directory_index, limits)?;
+                        files.push((file_name, directory_index.read_i32::<LE>()?));
                     }
                     directories.push((directory_name, files));
                 }
 
-                reader.seek(SeekFrom::Start(previous_pos))?;
                 directories
             } else {
                 return Err(PakError::pak_invalid());
             };
 
-            let _encoded_size = reader.read_u32::<LE>()? as usize;
-            let position = reader.stream_position()?;
+            let encoded_size = index.read_u32::<LE>()? as u64;
+            let encoded_entries = read_data(&mut index, encoded_size)?;
+
+            // entries which could not be bit encoded are stored as full headers
+            let unencoded_count = index.read_u32::<LE>()? as usize;
+            check_entry_count(unencoded_count as u64, limits)?;
+            let mut unencoded_entries = Vec::new();
+            for _ in 0..unencoded_count {
+                unencoded_entries.push(Header::read(
+                    &mut index,
+                    footer.pak_version,
+                    &footer.compression_methods,
+                )?);
+            }
 
             for (dir_name, dir) in &full_directory_index {
                 for (file_name, encoded_offset) in dir {
                     let mut path = dir_name.strip_prefix('/').unwrap_or(dir_name).to_owned();
                     path.push_str(file_name);
 
-                    reader.seek(SeekFrom::Start(position + *encoded_offset as u64))?;
-                    let entry = Header::read_encoded(
-                        &mut reader,
-                        footer.pak_version,
-                        &footer.compression_methods,
-                    )?;
+                    let entry = if *encoded_offset >= 0 {
+                        let mut encoded = Cursor::new(
+                            encoded_entries
+                                .get(*encoded_offset as usize..)
+                                .ok_or_else(PakError::pak_invalid)?,
+                        );
+                        Header::read_encoded(
+                            &mut encoded,
+                            footer.pak_version,
+                            &footer.compression_methods,
+                        )?
+                    } else {
+                        unencoded_entries
+                            .get((-(*encoded_offset as i64) - 1) as usize)
+                            .ok_or_else(PakError::pak_invalid)?
+                            .clone()
+                    };
 
                     entries.push((path, entry));
                 }
@@ -102,12 +202,49 @@ impl Index {
             mount_point,
             path_hash_seed,
             entries,
+            directory_indexes,
             footer,
         })
     }
 
-    pub(crate) fn write<W: Write + Seek>(writer: &mut W, mut index: Self) -> Result<(), PakError> {
+    /// Locations of all parts of the index which were read, the primary index first
+    pub(crate) fn part_locations(&self) -> Vec<IndexPartLocation> {
+        let mut locations = vec![IndexPartLocation {
+            part: IndexPart::PrimaryIndex,
+            offset: self.footer.index_offset,
+            size: self.footer.index_size,
+            hash: self.footer.index_hash,
+            encrypted: self.footer.index_encrypted.unwrap_or(false),
+        }];
+        locations.extend(self.directory_indexes.iter().cloned());
+        locations
+    }
+
+    pub(crate) fn write<W: Write + Seek>(
+        writer: &mut W,
+        index: Self,
+        key: Option<&EncryptionKey>,
+    ) -> Result<(), PakError> {
+        let footer = Self::write_without_footer(writer, index, key)?;
+        Footer::write(writer, footer)
+    }
+
+    /// Write the index at the current position, returning the footer which still has to be written
+    pub(crate) fn write_without_footer<W: Write + Seek>(
+        writer: &mut W,
+        mut index: Self,
+        key: Option<&EncryptionKey>,
+    ) -> Result<Footer, PakError> {
         let index_offset = writer.stream_position()?;
+        let index_encrypted = index.footer.index_encrypted.unwrap_or(false);
+        let pak_version = index.footer.pak_version;
+        let compression = index.footer.compression_methods;
+
+        if pak_version < PakVersion::DeleteRecords
+            && index.entries.iter().any(|(_, header)| header.is_deleted())
+        {
+            return Err(PakError::pak_version_unsupported(pak_version));
+        }
 
         let mut index_writer = Cursor::new(Vec::new());
 
@@ -115,32 +252,123 @@ impl Index {
 
         index_writer.write_u32::<LE>(index.entries.len() as u32)?;
 
-        if index.footer.pak_version < PakVersion::PathHashIndex {
-            for (name, header) in index.entries {
+        let mut secondary_indexes = Vec::new();
+
+        if pak_version < PakVersion::PathHashIndex {
+            for (name, header) in &index.entries {
                 index_writer.write_fstring(Some(name.as_str()))?;
-                Header::write(
-                    &mut index_writer,
-                    index.footer.pak_version,
-                    &index.footer.compression_methods,
-                    &header,
-                )?;
+                Header::write(&mut index_writer, pak_version, &compression, header)?;
             }
         } else {
-            dbg!(index.path_hash_seed);
-            return Err(PakError::pak_version_unsupported(index.footer.pak_version));
+            let path_hash_seed = index.path_hash_seed.unwrap_or_default();
+
+            let mut encoded_entries = Cursor::new(Vec::new());
+            let mut unencoded_entries = Vec::new();
+            let mut path_hash_index = Vec::with_capacity(index.entries.len());
+            let mut directory_index: BTreeMap<String, BTreeMap<String, i32>> = BTreeMap::new();
+
+            for (name, header) in &index.entries {
+                // negative locations point into the list of headers which could not be encoded
+                let location = if header.is_encodable(pak_version, &compression) {
+                    let location = encoded_entries.position() as i32;
+                    Header::write_encoded(&mut encoded_entries, pak_version, &compression, header)?;
+                    location
+                } else {
+                    unencoded_entries.push(header);
+                    -(unencoded_entries.len() as i32)
+                };
+
+                path_hash_index
+                    .push((fnv64_path_hash(name, path_hash_seed, pak_version), location));
+
+                let (directory, file_name) = split_path(name);
+                directory_index
+                    .entry(directory.clone())
+                    .or_default()
+                    .insert(file_name, location);
+
+                // the engine expects every parent directory to be listed as well
+                let mut parent = directory;
+                while parent != "/" {
+                    parent = split_path(parent.trim_end_matches('/')).0;
+                    directory_index.entry(parent.clone()).or_default();
+                }
+            }
+
+            let mut path_hash_index_writer = Cursor::new(Vec::new());
+            path_hash_index_writer.write_u32::<LE>(path_hash_index.len() as u32)?;
+            for (path_hash, location) in path_hash_index {
+                path_hash_index_writer.write_u64::<LE>(path_hash)?;
+                path_hash_index_writer.write_i32::<LE>(location)?;
+            }
+            // pruned directory index, not needed when the full directory index is present
+            path_hash_index_writer.write_u32::<LE>(0)?;
+
+            let mut directory_index_writer = Cursor::new(Vec::new());
+            directory_index_writer.write_u32::<LE>(directory_index.len() as u32)?;
+            for (directory, files) in directory_index {
+                directory_index_writer.write_fstring(Some(&directory))?;
+                directory_index_writer.write_u32::<LE>(files.len() as u32)?;
+                for (file_name, location) in files {
+                    directory_index_writer.write_fstring(Some(&file_name))?;
+                    directory_index_writer.write_i32::<LE>(location)?;
+                }
+            }
+
+            secondary_indexes.push(pad_index_data(
+                path_hash_index_writer.into_inner(),
+                index_encrypted,
+            ));
+            secondary_indexes.push(pad_index_data(
+                directory_index_writer.into_inner(),
+                index_encrypted,
+            ));
+
+            let mut entries_writer = Cursor::new(Vec::new());
+            entries_writer.write_u32::<LE>(encoded_entries.get_ref().len() as u32)?;
+            entries_writer.write_all(encoded_entries.get_ref())?;
+            entries_writer.write_u32::<LE>(unencoded_entries.len() as u32)?;
+            for header in unencoded_entries {
+                Header::write(&mut entries_writer, pak_version, &compression, header)?;
+            }
+            let entries_data = entries_writer.into_inner();
+
+            index_writer.write_u64::<LE>(path_hash_seed)?;
+
+            // the secondary indexes are written right after the primary index,
+            // the remaining fields of which are (u32 flag, u64 offset, u64 size, hash) twice
+            let primary_len =
+                index_writer.position() + 2 * (4 + 8 + 8 + 20) + entries_data.len() as u64;
+            let mut secondary_offset = index_offset + pad_len(primary_len, index_encrypted);
+
+            for secondary_index in &secondary_indexes {
+                index_writer.write_u32::<LE>(1)?;
+                index_writer.write_u64::<LE>(secondary_offset)?;
+                index_writer.write_u64::<LE>(secondary_index.len() as u64)?;
+                index_writer.write_all(&hash(secondary_index))?;
+                secondary_offset += secondary_index.len() as u64;
+            }
+
+            index_writer.write_all(&entries_data)?;
         }
 
-        let index_data = index_writer.into_inner();
+        // the hashes are of the padded but not yet encrypted index
+        let index_data = pad_index_data(index_writer.into_inner(), index_encrypted);
+
         index.footer.index_offset = index_offset;
         index.footer.index_size = index_data.len() as u64;
 
         index.footer.index_hash = hash(&index_data);
 
-        writer.write_all(&index_data)?;
-
-        Footer::write(writer, index.footer)?;
+        for mut data in std::iter::once(index_data).chain(secondary_indexes) {
+            if index_encrypted {
+                key.ok_or_else(PakError::encryption_key_missing)?
+                    .encrypt(&mut data)?;
+            }
+            writer.write_all(&data)?;
+        }
 
-        Ok(())
+        Ok(index.footer)
     }
 }
 
@@ -153,6 +381,8 @@ pub(crate) struct Footer {
     pub compression_methods: CompressionMethods,
     pub index_encrypted: Option<bool>,
     pub encryption_key_guid: Option<[u8; 0x10]>,
+    /// Whether the index is stored as a memory image (v9 only), these can only be read
+    pub index_frozen: bool,
 }
 
 impl Footer {
@@ -173,6 +403,9 @@ impl Footer {
         reader.seek(SeekFrom::End(magic_offset + 4))?;
 
         let mut pak_version = PakVersion::from_num(reader.read_u32::<LE>()?);
+        if pak_version == PakVersion::Invalid {
+            return Err(PakError::pak_version_unsupported(pak_version));
+        }
         if magic_offset == -0xAC {
             pak_version.set_subversion();
         }
@@ -183,9 +416,9 @@ impl Footer {
         let mut index_hash = [0u8; 20];
         reader.read_exact(&mut index_hash)?;
 
-        // if version 9 skip frozen index byte
+        let mut index_frozen = false;
         if pak_version == PakVersion::FrozenIndex {
-            reader.seek(SeekFrom::Current(1))?;
+            index_frozen = reader.read_u8()? != 0;
         }
 
         let compression_methods = if pak_version >= PakVersion::FnameBasedCompressionMethod {
@@ -218,6 +451,7 @@ impl Footer {
             compression_methods,
             index_encrypted,
             encryption_key_guid,
+            index_frozen,
         })
     }
 
@@ -236,7 +470,7 @@ impl Footer {
 
         // write magic and pak version
         writer.write_u32::<BE>(PAK_MAGIC)?;
-        writer.write_u32::<LE>(footer.pak_version.to_num())?;
+        writer.write_u32::<LE>(footer.pak_version.to_num()?)?;
 
         // write index offset and length
         writer.write_u64::<LE>(footer.index_offset)?;
@@ -245,7 +479,7 @@ impl Footer {
         // write hash
         writer.write_all(&footer.index_hash)?;
 
-        // frozen index
+        // frozen index, only the regular index layout is written
         if footer.pak_version == PakVersion::FrozenIndex {
             writer.write_u8(0)?;
         }
@@ -259,6 +493,118 @@ impl Footer {
     }
 }
 
+/// Fail if a part of the index is larger than the limit
+fn check_index_size(size: u64, limits: &PakLimits) -> Result<(), PakError> {
+    if size > limits.max_index_size {
+        return Err(PakError::limit_exceeded(
+            "Index size",
+            size,
+            limits.max_index_size,
+        ));
+    }
+    Ok(())
+}
+
+/// Fail if there are more entries than the limit
+fn check_entry_count(count: u64, limits: &PakLimits) -> Result<(), PakError> {
+    if count > limits.max_entry_count as u64 {
+        return Err(PakError::limit_exceeded(
+            "Entry count",
+            count,
+            limits.max_entry_count as u64,
+        ));
+    }
+    Ok(())
+}
+
+/// Read an FString from the index, checking its length against the limit first
+fn read_string<R: Read + Seek>(reader: &mut R, limits: &PakLimits) -> Result<String, PakError> {
+    let len = reader.read_i32::<LE>()?;
+    let is_wide = len < 0;
+    let chars = len.unsigned_abs();
+
+    let byte_len = chars as u64 * if is_wide { 2 } else { 1 };
+    if byte_len > limits.max_string_len as u64 {
+        return Err(PakError::limit_exceeded(
+            "String length",
+            byte_len,
+            limits.max_string_len as u64,
+        ));
+    }
+
+    Ok(read_fstring_len(reader, chars as i32, is_wide)?.unwrap_or_default())
+}
+
+/// Read a part of the index, decrypting it if required
+pub(crate) fn read_index_data<R: Read + Seek>(
+    reader: &mut R,
+    offset: u64,
+    size: u64,
+    encrypted: bool,
+    key: Option<&EncryptionKey>,
+) -> Result<Vec<u8>, PakError> {
+    reader.seek(SeekFrom::Start(offset))?;
+
+    let mut data = read_data(reader, size)?;
+
+    if encrypted {
+        encryption::decrypt(key, &mut data)?;
+    }
+
+    Ok(data)
+}
+
+/// Pad a part of the index to the AES block size if it is going to be encrypted
+fn pad_index_data(mut data: Vec<u8>, encrypted: bool) -> Vec<u8> {
+    data.resize(pad_len(data.len() as u64, encrypted) as usize, 0);
+    data
+}
+
+fn pad_len(len: u64, encrypted: bool) -> u64 {
+    if encrypted {
+        encryption::align(len)
+    } else {
+        len
+    }
+}
+
+/// Split a path relative to the mount point into the directory as stored in the
+/// directory index (with a trailing `/`, `/` for the root) and the file name
+fn split_path(path: &str) -> (String, String) {
+    match path.rsplit_once('/') {
+        Some((directory, file_name)) if !directory.is_empty() => {
+            (directory.to_owned() + "/", file_name.to_owned())
+        }
+        Some((_, file_name)) => ("/".to_owned(), file_name.to_owned()),
+        None => ("/".to_owned(), path.to_owned()),
+    }
+}
+
+/// FNV64 hash of a path relative to the mount point as stored in the path hash index.
+/// The engine hashes the lowercase UTF-16 path, before [`PakVersion::Fnv64BugFix`] only
+/// the first half of the bytes got hashed.
+pub(crate) fn fnv64_path_hash(path: &str, seed: u64, pak_version: PakVersion) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x00000100000001b3;
+
+    let bytes = path
+        .to_lowercase()
+        .encode_utf16()
+        .flat_map(u16::to_le_bytes)
+        .collect::<Vec<_>>();
+    let len = if pak_version >= PakVersion::Fnv64BugFix {
+        bytes.len()
+    } else {
+        bytes.len() / 2
+    };
+
+    bytes[..len]
+        .iter()
+        .fold(OFFSET.wrapping_add(seed), |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(PRIME)
+        })
+}
+
 // 64 bit LE num, but always less than u32::MAX
 pub(crate) fn random_path_hash_seed() -> u64 {
     use rand::Rng;
@@ -16,27 +16,60 @@ File parts:
 //!
 //! Utility crate for working with Unreal Engine .pak files.
 //! Supports both reading and writing and aims to support all pak versions.
-//! Encrytion is currently unsupported
+//! Encrypted paks can be read and written by providing an [`EncryptionKey`].
+//! Paks for games with pak signing enabled can be signed with [`PakSignature`].
+//!
+//! ## Feature flags
+//!
+//! - `threading`: Enables compressing entries on a thread pool.
+//! - `serde`: Enables `serde` support for [`diff::PakDiff`] and [`pakreader::EntryInfo`].
 
 pub mod compression;
+pub mod diff;
+pub mod encryption;
 mod entry;
 pub mod error;
+mod frozen_index;
 mod header;
 mod index;
+pub mod limits;
 pub mod pakmemory;
 pub mod pakreader;
+pub mod pakupdater;
 pub mod pakversion;
 pub mod pakwriter;
+pub mod sharedpakreader;
+pub mod signature;
+pub mod verify;
+pub mod vfs;
+mod workers;
 
 pub use pakmemory::PakMemory;
 pub use pakreader::PakReader;
+pub use pakupdater::PakUpdater;
 pub use pakwriter::PakWriter;
+pub use sharedpakreader::SharedPakReader;
+pub use signature::PakSignature;
+pub use vfs::PakVfs;
 
 pub use compression::Compression;
+pub use encryption::EncryptionKey;
 pub use error::PakError;
+pub use limits::PakLimits;
 
 pub(crate) const PAK_MAGIC: u32 = u32::from_be_bytes([0xE1, 0x12, 0x6F, 0x5A]);
 
+/// Read exactly `len` bytes, only allocating as much memory as data is actually available
+pub(crate) fn read_data<R: std::io::Read>(reader: &mut R, len: u64) -> std::io::Result<Vec<u8>> {
+    use std::io::{Error, ErrorKind, Read};
+    let mut data = Vec::with_capacity(len.min(0x100000) as usize);
+    reader.by_ref().take(len).read_to_end(&mut data)?;
+    if (data.len() as u64) < len {
+        return Err(Error::from(ErrorKind::UnexpectedEof));
+    }
+    Ok(data)
+}
+
 pub(crate) fn hash(data: &[u8]) -> [u8; 20] {
     use sha1::{Digest, Sha1};
     let mut hasher = Sha1::new();
@@ -0,0 +1,42 @@
+//! Limits applied while parsing pak files
+
+/// Limits checked against the sizes and counts read from a pak index or entry header before
+/// anything is allocated for them.
+///
+/// The default entry count and index size allow an index with a few million files, entries
+/// may decompress to up to 4 GiB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PakLimits {
+    /// Maximum amount of entries and delete records in the index
+    pub max_entry_count: u32,
+    /// Maximum size of each part of the index in bytes
+    pub max_index_size: u64,
+    /// Maximum length of the mount point, entry names and directory names in bytes
+    pub max_string_len: u32,
+    /// Maximum decompressed size of a single entry in bytes
+    pub max_entry_size: u64,
+}
+
+impl Default for PakLimits {
+    fn default() -> Self {
+        Self {
+            max_entry_count: 0x400000,
+            max_index_size: 0x40000000,
+            max_string_len: 0x1000,
+            max_entry_size: 0x100000000,
+        }
+    }
+}
+
+impl PakLimits {
+    /// Limits which accept any count or size stored in the pak, for trusted files with entries
+    /// larger than the default 4 GiB
+    pub fn unlimited() -> Self {
+        Self {
+            max_entry_count: u32::MAX,
+            max_index_size: u64::MAX,
+            max_string_len: u32::MAX,
+            max_entry_size: u64::MAX,
+        }
+    }
+}
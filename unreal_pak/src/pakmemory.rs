@@ -1,13 +1,18 @@
 //! PakMemory data structure for more flexible pak files
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::{Read, Seek, Write};
 
-use crate::compression::CompressionMethods;
-use crate::entry::{read_entry, write_entry};
+use crate::compression::{Compression, CompressionMethods};
+use crate::encryption::EncryptionKey;
+use crate::entry::{prepare_entry, read_entry, write_prepared_entry};
 use crate::error::PakError;
-use crate::index::{random_path_hash_seed, Footer, Index};
+use crate::header::Header;
+use crate::index::{Footer, Index};
+use crate::limits::PakLimits;
 use crate::pakversion::PakVersion;
+use crate::pakwriter::PathHashSeed;
+use crate::workers::Workers;
 
 /// A Unreal Pak file which keeps all of it's data in memory.
 /// It allows reading and writing of the same entries before comitting the file to disk.
@@ -21,7 +26,12 @@ pub struct PakMemory {
     compression: CompressionMethods,
     /// the compression block size
     pub block_size: u32,
+    /// how the seed of the path hash index is chosen when writing
+    pub path_hash_seed: PathHashSeed,
+    workers: Workers,
+    limits: PakLimits,
     entries: BTreeMap<String, Vec<u8>>,
+    delete_records: BTreeSet<String>,
 }
 
 impl PakMemory {
@@ -32,19 +42,50 @@ impl PakMemory {
             mount_point: "../../../".to_owned(),
             compression: CompressionMethods::default(),
             block_size: 0x010000,
+            path_hash_seed: PathHashSeed::default(),
+            workers: Workers::default(),
+            limits: PakLimits::default(),
             entries: BTreeMap::new(),
+            delete_records: BTreeSet::new(),
         }
     }
 
+    /// Sets the limits used to reject malformed pak files when loading them
+    pub fn set_limits(&mut self, limits: PakLimits) {
+        self.limits = limits;
+    }
+
     /// Loads the data contained in the pak file in the reader into this PakMemory
-    pub fn load<R: Read + Seek>(&mut self, mut reader: &mut R) -> Result<(), PakError> {
-        let index = Index::read(reader)?;
+    pub fn load<R: Read + Seek>(&mut self, reader: &mut R) -> Result<(), PakError> {
+        self.load_with_key(reader, None)
+    }
+
+    /// Loads the data contained in the encrypted pak file in the reader into this PakMemory
+    pub fn load_encrypted<R: Read + Seek>(
+        &mut self,
+        reader: &mut R,
+        key: &EncryptionKey,
+    ) -> Result<(), PakError> {
+        self.load_with_key(reader, Some(key))
+    }
+
+    fn load_with_key<R: Read + Seek>(
+        &mut self,
+        mut reader: &mut R,
+        key: Option<&EncryptionKey>,
+    ) -> Result<(), PakError> {
+        let index = Index::read(reader, key, &self.limits)?;
 
         self.pak_version = index.footer.pak_version;
         self.mount_point = index.mount_point.clone();
         self.compression = index.footer.compression_methods;
 
         for (name, header) in index.entries {
+            if header.is_deleted() {
+                self.delete_records.insert(name);
+                continue;
+            }
+
             self.entries.insert(
                 name,
                 read_entry(
@@ -52,6 +93,8 @@ impl PakMemory {
                     self.pak_version,
                     &self.compression,
                     header.offset,
+                    key,
+                    &self.limits,
                 )?,
             );
         }
@@ -66,6 +109,30 @@ impl PakMemory {
         Ok(pak_memory)
     }
 
+    /// Create a new PakMemory based on the data of the encrypted pak file in the reader.
+    pub fn load_from_encrypted<R: Read + Seek>(
+        reader: &mut R,
+        key: &EncryptionKey,
+    ) -> Result<Self, PakError> {
+        let mut pak_memory = Self::new(PakVersion::Invalid);
+        pak_memory.load_encrypted(reader, key)?;
+        Ok(pak_memory)
+    }
+
+    /// Sets the compression method used when writing this PakMemory.
+    pub fn set_compression(&mut self, compression: Compression) {
+        self.compression = CompressionMethods::single(compression);
+    }
+
+    /// Sets the amount of threads used to compress entries when writing.
+    /// `0` uses one thread per core, `1` (the default) compresses on the calling thread.
+    /// The written data does not depend on the amount of threads.
+    #[cfg(feature = "threading")]
+    pub fn set_jobs(&mut self, jobs: usize) -> Result<(), PakError> {
+        self.workers = Workers::new(jobs)?;
+        Ok(())
+    }
+
     /// Returns the names of all entries stored in this PakMemory.
     pub fn get_entry_names(&self) -> Vec<&String> {
         self.entries.keys().collect()
@@ -81,26 +148,85 @@ impl PakMemory {
         self.entries.get(name)
     }
 
-    /// Set the data for an entry
+    /// Set the data for an entry, replacing a delete record with the same name
     pub fn set_entry(&mut self, name: String, data: Vec<u8>) {
+        self.delete_records.remove(&name);
         self.entries.insert(name, data);
     }
 
+    /// Returns the names of all delete records, files which this pak removes from
+    /// paks with a lower priority.
+    pub fn get_delete_records(&self) -> Vec<&String> {
+        self.delete_records.iter().collect()
+    }
+
+    /// Checks if the pak file contains a delete record with the given name
+    pub fn contains_delete_record(&self, name: &String) -> bool {
+        self.delete_records.contains(name)
+    }
+
+    /// Add a delete record which removes the file with the given name from paks with a lower
+    /// priority, replacing an entry with the same name.
+    /// Requires at least [`PakVersion::DeleteRecords`] when writing.
+    pub fn set_delete_record(&mut self, name: String) {
+        self.entries.remove(&name);
+        self.delete_records.insert(name);
+    }
+
+    /// Remove an entry or delete record from this PakMemory
+    pub fn remove_entry(&mut self, name: &String) {
+        self.entries.remove(name);
+        self.delete_records.remove(name);
+    }
+
     /// Write all the data as a finished pak file into the provided writer.
     pub fn write<W: Write + Seek>(&self, writer: &mut W) -> Result<(), PakError> {
-        let mut written_entries = Vec::new();
+        self.write_with_key(writer, None, [0u8; 0x10], false)
+    }
 
-        for (name, data) in self.entries.iter() {
-            let header = write_entry(
-                writer,
+    /// Write all the data as a finished pak file into the provided writer,
+    /// encrypting all entries and optionally the index with the given key.
+    /// The GUID of the key is stored in the footer, the engine uses it to find the matching key.
+    pub fn write_encrypted<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        key: &EncryptionKey,
+        encryption_key_guid: [u8; 0x10],
+        encrypt_index: bool,
+    ) -> Result<(), PakError> {
+        self.write_with_key(writer, Some(key), encryption_key_guid, encrypt_index)
+    }
+
+    fn write_with_key<W: Write + Seek>(
+        &self,
+        writer: &mut W,
+        key: Option<&EncryptionKey>,
+        encryption_key_guid: [u8; 0x10],
+        encrypt_index: bool,
+    ) -> Result<(), PakError> {
+        // compress all entries first, which can happen in parallel, then write them in order
+        let entries = self.entries.iter().collect::<Vec<_>>();
+        let prepared_entries = self.workers.map(&entries, |(_, data)| {
+            prepare_entry(
                 self.pak_version,
                 data,
                 true,
                 &self.compression,
                 self.block_size,
-            )?;
+                key,
+                &self.workers,
+            )
+        })?;
+
+        let mut written_entries = Vec::with_capacity(entries.len());
+        for ((name, _), entry) in entries.into_iter().zip(prepared_entries) {
+            let header = write_prepared_entry(writer, self.pak_version, &self.compression, entry)?;
             written_entries.push((name.clone(), header));
         }
+        for name in &self.delete_records {
+            written_entries.push((name.clone(), Header::delete_record()));
+        }
+        written_entries.sort_by(|(a, _), (b, _)| a.cmp(b));
 
         let footer = Footer {
             pak_version: self.pak_version,
@@ -109,18 +235,23 @@ impl PakMemory {
             index_size: 0,
             index_hash: [0u8; 20],
             compression_methods: self.compression,
-            index_encrypted: Some(false),
-            encryption_key_guid: Some([0u8; 0x10]),
+            index_encrypted: Some(encrypt_index && key.is_some()),
+            encryption_key_guid: Some(encryption_key_guid),
+            index_frozen: false,
         };
 
         let index = Index {
             mount_point: self.mount_point.clone(),
-            path_hash_seed: Some(random_path_hash_seed()),
+            path_hash_seed: Some(
+                self.path_hash_seed
+                    .resolve(written_entries.iter().map(|(name, _)| name)),
+            ),
             entries: written_entries,
+            directory_indexes: Vec::new(),
             footer,
         };
 
-        Index::write(writer, index)
+        Index::write(writer, index, key)
     }
 
     /// Iterate over the entries in the PakMemory
@@ -1,14 +1,34 @@
 //! PakFile data structure for reading large pak files
 
-use std::collections::BTreeMap;
-use std::io::{Read, Seek};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Read, Seek, SeekFrom};
 
-use crate::compression::CompressionMethods;
-use crate::entry::read_entry;
+use crate::compression::{Compression, CompressionMethods};
+use crate::encryption::{self, EncryptionKey};
+use crate::entry::{check_entry_size, read_entry};
 use crate::error::PakError;
 use crate::header::Header;
-use crate::index::Index;
+use crate::index::{Index, IndexPartLocation};
+use crate::limits::PakLimits;
 use crate::pakversion::PakVersion;
+use crate::read_data;
+use crate::verify::{verify, VerifyReport};
+
+/// How an entry is stored in a pak file, see [`PakReader::get_entry_info`]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EntryInfo {
+    /// offset of the entry header in the pak file
+    pub offset: u64,
+    /// size as stored in the pak
+    pub compressed_size: u64,
+    /// size after decompression
+    pub decompressed_size: u64,
+    /// compression method of the entry
+    pub compression: Compression,
+    /// whether the entry data is encrypted
+    pub encrypted: bool,
+}
 
 /// An Unreal pak file reader with it's data kept on disk and only read on demand.
 #[derive(Debug)]
@@ -22,6 +42,10 @@ where
     pub mount_point: String,
     compression: CompressionMethods,
     entries: BTreeMap<String, Header>,
+    delete_records: BTreeSet<String>,
+    index_parts: Vec<IndexPartLocation>,
+    key: Option<EncryptionKey>,
+    limits: PakLimits,
     reader: R,
 }
 
@@ -38,25 +62,55 @@ where
             mount_point: "".to_owned(),
             compression: Default::default(),
             entries: BTreeMap::new(),
+            delete_records: BTreeSet::new(),
+            index_parts: Vec::new(),
+            key: None,
+            limits: PakLimits::default(),
             reader,
         }
     }
 
+    /// Creates a new `PakReader` that reads from the provided reader and decrypts
+    /// the index and entries with the given key where needed.
+    pub fn new_encrypted(reader: R, key: EncryptionKey) -> Self {
+        Self {
+            key: Some(key),
+            ..Self::new(reader)
+        }
+    }
+
+    /// Sets the limits used to reject malformed pak files, this has to happen before the index
+    /// is loaded.
+    pub fn set_limits(&mut self, limits: PakLimits) {
+        self.limits = limits;
+    }
+
     /// Load the entry info contained in the footer into memory to start reading individual entries.
     pub fn load_index(&mut self) -> Result<(), PakError> {
-        let index = Index::read(&mut self.reader)?;
+        let index = Index::read(&mut self.reader, self.key.as_ref(), &self.limits)?;
 
         self.pak_version = index.footer.pak_version;
         self.mount_point = index.mount_point.clone();
         self.compression = index.footer.compression_methods;
 
+        self.index_parts = index.part_locations();
+
         for (name, header) in index.entries {
-            self.entries.insert(name, header);
+            if header.is_deleted() {
+                self.delete_records.insert(name);
+            } else {
+                self.entries.insert(name, header);
+            }
         }
 
         Ok(())
     }
 
+    /// Returns the version of the pak file format, only valid after the index has been loaded.
+    pub fn get_pak_version(&self) -> PakVersion {
+        self.pak_version
+    }
+
     /// Returns the names of all entries which have been found.
     pub fn get_entry_names(&self) -> Vec<&String> {
         self.entries.keys().collect()
@@ -67,6 +121,28 @@ where
         self.entries.contains_key(name)
     }
 
+    /// Returns information about how an entry is stored, without reading any data.
+    pub fn get_entry_info(&self, name: &String) -> Option<EntryInfo> {
+        self.entries.get(name).map(|header| EntryInfo {
+            offset: header.offset,
+            compressed_size: header.compressed_size,
+            decompressed_size: header.decompressed_size,
+            compression: header.compression_method,
+            encrypted: header.is_encrypted(),
+        })
+    }
+
+    /// Returns the names of all delete records, files which this pak removes from
+    /// paks with a lower priority.
+    pub fn get_delete_records(&self) -> Vec<&String> {
+        self.delete_records.iter().collect()
+    }
+
+    /// Checks if the pak file contains a delete record with the given name
+    pub fn contains_delete_record(&self, name: &String) -> bool {
+        self.delete_records.contains(name)
+    }
+
     /// Reads an entry from the pak on disk into memory and returns it's data.
     pub fn read_entry(&mut self, name: &String) -> Result<Vec<u8>, PakError> {
         let header = self
@@ -76,12 +152,57 @@ where
         self.read_entry_at_offset(header.offset)
     }
 
+    /// Verifies the integrity of the whole pak file, requires the index to be loaded.
+    /// Checks the hashes of the index and of all entries, that the headers in the index match the
+    /// ones in front of the entry data and that entries neither overlap nor reach into the index.
+    pub fn verify(&mut self) -> Result<VerifyReport, PakError> {
+        verify(
+            &mut self.reader,
+            self.pak_version,
+            &self.compression,
+            self.key.as_ref(),
+            &self.limits,
+            &self.index_parts,
+            &self.entries,
+        )
+    }
+
+    /// Opens an entry for streaming reads without loading all of it's data into memory.
+    /// Only the compression blocks which are actually read get decompressed.
+    pub fn open_entry(&mut self, name: &String) -> Result<PakEntryReader<'_, R>, PakError> {
+        let header = self
+            .entries
+            .get(name)
+            .ok_or_else(|| PakError::entry_not_found(name.clone()))?;
+        PakEntryReader::new(
+            &mut self.reader,
+            self.pak_version,
+            &self.compression,
+            header.offset,
+            self.key.as_ref(),
+            &self.limits,
+        )
+    }
+
+    /// Reads the header in front of the data of an entry, unlike the one in the index of
+    /// v10+ paks this one always contains the hash of the entry.
+    pub(crate) fn read_entry_header(&mut self, name: &String) -> Result<Header, PakError> {
+        let header = self
+            .entries
+            .get(name)
+            .ok_or_else(|| PakError::entry_not_found(name.clone()))?;
+        self.reader.seek(SeekFrom::Start(header.offset))?;
+        Header::read(&mut self.reader, self.pak_version, &self.compression)
+    }
+
     fn read_entry_at_offset(&mut self, offset: u64) -> Result<Vec<u8>, PakError> {
         read_entry(
             &mut self.reader,
             self.pak_version,
             &self.compression,
             offset,
+            self.key.as_ref(),
+            &self.limits,
         )
     }
 
@@ -91,6 +212,8 @@ where
             reader: &mut self.reader,
             pak_version: self.pak_version,
             compression: self.compression,
+            key: self.key.as_ref(),
+            limits: &self.limits,
             iter: self.entries.iter(),
         }
     }
@@ -102,6 +225,200 @@ where
     }
 }
 
+/// Size of the chunks uncompressed entries are read in
+const UNCOMPRESSED_BLOCK_SIZE: u64 = 0x010000;
+
+/// A single entry of a [`PakReader`] which can be read and seeked like a file.
+/// Compression blocks are decompressed on demand and the last one is cached,
+/// so reading small parts of large entries stays cheap.
+///
+/// Returned by [`PakReader::open_entry`].
+pub struct PakEntryReader<'a, R>
+where
+    R: Read + Seek,
+{
+    reader: &'a mut R,
+    compression_method: Compression,
+    key: Option<&'a EncryptionKey>,
+    encrypted: bool,
+    /// decompressed size of every block except the last one
+    block_size: u64,
+    blocks: Vec<EntryBlock>,
+    size: u64,
+    position: u64,
+    /// index and decompressed data of the last block read
+    cached_index: Option<usize>,
+    cached_data: Vec<u8>,
+}
+
+/// Location of one block of entry data in the pak file
+struct EntryBlock {
+    /// absolute offset of the block
+    offset: u64,
+    /// size of the stored block without encryption padding
+    size: u64,
+}
+
+impl<'a, R> PakEntryReader<'a, R>
+where
+    R: Read + Seek,
+{
+    fn new(
+        reader: &'a mut R,
+        pak_version: PakVersion,
+        compression: &CompressionMethods,
+        offset: u64,
+        key: Option<&'a EncryptionKey>,
+        limits: &PakLimits,
+    ) -> Result<Self, PakError> {
+        reader.seek(SeekFrom::Start(offset))?;
+        let header = Header::read(reader, pak_version, compression)?;
+        check_entry_size(&header, limits)?;
+        let encrypted = header.is_encrypted();
+        let mut data_offset = reader.stream_position()?;
+
+        let (block_size, blocks) = match header.compression_method {
+            Compression::None => {
+                // uncompressed entries are split into blocks too so they can be decrypted in parts
+                let blocks = (0..header.decompressed_size)
+                    .step_by(UNCOMPRESSED_BLOCK_SIZE as usize)
+                    .map(|start| EntryBlock {
+                        offset: data_offset + start,
+                        size: UNCOMPRESSED_BLOCK_SIZE.min(header.decompressed_size - start),
+                    })
+                    .collect();
+                (UNCOMPRESSED_BLOCK_SIZE, blocks)
+            }
+            _ => {
+                let block_size = match header.compression_block_size {
+                    Some(block_size) if block_size != 0 => block_size as u64,
+                    _ => header.decompressed_size,
+                };
+
+                // compression blocks are continuous, when encrypted every block is padded on its own
+                let blocks = header
+                    .compression_blocks
+                    .as_ref()
+                    .ok_or_else(PakError::entry_invalid)?
+                    .iter()
+                    .map(|block| {
+                        let entry_block = EntryBlock {
+                            offset: data_offset,
+                            size: block.size,
+                        };
+                        data_offset += match encrypted {
+                            true => encryption::align(block.size),
+                            false => block.size,
+                        };
+                        entry_block
+                    })
+                    .collect();
+                (block_size, blocks)
+            }
+        };
+
+        Ok(PakEntryReader {
+            reader,
+            compression_method: header.compression_method,
+            key,
+            encrypted,
+            block_size,
+            blocks,
+            size: header.decompressed_size,
+            position: 0,
+            cached_index: None,
+            cached_data: Vec::new(),
+        })
+    }
+
+    /// Returns the decompressed size of the entry.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns the decompressed data of a block, reading it from the pak if it is not cached.
+    fn block(&mut self, index: usize) -> Result<&[u8], PakError> {
+        if self.cached_index != Some(index) {
+            let block = self.blocks.get(index).ok_or_else(PakError::entry_invalid)?;
+            let stored_size = match self.encrypted {
+                true => encryption::align(block.size),
+                false => block.size,
+            };
+
+            self.reader.seek(SeekFrom::Start(block.offset))?;
+            let mut data = read_data(self.reader, stored_size)?;
+            if self.encrypted {
+                encryption::decrypt(self.key, &mut data)?;
+            }
+            data.truncate(block.size as usize);
+
+            if !matches!(self.compression_method, Compression::None) {
+                let decompressed_size = self
+                    .block_size
+                    .min(self.size.saturating_sub(index as u64 * self.block_size));
+                // the block size comes from the header, don't trust it for the allocation
+                let mut decompressed = Vec::with_capacity(decompressed_size.min(0x100000) as usize);
+                self.compression_method.decompress(
+                    &mut decompressed,
+                    &data,
+                    decompressed_size as usize,
+                )?;
+                data = decompressed;
+            }
+
+            self.cached_index = Some(index);
+            self.cached_data = data;
+        }
+
+        Ok(&self.cached_data)
+    }
+}
+
+impl<'a, R> Read for PakEntryReader<'a, R>
+where
+    R: Read + Seek,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.size || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let index = self.position / self.block_size;
+        let offset = (self.position - index * self.block_size) as usize;
+        let block = self.block(index as usize)?;
+        let available = block.get(offset..).ok_or_else(PakError::entry_invalid)?;
+        if available.is_empty() {
+            return Err(PakError::entry_invalid().into());
+        }
+
+        let len = buf.len().min(available.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl<'a, R> Seek for PakEntryReader<'a, R>
+where
+    R: Read + Seek,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        self.position = position.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
 /// An iterator over the entries of a PakReader
 pub struct PakReaderIter<'a, R>
 where
@@ -110,6 +427,8 @@ where
     reader: &'a mut R,
     pak_version: PakVersion,
     compression: CompressionMethods,
+    key: Option<&'a EncryptionKey>,
+    limits: &'a PakLimits,
     iter: std::collections::btree_map::Iter<'a, String, Header>,
 }
 
@@ -128,6 +447,8 @@ where
                     self.pak_version,
                     &self.compression,
                     header.offset,
+                    self.key,
+                    self.limits,
                 ),
             )
         })
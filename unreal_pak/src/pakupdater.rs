@@ -0,0 +1,389 @@
+//! PakFile data structure for updating existing pak files in place
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::compression::{Compression, CompressionMethods};
+use crate::encryption::EncryptionKey;
+use crate::entry::{copy_entry, prepare_entry, read_entry, write_prepared_entry};
+use crate::error::PakError;
+use crate::header::Header;
+use crate::index::{random_path_hash_seed, Footer, Index};
+use crate::limits::PakLimits;
+use crate::pakversion::PakVersion;
+use crate::workers::Workers;
+
+/// An Unreal pak file updater which appends new or replaced entries to an existing pak file.
+/// New data is written after the last entry, where the old index used to be, followed by a
+/// fresh index and footer. The data of unchanged entries is never touched.
+///
+/// Replaced and removed entries leave dead space behind, which can be reclaimed by writing a
+/// new pak file with [`PakUpdater::compact`].
+///
+/// The new data overwrites the old index, so the pak file is corrupted when writing it is
+/// interrupted. [`PakUpdater::update_file`] updates a copy instead and only replaces the
+/// original when everything has been written.
+#[derive(Debug)]
+pub struct PakUpdater<F>
+where
+    F: Read + Write + Seek + SetLen,
+{
+    /// Version of the pak file format this one is using
+    pak_version: PakVersion,
+    /// Mount point. Typically `../../../`.
+    pub mount_point: String,
+    /// Compression methods referenced by the entries of this file
+    compression: CompressionMethods,
+    /// Compression method used for new compressed entries
+    compression_method: Compression,
+    /// Compression block size
+    pub block_size: u32,
+    key: Option<EncryptionKey>,
+    index_encrypted: Option<bool>,
+    encryption_key_guid: Option<[u8; 0x10]>,
+    path_hash_seed: Option<u64>,
+    workers: Workers,
+    limits: PakLimits,
+    entries: BTreeMap<String, Header>,
+    /// offset new entries are written at
+    position: u64,
+    /// length of the file before it was updated
+    file_len: u64,
+    file: F,
+}
+
+/// Data whose length can be changed, so updated pak files can get shorter
+pub trait SetLen {
+    /// Truncate or extend the data to `len` bytes, extending fills it with zeros
+    fn set_len(&mut self, len: u64) -> io::Result<()>;
+}
+
+impl SetLen for File {
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        File::set_len(self, len)
+    }
+}
+
+impl SetLen for Cursor<Vec<u8>> {
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.get_mut().resize(len as usize, 0);
+        Ok(())
+    }
+}
+
+impl SetLen for Cursor<&mut Vec<u8>> {
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.get_mut().resize(len as usize, 0);
+        Ok(())
+    }
+}
+
+impl<W> SetLen for BufWriter<W>
+where
+    W: Write + SetLen,
+{
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.flush()?;
+        self.get_mut().set_len(len)
+    }
+}
+
+impl PakUpdater<File> {
+    /// Updates the pak file at `path` without risking to corrupt it.
+    /// The pak file is copied next to the original, `update` is applied to the copy and the
+    /// copy then replaces the original. When anything fails the original is left untouched.
+    pub fn update_file<T>(
+        path: &Path,
+        key: Option<EncryptionKey>,
+        limits: PakLimits,
+        update: impl FnOnce(&mut PakUpdater<File>) -> Result<T, PakError>,
+    ) -> Result<T, PakError> {
+        let temp_path = temp_path(path);
+
+        let result = (|| {
+            fs::copy(path, &temp_path)?;
+            let file = OpenOptions::new().read(true).write(true).open(&temp_path)?;
+
+            let mut updater = Self::new_with_limits(file, key, limits)?;
+            let value = update(&mut updater)?;
+            updater.finish_write()?.sync_all()?;
+
+            fs::rename(&temp_path, path)?;
+            Ok(value)
+        })();
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result
+    }
+}
+
+impl<F> PakUpdater<F>
+where
+    F: Read + Write + Seek + SetLen,
+{
+    /// Opens an existing pak file for updating and loads its index.
+    pub fn new(file: F) -> Result<Self, PakError> {
+        Self::new_with_limits(file, None, PakLimits::default())
+    }
+
+    /// Opens an existing pak file for updating, decrypting the index with the given key where
+    /// needed. New entries are encrypted with the key too.
+    pub fn new_encrypted(file: F, key: EncryptionKey) -> Result<Self, PakError> {
+        Self::new_with_limits(file, Some(key), PakLimits::default())
+    }
+
+    /// Opens an existing pak file for updating and loads its index with the given limits,
+    /// which are also used when reading entries.
+    pub fn new_with_limits(
+        mut file: F,
+        key: Option<EncryptionKey>,
+        limits: PakLimits,
+    ) -> Result<Self, PakError> {
+        let index = Index::read(&mut file, key.as_ref(), &limits)?;
+        let file_len = file.seek(SeekFrom::End(0))?;
+
+        let mut compression = index.footer.compression_methods;
+        let compression_method = match compression.0[0] {
+            Compression::None => Compression::zlib(),
+            method => method,
+        };
+        add_compression_method(&mut compression, compression_method)?;
+
+        Ok(Self {
+            pak_version: index.footer.pak_version,
+            mount_point: index.mount_point,
+            compression,
+            compression_method,
+            block_size: 0x010000,
+            key,
+            index_encrypted: index.footer.index_encrypted,
+            encryption_key_guid: index.footer.encryption_key_guid,
+            path_hash_seed: index.path_hash_seed,
+            workers: Workers::default(),
+            limits,
+            entries: index.entries.into_iter().collect(),
+            position: index.footer.index_offset,
+            file_len,
+            file,
+        })
+    }
+
+    /// Sets the limits used to reject malformed entries when reading them.
+    /// Use [`PakUpdater::new_with_limits`] to limit reading the index.
+    pub fn set_limits(&mut self, limits: PakLimits) {
+        self.limits = limits;
+    }
+
+    /// Returns the version of the pak file format
+    pub fn get_pak_version(&self) -> PakVersion {
+        self.pak_version
+    }
+
+    /// Sets the compression method used for new compressed entries, defaults to the first
+    /// method of the pak file or Zlib. Pak files before
+    /// [`PakVersion::FnameBasedCompressionMethod`] only support Zlib and Gzip, newer ones at
+    /// most 5 different methods.
+    pub fn set_compression(&mut self, compression: Compression) -> Result<(), PakError> {
+        add_compression_method(&mut self.compression, compression)?;
+        self.compression_method = compression;
+        Ok(())
+    }
+
+    /// Sets the amount of threads used to compress the blocks of each entry.
+    /// `0` uses one thread per core, `1` (the default) compresses on the calling thread.
+    /// The written data does not depend on the amount of threads.
+    #[cfg(feature = "threading")]
+    pub fn set_jobs(&mut self, jobs: usize) -> Result<(), PakError> {
+        self.workers = Workers::new(jobs)?;
+        Ok(())
+    }
+
+    /// Returns the names of all entries, including the ones written since opening the file.
+    pub fn get_entry_names(&self) -> Vec<&String> {
+        self.entries
+            .iter()
+            .filter(|(_, header)| !header.is_deleted())
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Checks if the pak file contains an entry with the given name
+    pub fn contains_entry(&self, name: &str) -> bool {
+        self.entries
+            .get(name)
+            .is_some_and(|header| !header.is_deleted())
+    }
+
+    /// Reads an entry from the pak file into memory and returns it's data.
+    pub fn read_entry(&mut self, name: &str) -> Result<Vec<u8>, PakError> {
+        let header = self
+            .entries
+            .get(name)
+            .filter(|header| !header.is_deleted())
+            .ok_or_else(|| PakError::entry_not_found(name.to_owned()))?;
+        read_entry(
+            &mut self.file,
+            self.pak_version,
+            &self.compression,
+            header.offset,
+            self.key.as_ref(),
+            &self.limits,
+        )
+    }
+
+    /// Appends the given data to the pak file, replacing the entry if it already exists.
+    /// The data of a replaced entry stays in the file until it is compacted.
+    /// Entries under 32 bytes are never compressed.
+    pub fn write_entry(&mut self, name: &str, data: &[u8], compress: bool) -> Result<(), PakError> {
+        let entry = prepare_entry(
+            self.pak_version,
+            data,
+            compress,
+            &CompressionMethods::single(self.compression_method),
+            self.block_size,
+            self.key.as_ref(),
+            &self.workers,
+        )?;
+
+        self.file.seek(SeekFrom::Start(self.position))?;
+        let header =
+            write_prepared_entry(&mut self.file, self.pak_version, &self.compression, entry)?;
+        self.position = self.file.stream_position()?;
+
+        self.entries.insert(name.to_owned(), header);
+
+        Ok(())
+    }
+
+    /// Removes an entry from the index, it's data stays in the file until it is compacted.
+    pub fn remove_entry(&mut self, name: &str) -> Result<(), PakError> {
+        match self.entries.get(name) {
+            Some(header) if !header.is_deleted() => {
+                self.entries.remove(name);
+                Ok(())
+            }
+            _ => Err(PakError::entry_not_found(name.to_owned())),
+        }
+    }
+
+    /// Writes a delete record into the index, which removes the file with the given name
+    /// from paks with a lower priority. Replaces an entry with the same name.
+    /// Requires at least [`PakVersion::DeleteRecords`].
+    pub fn write_delete_record(&mut self, name: &str) -> Result<(), PakError> {
+        if self.pak_version < PakVersion::DeleteRecords {
+            return Err(PakError::pak_version_unsupported(self.pak_version));
+        }
+
+        self.entries
+            .insert(name.to_owned(), Header::delete_record());
+
+        Ok(())
+    }
+
+    /// Finish updating the pak file by writing a fresh index and footer after the last entry,
+    /// returning the wrapped file. The file is truncated when it got shorter.
+    pub fn finish_write(mut self) -> Result<F, PakError> {
+        self.file.seek(SeekFrom::Start(self.position))?;
+
+        let key = self.key.take();
+        let file_len = self.file_len;
+        let (index, mut file) = self.into_index();
+        let footer = Index::write_without_footer(&mut file, index, key.as_ref())?;
+
+        Footer::write(&mut file, footer)?;
+
+        let end = file.stream_position()?;
+        if end < file_len {
+            file.set_len(end)?;
+        }
+
+        Ok(file)
+    }
+
+    /// Writes the current state of the pak file into a new pak file without any dead space,
+    /// copying the stored data of all entries without recompressing it.
+    /// The wrapped file is not changed, so [`PakUpdater::finish_write`] is not needed first.
+    pub fn compact<W>(mut self, mut writer: W) -> Result<W, PakError>
+    where
+        W: Write + Seek,
+    {
+        // keep the order the entries had in the original file
+        let mut entries = self.entries.iter_mut().collect::<Vec<_>>();
+        entries.sort_by_key(|(_, header)| header.offset);
+
+        for (_, header) in entries {
+            if header.is_deleted() {
+                continue;
+            }
+
+            *header = copy_entry(
+                &mut self.file,
+                &mut writer,
+                self.pak_version,
+                &self.compression,
+                header.offset,
+            )?;
+        }
+
+        let key = self.key.take();
+        let (index, _) = self.into_index();
+        Index::write(&mut writer, index, key.as_ref())?;
+
+        Ok(writer)
+    }
+
+    /// Split into the index which has to be written and the wrapped file
+    fn into_index(self) -> (Index, F) {
+        let footer = Footer {
+            pak_version: self.pak_version,
+            // these are set in write_index
+            index_offset: 0,
+            index_size: 0,
+            index_hash: [0u8; 20],
+            compression_methods: self.compression,
+            index_encrypted: self.index_encrypted,
+            encryption_key_guid: self.encryption_key_guid,
+            index_frozen: false,
+        };
+
+        let index = Index {
+            mount_point: self.mount_point,
+            path_hash_seed: Some(self.path_hash_seed.unwrap_or_else(random_path_hash_seed)),
+            entries: self.entries.into_iter().collect::<Vec<_>>(),
+            directory_indexes: Vec::new(),
+            footer,
+        };
+
+        (index, self.file)
+    }
+}
+
+/// Path of the copy of a pak file which is updated, in the same directory so it can be renamed
+fn temp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().map_or_else(OsString::new, OsString::from);
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+/// Add a compression method to the ones referenced by entries, without moving existing ones
+fn add_compression_method(
+    compression: &mut CompressionMethods,
+    method: Compression,
+) -> Result<(), PakError> {
+    if method == Compression::None || compression.0.contains(&method) {
+        return Ok(());
+    }
+
+    let free = compression
+        .0
+        .iter_mut()
+        .find(|slot| **slot == Compression::None)
+        .ok_or_else(PakError::configuration_invalid)?;
+    *free = method;
+    Ok(())
+}
@@ -1,5 +1,7 @@
 //! Used to represent the version of a pak file
 
+use crate::error::PakError;
+
 /// Enum representing all versions of the pak file format
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PakVersion {
@@ -51,9 +53,9 @@ impl PakVersion {
         }
     }
 
-    /// Convert version to a u32.
-    pub fn to_num(&self) -> u32 {
-        match self {
+    /// Convert version to a u32, fails for [`PakVersion::Invalid`].
+    pub fn to_num(&self) -> Result<u32, PakError> {
+        Ok(match self {
             Self::Initial => 1,
             Self::NoTimestamps => 2,
             Self::CompressionEncryption => 3,
@@ -66,8 +68,8 @@ impl PakVersion {
             Self::FrozenIndex => 9,
             Self::PathHashIndex => 10,
             Self::Fnv64BugFix => 11,
-            Self::Invalid => panic!("Attempted to write invalid pak version as byte!"),
-        }
+            Self::Invalid => return Err(PakError::pak_version_unsupported(*self)),
+        })
     }
 
     // how to deal with the stupid 4.22 version
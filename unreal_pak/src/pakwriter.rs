@@ -3,12 +3,40 @@
 use std::collections::BTreeMap;
 use std::io::{Seek, Write};
 
-use crate::compression::CompressionMethods;
+use crate::compression::{Compression, CompressionMethods};
+use crate::encryption::EncryptionKey;
 use crate::entry::write_entry;
 use crate::error::PakError;
 use crate::header::Header;
-use crate::index::{random_path_hash_seed, Footer, Index};
+use crate::index::{fnv64_path_hash, random_path_hash_seed, Footer, Index};
 use crate::pakversion::PakVersion;
+use crate::workers::Workers;
+
+/// How the seed of the path hash index (v10+) is chosen when writing a pak
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathHashSeed {
+    /// A random seed, so writing the same entries twice results in different files
+    #[default]
+    Random,
+    /// A fixed seed
+    Fixed(u32),
+    /// A seed derived from the entry names, writing the same entries always results in the same file
+    Derived,
+}
+
+impl PathHashSeed {
+    /// Get the seed to use for a pak containing the given entries
+    pub(crate) fn resolve<'a>(&self, names: impl Iterator<Item = &'a String>) -> u64 {
+        match self {
+            Self::Random => random_path_hash_seed(),
+            Self::Fixed(seed) => *seed as u64,
+            // the engine only uses 32 bits
+            Self::Derived => names.fold(0, |seed, name| {
+                fnv64_path_hash(name, seed, PakVersion::Fnv64BugFix) as u32 as u64
+            }),
+        }
+    }
+}
 
 /// An Unreal pak file writer which allows incrementally writing data.
 /// Good for working with very large files, but it has restrictions when it
@@ -28,6 +56,15 @@ where
     compression: CompressionMethods,
     /// Compression block size
     pub block_size: u32,
+    /// Whether the index should be encrypted too, only used when a key is set
+    pub encrypt_index: bool,
+    /// Whether entries written from now on are encrypted, only used when a key is set
+    pub encrypt_entries: bool,
+    /// How the seed of the path hash index is chosen
+    pub path_hash_seed: PathHashSeed,
+    key: Option<EncryptionKey>,
+    encryption_key_guid: [u8; 0x10],
+    workers: Workers,
     entries: BTreeMap<String, Header>,
     writer: W,
 }
@@ -45,14 +82,61 @@ where
             mount_point: "../../../".to_owned(),
             compression: CompressionMethods::zlib(),
             block_size: 0x010000,
+            encrypt_index: false,
+            encrypt_entries: false,
+            path_hash_seed: PathHashSeed::default(),
+            key: None,
+            encryption_key_guid: [0u8; 0x10],
+            workers: Workers::default(),
             entries: BTreeMap::new(),
             writer,
         }
     }
 
+    /// Creates a new `PakWriter` which encrypts all entries and the index with the given key.
+    /// The GUID of the key is stored in the footer, the engine uses it to find the matching key.
+    pub fn new_encrypted(
+        writer: W,
+        pak_version: PakVersion,
+        key: EncryptionKey,
+        encryption_key_guid: [u8; 0x10],
+    ) -> Self {
+        Self {
+            encrypt_index: true,
+            encrypt_entries: true,
+            key: Some(key),
+            encryption_key_guid,
+            ..Self::new(writer, pak_version)
+        }
+    }
+
+    /// Sets the compression method used for compressed entries, defaults to Zlib.
+    /// This has to happen before any entries are written.
+    pub fn set_compression(&mut self, compression: Compression) -> Result<(), PakError> {
+        if !self.entries.is_empty() {
+            return Err(PakError::configuration_invalid());
+        }
+
+        self.compression = CompressionMethods::single(compression);
+        Ok(())
+    }
+
+    /// Sets the amount of threads used to compress the blocks of each entry.
+    /// `0` uses one thread per core, `1` (the default) compresses on the calling thread.
+    /// The written data does not depend on the amount of threads.
+    #[cfg(feature = "threading")]
+    pub fn set_jobs(&mut self, jobs: usize) -> Result<(), PakError> {
+        self.workers = Workers::new(jobs)?;
+        Ok(())
+    }
+
     /// Returns the names of all entries which have been found.
     pub fn get_entry_names(&self) -> Vec<&String> {
-        self.entries.keys().collect()
+        self.entries
+            .iter()
+            .filter(|(_, header)| !header.is_deleted())
+            .map(|(name, _)| name)
+            .collect()
     }
 
     /// Writes the given data into the pak file on disk.
@@ -75,14 +159,31 @@ where
             compress,
             &self.compression,
             self.block_size,
+            self.key.as_ref().filter(|_| self.encrypt_entries),
+            &self.workers,
         )?;
         self.entries.insert(name.clone(), header);
 
         Ok(())
     }
 
-    /// Finish writing the pak file by writing index and footer
-    pub fn finish_write(mut self) -> Result<(), PakError> {
+    /// Writes a delete record into the index, which removes the file with the given name
+    /// from paks with a lower priority. Requires at least [`PakVersion::DeleteRecords`].
+    pub fn write_delete_record(&mut self, name: &String) -> Result<(), PakError> {
+        if self.pak_version < PakVersion::DeleteRecords {
+            return Err(PakError::pak_version_unsupported(self.pak_version));
+        }
+        if self.entries.contains_key(name) {
+            return Err(PakError::double_write(name.clone()));
+        }
+
+        self.entries.insert(name.clone(), Header::delete_record());
+
+        Ok(())
+    }
+
+    /// Finish writing the pak file by writing index and footer, returning the wrapped writer.
+    pub fn finish_write(mut self) -> Result<W, PakError> {
         let footer = Footer {
             pak_version: self.pak_version,
             // these are set in write_index
@@ -90,17 +191,21 @@ where
             index_size: 0,
             index_hash: [0u8; 20],
             compression_methods: self.compression,
-            index_encrypted: Some(false),
-            encryption_key_guid: Some([0u8; 0x10]),
+            index_encrypted: Some(self.encrypt_index && self.key.is_some()),
+            encryption_key_guid: Some(self.encryption_key_guid),
+            index_frozen: false,
         };
 
         let index = Index {
             mount_point: self.mount_point,
-            path_hash_seed: Some(random_path_hash_seed()),
+            path_hash_seed: Some(self.path_hash_seed.resolve(self.entries.keys())),
             entries: self.entries.into_iter().collect::<Vec<_>>(),
+            directory_indexes: Vec::new(),
             footer,
         };
 
-        Index::write(&mut self.writer, index)
+        Index::write(&mut self.writer, index, self.key.as_ref())?;
+
+        Ok(self.writer)
     }
 }
@@ -0,0 +1,309 @@
+//! PakFile data structure for reading pak files from multiple threads at once
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use crate::compression::CompressionMethods;
+use crate::encryption::EncryptionKey;
+use crate::entry::read_entry;
+use crate::error::PakError;
+use crate::header::Header;
+use crate::index::{Index, IndexPartLocation};
+use crate::limits::PakLimits;
+use crate::pakversion::PakVersion;
+use crate::verify::{verify, VerifyReport};
+
+/// A source of data which can be read at any offset without changing any state,
+/// so it can be shared between threads.
+pub trait ReadAt {
+    /// Read bytes starting at `offset` into `buf`, returning how many bytes were read.
+    /// `0` is only returned at the end of the data or for an empty `buf`.
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+
+    /// Size of the data in bytes
+    fn size(&self) -> io::Result<u64>;
+}
+
+#[cfg(unix)]
+impl ReadAt for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        std::os::unix::fs::FileExt::read_at(self, buf, offset)
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+#[cfg(windows)]
+impl ReadAt for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        // this moves the cursor of the file, which is never used for positional reads
+        std::os::windows::fs::FileExt::seek_read(self, buf, offset)
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+/// Data which is already in memory, e.g. a `Vec<u8>` or a memory mapped file.
+#[derive(Debug, Clone)]
+pub struct InMemory<T>(pub T)
+where
+    T: AsRef<[u8]>;
+
+impl<T> ReadAt for InMemory<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let data = self.0.as_ref();
+        let available = usize::try_from(offset)
+            .ok()
+            .and_then(|offset| data.get(offset..))
+            .unwrap_or_default();
+
+        let len = buf.len().min(available.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        Ok(len)
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.0.as_ref().len() as u64)
+    }
+}
+
+/// Implements `Read + Seek` on top of a [`ReadAt`], each one has its own position
+struct SourceReader<'a, S>
+where
+    S: ReadAt,
+{
+    source: &'a S,
+    size: u64,
+    position: u64,
+}
+
+impl<'a, S> SourceReader<'a, S>
+where
+    S: ReadAt,
+{
+    fn new(source: &'a S, size: u64) -> Self {
+        Self {
+            source,
+            size,
+            position: 0,
+        }
+    }
+}
+
+impl<'a, S> Read for SourceReader<'a, S>
+where
+    S: ReadAt,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.source.read_at(buf, self.position)?;
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl<'a, S> Seek for SourceReader<'a, S>
+where
+    S: ReadAt,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        self.position = position.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        Ok(self.position)
+    }
+}
+
+#[derive(Debug)]
+struct SharedPak<S>
+where
+    S: ReadAt,
+{
+    pak_version: PakVersion,
+    mount_point: String,
+    compression: CompressionMethods,
+    entries: BTreeMap<String, Header>,
+    delete_records: BTreeSet<String>,
+    index_parts: Vec<IndexPartLocation>,
+    key: Option<EncryptionKey>,
+    size: u64,
+    source: S,
+}
+
+/// An Unreal pak file reader which can read entries from many threads at once.
+/// Unlike [`PakReader`] all reads use positional reads on a [`ReadAt`] source, so reading
+/// only needs `&self`. Cloning is cheap and shares the loaded index.
+///
+/// Sources are implemented for a [`File`] and for data in memory with [`InMemory`],
+/// which can also wrap a memory mapped file.
+///
+/// [`PakReader`]: crate::pakreader::PakReader
+#[derive(Debug)]
+pub struct SharedPakReader<S>
+where
+    S: ReadAt,
+{
+    pak: Arc<SharedPak<S>>,
+    limits: PakLimits,
+}
+
+impl<S> Clone for SharedPakReader<S>
+where
+    S: ReadAt,
+{
+    fn clone(&self) -> Self {
+        Self {
+            pak: Arc::clone(&self.pak),
+            limits: self.limits,
+        }
+    }
+}
+
+impl<S> SharedPakReader<S>
+where
+    S: ReadAt,
+{
+    /// Creates a new `SharedPakReader` that reads from the provided source and loads the index.
+    pub fn new(source: S) -> Result<Self, PakError> {
+        Self::new_with_limits(source, None, PakLimits::default())
+    }
+
+    /// Creates a new `SharedPakReader` that reads from the provided source and loads the index,
+    /// decrypting the index and entries with the given key where needed.
+    pub fn new_encrypted(source: S, key: EncryptionKey) -> Result<Self, PakError> {
+        Self::new_with_limits(source, Some(key), PakLimits::default())
+    }
+
+    /// Creates a new `SharedPakReader` that reads from the provided source and loads the index
+    /// with the given limits, which are also used when reading entries.
+    pub fn new_with_limits(
+        source: S,
+        key: Option<EncryptionKey>,
+        limits: PakLimits,
+    ) -> Result<Self, PakError> {
+        let size = source.size()?;
+        let index = Index::read(&mut SourceReader::new(&source, size), key.as_ref(), &limits)?;
+        let index_parts = index.part_locations();
+
+        let mut entries = BTreeMap::new();
+        let mut delete_records = BTreeSet::new();
+        for (name, header) in index.entries {
+            if header.is_deleted() {
+                delete_records.insert(name);
+            } else {
+                entries.insert(name, header);
+            }
+        }
+
+        Ok(Self {
+            pak: Arc::new(SharedPak {
+                pak_version: index.footer.pak_version,
+                mount_point: index.mount_point,
+                compression: index.footer.compression_methods,
+                entries,
+                delete_records,
+                index_parts,
+                key,
+                size,
+                source,
+            }),
+            limits,
+        })
+    }
+
+    /// Sets the limits used to reject malformed entries, clones share the index but not
+    /// the limits. Use [`SharedPakReader::new_with_limits`] to limit reading the index.
+    pub fn set_limits(&mut self, limits: PakLimits) {
+        self.limits = limits;
+    }
+
+    /// Returns the version of the pak file format.
+    pub fn get_pak_version(&self) -> PakVersion {
+        self.pak.pak_version
+    }
+
+    /// Returns the mount point of the pak file, typically `../../../`.
+    pub fn get_mount_point(&self) -> &str {
+        &self.pak.mount_point
+    }
+
+    /// Returns the names of all entries which have been found.
+    pub fn get_entry_names(&self) -> Vec<&String> {
+        self.pak.entries.keys().collect()
+    }
+
+    /// Checks if the pak file contains an entry with the given name
+    pub fn contains_entry(&self, name: &String) -> bool {
+        self.pak.entries.contains_key(name)
+    }
+
+    /// Returns the names of all delete records, files which this pak removes from
+    /// paks with a lower priority.
+    pub fn get_delete_records(&self) -> Vec<&String> {
+        self.pak.delete_records.iter().collect()
+    }
+
+    /// Checks if the pak file contains a delete record with the given name
+    pub fn contains_delete_record(&self, name: &String) -> bool {
+        self.pak.delete_records.contains(name)
+    }
+
+    /// Reads an entry from the pak into memory and returns it's data.
+    /// This can be called from multiple threads at once.
+    pub fn read_entry(&self, name: &String) -> Result<Vec<u8>, PakError> {
+        let header = self
+            .pak
+            .entries
+            .get(name)
+            .ok_or_else(|| PakError::entry_not_found(name.clone()))?;
+        read_entry(
+            &mut self.reader(),
+            self.pak.pak_version,
+            &self.pak.compression,
+            header.offset,
+            self.pak.key.as_ref(),
+            &self.limits,
+        )
+    }
+
+    /// Verifies the integrity of the whole pak file, see [`PakReader::verify`].
+    ///
+    /// [`PakReader::verify`]: crate::pakreader::PakReader::verify
+    pub fn verify(&self) -> Result<VerifyReport, PakError> {
+        verify(
+            &mut self.reader(),
+            self.pak.pak_version,
+            &self.pak.compression,
+            self.pak.key.as_ref(),
+            &self.limits,
+            &self.pak.index_parts,
+            &self.pak.entries,
+        )
+    }
+
+    /// Returns the source the pak file is read from.
+    pub fn get_source(&self) -> &S {
+        &self.pak.source
+    }
+
+    fn reader(&self) -> SourceReader<'_, S> {
+        SourceReader::new(&self.pak.source, self.pak.size)
+    }
+}
@@ -0,0 +1,194 @@
+//! Pak signature (`.sig`) files
+//!
+//! Games with pak signing enabled only mount paks which have a `.sig` file next to them.
+//! The signature file contains a CRC-32 for every 64 KiB chunk of the pak, the engine checks
+//! chunks against these hashes when they are read. The hash table itself is signed by
+//! encrypting its SHA-1 hash with the RSA private key of the game.
+
+use std::io::{Read, Write};
+
+use byteorder::{ReadBytesExt, WriteBytesExt, LE};
+use rsa::Pkcs1v15Sign;
+use sha1::{Digest, Sha1};
+
+use crate::error::PakError;
+use crate::read_data;
+
+/// Keys are handled by the `rsa` crate, e.g. loaded from PEM with its `pkcs8` traits
+pub use rsa::{self, RsaPrivateKey, RsaPublicKey};
+
+/// Magic at the start of `.sig` files
+pub const SIGNATURE_MAGIC: u32 = 0x73832DAA;
+/// Size of the pak chunks which are hashed separately
+pub const SIGNATURE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Version of the `.sig` file format, the only version since 4.23
+const SIGNATURE_VERSION: i32 = 1;
+
+/// Contents of a pak signature file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakSignature {
+    /// SHA-1 hash of the chunk hashes encrypted with the private signing key
+    pub encrypted_hash: Vec<u8>,
+    /// CRC-32 of every chunk of the pak
+    pub chunk_hashes: Vec<u32>,
+}
+
+/// Result of [`PakSignature::verify`]
+#[derive(Debug, Default)]
+pub struct SignatureReport {
+    /// whether the encrypted hash matches the chunk hashes stored in the signature
+    pub signature_valid: bool,
+    /// amount of chunks the pak consists of
+    pub chunk_count: usize,
+    /// indices of chunks which do not match the stored hashes or are missing in the signature
+    pub mismatched_chunks: Vec<usize>,
+}
+
+impl SignatureReport {
+    /// Whether the signature is valid and matches the pak
+    pub fn is_ok(&self) -> bool {
+        self.signature_valid && self.mismatched_chunks.is_empty()
+    }
+}
+
+/// Compute the CRC-32 of every [`SIGNATURE_CHUNK_SIZE`] chunk of a pak, the last chunk is not padded
+pub fn compute_chunk_hashes<R: Read>(pak: &mut R) -> Result<Vec<u32>, PakError> {
+    let mut chunk_hashes = Vec::new();
+    let mut buf = vec![0u8; SIGNATURE_CHUNK_SIZE];
+    loop {
+        let mut len = 0;
+        while len < buf.len() {
+            match pak.read(&mut buf[len..])? {
+                0 => break,
+                read => len += read,
+            }
+        }
+        if len == 0 {
+            break;
+        }
+
+        let mut crc = flate2::Crc::new();
+        crc.update(&buf[..len]);
+        chunk_hashes.push(crc.sum());
+
+        if len < buf.len() {
+            break;
+        }
+    }
+    Ok(chunk_hashes)
+}
+
+impl PakSignature {
+    /// Create the signature of a pak
+    ///
+    /// `pak_file_name` is the file name of the pak without its directory, newer engine versions
+    /// mix it into the signed hash so signatures can not be reused for other paks.
+    pub fn sign<R: Read>(
+        pak: &mut R,
+        pak_file_name: Option<&str>,
+        private_key: &RsaPrivateKey,
+    ) -> Result<Self, PakError> {
+        let chunk_hashes = compute_chunk_hashes(pak)?;
+        let hash = principal_hash(&chunk_hashes, pak_file_name);
+        let encrypted_hash = private_key
+            .sign(Pkcs1v15Sign::new_unprefixed(), &hash)
+            .map_err(|_| PakError::signing_key_invalid())?;
+
+        Ok(PakSignature {
+            encrypted_hash,
+            chunk_hashes,
+        })
+    }
+
+    /// Verify the signature and compare the stored chunk hashes against a pak
+    ///
+    /// `pak_file_name` has to match the one used for signing.
+    pub fn verify<R: Read>(
+        &self,
+        pak: &mut R,
+        pak_file_name: Option<&str>,
+        public_key: &RsaPublicKey,
+    ) -> Result<SignatureReport, PakError> {
+        let hash = principal_hash(&self.chunk_hashes, pak_file_name);
+        let signature_valid = public_key
+            .verify(Pkcs1v15Sign::new_unprefixed(), &hash, &self.encrypted_hash)
+            .is_ok();
+
+        let chunk_hashes = compute_chunk_hashes(pak)?;
+        let mut mismatched_chunks: Vec<usize> = chunk_hashes
+            .iter()
+            .enumerate()
+            .filter(|(i, hash)| self.chunk_hashes.get(*i) != Some(hash))
+            .map(|(i, _)| i)
+            .collect();
+        // hashes for chunks past the end of the pak
+        mismatched_chunks.extend(chunk_hashes.len()..self.chunk_hashes.len());
+
+        Ok(SignatureReport {
+            signature_valid,
+            chunk_count: chunk_hashes.len(),
+            mismatched_chunks,
+        })
+    }
+
+    /// Read a `.sig` file
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, PakError> {
+        if reader.read_u32::<LE>()? != SIGNATURE_MAGIC {
+            return Err(PakError::signature_invalid());
+        }
+        if reader.read_i32::<LE>()? != SIGNATURE_VERSION {
+            return Err(PakError::signature_invalid());
+        }
+
+        let encrypted_hash_len = reader.read_i32::<LE>()?;
+        if encrypted_hash_len < 0 {
+            return Err(PakError::signature_invalid());
+        }
+        let encrypted_hash = read_data(reader, encrypted_hash_len as u64)?;
+
+        let chunk_count = reader.read_i32::<LE>()?;
+        if chunk_count < 0 {
+            return Err(PakError::signature_invalid());
+        }
+        let chunk_hashes = read_data(reader, chunk_count as u64 * 4)?
+            .chunks_exact(4)
+            .map(|e| u32::from_le_bytes([e[0], e[1], e[2], e[3]]))
+            .collect();
+
+        Ok(PakSignature {
+            encrypted_hash,
+            chunk_hashes,
+        })
+    }
+
+    /// Write a `.sig` file
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), PakError> {
+        writer.write_u32::<LE>(SIGNATURE_MAGIC)?;
+        writer.write_i32::<LE>(SIGNATURE_VERSION)?;
+        writer.write_i32::<LE>(self.encrypted_hash.len() as i32)?;
+        writer.write_all(&self.encrypted_hash)?;
+        writer.write_i32::<LE>(self.chunk_hashes.len() as i32)?;
+        for hash in &self.chunk_hashes {
+            writer.write_u32::<LE>(*hash)?;
+        }
+        Ok(())
+    }
+}
+
+/// SHA-1 of the chunk hash table and the pak file name, this is what gets signed.
+/// The engine hashes the characters of the name as UTF-16 without a terminator.
+fn principal_hash(chunk_hashes: &[u32], pak_file_name: Option<&str>) -> [u8; 20] {
+    let mut hasher = Sha1::new();
+    for hash in chunk_hashes {
+        hasher.update(hash.to_le_bytes());
+    }
+    if let Some(pak_file_name) = pak_file_name {
+        let name = pak_file_name
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+        hasher.update(name);
+    }
+    hasher.finalize().into()
+}
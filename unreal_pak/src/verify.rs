@@ -0,0 +1,343 @@
+//! Integrity verification of pak files
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::compression::{Compression, CompressionMethods};
+use crate::encryption::{self, EncryptionKey};
+use crate::entry::read_entry;
+use crate::error::PakError;
+use crate::header::Header;
+use crate::index::{read_index_data, IndexPartLocation};
+use crate::limits::PakLimits;
+use crate::pakversion::PakVersion;
+use crate::{hash, read_data};
+
+/// A part of the index which is hashed separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexPart {
+    /// The primary index the footer points to
+    PrimaryIndex,
+    /// The path hash index (v10+)
+    PathHashIndex,
+    /// The full directory index (v10+)
+    FullDirectoryIndex,
+}
+
+impl fmt::Display for IndexPart {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexPart::PrimaryIndex => write!(f, "primary index"),
+            IndexPart::PathHashIndex => write!(f, "path hash index"),
+            IndexPart::FullDirectoryIndex => write!(f, "full directory index"),
+        }
+    }
+}
+
+/// A single problem found while verifying a pak file
+#[derive(Debug)]
+pub enum VerifyIssue {
+    /// The SHA-1 hash of a part of the index does not match the stored one
+    IndexHashMismatch {
+        /// which part of the index
+        part: IndexPart,
+        /// hash stored in the pak
+        expected: [u8; 20],
+        /// hash of the data actually found
+        actual: [u8; 20],
+    },
+    /// The SHA-1 hash of the stored data of an entry does not match the one in its header
+    EntryHashMismatch {
+        /// name of the entry
+        name: String,
+        /// hash stored in the header
+        expected: [u8; 20],
+        /// hash of the data actually found
+        actual: [u8; 20],
+    },
+    /// The header in the index and the header in front of the entry data disagree
+    HeaderMismatch {
+        /// name of the entry
+        name: String,
+        /// the field which differs
+        field: &'static str,
+    },
+    /// The data of an entry reaches outside of the data section of the pak
+    EntryOutOfBounds {
+        /// name of the entry
+        name: String,
+        /// start of the entry including its header
+        start: u64,
+        /// end of the entry data
+        end: u64,
+    },
+    /// The data of two entries overlaps
+    EntriesOverlapping {
+        /// name of the entry which starts first
+        first: String,
+        /// name of the entry which starts inside of the first one
+        second: String,
+    },
+    /// An entry could not be read or decompressed
+    EntryUnreadable {
+        /// name of the entry
+        name: String,
+        /// error encountered while reading
+        error: PakError,
+    },
+}
+
+impl fmt::Display for VerifyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VerifyIssue::IndexHashMismatch {
+                part,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Hash mismatch in {part}: expected {}, found {}",
+                hex(expected),
+                hex(actual)
+            ),
+            VerifyIssue::EntryHashMismatch {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Hash mismatch in {name}: expected {}, found {}",
+                hex(expected),
+                hex(actual)
+            ),
+            VerifyIssue::HeaderMismatch { name, field } => {
+                write!(
+                    f,
+                    "Header mismatch in {name}: {field} differs from the index"
+                )
+            }
+            VerifyIssue::EntryOutOfBounds { name, start, end } => {
+                write!(f, "Entry {name} is out of bounds: {start:#x}..{end:#x}")
+            }
+            VerifyIssue::EntriesOverlapping { first, second } => {
+                write!(f, "Entries {first} and {second} overlap")
+            }
+            VerifyIssue::EntryUnreadable { name, error } => {
+                write!(f, "Entry {name} could not be read: {error}")
+            }
+        }
+    }
+}
+
+/// Result of [`PakReader::verify`]
+///
+/// [`PakReader::verify`]: crate::pakreader::PakReader::verify
+#[derive(Debug, Default)]
+pub struct VerifyReport {
+    /// amount of entries which have been checked
+    pub entry_count: usize,
+    /// all problems which have been found
+    pub issues: Vec<VerifyIssue>,
+}
+
+impl VerifyReport {
+    /// Whether no problems have been found
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        write!(
+            f,
+            "Checked {} entries, found {} issues",
+            self.entry_count,
+            self.issues.len()
+        )
+    }
+}
+
+fn hex(hash: &[u8; 20]) -> String {
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Verify the index parts and all entries of a pak file
+pub(crate) fn verify<R: Read + Seek>(
+    reader: &mut R,
+    pak_version: PakVersion,
+    compression: &CompressionMethods,
+    key: Option<&EncryptionKey>,
+    limits: &PakLimits,
+    index_parts: &[IndexPartLocation],
+    entries: &BTreeMap<String, Header>,
+) -> Result<VerifyReport, PakError> {
+    let mut report = VerifyReport {
+        entry_count: entries.len(),
+        issues: Vec::new(),
+    };
+
+    for location in index_parts {
+        let data = read_index_data(
+            reader,
+            location.offset,
+            location.size,
+            location.encrypted,
+            key,
+        )?;
+        let actual = hash(&data);
+        if actual != location.hash {
+            report.issues.push(VerifyIssue::IndexHashMismatch {
+                part: location.part,
+                expected: location.hash,
+                actual,
+            });
+        }
+    }
+
+    // entry data has to be in front of the index
+    let data_end = index_parts
+        .iter()
+        .map(|location| location.offset)
+        .min()
+        .unwrap_or(u64::MAX);
+
+    let mut ranges = Vec::with_capacity(entries.len());
+    for (name, index_header) in entries {
+        match verify_entry(
+            reader,
+            pak_version,
+            compression,
+            key,
+            limits,
+            name,
+            index_header,
+            data_end,
+        ) {
+            Ok((range, issues)) => {
+                report.issues.extend(issues);
+                ranges.push((range, name));
+            }
+            Err(error) => report.issues.push(VerifyIssue::EntryUnreadable {
+                name: name.clone(),
+                error,
+            }),
+        }
+    }
+
+    ranges.sort();
+    let mut furthest: Option<(u64, &String)> = None;
+    for ((start, end), name) in ranges {
+        match furthest {
+            Some((furthest_end, first)) if start < furthest_end => {
+                report.issues.push(VerifyIssue::EntriesOverlapping {
+                    first: first.clone(),
+                    second: name.clone(),
+                });
+                if end > furthest_end {
+                    furthest = Some((end, name));
+                }
+            }
+            _ => furthest = Some((end, name)),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Verify a single entry, returning the range it occupies in the pak and the issues found
+#[allow(clippy::too_many_arguments)]
+fn verify_entry<R: Read + Seek>(
+    reader: &mut R,
+    pak_version: PakVersion,
+    compression: &CompressionMethods,
+    key: Option<&EncryptionKey>,
+    limits: &PakLimits,
+    name: &str,
+    index_header: &Header,
+    data_end: u64,
+) -> Result<((u64, u64), Vec<VerifyIssue>), PakError> {
+    let mut issues = Vec::new();
+    let mut mismatch = |field| {
+        issues.push(VerifyIssue::HeaderMismatch {
+            name: name.to_owned(),
+            field,
+        })
+    };
+
+    reader.seek(SeekFrom::Start(index_header.offset))?;
+    let header = Header::read(reader, pak_version, compression)?;
+    let data_offset = reader.stream_position()?;
+
+    if header.compressed_size != index_header.compressed_size {
+        mismatch("compressed size");
+    }
+    if header.decompressed_size != index_header.decompressed_size {
+        mismatch("decompressed size");
+    }
+    if header.compression_method != index_header.compression_method {
+        mismatch("compression method");
+    }
+    if header.is_encrypted() != index_header.is_encrypted() {
+        mismatch("encrypted flag");
+    }
+    // encoded headers (v10+) do not contain a hash or the compression blocks
+    if index_header.hash != [0u8; 20] && header.hash != index_header.hash {
+        mismatch("hash");
+    }
+    if let (Some(blocks), Some(index_blocks)) =
+        (&header.compression_blocks, &index_header.compression_blocks)
+    {
+        if blocks.len() != index_blocks.len()
+            || blocks
+                .iter()
+                .zip(index_blocks)
+                .any(|(block, index_block)| block.size != index_block.size)
+        {
+            mismatch("compression blocks");
+        }
+    }
+
+    // the hash covers the data as stored, after compression and encryption
+    let stored_size = match header.is_encrypted() {
+        true => encryption::align(header.compressed_size),
+        false => header.compressed_size,
+    };
+    let range = (index_header.offset, data_offset.saturating_add(stored_size));
+    if range.1 > data_end {
+        issues.push(VerifyIssue::EntryOutOfBounds {
+            name: name.to_owned(),
+            start: range.0,
+            end: range.1,
+        });
+        return Ok((range, issues));
+    }
+
+    let data = read_data(reader, stored_size)?;
+    let actual = hash(&data);
+    if actual != header.hash {
+        issues.push(VerifyIssue::EntryHashMismatch {
+            name: name.to_owned(),
+            expected: header.hash,
+            actual,
+        });
+    }
+
+    // make sure the entry can actually be decompressed
+    if !matches!(header.compression_method, Compression::None) || header.is_encrypted() {
+        read_entry(
+            reader,
+            pak_version,
+            compression,
+            index_header.offset,
+            key,
+            limits,
+        )?;
+    }
+
+    Ok((range, issues))
+}
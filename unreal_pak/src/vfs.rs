@@ -0,0 +1,281 @@
+//! Layered view over multiple pak files which resolves overrides like the engine does
+//!
+//! The engine gives every mounted pak an order based on the directory it is in. Patch paks
+//! (ending in `_P.pak`) get `100 * chunk version` added to that, so they override all regular
+//! paks. When a file exists in multiple paks the one with the highest order wins. Paks are
+//! mounted in descending order of their file names, and for paks with the same order the one
+//! mounted first wins. This is why mods use numeric prefixes like `900-ModIntegrator_P.pak`,
+//! the pak with the higher prefix overrides the others.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+
+use crate::error::PakError;
+use crate::pakreader::PakReader;
+
+/// Base order of paks named after the project in `<Project>/Content/Paks`, e.g. the game paks
+pub const PROJECT_PAKS_ORDER: u32 = 4;
+/// Base order of other paks in `<Project>/Content`, e.g. in `Content/Paks/~mods`
+pub const PROJECT_CONTENT_ORDER: u32 = 3;
+/// Base order of paks in `Engine/Content`
+pub const ENGINE_CONTENT_ORDER: u32 = 2;
+/// Base order of paks in `<Project>/Saved/Paks`
+pub const PROJECT_SAVED_ORDER: u32 = 1;
+
+/// Priority of a mounted pak, files in paks with a higher priority override the ones in lower
+/// priority paks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakPriority {
+    order: u32,
+    /// lowercase file name, the engine compares them case insensitively
+    file_name: String,
+}
+
+impl PakPriority {
+    /// Calculate the priority of a pak from its file name and the base order of the directory
+    /// it is in, see the `*_ORDER` constants.
+    pub fn new(file_name: &str, base_order: u32) -> Self {
+        let file_name = file_name.to_lowercase();
+
+        let mut order = base_order;
+        if let Some(stem) = file_name.strip_suffix("_p.pak") {
+            // `Name_2_P.pak` is chunk version 3, everything else version 1
+            let chunk_version = stem
+                .rsplit_once('_')
+                .map(|(_, version)| version)
+                .filter(|version| {
+                    !version.is_empty() && version.bytes().all(|b| b.is_ascii_digit())
+                })
+                .and_then(|version| version.parse::<u32>().ok())
+                .filter(|version| *version >= 1)
+                .map_or(1, |version| version + 1);
+            order = order.saturating_add(chunk_version.saturating_mul(100));
+        }
+
+        PakPriority { order, file_name }
+    }
+
+    /// Order of the pak, including the bonus for patch paks
+    pub fn get_order(&self) -> u32 {
+        self.order
+    }
+}
+
+impl PartialOrd for PakPriority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PakPriority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order
+            .cmp(&other.order)
+            .then_with(|| self.file_name.cmp(&other.file_name))
+    }
+}
+
+/// A file in a mounted pak
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VfsEntry {
+    /// index of the pak the file is in, as returned by [`PakVfs::mount`]
+    pub pak: usize,
+    /// name of the entry in the pak
+    pub name: String,
+    /// path of the file with the mount point applied, e.g. `Game/Content/Asset.uasset`
+    pub path: String,
+    /// whether this is a delete record, which hides the file in paks with a lower priority
+    pub deleted: bool,
+}
+
+#[derive(Debug)]
+struct MountedPak<R>
+where
+    R: Read + Seek,
+{
+    file_name: String,
+    priority: PakPriority,
+    pak: PakReader<R>,
+}
+
+/// Multiple pak files mounted on top of each other, see the [module docs](self) for how
+/// the winning pak for a file is chosen.
+#[derive(Debug)]
+pub struct PakVfs<R>
+where
+    R: Read + Seek,
+{
+    paks: Vec<MountedPak<R>>,
+    /// lowercase path to all paks containing it, the winning one first
+    files: BTreeMap<String, Vec<VfsEntry>>,
+}
+
+impl<R> Default for PakVfs<R>
+where
+    R: Read + Seek,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R> PakVfs<R>
+where
+    R: Read + Seek,
+{
+    /// Creates a new `PakVfs` without any paks mounted
+    pub fn new() -> Self {
+        Self {
+            paks: Vec::new(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Mount a pak with its file name and the base order of the directory it is in,
+    /// see the `*_ORDER` constants. The index of the pak has to be loaded already.
+    /// Returns the index of the pak used in [`VfsEntry`].
+    pub fn mount(&mut self, file_name: &str, base_order: u32, pak: PakReader<R>) -> usize {
+        let index = self.paks.len();
+        let priority = PakPriority::new(file_name, base_order);
+
+        let entries = pak.get_entry_names().into_iter().map(|name| (name, false));
+        let delete_records = pak
+            .get_delete_records()
+            .into_iter()
+            .map(|name| (name, true));
+        for (name, deleted) in entries.chain(delete_records) {
+            let path = virtual_path(&pak.mount_point, name);
+            let entries = self.files.entry(path.to_lowercase()).or_default();
+
+            // paks with the same priority do not override already mounted ones
+            let position =
+                entries.partition_point(|entry| self.paks[entry.pak].priority >= priority);
+            entries.insert(
+                position,
+                VfsEntry {
+                    pak: index,
+                    name: name.clone(),
+                    path,
+                    deleted,
+                },
+            );
+        }
+
+        self.paks.push(MountedPak {
+            file_name: file_name.to_owned(),
+            priority,
+            pak,
+        });
+
+        index
+    }
+
+    /// Returns the amount of mounted paks
+    pub fn get_pak_count(&self) -> usize {
+        self.paks.len()
+    }
+
+    /// Returns the file name a pak was mounted with
+    pub fn get_pak_name(&self, pak: usize) -> Option<&str> {
+        self.paks.get(pak).map(|mounted| mounted.file_name.as_str())
+    }
+
+    /// Returns the priority of a mounted pak
+    pub fn get_pak_priority(&self, pak: usize) -> Option<&PakPriority> {
+        self.paks.get(pak).map(|mounted| &mounted.priority)
+    }
+
+    /// Returns a mounted pak
+    pub fn get_pak(&self, pak: usize) -> Option<&PakReader<R>> {
+        self.paks.get(pak).map(|mounted| &mounted.pak)
+    }
+
+    /// Returns a mounted pak mutably
+    pub fn get_pak_mut(&mut self, pak: usize) -> Option<&mut PakReader<R>> {
+        self.paks.get_mut(pak).map(|mounted| &mut mounted.pak)
+    }
+
+    /// Checks if the file exists in any mounted pak and has not been deleted,
+    /// paths are compared case insensitively
+    pub fn contains(&self, path: &str) -> bool {
+        self.find(path).is_some()
+    }
+
+    /// Find the entry of a file the engine would load, in the pak which wins.
+    /// Returns `None` when the winning pak contains a delete record for the file.
+    pub fn find(&self, path: &str) -> Option<&VfsEntry> {
+        self.find_all(path).first().filter(|entry| !entry.deleted)
+    }
+
+    /// Find all entries of a file across all mounted paks including delete records,
+    /// the winning one first
+    pub fn find_all(&self, path: &str) -> &[VfsEntry] {
+        self.files
+            .get(&lookup_key(path))
+            .map_or(&[], |entries| entries.as_slice())
+    }
+
+    /// Read the data of a file from the pak which wins
+    pub fn read(&mut self, path: &str) -> Result<Vec<u8>, PakError> {
+        let entry = self
+            .find(path)
+            .ok_or_else(|| PakError::entry_not_found(path.to_owned()))?;
+        let (pak, name) = (entry.pak, entry.name.clone());
+        self.paks[pak].pak.read_entry(&name)
+    }
+
+    /// Iterate over the winning entries of all files which have not been deleted,
+    /// sorted by their path
+    pub fn iter(&self) -> impl Iterator<Item = &VfsEntry> {
+        self.files
+            .values()
+            .filter_map(|entries| winning_entry(entries))
+    }
+
+    /// Iterate over the winning entries of all files in a directory and its subdirectories
+    pub fn iter_dir<'a>(&'a self, directory: &str) -> impl Iterator<Item = &'a VfsEntry> {
+        let mut prefix = lookup_key(directory);
+        if !prefix.is_empty() && !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+
+        self.files
+            .range(prefix.clone()..)
+            .take_while(move |(path, _)| path.starts_with(&prefix))
+            .filter_map(|(_, entries)| winning_entry(entries))
+    }
+}
+
+/// Entry which would be loaded, if the file has not been deleted
+fn winning_entry(entries: &[VfsEntry]) -> Option<&VfsEntry> {
+    entries.first().filter(|entry| !entry.deleted)
+}
+
+/// Prefix of mount points relative to the engine directory, files are relative to the root
+/// directory of the game which is the same for all paks
+const ROOT_MOUNT_POINT: &str = "../../../";
+
+/// Path of an entry with the mount point of the pak applied
+fn virtual_path(mount_point: &str, name: &str) -> String {
+    let mut path = normalize(mount_point);
+    if !path.is_empty() && !path.ends_with('/') {
+        path.push('/');
+    }
+    path.push_str(&normalize(name));
+    path
+}
+
+/// Use forward slashes and make a path relative to the root directory of the game
+fn normalize(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.strip_prefix(ROOT_MOUNT_POINT)
+        .unwrap_or(&path)
+        .trim_start_matches('/')
+        .to_owned()
+}
+
+/// Key of a path as used in [`PakVfs`] lookups
+fn lookup_key(path: &str) -> String {
+    normalize(path).to_lowercase()
+}
@@ -0,0 +1,46 @@
+//! Optional thread pool used to compress entries in parallel
+
+use crate::error::PakError;
+
+/// Runs work either on the calling thread or, with the `threading` feature, on a thread pool.
+/// Results are always returned in the order of the input, so output stays deterministic.
+#[derive(Debug, Default)]
+pub(crate) struct Workers {
+    #[cfg(feature = "threading")]
+    pool: Option<rayon::ThreadPool>,
+}
+
+impl Workers {
+    /// Create workers with the given amount of threads, `0` uses one thread per core
+    /// and `1` does all work on the calling thread.
+    #[cfg(feature = "threading")]
+    pub(crate) fn new(jobs: usize) -> Result<Self, PakError> {
+        if jobs == 1 {
+            return Ok(Self::default());
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .thread_name(|i| format!("unreal_pak-{i}"))
+            .build()
+            .map_err(|_| PakError::configuration_invalid())?;
+
+        Ok(Self { pool: Some(pool) })
+    }
+
+    /// Map every item with `f`, stopping at the first error
+    pub(crate) fn map<T, U, F>(&self, items: &[T], f: F) -> Result<Vec<U>, PakError>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&T) -> Result<U, PakError> + Sync,
+    {
+        #[cfg(feature = "threading")]
+        if let Some(pool) = &self.pool {
+            use rayon::prelude::*;
+            return pool.install(|| items.par_iter().map(&f).collect());
+        }
+
+        items.iter().map(f).collect()
+    }
+}
@@ -0,0 +1,88 @@
+/// Entries shared by the tests with their name, data and whether they should be compressed.
+/// Covers small, empty and multi block entries, sorted by name like `PakMemory` writes them.
+#[allow(dead_code)]
+pub(crate) fn test_entries() -> Vec<(String, Vec<u8>, bool)> {
+    vec![
+        (
+            "Game/Config/DefaultGame.ini".to_string(),
+            b"[/Script]".to_vec(),
+            false,
+        ),
+        (
+            "Game/Content/Data.uasset".to_string(),
+            (0..30_000u32).map(|i| (i % 7) as u8).collect(),
+            true,
+        ),
+        (
+            "Game/Content/Data.uexp".to_string(),
+            b"uncompressed entry data".to_vec(),
+            false,
+        ),
+        ("Game/Content/Empty.uexp".to_string(), Vec::new(), true),
+        (
+            "Game/Content/Maps/Level.ubulk".to_string(),
+            (0..200_000u32).map(|i| (i % 89) as u8).collect(),
+            false,
+        ),
+        (
+            "Game/Content/Maps/Level.umap".to_string(),
+            (0..300_000u32).map(|i| (i % 97) as u8).collect(),
+            true,
+        ),
+        (
+            "Game/Content/small.txt".to_string(),
+            b"tiny".to_vec(),
+            false,
+        ),
+        (
+            "metadata.json".to_string(),
+            br#"{"name":"test"}"#.to_vec(),
+            false,
+        ),
+    ]
+}
+
+/// Corrupts bytes at pseudo random positions using a xorshift generator.
+/// The same seed always produces the same corruptions so failures can be reproduced.
+#[allow(dead_code)]
+pub(crate) struct Mutator {
+    state: u64,
+}
+
+#[allow(dead_code)]
+impl Mutator {
+    /// Create a new `Mutator`, a seed of 0 is replaced as xorshift would only produce zeroes
+    pub(crate) fn new(seed: u64) -> Self {
+        Mutator {
+            state: match seed {
+                0 => 0x2545F4914F6CDD1D,
+                seed => seed,
+            },
+        }
+    }
+
+    /// Get the next pseudo random number
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Corrupt a single byte by setting it to `0xFF`, `0x00` or flipping random bits.
+    /// Returns the position of the byte, `None` if `data` is empty.
+    pub(crate) fn mutate(&mut self, data: &mut [u8]) -> Option<usize> {
+        if data.is_empty() {
+            return None;
+        }
+
+        let random = self.next_u64();
+        let position = (random >> 8) as usize % data.len();
+        data[position] = match (random >> 40) % 3 {
+            0 => 0xFF,
+            1 => 0x00,
+            _ => data[position] ^ ((random >> 48) as u8 | 1),
+        };
+        Some(position)
+    }
+}
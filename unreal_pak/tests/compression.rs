@@ -0,0 +1,128 @@
+use std::io::{self, Cursor};
+
+use unreal_pak::{
+    compression::{register_codec, CompressionCodec},
+    error::PakErrorKind,
+    pakversion::PakVersion,
+    Compression, PakError, PakMemory, PakReader, PakWriter,
+};
+
+mod common;
+
+/// Stand in for a proprietary method, stores blocks reversed
+struct ReverseCodec;
+
+impl CompressionCodec for ReverseCodec {
+    fn name(&self) -> &str {
+        "Reverse"
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        Ok(data.iter().rev().copied().collect())
+    }
+
+    fn decompress(
+        &self,
+        data: &[u8],
+        decompressed_size: usize,
+        buf: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        buf.extend(data[..decompressed_size].iter().rev());
+        Ok(())
+    }
+}
+
+fn round_trip(pak_version: PakVersion, compression: Compression) -> Result<(), PakError> {
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), pak_version);
+    pak.set_compression(compression)?;
+    for (name, data, _) in common::test_entries() {
+        pak.write_entry(&name, &data, true)?;
+    }
+    let data = pak.finish_write()?.into_inner();
+
+    let mut reader = PakReader::new(Cursor::new(data));
+    reader.load_index()?;
+    for (name, data, _) in common::test_entries() {
+        assert_eq!(reader.read_entry(&name)?, data);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn built_in_codecs() -> Result<(), PakError> {
+    for compression in [
+        Compression::zlib(),
+        Compression::gzip(),
+        Compression::lz4(),
+        Compression::zstd(),
+    ] {
+        round_trip(PakVersion::FnameBasedCompressionMethod, compression)?;
+        round_trip(PakVersion::Fnv64BugFix, compression)?;
+    }
+
+    round_trip(PakVersion::RelativeChunkOffsets, Compression::gzip())?;
+
+    Ok(())
+}
+
+#[test]
+fn custom_codec() -> Result<(), PakError> {
+    let compression = Compression::from_name("Reverse")?;
+    assert_eq!(compression.name(), Some("Reverse"));
+
+    // without the codec registered an error has to be returned
+    let mut pak = PakMemory::new(PakVersion::Fnv64BugFix);
+    pak.set_compression(compression);
+    for (name, data, _) in common::test_entries() {
+        pak.set_entry(name, data);
+    }
+    let err = pak
+        .write(&mut Cursor::new(Vec::new()))
+        .expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::CompressionUnsupported(_)));
+
+    register_codec(ReverseCodec);
+    round_trip(PakVersion::Fnv64BugFix, compression)?;
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_for_pak_version() {
+    let err =
+        round_trip(PakVersion::RelativeChunkOffsets, Compression::lz4()).expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::CompressionUnsupported(_)));
+}
+
+#[test]
+fn absolute_block_offsets() -> Result<(), PakError> {
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), PakVersion::IndexEncryption);
+    for (name, data, _) in common::test_entries() {
+        pak.write_entry(&name, &data, true)?;
+    }
+    let data = pak.finish_write()?.into_inner();
+
+    let mut reader = PakReader::new(Cursor::new(&data));
+    reader.load_index()?;
+    let mut compressed_entries = 0;
+    for (name, entry_data, _) in common::test_entries() {
+        assert_eq!(reader.read_entry(&name)?, entry_data);
+        let info = reader.get_entry_info(&name).expect("Missing entry info");
+        if matches!(info.compression, Compression::None) {
+            continue;
+        }
+        compressed_entries += 1;
+
+        // before RelativeChunkOffsets blocks point to absolute offsets in the pak file
+        let offset = info.offset as usize;
+        let read_u64 = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+        let block_count = u32::from_le_bytes(data[offset + 48..offset + 52].try_into().unwrap());
+        let header_size = 52 + 16 * block_count as u64 + 5;
+        assert_eq!(read_u64(offset + 52), info.offset + header_size);
+    }
+    assert!(compressed_entries > 1);
+    assert!(reader.verify()?.issues.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    error::PakErrorKind,
+    pakversion::PakVersion,
+    vfs::{PROJECT_PAKS_ORDER, PROJECT_SAVED_ORDER},
+    PakError, PakMemory, PakReader, PakVfs, PakWriter,
+};
+
+fn write_patch(pak_version: PakVersion) -> Result<Vec<u8>, PakError> {
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), pak_version);
+    pak.write_entry(
+        &"Game/Content/Kept.uasset".to_string(),
+        &b"patched".to_vec(),
+        false,
+    )?;
+    pak.write_delete_record(&"Game/Content/Removed.uasset".to_string())?;
+    Ok(pak.finish_write()?.into_inner())
+}
+
+#[test]
+fn read_write_delete_records() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::DeleteRecords,
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        let mut reader = PakReader::new(Cursor::new(write_patch(pak_version)?));
+        reader.load_index()?;
+
+        assert_eq!(reader.get_entry_names(), ["Game/Content/Kept.uasset"]);
+        assert_eq!(reader.get_delete_records(), ["Game/Content/Removed.uasset"]);
+        assert!(!reader.contains_entry(&"Game/Content/Removed.uasset".to_string()));
+        assert!(reader.verify()?.is_ok());
+
+        // delete records survive a round trip through PakMemory
+        let mut pak = PakMemory::load_from(&mut reader.into_inner())?;
+        assert!(pak.contains_delete_record(&"Game/Content/Removed.uasset".to_string()));
+        pak.set_delete_record("Game/Content/Kept.uasset".to_string());
+        assert!(pak.get_entry_names().is_empty());
+
+        let mut data = Cursor::new(Vec::new());
+        pak.write(&mut data)?;
+        let pak = PakMemory::load_from(&mut data)?;
+        assert_eq!(
+            pak.get_delete_records(),
+            ["Game/Content/Kept.uasset", "Game/Content/Removed.uasset"]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn unsupported_pak_version() {
+    let err = write_patch(PakVersion::RelativeChunkOffsets).expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::PakVersionUnsupported(_)));
+
+    let mut pak = PakMemory::new(PakVersion::RelativeChunkOffsets);
+    pak.set_delete_record("Game/Content/Removed.uasset".to_string());
+    let err = pak
+        .write(&mut Cursor::new(Vec::new()))
+        .expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::PakVersionUnsupported(_)));
+}
+
+#[test]
+fn vfs_hides_deleted_files() -> Result<(), PakError> {
+    let mut game = PakWriter::new(Cursor::new(Vec::new()), PakVersion::Fnv64BugFix);
+    for name in ["Game/Content/Kept.uasset", "Game/Content/Removed.uasset"] {
+        game.write_entry(&name.to_string(), &b"game".to_vec(), false)?;
+    }
+    let mut game = PakReader::new(Cursor::new(game.finish_write()?.into_inner()));
+    game.load_index()?;
+
+    let mut patch = PakReader::new(Cursor::new(write_patch(PakVersion::Fnv64BugFix)?));
+    patch.load_index()?;
+
+    let mut vfs = PakVfs::new();
+    vfs.mount("Game-WindowsNoEditor.pak", PROJECT_PAKS_ORDER, game);
+    vfs.mount("000-Patch-1.0.0_P.pak", PROJECT_SAVED_ORDER, patch);
+
+    assert_eq!(vfs.read("Game/Content/Kept.uasset")?, b"patched");
+    assert!(!vfs.contains("Game/Content/Removed.uasset"));
+    assert_eq!(vfs.find_all("Game/Content/Removed.uasset").len(), 2);
+    assert_eq!(vfs.iter().count(), 1);
+
+    Ok(())
+}
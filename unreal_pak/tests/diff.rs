@@ -0,0 +1,107 @@
+use std::io::Cursor;
+
+use unreal_pak::{diff::diff, pakversion::PakVersion, Compression, PakError, PakReader, PakWriter};
+
+fn write_pak(
+    pak_version: PakVersion,
+    entries: &[(&str, Vec<u8>, bool)],
+) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), pak_version);
+    for (name, data, compress) in entries {
+        pak.write_entry(&name.to_string(), data, *compress)?;
+    }
+
+    let mut reader = PakReader::new(Cursor::new(pak.finish_write()?.into_inner()));
+    reader.load_index()?;
+    Ok(reader)
+}
+
+#[test]
+fn diff_paks() -> Result<(), PakError> {
+    let data = (0..50_000u32).map(|i| (i % 31) as u8).collect::<Vec<_>>();
+    let mut modified = data.clone();
+    modified[1000] = 0xff;
+
+    for pak_version in [
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        let mut old = write_pak(
+            pak_version,
+            &[
+                ("Game/Content/Modified.uasset", data.clone(), true),
+                ("Game/Content/Recompressed.uasset", data.clone(), true),
+                ("Game/Content/Removed.uasset", data.clone(), true),
+                ("Game/Content/Same.uasset", data.clone(), true),
+            ],
+        )?;
+        let mut new = write_pak(
+            pak_version,
+            &[
+                ("Game/Content/Added.uasset", data.clone(), true),
+                ("Game/Content/Modified.uasset", modified.clone(), true),
+                ("Game/Content/Recompressed.uasset", data.clone(), false),
+                ("Game/Content/Same.uasset", data.clone(), true),
+            ],
+        )?;
+
+        let pak_diff = diff(&mut old, &mut new)?;
+        assert_eq!(pak_diff.added, ["Game/Content/Added.uasset"]);
+        assert_eq!(pak_diff.removed, ["Game/Content/Removed.uasset"]);
+        assert_eq!(pak_diff.modified.len(), 2);
+
+        let entry = &pak_diff.modified[0];
+        assert_eq!(entry.name, "Game/Content/Modified.uasset");
+        assert!(entry.content_changed);
+        assert!(entry.compression.is_none());
+        assert!(entry.decompressed_size.is_none());
+
+        let entry = &pak_diff.modified[1];
+        assert_eq!(entry.name, "Game/Content/Recompressed.uasset");
+        assert!(!entry.content_changed);
+        assert_eq!(
+            entry.compression,
+            Some((Compression::zlib(), Compression::None))
+        );
+        assert!(entry.compressed_size.is_some());
+
+        assert!(diff(&mut old, &mut old_copy(pak_version, &data)?)?.is_empty());
+    }
+
+    Ok(())
+}
+
+fn old_copy(pak_version: PakVersion, data: &[u8]) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+    write_pak(
+        pak_version,
+        &[
+            ("Game/Content/Modified.uasset", data.to_vec(), true),
+            ("Game/Content/Recompressed.uasset", data.to_vec(), true),
+            ("Game/Content/Removed.uasset", data.to_vec(), true),
+            ("Game/Content/Same.uasset", data.to_vec(), true),
+        ],
+    )
+}
+
+#[test]
+fn diff_block_sizes() -> Result<(), PakError> {
+    let data = (0..200_000u32).map(|i| (i % 29) as u8).collect::<Vec<_>>();
+    let name = "Game/Content/Reblocked.uasset".to_string();
+
+    let mut old = write_pak(PakVersion::Fnv64BugFix, &[(&name, data.clone(), true)])?;
+
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), PakVersion::Fnv64BugFix);
+    pak.block_size = 0x8000;
+    pak.write_entry(&name, &data, true)?;
+    let mut new = PakReader::new(Cursor::new(pak.finish_write()?.into_inner()));
+    new.load_index()?;
+
+    // the stored data differs with another block size but the contents are the same
+    let pak_diff = diff(&mut old, &mut new)?;
+    for entry in &pak_diff.modified {
+        assert_eq!(entry.name, name);
+        assert!(!entry.content_changed);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    error::PakErrorKind, pakversion::PakVersion, Compression, EncryptionKey, PakError, PakMemory,
+    PakReader, PakWriter,
+};
+
+mod common;
+
+const KEY_HEX: &str = "0x4BE5D3A4B0F1E2C3D4A5B6C7D8E9FA0B1C2D3E4F5061728394A5B6C7D8E9FA0B";
+const KEY_GUID: [u8; 0x10] = [0x11; 0x10];
+
+#[test]
+fn parse_key() -> Result<(), PakError> {
+    let hex = EncryptionKey::from_hex(KEY_HEX)?;
+    let base64 = EncryptionKey::from_base64("S+XTpLDx4sPUpbbH2On6CxwtPk9QYXKDlKW2x9jp+gs=")?;
+    assert_eq!(hex, base64);
+    assert_eq!(KEY_HEX.parse::<EncryptionKey>()?, hex);
+
+    let err = EncryptionKey::from_hex("0x1234").expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyInvalid));
+
+    Ok(())
+}
+
+#[test]
+fn pak_memory_round_trip() -> Result<(), PakError> {
+    let key = EncryptionKey::from_hex(KEY_HEX)?;
+
+    let mut pak = PakMemory::new(PakVersion::FnameBasedCompressionMethod);
+    for (name, data, _) in common::test_entries() {
+        pak.set_entry(name, data);
+    }
+
+    let mut writer = Cursor::new(Vec::new());
+    pak.write_encrypted(&mut writer, &key, KEY_GUID, true)?;
+    let data = writer.into_inner();
+
+    let mut reader = Cursor::new(&data);
+    let err = PakMemory::load_from(&mut reader).expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+
+    let loaded = PakMemory::load_from_encrypted(&mut Cursor::new(&data), &key)?;
+    for (name, data, _) in common::test_entries() {
+        assert_eq!(loaded.get_entry(&name), Some(&data));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pak_writer_round_trip() -> Result<(), PakError> {
+    let key = EncryptionKey::from_hex(KEY_HEX)?;
+
+    for encrypt_index in [false, true] {
+        let mut pak = PakWriter::new_encrypted(
+            Cursor::new(Vec::new()),
+            PakVersion::FnameBasedCompressionMethod,
+            key,
+            KEY_GUID,
+        );
+        pak.encrypt_index = encrypt_index;
+
+        for (name, data, _) in common::test_entries() {
+            pak.write_entry(&name, &data, true)?;
+        }
+        let data = pak.finish_write()?.into_inner();
+
+        // the encrypted data must not contain the plain text
+        assert!(!data.windows(4).any(|window| window == b"tiny"));
+
+        let mut reader = PakReader::new_encrypted(Cursor::new(&data), key);
+        reader.load_index()?;
+        for (name, data, _) in common::test_entries() {
+            assert_eq!(reader.read_entry(&name)?, data);
+        }
+
+        // reading the index must only require a key when it is encrypted
+        let mut reader = PakReader::new(Cursor::new(&data));
+        match reader.load_index() {
+            Ok(()) => {
+                assert!(!encrypt_index);
+                let err = reader
+                    .read_entry(&"metadata.json".to_string())
+                    .expect_err("Expected err");
+                assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+            }
+            Err(err) => {
+                assert!(encrypt_index);
+                assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pak_writer_partially_encrypted() -> Result<(), PakError> {
+    let key = EncryptionKey::from_hex(KEY_HEX)?;
+
+    let mut pak = PakWriter::new_encrypted(
+        Cursor::new(Vec::new()),
+        PakVersion::Fnv64BugFix,
+        key,
+        KEY_GUID,
+    );
+    pak.encrypt_index = false;
+    for (i, (name, data, _)) in common::test_entries().into_iter().enumerate() {
+        pak.encrypt_entries = i % 2 == 0;
+        pak.write_entry(&name, &data, true)?;
+    }
+    let data = pak.finish_write()?.into_inner();
+
+    let mut reader = PakReader::new_encrypted(Cursor::new(&data), key);
+    reader.load_index()?;
+    for (i, (name, data, _)) in common::test_entries().into_iter().enumerate() {
+        let info = reader.get_entry_info(&name).expect("Missing entry info");
+        assert_eq!(info.encrypted, i % 2 == 0);
+        assert_eq!(reader.read_entry(&name)?, data);
+    }
+
+    // unencrypted entries can be read without a key
+    let mut reader = PakReader::new(Cursor::new(&data));
+    reader.load_index()?;
+    let (name, data, _) = &common::test_entries()[1];
+    assert_eq!(reader.read_entry(name)?, *data);
+
+    Ok(())
+}
+
+#[test]
+fn uncompressed_entry_size() -> Result<(), PakError> {
+    let key = EncryptionKey::from_hex(KEY_HEX)?;
+
+    let mut pak = PakWriter::new_encrypted(
+        Cursor::new(Vec::new()),
+        PakVersion::FnameBasedCompressionMethod,
+        key,
+        KEY_GUID,
+    );
+    let name = "Game/Content/small.txt".to_string();
+    pak.write_entry(&name, &b"tiny".to_vec(), false)?;
+    let data = pak.finish_write()?.into_inner();
+
+    // like the engine the stored size of uncompressed entries excludes the encryption padding
+    let compressed_size = u64::from_le_bytes(data[8..16].try_into().unwrap());
+    assert_eq!(compressed_size, 4);
+
+    let mut reader = PakReader::new_encrypted(Cursor::new(&data), key);
+    reader.load_index()?;
+    let info = reader.get_entry_info(&name).expect("Missing entry info");
+    assert_eq!(info.compressed_size, 4);
+    assert_eq!(reader.read_entry(&name)?, b"tiny");
+    assert!(reader.verify()?.issues.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn unpadded_block_sizes() -> Result<(), PakError> {
+    let key = EncryptionKey::from_hex(KEY_HEX)?;
+    let (name, data, _) = common::test_entries().swap_remove(5);
+
+    for compression in [Compression::zlib(), Compression::lz4()] {
+        let mut pak = PakWriter::new_encrypted(
+            Cursor::new(Vec::new()),
+            PakVersion::FnameBasedCompressionMethod,
+            key,
+            KEY_GUID,
+        );
+        pak.set_compression(compression)?;
+        pak.write_entry(&name, &data, true)?;
+        let pak = pak.finish_write()?.into_inner();
+
+        // like the engine blocks store their compressed size, only their placement is aligned
+        let block_count = u32::from_le_bytes(pak[48..52].try_into().unwrap()) as usize;
+        assert!(block_count > 1);
+        let blocks = (0..block_count)
+            .map(|i| {
+                let block = &pak[52 + i * 16..68 + i * 16];
+                let start = u64::from_le_bytes(block[..8].try_into().unwrap());
+                let end = u64::from_le_bytes(block[8..].try_into().unwrap());
+                (start, end - start)
+            })
+            .collect::<Vec<_>>();
+        assert!(blocks.iter().any(|(_, size)| size % 16 != 0));
+        for window in blocks.windows(2) {
+            let ((start, size), (next_start, _)) = (window[0], window[1]);
+            assert_eq!(next_start, start + size.next_multiple_of(16));
+        }
+
+        let mut reader = PakReader::new_encrypted(Cursor::new(&pak), key);
+        reader.load_index()?;
+        assert_eq!(reader.read_entry(&name)?, data);
+        assert!(reader.verify()?.is_ok());
+    }
+
+    Ok(())
+}
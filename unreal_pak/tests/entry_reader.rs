@@ -0,0 +1,108 @@
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use unreal_pak::{
+    pakversion::PakVersion, Compression, EncryptionKey, PakError, PakReader, PakWriter,
+};
+
+mod common;
+
+fn write_pak(pak_version: PakVersion, key: Option<EncryptionKey>) -> Result<Vec<u8>, PakError> {
+    let mut pak = match key {
+        Some(key) => PakWriter::new_encrypted(Cursor::new(Vec::new()), pak_version, key, [0; 16]),
+        None => PakWriter::new(Cursor::new(Vec::new()), pak_version),
+    };
+    for (name, data, compress) in common::test_entries() {
+        pak.write_entry(&name, &data, compress)?;
+    }
+    Ok(pak.finish_write()?.into_inner())
+}
+
+fn check_entries<R: Read + Seek>(reader: &mut PakReader<R>) -> Result<(), PakError> {
+    for (name, data, _) in common::test_entries() {
+        let mut entry = reader.open_entry(&name)?;
+        assert_eq!(entry.size(), data.len() as u64);
+
+        let mut read = Vec::new();
+        entry.read_to_end(&mut read)?;
+        assert_eq!(read, data);
+
+        // ranges crossing block boundaries, read backwards to defeat the cache
+        for start in [data.len() / 2, 0x10000 - 5, 10, 0] {
+            if start + 10 > data.len() {
+                continue;
+            }
+            entry.seek(SeekFrom::Start(start as u64))?;
+            let mut buf = [0u8; 10];
+            entry.read_exact(&mut buf)?;
+            assert_eq!(buf, data[start..start + 10]);
+        }
+
+        assert_eq!(entry.seek(SeekFrom::End(0))?, data.len() as u64);
+        assert_eq!(entry.read(&mut [0u8; 4])?, 0);
+        assert!(entry
+            .seek(SeekFrom::Current(-(data.len() as i64) - 1))
+            .is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn stream_entries() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::CompressionEncryption,
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        let mut reader = PakReader::new(Cursor::new(write_pak(pak_version, None)?));
+        reader.load_index()?;
+        check_entries(&mut reader)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn stream_encrypted_entries() -> Result<(), PakError> {
+    let key = EncryptionKey::new([7u8; 32]);
+    let data = write_pak(PakVersion::Fnv64BugFix, Some(key))?;
+
+    let mut reader = PakReader::new_encrypted(Cursor::new(data), key);
+    reader.load_index()?;
+    check_entries(&mut reader)
+}
+
+#[test]
+fn entry_info() -> Result<(), PakError> {
+    let key = EncryptionKey::new([7u8; 32]);
+    for key in [None, Some(key)] {
+        let data = write_pak(PakVersion::Fnv64BugFix, key)?;
+        let mut reader = match key {
+            Some(key) => PakReader::new_encrypted(Cursor::new(data), key),
+            None => PakReader::new(Cursor::new(data)),
+        };
+        reader.load_index()?;
+
+        let mut last_offset = None;
+        for (name, data, compress) in common::test_entries() {
+            let info = reader.get_entry_info(&name).expect("Missing entry info");
+            assert_eq!(info.decompressed_size, data.len() as u64);
+            assert_eq!(info.encrypted, key.is_some());
+            assert!(last_offset < Some(info.offset));
+            last_offset = Some(info.offset);
+
+            if compress && !data.is_empty() {
+                assert_eq!(info.compression, Compression::zlib());
+                assert!(info.compressed_size < info.decompressed_size);
+            } else if !compress {
+                assert_eq!(info.compression, Compression::None);
+                assert_eq!(info.compressed_size, info.decompressed_size);
+            }
+        }
+        assert!(reader
+            .get_entry_info(&"Game/Content/Missing.uasset".to_string())
+            .is_none());
+    }
+
+    Ok(())
+}
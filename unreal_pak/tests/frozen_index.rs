@@ -0,0 +1,194 @@
+use std::io::Cursor;
+
+use sha1::{Digest, Sha1};
+use unreal_pak::{pakversion::PakVersion, PakError, PakMemory, PakReader, PakWriter};
+
+mod common;
+
+const MOUNT_POINT: &str = "../../../";
+
+/// Writes the memory image of an index the way the engine lays it out when freezing
+#[derive(Default)]
+struct ImageWriter {
+    data: Vec<u8>,
+}
+
+impl ImageWriter {
+    fn alloc(&mut self, size: usize) -> usize {
+        let position = self.data.len();
+        self.data.resize(position + size.next_multiple_of(8), 0);
+        position
+    }
+
+    fn write(&mut self, position: usize, bytes: &[u8]) {
+        self.data[position..position + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn pointer(&mut self, position: usize, target: usize) {
+        let offset = (target as i64 - position as i64) << 1 | 1;
+        self.write(position, &offset.to_le_bytes());
+    }
+
+    fn array(&mut self, position: usize, target: usize, count: usize) {
+        self.pointer(position, target);
+        self.write(position + 8, &(count as u32).to_le_bytes());
+        self.write(position + 12, &(count as u32).to_le_bytes());
+    }
+
+    fn string(&mut self, position: usize, string: &str) {
+        let chars = string.encode_utf16().chain([0]).collect::<Vec<_>>();
+        let target = self.alloc(chars.len() * 2);
+        for (i, c) in chars.iter().enumerate() {
+            self.write(target + i * 2, &c.to_le_bytes());
+        }
+        self.array(position, target, chars.len());
+    }
+
+    /// Write a map with one unallocated element in front, returning the element positions
+    fn map(&mut self, position: usize, element_size: usize, count: usize) -> Vec<usize> {
+        let elements = self.alloc((count + 1) * element_size);
+        self.array(position, elements, count + 1);
+
+        let bits = self.alloc(4);
+        let mask = (((1u64 << count) - 1) << 1) as u32;
+        self.write(bits, &mask.to_le_bytes());
+        self.array(position + 16, bits, count + 1);
+
+        (1..=count).map(|i| elements + i * element_size).collect()
+    }
+}
+
+/// Replace the index of a v9 pak with a frozen one
+fn freeze(pak: &[u8]) -> Result<Vec<u8>, PakError> {
+    let mut reader = PakReader::new(Cursor::new(pak));
+    reader.load_index()?;
+
+    let footer = &pak[pak.len() - 0xDE..];
+    let index_offset = u64::from_le_bytes(footer[0x19..0x21].try_into().unwrap()) as usize;
+
+    let mut image = ImageWriter::default();
+    let root = image.alloc(88);
+    image.string(root, MOUNT_POINT);
+
+    let entries = common::test_entries();
+    let files = image.alloc(80 * entries.len());
+    image.array(root + 16, files, entries.len());
+    for (i, (name, _, _)) in entries.iter().enumerate() {
+        let info = reader.get_entry_info(name).unwrap();
+        let position = files + i * 80;
+        let offset = info.offset as usize;
+
+        image.write(position, &info.offset.to_le_bytes());
+        image.write(position + 8, &info.compressed_size.to_le_bytes());
+        image.write(position + 16, &info.decompressed_size.to_le_bytes());
+        // the hash of the header in front of the data
+        image.write(position + 24, &pak[offset + 28..offset + 48]);
+        image.write(position + 64, &0x10000u32.to_le_bytes());
+
+        let compression_method =
+            u32::from_le_bytes(pak[offset + 24..offset + 28].try_into().unwrap());
+        if compression_method != 0 {
+            // the blocks are relative to the header in front of the data
+            let block_count =
+                u32::from_le_bytes(pak[offset + 48..offset + 52].try_into().unwrap()) as usize;
+            let blocks = image.alloc(16 * block_count);
+            image.write(blocks, &pak[offset + 52..offset + 52 + 16 * block_count]);
+            image.array(position + 48, blocks, block_count);
+            image.write(position + 68, &compression_method.to_le_bytes());
+        }
+    }
+
+    let mut directories: Vec<(String, Vec<(String, usize)>)> = Vec::new();
+    for (i, (name, _, _)) in entries.iter().enumerate() {
+        let (directory, file_name) = match name.rsplit_once('/') {
+            Some((directory, file_name)) => (format!("/{directory}/"), file_name),
+            None => ("/".to_string(), name.as_str()),
+        };
+        match directories.iter_mut().find(|(e, _)| *e == directory) {
+            Some((_, files)) => files.push((file_name.to_string(), i)),
+            None => directories.push((directory, vec![(file_name.to_string(), i)])),
+        }
+    }
+
+    let directory_elements = image.map(root + 32, 80, directories.len());
+    for ((directory, files), element) in directories.iter().zip(directory_elements) {
+        image.string(element, directory);
+        let file_elements = image.map(element + 16, 32, files.len());
+        for ((file_name, index), file_element) in files.iter().zip(file_elements) {
+            image.string(file_element, file_name);
+            image.write(file_element + 16, &(*index as u32).to_le_bytes());
+        }
+    }
+
+    let mut frozen = pak[..index_offset].to_vec();
+    frozen.extend_from_slice(&image.data);
+
+    let mut footer = footer.to_vec();
+    footer[0x21..0x29].copy_from_slice(&(image.data.len() as u64).to_le_bytes());
+    footer[0x29..0x3D].copy_from_slice(&Sha1::digest(&image.data));
+    footer[0x3D] = 1;
+    frozen.extend_from_slice(&footer);
+
+    Ok(frozen)
+}
+
+fn write_pak() -> Result<Vec<u8>, PakError> {
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), PakVersion::FrozenIndex);
+    for (name, data, compress) in common::test_entries() {
+        pak.write_entry(&name, &data, compress)?;
+    }
+    Ok(pak.finish_write()?.into_inner())
+}
+
+#[test]
+fn read_frozen_index() -> Result<(), PakError> {
+    let pak = freeze(&write_pak()?)?;
+
+    let mut reader = PakReader::new(Cursor::new(&pak));
+    reader.load_index()?;
+    assert_eq!(reader.get_pak_version(), PakVersion::FrozenIndex);
+    assert_eq!(reader.mount_point, MOUNT_POINT);
+    assert_eq!(reader.get_entry_names().len(), common::test_entries().len());
+    for (name, data, _) in common::test_entries() {
+        assert_eq!(reader.read_entry(&name)?, data);
+    }
+
+    let report = reader.verify()?;
+    assert!(report.is_ok(), "{report}");
+
+    let pak_memory = PakMemory::load_from(&mut Cursor::new(&pak))?;
+    for (name, data, _) in common::test_entries() {
+        assert_eq!(pak_memory.get_entry(&name), Some(&data));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn malformed_frozen_index() -> Result<(), PakError> {
+    let pak = freeze(&write_pak()?)?;
+    let index_offset =
+        u64::from_le_bytes(pak[pak.len() - 0xDE + 0x19..][..8].try_into().unwrap()) as usize;
+
+    // corrupt every byte of the index, reading must fail cleanly or still succeed
+    for position in index_offset..pak.len() - 0xDE {
+        for value in [0x00, 0x01, 0x7F, 0xFF] {
+            let mut pak = pak.clone();
+            pak[position] = value;
+
+            let mut reader = PakReader::new(Cursor::new(pak));
+            if reader.load_index().is_ok() {
+                for name in reader
+                    .get_entry_names()
+                    .into_iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                {
+                    let _ = reader.read_entry(&name);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
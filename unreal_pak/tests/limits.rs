@@ -0,0 +1,186 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    error::PakErrorKind, pakversion::PakVersion, sharedpakreader::InMemory, Compression, PakError,
+    PakLimits, PakMemory, PakReader, PakUpdater, PakWriter, SharedPakReader,
+};
+
+mod common;
+use common::Mutator;
+
+fn write_pak(pak_version: PakVersion, compression: Compression) -> Result<Vec<u8>, PakError> {
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), pak_version);
+    if pak_version >= PakVersion::FnameBasedCompressionMethod {
+        pak.set_compression(compression)?;
+    }
+    pak.write_entry(
+        &"Game/Content/First.uasset".to_string(),
+        &b"uncompressed entry data".to_vec(),
+        false,
+    )?;
+    pak.write_entry(
+        &"Game/Content/Second.uexp".to_string(),
+        &(0..100_000u32).map(|i| (i % 13) as u8).collect(),
+        true,
+    )?;
+    if pak_version >= PakVersion::DeleteRecords {
+        pak.write_delete_record(&"Game/Content/Removed.uasset".to_string())?;
+    }
+    Ok(pak.finish_write()?.into_inner())
+}
+
+/// Parse a pak file in every way possible, errors are fine but nothing may panic
+fn parse(data: &[u8]) {
+    let mut reader = PakReader::new(Cursor::new(data));
+    if reader.load_index().is_ok() {
+        let names = reader
+            .get_entry_names()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in names {
+            let _ = reader.read_entry(&name);
+            if let Ok(mut entry) = reader.open_entry(&name) {
+                let _ = std::io::copy(&mut entry, &mut std::io::sink());
+            }
+        }
+        let _ = reader.verify();
+    }
+
+    let _ = PakMemory::load_from(&mut Cursor::new(data));
+}
+
+#[test]
+fn malformed_paks_do_not_panic() -> Result<(), PakError> {
+    for (pak_version, compression) in [
+        (PakVersion::CompressionEncryption, Compression::zlib()),
+        (PakVersion::EncryptionKeyGuid, Compression::zlib()),
+        (PakVersion::FnameBasedCompressionMethod, Compression::lz4()),
+        (PakVersion::Fnv64BugFix, Compression::zstd()),
+        (PakVersion::Fnv64BugFix, Compression::zlib()),
+    ] {
+        let original = write_pak(pak_version, compression)?;
+
+        // truncated files
+        for len in (0..original.len()).step_by(97) {
+            parse(&original[..len]);
+        }
+
+        // single corrupted bytes, deterministic so failures can be reproduced
+        let mut mutator = Mutator::new(0x2545F4914F6CDD1D);
+        for _ in 0..1000 {
+            let mut data = original.clone();
+            // most of the structure is in the index and footer at the end
+            let tail = data.len() - data.len().min(0x200);
+            match mutator.next_u64() % 4 {
+                0 => mutator.mutate(&mut data),
+                _ => mutator.mutate(&mut data[tail..]),
+            };
+            parse(&data);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn limits_are_enforced() -> Result<(), PakError> {
+    let data = write_pak(PakVersion::Fnv64BugFix, Compression::zlib())?;
+
+    let load = |limits: PakLimits| {
+        let mut reader = PakReader::new(Cursor::new(data.clone()));
+        reader.set_limits(limits);
+        reader.load_index().map(|_| reader)
+    };
+    let is_limit = |err: PakError| matches!(err.kind, PakErrorKind::LimitExceeded(..));
+
+    let err = load(PakLimits {
+        max_entry_count: 2,
+        ..Default::default()
+    })
+    .expect_err("Expected err");
+    assert!(is_limit(err));
+
+    let err = load(PakLimits {
+        max_string_len: 12,
+        ..Default::default()
+    })
+    .expect_err("Expected err");
+    assert!(is_limit(err));
+
+    let err = load(PakLimits {
+        max_index_size: 16,
+        ..Default::default()
+    })
+    .expect_err("Expected err");
+    assert!(is_limit(err));
+
+    let mut reader = load(PakLimits {
+        max_entry_size: 1000,
+        ..Default::default()
+    })?;
+    reader.read_entry(&"Game/Content/First.uasset".to_string())?;
+    let err = reader
+        .read_entry(&"Game/Content/Second.uexp".to_string())
+        .expect_err("Expected err");
+    assert!(is_limit(err));
+
+    let mut pak = PakMemory::new(PakVersion::Invalid);
+    pak.set_limits(PakLimits {
+        max_entry_size: 1000,
+        ..Default::default()
+    });
+    let err = pak.load(&mut Cursor::new(data)).expect_err("Expected err");
+    assert!(is_limit(err));
+
+    Ok(())
+}
+
+#[test]
+fn shared_reader_and_updater_limits() -> Result<(), PakError> {
+    let data = write_pak(PakVersion::Fnv64BugFix, Compression::zlib())?;
+    let is_limit = |err: PakError| matches!(err.kind, PakErrorKind::LimitExceeded(..));
+    let index_limits = PakLimits {
+        max_entry_count: 2,
+        ..Default::default()
+    };
+    let entry_limits = PakLimits {
+        max_entry_size: 1000,
+        ..Default::default()
+    };
+    let first = "Game/Content/First.uasset".to_string();
+    let second = "Game/Content/Second.uexp".to_string();
+
+    let err = SharedPakReader::new_with_limits(InMemory(data.clone()), None, index_limits)
+        .expect_err("Expected err");
+    assert!(is_limit(err));
+
+    let mut pak = SharedPakReader::new(InMemory(data.clone()))?;
+    pak.set_limits(entry_limits);
+    pak.read_entry(&first)?;
+    assert!(is_limit(pak.read_entry(&second).expect_err("Expected err")));
+
+    let err = PakUpdater::new_with_limits(Cursor::new(data.clone()), None, index_limits)
+        .expect_err("Expected err");
+    assert!(is_limit(err));
+
+    let mut updater = PakUpdater::new(Cursor::new(data))?;
+    updater.set_limits(entry_limits);
+    updater.read_entry(&first)?;
+    assert!(is_limit(
+        updater.read_entry(&second).expect_err("Expected err")
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_pak_version() {
+    let err = PakVersion::Invalid.to_num().expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::PakVersionUnsupported(_)));
+
+    let err = PakWriter::new(Cursor::new(Vec::new()), PakVersion::Invalid)
+        .finish_write()
+        .expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::PakVersionUnsupported(_)));
+}
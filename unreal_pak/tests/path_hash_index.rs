@@ -0,0 +1,51 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    pakversion::PakVersion, EncryptionKey, PakError, PakMemory, PakReader, PakWriter,
+};
+
+mod common;
+
+#[test]
+fn pak_writer_round_trip() -> Result<(), PakError> {
+    for pak_version in [PakVersion::PathHashIndex, PakVersion::Fnv64BugFix] {
+        for compress in [false, true] {
+            let mut pak = PakWriter::new(Cursor::new(Vec::new()), pak_version);
+            for (name, data, _) in common::test_entries() {
+                pak.write_entry(&name, &data, compress)?;
+            }
+            let data = pak.finish_write()?.into_inner();
+
+            let mut reader = PakReader::new(Cursor::new(data));
+            reader.load_index()?;
+            assert_eq!(reader.get_pak_version(), pak_version);
+            assert_eq!(reader.get_entry_names().len(), common::test_entries().len());
+            for (name, data, _) in common::test_entries() {
+                assert_eq!(reader.read_entry(&name)?, data);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pak_memory_round_trip_encrypted() -> Result<(), PakError> {
+    let key = EncryptionKey::new([0x5a; 32]);
+
+    let mut pak = PakMemory::new(PakVersion::Fnv64BugFix);
+    for (name, data, _) in common::test_entries() {
+        pak.set_entry(name, data);
+    }
+
+    let mut writer = Cursor::new(Vec::new());
+    pak.write_encrypted(&mut writer, &key, [0u8; 0x10], true)?;
+
+    let loaded = PakMemory::load_from_encrypted(&mut Cursor::new(writer.into_inner()), &key)?;
+    assert_eq!(loaded.pak_version, PakVersion::Fnv64BugFix);
+    for (name, data, _) in common::test_entries() {
+        assert_eq!(loaded.get_entry(&name), Some(&data));
+    }
+
+    Ok(())
+}
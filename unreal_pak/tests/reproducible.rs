@@ -0,0 +1,138 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    pakversion::PakVersion, pakwriter::PathHashSeed, EncryptionKey, PakError, PakMemory, PakReader,
+    PakWriter,
+};
+
+mod common;
+
+fn write_pak(
+    pak_version: PakVersion,
+    key: Option<EncryptionKey>,
+    path_hash_seed: PathHashSeed,
+) -> Result<Vec<u8>, PakError> {
+    let mut pak = match key {
+        Some(key) => PakWriter::new_encrypted(Cursor::new(Vec::new()), pak_version, key, [0; 16]),
+        None => PakWriter::new(Cursor::new(Vec::new()), pak_version),
+    };
+    pak.path_hash_seed = path_hash_seed;
+    for (name, data, _) in common::test_entries() {
+        pak.write_entry(
+            &name,
+            &data,
+            pak_version >= PakVersion::CompressionEncryption,
+        )?;
+    }
+    Ok(pak.finish_write()?.into_inner())
+}
+
+fn write_pak_memory(
+    key: Option<EncryptionKey>,
+    path_hash_seed: PathHashSeed,
+) -> Result<Vec<u8>, PakError> {
+    let mut pak = PakMemory::new(PakVersion::Fnv64BugFix);
+    pak.path_hash_seed = path_hash_seed;
+    for (name, data, _) in common::test_entries() {
+        pak.set_entry(name, data);
+    }
+
+    let mut writer = Cursor::new(Vec::new());
+    match key {
+        Some(key) => pak.write_encrypted(&mut writer, &key, [0; 16], true)?,
+        None => pak.write(&mut writer)?,
+    }
+    Ok(writer.into_inner())
+}
+
+#[test]
+fn repeated_builds_are_identical() -> Result<(), PakError> {
+    let key = EncryptionKey::new([0x3c; 32]);
+
+    for key in [None, Some(key)] {
+        for pak_version in [
+            PakVersion::Initial,
+            PakVersion::FnameBasedCompressionMethod,
+            PakVersion::PathHashIndex,
+            PakVersion::Fnv64BugFix,
+        ] {
+            if key.is_some() && pak_version < PakVersion::CompressionEncryption {
+                continue;
+            }
+
+            let data = write_pak(pak_version, key, PathHashSeed::Derived)?;
+            assert_eq!(data, write_pak(pak_version, key, PathHashSeed::Derived)?);
+
+            let mut reader = match key {
+                Some(key) => PakReader::new_encrypted(Cursor::new(data), key),
+                None => PakReader::new(Cursor::new(data)),
+            };
+            reader.load_index()?;
+            for (name, data, _) in common::test_entries() {
+                assert_eq!(reader.read_entry(&name)?, data);
+            }
+        }
+
+        let data = write_pak_memory(key, PathHashSeed::Derived)?;
+        assert_eq!(data, write_pak_memory(key, PathHashSeed::Derived)?);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn fixed_path_hash_seed() -> Result<(), PakError> {
+    let data = write_pak(PakVersion::Fnv64BugFix, None, PathHashSeed::Fixed(1))?;
+    assert_eq!(
+        data,
+        write_pak(PakVersion::Fnv64BugFix, None, PathHashSeed::Fixed(1))?
+    );
+    assert_ne!(
+        data,
+        write_pak(PakVersion::Fnv64BugFix, None, PathHashSeed::Fixed(2))?
+    );
+
+    // the seed only matters for the path hash index
+    assert_eq!(
+        write_pak(
+            PakVersion::FnameBasedCompressionMethod,
+            None,
+            PathHashSeed::Random
+        )?,
+        write_pak(
+            PakVersion::FnameBasedCompressionMethod,
+            None,
+            PathHashSeed::Random
+        )?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn initial_version_timestamps() -> Result<(), PakError> {
+    let data = write_pak(PakVersion::Initial, None, PathHashSeed::Derived)?;
+
+    let mut reader = PakReader::new(Cursor::new(&data));
+    reader.load_index()?;
+    assert_eq!(reader.get_pak_version(), PakVersion::Initial);
+
+    // v1 entry headers have a timestamp between the compression method and the hash
+    let (name, entry_data, _) = &common::test_entries()[0];
+    let offset = reader.get_entry_info(name).unwrap().offset as usize;
+    assert_eq!(&data[offset + 28..offset + 36], &[0; 8]);
+    assert_eq!(
+        &data[offset + 56..offset + 56 + entry_data.len()],
+        &entry_data[..]
+    );
+
+    assert_eq!(reader.read_entry(name)?, *entry_data);
+    assert!(reader.verify()?.issues.is_empty());
+
+    let pak = PakMemory::load_from(&mut Cursor::new(&data))?;
+    let mut writer = Cursor::new(Vec::new());
+    pak.write(&mut writer)?;
+    assert_eq!(writer.into_inner(), data);
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use std::fs::File;
+use std::io::Cursor;
+use std::thread;
+
+use unreal_pak::{
+    error::PakErrorKind,
+    pakversion::PakVersion,
+    sharedpakreader::{InMemory, ReadAt},
+    EncryptionKey, PakError, PakWriter, SharedPakReader,
+};
+
+const KEY_HEX: &str = "4BE5D3A4B0F1E2C3D4A5B6C7D8E9FA0B1C2D3E4F5061728394A5B6C7D8E9FA0B";
+
+fn entry_data(i: u32) -> Vec<u8> {
+    (0..10_000 + i * 1000)
+        .map(|j| ((i + j) % 7) as u8)
+        .collect()
+}
+
+fn write_pak(key: Option<EncryptionKey>) -> Result<Vec<u8>, PakError> {
+    let mut pak = match key {
+        Some(key) => PakWriter::new_encrypted(
+            Cursor::new(Vec::new()),
+            PakVersion::Fnv64BugFix,
+            key,
+            [0; 16],
+        ),
+        None => PakWriter::new(Cursor::new(Vec::new()), PakVersion::Fnv64BugFix),
+    };
+    for i in 0..16 {
+        pak.write_entry(
+            &format!("Game/Content/{i}.uasset"),
+            &entry_data(i),
+            i % 2 == 0,
+        )?;
+    }
+    Ok(pak.finish_write()?.into_inner())
+}
+
+fn read_in_parallel<S: ReadAt + Send + Sync>(pak: &SharedPakReader<S>) {
+    thread::scope(|scope| {
+        for thread in 0..4 {
+            let pak = pak.clone();
+            scope.spawn(move || {
+                for i in (0..16).map(|i| (i + thread * 4) % 16) {
+                    let data = pak.read_entry(&format!("Game/Content/{i}.uasset")).unwrap();
+                    assert_eq!(data, entry_data(i));
+                }
+            });
+        }
+    });
+}
+
+#[test]
+fn read_from_memory() -> Result<(), PakError> {
+    let pak = SharedPakReader::new(InMemory(write_pak(None)?))?;
+    assert_eq!(pak.get_pak_version(), PakVersion::Fnv64BugFix);
+    assert_eq!(pak.get_mount_point(), "../../../");
+    assert_eq!(pak.get_entry_names().len(), 16);
+    assert!(pak.verify()?.is_ok());
+
+    read_in_parallel(&pak);
+
+    let err = pak
+        .read_entry(&"Game/Content/Missing.uasset".to_string())
+        .expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::EntryNotFound(_)));
+
+    Ok(())
+}
+
+#[test]
+fn read_from_file() -> Result<(), PakError> {
+    let path = std::env::temp_dir().join(format!("unreal_pak_shared_{}.pak", std::process::id()));
+    std::fs::write(&path, write_pak(None)?)?;
+
+    let pak = SharedPakReader::new(File::open(&path)?)?;
+    read_in_parallel(&pak);
+    drop(pak);
+
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn read_encrypted() -> Result<(), PakError> {
+    let key = EncryptionKey::from_hex(KEY_HEX)?;
+    let data = write_pak(Some(key))?;
+
+    let err = SharedPakReader::new(InMemory(data.as_slice())).expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+
+    let pak = SharedPakReader::new_encrypted(InMemory(data.as_slice()), key)?;
+    read_in_parallel(&pak);
+
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::io::Cursor;
+
+use rand::{rngs::StdRng, SeedableRng};
+use sha1::{Digest, Sha1};
+
+use unreal_pak::{
+    error::PakErrorKind,
+    pakversion::PakVersion,
+    signature::{
+        compute_chunk_hashes, rsa::Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey, SIGNATURE_CHUNK_SIZE,
+    },
+    PakError, PakSignature, PakWriter,
+};
+
+const PAK_FILE_NAME: &str = "Mod_P.pak";
+
+fn write_pak() -> Result<Vec<u8>, PakError> {
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), PakVersion::Fnv64BugFix);
+    pak.write_entry(
+        &"Game/Content/Data.uasset".to_string(),
+        &(0..200_000u32).map(|i| (i % 251) as u8).collect(),
+        false,
+    )?;
+    Ok(pak.finish_write()?.into_inner())
+}
+
+/// Locally generated test key, small to keep the tests fast
+fn key_from_seed(seed: u64) -> RsaPrivateKey {
+    RsaPrivateKey::new(&mut StdRng::seed_from_u64(seed), 1024).unwrap()
+}
+
+fn key() -> RsaPrivateKey {
+    key_from_seed(0x2545f491)
+}
+
+#[test]
+fn chunk_hashes() -> Result<(), PakError> {
+    let data = write_pak()?;
+    let hashes = compute_chunk_hashes(&mut Cursor::new(&data))?;
+    assert_eq!(hashes.len(), data.len().div_ceil(SIGNATURE_CHUNK_SIZE));
+
+    let mut crc = flate2::Crc::new();
+    crc.update(&data[SIGNATURE_CHUNK_SIZE * 3..]);
+    assert_eq!(hashes[3], crc.sum());
+
+    assert!(compute_chunk_hashes(&mut Cursor::new(Vec::new()))?.is_empty());
+    assert_eq!(
+        compute_chunk_hashes(&mut Cursor::new(vec![0u8; SIGNATURE_CHUNK_SIZE]))?.len(),
+        1
+    );
+
+    Ok(())
+}
+
+#[test]
+fn sign_and_verify() -> Result<(), PakError> {
+    let data = write_pak()?;
+    let private_key = key();
+    let public_key = RsaPublicKey::from(&private_key);
+
+    for pak_file_name in [None, Some(PAK_FILE_NAME)] {
+        let signature = PakSignature::sign(&mut Cursor::new(&data), pak_file_name, &private_key)?;
+        assert_eq!(signature.encrypted_hash.len(), 128);
+
+        let mut sig_file = Vec::new();
+        signature.write(&mut sig_file)?;
+        let read = PakSignature::read(&mut Cursor::new(sig_file))?;
+        assert_eq!(read, signature);
+
+        let report = read.verify(&mut Cursor::new(&data), pak_file_name, &public_key)?;
+        assert!(report.is_ok(), "{report:?}");
+        assert_eq!(report.chunk_count, signature.chunk_hashes.len());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn principal_hash_layout() -> Result<(), PakError> {
+    let data = write_pak()?;
+    let private_key = key();
+    let public_key = RsaPublicKey::from(&private_key);
+
+    let signature = PakSignature::sign(&mut Cursor::new(&data), Some(PAK_FILE_NAME), &private_key)?;
+
+    // the chunk hashes followed by the TCHAR data of the file name, without a terminator
+    let mut principal = Vec::new();
+    for hash in &signature.chunk_hashes {
+        principal.extend_from_slice(&hash.to_le_bytes());
+    }
+    for c in PAK_FILE_NAME.encode_utf16() {
+        principal.extend_from_slice(&c.to_le_bytes());
+    }
+    let hash = Sha1::digest(&principal);
+
+    public_key
+        .verify(
+            Pkcs1v15Sign::new_unprefixed(),
+            &hash,
+            &signature.encrypted_hash,
+        )
+        .expect("Signature does not match the engine layout");
+
+    Ok(())
+}
+
+#[test]
+fn verify_mismatches() -> Result<(), PakError> {
+    let data = write_pak()?;
+    let private_key = key();
+    let public_key = RsaPublicKey::from(&private_key);
+    let signature = PakSignature::sign(&mut Cursor::new(&data), Some(PAK_FILE_NAME), &private_key)?;
+
+    // modified pak data
+    let mut modified = data.clone();
+    modified[SIGNATURE_CHUNK_SIZE + 10] ^= 0xff;
+    let report = signature.verify(
+        &mut Cursor::new(&modified),
+        Some(PAK_FILE_NAME),
+        &public_key,
+    )?;
+    assert!(report.signature_valid);
+    assert_eq!(report.mismatched_chunks, vec![1]);
+
+    // truncated pak
+    let report = signature.verify(
+        &mut Cursor::new(&data[..SIGNATURE_CHUNK_SIZE]),
+        Some(PAK_FILE_NAME),
+        &public_key,
+    )?;
+    assert_eq!(
+        report.mismatched_chunks,
+        (1..signature.chunk_hashes.len()).collect::<Vec<_>>()
+    );
+
+    // signature for a different file name
+    let report = signature.verify(&mut Cursor::new(&data), Some("Other_P.pak"), &public_key)?;
+    assert!(!report.signature_valid);
+    assert!(report.mismatched_chunks.is_empty());
+
+    // tampered chunk hashes
+    let mut tampered = signature.clone();
+    tampered.chunk_hashes[0] ^= 1;
+    let report = tampered.verify(&mut Cursor::new(&data), Some(PAK_FILE_NAME), &public_key)?;
+    assert!(!report.signature_valid);
+    assert_eq!(report.mismatched_chunks, vec![0]);
+
+    // different key
+    let other_key = RsaPublicKey::from(&key_from_seed(7));
+    let report = signature.verify(&mut Cursor::new(&data), Some(PAK_FILE_NAME), &other_key)?;
+    assert!(!report.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn invalid_signature_files() -> Result<(), PakError> {
+    let data = write_pak()?;
+    let signature = PakSignature::sign(&mut Cursor::new(&data), None, &key())?;
+    let mut sig_file = Vec::new();
+    signature.write(&mut sig_file)?;
+
+    for len in 0..sig_file.len() {
+        assert!(PakSignature::read(&mut Cursor::new(&sig_file[..len])).is_err());
+    }
+
+    let mut bad_magic = sig_file.clone();
+    bad_magic[0] ^= 1;
+    assert!(matches!(
+        PakSignature::read(&mut Cursor::new(bad_magic)),
+        Err(PakError {
+            kind: PakErrorKind::SignatureInvalid
+        })
+    ));
+
+    // huge lengths must not allocate
+    let mut huge = sig_file[..8].to_vec();
+    huge.extend(i32::MAX.to_le_bytes());
+    assert!(PakSignature::read(&mut Cursor::new(huge)).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+#![cfg(feature = "threading")]
+
+use std::io::Cursor;
+
+use unreal_pak::{pakversion::PakVersion, PakError, PakMemory, PakReader, PakWriter};
+
+mod common;
+
+fn write_pak_writer(jobs: usize) -> Result<Vec<u8>, PakError> {
+    // v8 does not contain a random path hash seed
+    let mut pak = PakWriter::new(
+        Cursor::new(Vec::new()),
+        PakVersion::FnameBasedCompressionMethod,
+    );
+    pak.set_jobs(jobs)?;
+    for (name, data, _) in common::test_entries() {
+        pak.write_entry(&name, &data, true)?;
+    }
+    Ok(pak.finish_write()?.into_inner())
+}
+
+fn write_pak_memory(jobs: usize) -> Result<Vec<u8>, PakError> {
+    let mut pak = PakMemory::new(PakVersion::FnameBasedCompressionMethod);
+    pak.set_compression(unreal_pak::Compression::zlib());
+    pak.set_jobs(jobs)?;
+    for (name, data, _) in common::test_entries() {
+        pak.set_entry(name, data);
+    }
+
+    let mut writer = Cursor::new(Vec::new());
+    pak.write(&mut writer)?;
+    Ok(writer.into_inner())
+}
+
+#[test]
+fn deterministic_output() -> Result<(), PakError> {
+    let single_threaded = write_pak_writer(1)?;
+    assert_eq!(write_pak_writer(4)?, single_threaded);
+    assert_eq!(write_pak_writer(0)?, single_threaded);
+
+    let single_threaded = write_pak_memory(1)?;
+    assert_eq!(write_pak_memory(4)?, single_threaded);
+
+    let mut reader = PakReader::new(Cursor::new(single_threaded));
+    reader.load_index()?;
+    for (name, data, _) in common::test_entries() {
+        assert_eq!(reader.read_entry(&name)?, data);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use std::fs;
+use std::io::Cursor;
+
+use unreal_pak::{
+    error::PakErrorKind, pakversion::PakVersion, EncryptionKey, PakError, PakLimits, PakReader,
+    PakUpdater, PakWriter,
+};
+
+const KEY_HEX: &str = "4BE5D3A4B0F1E2C3D4A5B6C7D8E9FA0B1C2D3E4F5061728394A5B6C7D8E9FA0B";
+
+fn large_data() -> Vec<u8> {
+    (0..100_000u32).map(|i| (i % 13) as u8).collect()
+}
+
+fn write_pak(pak_version: PakVersion, key: Option<EncryptionKey>) -> Result<Vec<u8>, PakError> {
+    let mut pak = match key {
+        Some(key) => PakWriter::new_encrypted(Cursor::new(Vec::new()), pak_version, key, [0; 16]),
+        None => PakWriter::new(Cursor::new(Vec::new()), pak_version),
+    };
+    pak.write_entry(
+        &"Game/Content/First.uasset".to_string(),
+        &b"uncompressed entry data".to_vec(),
+        false,
+    )?;
+    pak.write_entry(&"Game/Content/Second.uexp".to_string(), &large_data(), true)?;
+    Ok(pak.finish_write()?.into_inner())
+}
+
+fn open_reader(
+    data: Vec<u8>,
+    key: Option<EncryptionKey>,
+) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+    let mut reader = match key {
+        Some(key) => PakReader::new_encrypted(Cursor::new(data), key),
+        None => PakReader::new(Cursor::new(data)),
+    };
+    reader.load_index()?;
+    assert!(reader.verify()?.is_ok());
+    Ok(reader)
+}
+
+#[test]
+fn append_and_replace() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::CompressionEncryption,
+        PakVersion::RelativeChunkOffsets,
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        let original = write_pak(pak_version, None)?;
+        let mut updater = PakUpdater::new(Cursor::new(original.clone()))?;
+        assert_eq!(updater.get_pak_version(), pak_version);
+
+        updater.write_entry("Game/Content/First.uasset", b"replaced entry data", false)?;
+        updater.write_entry("Game/Content/Third.uasset", &large_data(), true)?;
+        assert_eq!(
+            updater.read_entry("Game/Content/Third.uasset")?,
+            large_data()
+        );
+        let updated = updater.finish_write()?.into_inner();
+
+        // the data of the original entries is left untouched
+        let data_end = {
+            let mut reader = open_reader(original.clone(), None)?;
+            reader.read_entry(&"Game/Content/Second.uexp".to_string())?;
+            reader.into_inner().position() as usize
+        };
+        assert_eq!(updated[..data_end], original[..data_end]);
+
+        let mut reader = open_reader(updated, None)?;
+        assert_eq!(
+            reader.get_entry_names(),
+            [
+                "Game/Content/First.uasset",
+                "Game/Content/Second.uexp",
+                "Game/Content/Third.uasset"
+            ]
+        );
+        assert_eq!(
+            reader.read_entry(&"Game/Content/First.uasset".to_string())?,
+            b"replaced entry data"
+        );
+        assert_eq!(
+            reader.read_entry(&"Game/Content/Second.uexp".to_string())?,
+            large_data()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn shorter_index_truncates_file() -> Result<(), PakError> {
+    let original = write_pak(PakVersion::Fnv64BugFix, None)?;
+    let mut updater = PakUpdater::new(Cursor::new(original.clone()))?;
+    updater.remove_entry("Game/Content/Second.uexp")?;
+    let updated = updater.finish_write()?.into_inner();
+    assert!(updated.len() < original.len());
+
+    let mut reader = open_reader(updated, None)?;
+    assert_eq!(reader.get_entry_names(), ["Game/Content/First.uasset"]);
+    assert_eq!(
+        reader.read_entry(&"Game/Content/First.uasset".to_string())?,
+        b"uncompressed entry data"
+    );
+
+    let mut updater = PakUpdater::new(reader.into_inner())?;
+    let err = updater
+        .remove_entry("Game/Content/Second.uexp")
+        .expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::EntryNotFound(_)));
+
+    Ok(())
+}
+
+#[test]
+fn update_file() -> Result<(), PakError> {
+    let path = std::env::temp_dir().join(format!("unreal_pak_updater_{}.pak", std::process::id()));
+    let temp_path =
+        path.with_file_name(format!("unreal_pak_updater_{}.pak.tmp", std::process::id()));
+    let original = write_pak(PakVersion::Fnv64BugFix, None)?;
+    fs::write(&path, &original)?;
+
+    // a failed update leaves the original untouched
+    let err = PakUpdater::update_file(&path, None, PakLimits::default(), |updater| {
+        updater.write_entry("Game/Content/Third.uasset", &large_data(), true)?;
+        updater.remove_entry("Game/Content/Missing.uasset")
+    })
+    .expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::EntryNotFound(_)));
+    assert_eq!(fs::read(&path)?, original);
+    assert!(!temp_path.exists());
+
+    PakUpdater::update_file(&path, None, PakLimits::default(), |updater| {
+        updater.write_entry("Game/Content/Third.uasset", &large_data(), true)
+    })?;
+    assert!(!temp_path.exists());
+
+    let mut reader = open_reader(fs::read(&path)?, None)?;
+    assert_eq!(
+        reader.read_entry(&"Game/Content/Third.uasset".to_string())?,
+        large_data()
+    );
+
+    fs::remove_file(&path)?;
+    Ok(())
+}
+
+#[test]
+fn compact() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::CompressionEncryption,
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        let original = write_pak(pak_version, None)?;
+        let mut updater = PakUpdater::new(Cursor::new(original.clone()))?;
+        updater.write_entry("Game/Content/Second.uexp", b"small", false)?;
+        if pak_version >= PakVersion::DeleteRecords {
+            updater.write_delete_record("Game/Content/Removed.uasset")?;
+        }
+        let updated = updater.finish_write()?;
+        let updated_len = updated.get_ref().len();
+
+        let updater = PakUpdater::new(updated)?;
+        let compacted = updater.compact(Cursor::new(Vec::new()))?.into_inner();
+        assert!(compacted.len() < updated_len);
+
+        let mut reader = open_reader(compacted, None)?;
+        assert_eq!(
+            reader.read_entry(&"Game/Content/First.uasset".to_string())?,
+            b"uncompressed entry data"
+        );
+        assert_eq!(
+            reader.read_entry(&"Game/Content/Second.uexp".to_string())?,
+            b"small"
+        );
+        if pak_version >= PakVersion::DeleteRecords {
+            assert_eq!(reader.get_delete_records(), ["Game/Content/Removed.uasset"]);
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn encrypted() -> Result<(), PakError> {
+    let key = EncryptionKey::from_hex(KEY_HEX)?;
+    let original = write_pak(PakVersion::Fnv64BugFix, Some(key))?;
+
+    let err = PakUpdater::new(Cursor::new(original.clone())).expect_err("Expected err");
+    assert!(matches!(err.kind, PakErrorKind::EncryptionKeyMissing));
+
+    let mut updater = PakUpdater::new_encrypted(Cursor::new(original), key)?;
+    updater.write_entry("Game/Content/Third.uasset", &large_data(), true)?;
+    let updated = updater.finish_write()?.into_inner();
+
+    let mut reader = open_reader(updated.clone(), Some(key))?;
+    assert_eq!(
+        reader.read_entry(&"Game/Content/Third.uasset".to_string())?,
+        large_data()
+    );
+
+    let updater = PakUpdater::new_encrypted(Cursor::new(updated), key)?;
+    let compacted = updater.compact(Cursor::new(Vec::new()))?.into_inner();
+    let mut reader = open_reader(compacted, Some(key))?;
+    assert_eq!(
+        reader.read_entry(&"Game/Content/Second.uexp".to_string())?,
+        large_data()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    pakversion::PakVersion,
+    verify::{IndexPart, VerifyIssue},
+    EncryptionKey, PakError, PakReader, PakWriter,
+};
+
+fn write_pak(pak_version: PakVersion, key: Option<EncryptionKey>) -> Result<Vec<u8>, PakError> {
+    let mut pak = match key {
+        Some(key) => PakWriter::new_encrypted(Cursor::new(Vec::new()), pak_version, key, [0; 16]),
+        None => PakWriter::new(Cursor::new(Vec::new()), pak_version),
+    };
+    pak.write_entry(
+        &"Game/Content/First.uasset".to_string(),
+        &b"uncompressed entry data".to_vec(),
+        false,
+    )?;
+    pak.write_entry(
+        &"Game/Content/Second.uexp".to_string(),
+        &(0..100_000u32).map(|i| (i % 13) as u8).collect(),
+        true,
+    )?;
+    Ok(pak.finish_write()?.into_inner())
+}
+
+fn verify(data: Vec<u8>, key: Option<EncryptionKey>) -> Result<Vec<VerifyIssue>, PakError> {
+    let mut reader = match key {
+        Some(key) => PakReader::new_encrypted(Cursor::new(data), key),
+        None => PakReader::new(Cursor::new(data)),
+    };
+    reader.load_index()?;
+    let report = reader.verify()?;
+    assert_eq!(report.entry_count, 2);
+    Ok(report.issues)
+}
+
+#[test]
+fn valid_paks() -> Result<(), PakError> {
+    for pak_version in [
+        PakVersion::CompressionEncryption,
+        PakVersion::FnameBasedCompressionMethod,
+        PakVersion::Fnv64BugFix,
+    ] {
+        let issues = verify(write_pak(pak_version, None)?, None)?;
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    let key = EncryptionKey::new([3u8; 32]);
+    let issues = verify(write_pak(PakVersion::Fnv64BugFix, Some(key))?, Some(key))?;
+    assert!(issues.is_empty(), "{issues:?}");
+
+    Ok(())
+}
+
+#[test]
+fn corrupted_entry_data() -> Result<(), PakError> {
+    let mut data = write_pak(PakVersion::FnameBasedCompressionMethod, None)?;
+    let position = data
+        .windows(5)
+        .position(|window| window == b"uncom")
+        .unwrap();
+    data[position] = b'U';
+
+    let issues = verify(data, None)?;
+    assert!(matches!(
+        issues.as_slice(),
+        [VerifyIssue::EntryHashMismatch { name, .. }] if name == "Game/Content/First.uasset"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn corrupted_header() -> Result<(), PakError> {
+    let mut data = write_pak(PakVersion::FnameBasedCompressionMethod, None)?;
+    // decompressed size of the first entry, which is written at the start of the file
+    data[16] += 1;
+
+    let issues = verify(data, None)?;
+    assert!(matches!(
+        issues.as_slice(),
+        [VerifyIssue::HeaderMismatch {
+            field: "decompressed size",
+            ..
+        }]
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn corrupted_index() -> Result<(), PakError> {
+    let mut data = write_pak(PakVersion::Fnv64BugFix, None)?;
+    let position = data
+        .windows(9)
+        .rposition(|window| window == b"../../../")
+        .unwrap();
+    data[position] = b',';
+
+    let issues = verify(data, None)?;
+    assert!(matches!(
+        issues.as_slice(),
+        [VerifyIssue::IndexHashMismatch {
+            part: IndexPart::PrimaryIndex,
+            ..
+        }]
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use std::io::Cursor;
+
+use unreal_pak::{
+    pakversion::PakVersion,
+    vfs::{PakPriority, PROJECT_PAKS_ORDER, PROJECT_SAVED_ORDER},
+    PakError, PakReader, PakVfs, PakWriter,
+};
+
+fn pak(
+    mount_point: &str,
+    entries: &[(&str, &str)],
+) -> Result<PakReader<Cursor<Vec<u8>>>, PakError> {
+    let mut pak = PakWriter::new(Cursor::new(Vec::new()), PakVersion::Fnv64BugFix);
+    pak.mount_point = mount_point.to_owned();
+    for (name, data) in entries {
+        pak.write_entry(&name.to_string(), &data.as_bytes().to_vec(), false)?;
+    }
+
+    let mut reader = PakReader::new(Cursor::new(pak.finish_write()?.into_inner()));
+    reader.load_index()?;
+    Ok(reader)
+}
+
+type TestPak = (&'static str, u32, PakReader<Cursor<Vec<u8>>>);
+
+fn paks() -> Result<Vec<TestPak>, PakError> {
+    Ok(vec![
+        (
+            "Game-WindowsNoEditor.pak",
+            PROJECT_PAKS_ORDER,
+            pak(
+                "../../../",
+                &[
+                    ("Game/Content/A.uasset", "game a"),
+                    ("Game/Content/B.uasset", "game b"),
+                    ("Game/Content/Maps/Map.umap", "game map"),
+                ],
+            )?,
+        ),
+        (
+            "000-ModA-1.0.0_P.pak",
+            PROJECT_SAVED_ORDER,
+            pak(
+                "../../../",
+                &[
+                    ("Game/Content/A.uasset", "mod a"),
+                    ("Game/Content/ModA.uasset", "mod a only"),
+                ],
+            )?,
+        ),
+        (
+            "001-ModB-1.0.0_P.pak",
+            PROJECT_SAVED_ORDER,
+            pak("../../../Game/Content/", &[("A.uasset", "mod b")])?,
+        ),
+    ])
+}
+
+#[test]
+fn priority() {
+    let game = PakPriority::new("Game-WindowsNoEditor.pak", PROJECT_PAKS_ORDER);
+    let patch = PakPriority::new("Game-WindowsNoEditor_P.pak", PROJECT_PAKS_ORDER);
+    let chunk_patch = PakPriority::new("pakchunk0-WindowsNoEditor_2_P.pak", PROJECT_PAKS_ORDER);
+    let mod_a = PakPriority::new("000-ModA-1.0.0_P.pak", PROJECT_SAVED_ORDER);
+    let mod_b = PakPriority::new("001-ModB-1.0.0_P.pak", PROJECT_SAVED_ORDER);
+
+    assert_eq!(game.get_order(), 4);
+    assert_eq!(patch.get_order(), 104);
+    assert_eq!(chunk_patch.get_order(), 304);
+    assert_eq!(mod_a.get_order(), 101);
+
+    assert!(mod_a > game);
+    assert!(patch > mod_a);
+    assert!(mod_b > mod_a);
+    assert!(chunk_patch > patch);
+}
+
+#[test]
+fn lookup() -> Result<(), PakError> {
+    // the winner must not depend on the order paks are mounted in
+    for reverse in [false, true] {
+        let mut paks = paks()?;
+        if reverse {
+            paks.reverse();
+        }
+
+        let mut vfs = PakVfs::new();
+        for (file_name, order, pak) in paks {
+            vfs.mount(file_name, order, pak);
+        }
+
+        let winner = vfs.find("Game/Content/A.uasset").unwrap();
+        assert_eq!(vfs.get_pak_name(winner.pak), Some("001-ModB-1.0.0_P.pak"));
+        assert_eq!(winner.name, "A.uasset");
+        assert_eq!(vfs.find_all("Game/Content/A.uasset").len(), 3);
+        assert_eq!(vfs.read("../../../game/content/a.uasset")?, b"mod b");
+        assert_eq!(vfs.read("Game/Content/B.uasset")?, b"game b");
+        assert_eq!(vfs.read("Game/Content/ModA.uasset")?, b"mod a only");
+
+        assert!(vfs.contains("GAME/CONTENT/MAPS/MAP.UMAP"));
+        assert!(!vfs.contains("Game/Content/C.uasset"));
+
+        let paths = vfs
+            .iter()
+            .map(|entry| entry.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "Game/Content/A.uasset",
+                "Game/Content/B.uasset",
+                "Game/Content/Maps/Map.umap",
+                "Game/Content/ModA.uasset",
+            ]
+        );
+
+        let paths = vfs
+            .iter_dir("Game/Content/Maps")
+            .map(|entry| entry.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["Game/Content/Maps/Map.umap"]);
+    }
+
+    Ok(())
+}
@@ -1,14 +1,20 @@
 use std::fs::{File, OpenOptions};
-use std::io::{BufReader, BufWriter, Write};
+use std::io::{self, BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::time::SystemTime;
 
 use clap::{Parser, Subcommand};
+use glob::Pattern;
 use path_absolutize::Absolutize;
-use unreal_pak::{pakversion::PakVersion, PakReader, PakWriter};
+use unreal_pak::{
+    diff::diff, pakversion::PakVersion, pakwriter::PathHashSeed, Compression, EncryptionKey,
+    PakReader, PakWriter,
+};
 use walkdir::WalkDir;
 
+mod response_file;
+
 /// Command line tool for working with Unreal Engine .pak files.
 /// Use `unreal_pak_cli <SUBCOMMAND> -h` for more information on a subcommand.
 #[derive(Parser, Debug)]
@@ -18,6 +24,9 @@ struct Args {
     /// What to do
     #[clap(subcommand)]
     commands: Commands,
+    /// AES-256 key used to read or create encrypted .pak files, as hex or base64
+    #[clap(long, global = true)]
+    aes_key: Option<String>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -34,23 +43,81 @@ enum Commands {
         pakfile: String,
     },
 
+    /// List the entries of a .pak file with their sizes, compression and location.
+    List {
+        /// The .pak file to list
+        pakfile: String,
+        /// Only list entries matching this glob pattern, e.g. "*.uasset"
+        filter: Option<String>,
+        /// Print the entries as JSON
+        #[clap(long)]
+        json: bool,
+    },
+
     /// Extract a .pak file to a directory.
     Extract {
         /// The .pak file to extract
         pakfile: String,
         /// The directory to extract to, if not specified the .pak file name will be used
         outdir: Option<String>,
+        /// Only extract entries matching this glob pattern, can be given multiple times
+        #[clap(long)]
+        include: Vec<String>,
+        /// Skip entries matching this glob pattern, can be given multiple times
+        #[clap(long)]
+        exclude: Vec<String>,
+        /// Write the data of the single matching entry to stdout instead of extracting to a directory
+        #[clap(long, conflicts_with_all = ["outdir", "keep_going", "mount_point_relative"])]
+        to_stdout: bool,
+        /// Continue with the other entries when one fails and report all failures at the end
+        #[clap(short, long)]
+        keep_going: bool,
+        /// Place entries under the pak's mount point inside the output directory
+        #[clap(long)]
+        mount_point_relative: bool,
     },
 
-    /// create a new .pak file from the files from a directory, optionally disabling compression.
+    /// create a new .pak file from the files from a directory or an UnrealPak response file, optionally disabling compression.
     Create {
-        /// The directory to create the file from
+        /// The directory or UnrealPak response file to create the file from.
+        /// Each line of a response file contains a file path, its path in the pak including the
+        /// mount point and optionally `-compress` and `-encrypt`. Entries are written in that order.
         indir: String,
         /// The .pak file to create, if not supplied the dir name will be used
         pakfile: Option<String>,
         /// Do not use compression when writing the file
         #[clap(short, long)]
         no_compression: bool,
+        /// GUID of the encryption key as 32 hex characters, written to the footer when encrypting
+        #[clap(long)]
+        key_guid: Option<String>,
+        /// Amount of threads used for compression, 0 uses one thread per core
+        #[clap(short, long, default_value_t = 0)]
+        jobs: usize,
+        /// Version of the pak file format to write
+        #[clap(long, default_value_t = 11)]
+        pak_version: u32,
+        /// Mount point of the pak, defaults to `../../../` for directories and to the directory
+        /// shared by all pak paths for response files
+        #[clap(long)]
+        mount_point: Option<String>,
+        /// Size of the blocks entries are compressed in
+        #[clap(long, default_value_t = 0x10000)]
+        block_size: u32,
+        /// Compression method used for compressed entries
+        #[clap(long, default_value = "Zlib")]
+        compression: String,
+    },
+
+    /// List added, removed and modified entries between two .pak files.
+    Diff {
+        /// The old .pak file
+        old: String,
+        /// The new .pak file
+        new: String,
+        /// Print the changes as JSON
+        #[clap(long)]
+        json: bool,
     },
 }
 
@@ -59,113 +126,222 @@ fn main() {
 
     let start = SystemTime::now();
 
+    let key = args.aes_key.as_deref().map(parse_key);
+
     match args.commands {
         Commands::CheckHeader { pakfile } => {
-            let file = open_file(Path::new(&pakfile));
-            let mut pak = PakReader::new(file);
+            let mut pak = open_pak(Path::new(&pakfile), key);
             check_header(&mut pak);
         }
         Commands::Check { pakfile } => {
-            let file = open_file(Path::new(&pakfile));
-            let mut pak = PakReader::new(file);
+            let mut pak = open_pak(Path::new(&pakfile), key);
             check_header(&mut pak);
 
-            for (i, (file_name, data)) in pak.iter().enumerate() {
-                println!("Record {i}: {file_name:?}");
-
-                match data {
-                    Ok(_) => (),
-                    Err(e) => {
-                        eprintln!("Error reading record {i}: {file_name:?}! Error: {e}");
-                        exit(1);
-                    }
+            let report = match pak.verify() {
+                Ok(report) => report,
+                Err(e) => {
+                    eprintln!("Error verifying pak file! Error: {e}");
+                    exit(1);
                 }
+            };
+
+            println!("{report}");
+            if !report.is_ok() {
+                exit(1);
+            }
+        }
+        Commands::List {
+            pakfile,
+            filter,
+            json,
+        } => {
+            let mut pak = open_pak(Path::new(&pakfile), key);
+            load_index(&mut pak);
+
+            let filter = filter.as_deref().map(parse_glob);
+            let entries = pak
+                .get_entry_names()
+                .into_iter()
+                .filter(|name| match &filter {
+                    Some(filter) => filter.matches(name),
+                    None => true,
+                })
+                .filter_map(|name| Some((name, pak.get_entry_info(name)?)))
+                .collect::<Vec<_>>();
+
+            if json {
+                let entries = entries
+                    .iter()
+                    .map(|(name, info)| {
+                        serde_json::json!({
+                            "name": name,
+                            "offset": info.offset,
+                            "compressed_size": info.compressed_size,
+                            "decompressed_size": info.decompressed_size,
+                            "compression": info.compression,
+                            "encrypted": info.encrypted,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let list = serde_json::json!({
+                    "pak_version": pak.get_pak_version().to_num().ok(),
+                    "mount_point": pak.mount_point,
+                    "entries": entries,
+                });
+
+                // only print the JSON so the output can be piped into other tools
+                println!("{}", serde_json::to_string_pretty(&list).unwrap());
+                return;
+            }
+
+            println!("Pak version: {:?}", pak.get_pak_version());
+            println!("Mount point: {}", pak.mount_point);
+            println!(
+                "{:>12} {:>12} {:>12} {:>6} {:<11} {:<9} Name",
+                "Offset", "Compressed", "Size", "Ratio", "Compression", "Encrypted"
+            );
+
+            let (mut total_compressed, mut total_decompressed) = (0, 0);
+            for (name, info) in &entries {
+                println!(
+                    "{:>12} {:>12} {:>12} {:>6} {:<11} {:<9} {}",
+                    info.offset,
+                    info.compressed_size,
+                    info.decompressed_size,
+                    ratio(info.compressed_size, info.decompressed_size),
+                    info.compression.to_string(),
+                    if info.encrypted { "yes" } else { "no" },
+                    name
+                );
+                total_compressed += info.compressed_size;
+                total_decompressed += info.decompressed_size;
             }
+            println!(
+                "{} entries, {total_compressed} bytes compressed, {total_decompressed} bytes uncompressed ({})",
+                entries.len(),
+                ratio(total_compressed, total_decompressed)
+            );
         }
-        Commands::Extract { pakfile, outdir } => {
+        Commands::Extract {
+            pakfile,
+            outdir,
+            include,
+            exclude,
+            to_stdout,
+            keep_going,
+            mount_point_relative,
+        } => {
             let path = Path::new(&pakfile);
-            let file = open_file(path);
-            let mut pak = PakReader::new(file);
+            let mut pak = open_pak(path, key);
+
+            let include = include.iter().map(|e| parse_glob(e)).collect::<Vec<_>>();
+            let exclude = exclude.iter().map(|e| parse_glob(e)).collect::<Vec<_>>();
+
+            if to_stdout {
+                // stdout only gets the entry data, everything else goes to stderr
+                load_index(&mut pak);
+                let names = filter_entries(&pak, &include, &exclude);
+                if names.len() != 1 {
+                    eprintln!(
+                        "--to-stdout requires exactly one matching entry, found {}",
+                        names.len()
+                    );
+                    exit(1);
+                }
+
+                let result = pak
+                    .open_entry(&names[0])
+                    .map_err(|err| err.to_string())
+                    .and_then(|mut entry| {
+                        io::copy(&mut entry, &mut io::stdout().lock())
+                            .map_err(|err| err.to_string())
+                    });
+                if let Err(err) = result {
+                    eprintln!("Error reading record {:?}! Error: {err}", names[0]);
+                    exit(1);
+                }
+                return;
+            }
+
             check_header(&mut pak);
 
             // temp values required to extend lifetimes outside of match scope
-            let output_folder: PathBuf = match outdir {
+            let mut output_folder: PathBuf = match outdir {
                 Some(ref outdir) => PathBuf::from(outdir),
                 None => path.parent().unwrap().join(path.file_stem().unwrap()),
             };
+            if mount_point_relative {
+                output_folder = output_folder.join(relative_path(&pak.mount_point));
+            }
 
-            println!("Extracting to {output_folder:?}");
+            let names = filter_entries(&pak, &include, &exclude);
+            println!("Extracting {} records to {output_folder:?}", names.len());
 
-            for (i, (file_name, data)) in pak.iter().enumerate() {
-                match data {
-                    Ok(data) => {
-                        let path = output_folder.join(file_name);
-                        let dir_path = match path.parent() {
-                            Some(dir) => dir,
-                            None => {
-                                eprintln!("No parent directories found! {i}: {file_name:?}");
-                                exit(1);
-                            }
-                        };
-
-                        // Create the parent directories, then files.
-                        match std::fs::create_dir_all(dir_path) {
-                            Ok(_) => {
-                                // Create the file
-                                let mut file = match File::create(&path) {
-                                    Ok(file) => file,
-                                    Err(err) => {
-                                        eprintln!(
-                                            "Error creating file {i}: {path:?}! Error: {err}"
-                                        );
-                                        exit(1);
-                                    }
-                                };
-                                // Write the file
-                                match file.write_all(data.as_slice()) {
-                                    Ok(_) => {
-                                        println!("Record {i}: {file_name}");
-                                    }
-                                    Err(err) => {
-                                        eprintln!(
-                                            "Error writing to file {i}: {path:?}! Error: {err}"
-                                        );
-                                        exit(1);
-                                    }
-                                }
-                            }
-                            Err(err) => {
-                                eprintln!("Error creating directories {dir_path:?}! Error: {err}");
-                                exit(1);
-                            }
-                        };
+            let mut failed = Vec::new();
+            for (i, file_name) in names.iter().enumerate() {
+                let path = output_folder.join(relative_path(file_name));
+                match extract_entry(&mut pak, file_name, &path) {
+                    Ok(_) => println!("Record {i}: {file_name}"),
+                    Err(err) if keep_going => {
+                        eprintln!("Error extracting record {i}: {file_name:?}! Error: {err}");
+                        failed.push(file_name);
                     }
                     Err(err) => {
-                        eprintln!("Error reading record {i}: {file_name:?}! Error: {err}");
+                        eprintln!("Error extracting record {i}: {file_name:?}! Error: {err}");
                         exit(1);
                     }
                 }
             }
+
+            if !failed.is_empty() {
+                eprintln!("Failed to extract {} records:", failed.len());
+                for file_name in failed {
+                    eprintln!("  {file_name}");
+                }
+                exit(1);
+            }
         }
         Commands::Create {
             indir,
             pakfile,
             no_compression,
+            key_guid,
+            jobs,
+            pak_version,
+            mount_point,
+            block_size,
+            compression,
         } => {
+            let indir = Path::new(&indir).absolutize().unwrap().to_path_buf();
+            let is_response_file = indir.is_file();
+
             let pakfile = match pakfile {
                 Some(pakfile) => Path::new(&pakfile).absolutize().unwrap().to_path_buf(),
+                None if is_response_file => indir.with_extension("pak"),
                 None => {
-                    let mut path = Path::new(&indir)
-                        .absolutize()
-                        .unwrap()
-                        .to_path_buf()
-                        .into_os_string();
+                    let mut path = indir.clone().into_os_string();
                     path.push(".pak");
                     PathBuf::from(path)
                 }
             };
-            let indir = Path::new(&indir).absolutize().unwrap().to_path_buf();
-            let indir_len = indir.components().count();
+
+            let (mount_point, files) = match is_response_file {
+                true => response_file_entries(&indir, mount_point, key.is_some()),
+                false => (
+                    mount_point.unwrap_or_else(|| "../../../".to_string()),
+                    directory_entries(&indir),
+                ),
+            };
+
+            let pak_version = PakVersion::from_num(pak_version);
+            if pak_version == PakVersion::Invalid {
+                eprintln!("Unsupported pak version!");
+                exit(1);
+            }
+            if block_size == 0 {
+                eprintln!("Block size must not be 0!");
+                exit(1);
+            }
 
             println!("Creating {pakfile:?}");
 
@@ -179,46 +355,59 @@ fn main() {
 
             let file = OpenOptions::new().append(true).open(&pakfile).unwrap();
 
-            let mut pak = PakWriter::new(
-                BufWriter::new(file),
-                PakVersion::FnameBasedCompressionMethod,
-            );
-
-            // Get all files and write them to the .pak file
-            let files = WalkDir::new(&indir)
-                .into_iter()
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| entry.file_type().is_file())
-                .collect::<Vec<_>>();
-            //files.sort_unstable_by_key(|entry| entry.file_name().to_owned());
-
-            println!("Writing {} files", files.len());
-
-            for (i, entry) in files.iter().enumerate() {
-                // file_path is the OS absolute path, file_name is the folders and file name written to the pak
-                let file_path = entry.path();
-                let mut components = file_path.components();
-                for _ in 0..indir_len {
-                    components.next();
+            let mut pak = match key {
+                Some(key) => PakWriter::new_encrypted(
+                    BufWriter::new(file),
+                    pak_version,
+                    key,
+                    key_guid
+                        .as_deref()
+                        .map(|guid| {
+                            parse_guid(guid).unwrap_or_else(|err| {
+                                eprintln!("{err}");
+                                exit(1);
+                            })
+                        })
+                        .unwrap_or_default(),
+                ),
+                None => PakWriter::new(BufWriter::new(file), pak_version),
+            };
+            pak.mount_point = mount_point;
+            pak.block_size = block_size;
+            pak.path_hash_seed = PathHashSeed::Derived;
+            // only the built in methods can be used to compress entries
+            let method = match Compression::from_name(&compression) {
+                Ok(Compression::Unknown(_)) => {
+                    eprintln!("Unknown compression method {compression:?}!");
+                    exit(1);
                 }
+                result => result,
+            };
+            if let Err(err) = method.and_then(|method| pak.set_compression(method)) {
+                eprintln!("Invalid compression method {compression:?}! Error: {err}");
+                exit(1);
+            }
+            if let Err(err) = pak.set_jobs(jobs) {
+                eprintln!("Error creating thread pool! Error: {err}");
+                exit(1);
+            }
 
-                let mut file_name = components.as_path().to_string_lossy().replace('\\', "/");
-                if file_name.starts_with('/') {
-                    file_name = file_name[1..].to_owned();
-                }
+            println!("Writing {} files", files.len());
 
-                let file_data = match std::fs::read(file_path) {
+            for (i, file) in files.iter().enumerate() {
+                let file_data = match std::fs::read(&file.path) {
                     Ok(file_data) => file_data,
                     Err(err) => {
-                        eprintln!("Error reading file {file_path:?}! Error: {err}");
+                        eprintln!("Error reading file {:?}! Error: {err}", file.path);
                         exit(1);
                     }
                 };
 
-                match pak.write_entry(&file_name, &file_data, !no_compression) {
-                    Ok(_) => println!("Wrote file {i}: {file_name}"),
+                pak.encrypt_entries = file.encrypt;
+                match pak.write_entry(&file.name, &file_data, file.compress && !no_compression) {
+                    Ok(_) => println!("Wrote file {i}: {}", file.name),
                     Err(err) => {
-                        eprintln!("Error writing file in pak {file_name:?}! Error: {err}");
+                        eprintln!("Error writing file in pak {:?}! Error: {err}", file.name);
                         exit(1);
                     }
                 }
@@ -232,6 +421,55 @@ fn main() {
                 }
             }
         }
+        Commands::Diff { old, new, json } => {
+            let mut old_pak = open_pak(Path::new(&old), key);
+            let mut new_pak = open_pak(Path::new(&new), key);
+            load_index(&mut old_pak);
+            load_index(&mut new_pak);
+
+            let pak_diff = match diff(&mut old_pak, &mut new_pak) {
+                Ok(pak_diff) => pak_diff,
+                Err(err) => {
+                    eprintln!("Error comparing pak files! Error: {err}");
+                    exit(1);
+                }
+            };
+
+            if json {
+                // only print the JSON so the output can be piped into other tools
+                println!("{}", serde_json::to_string_pretty(&pak_diff).unwrap());
+                return;
+            }
+
+            for name in &pak_diff.added {
+                println!("+ {name}");
+            }
+            for name in &pak_diff.removed {
+                println!("- {name}");
+            }
+            for entry in &pak_diff.modified {
+                let mut changes = Vec::new();
+                if entry.content_changed {
+                    changes.push("content".to_string());
+                }
+                if let Some((old, new)) = entry.compression {
+                    changes.push(format!("compression {old} -> {new}"));
+                }
+                if let Some((old, new)) = entry.decompressed_size {
+                    changes.push(format!("size {old} -> {new}"));
+                }
+                if let Some((old, new)) = entry.compressed_size {
+                    changes.push(format!("compressed size {old} -> {new}"));
+                }
+                println!("~ {} ({})", entry.name, changes.join(", "));
+            }
+            println!(
+                "{} added, {} removed, {} modified",
+                pak_diff.added.len(),
+                pak_diff.removed.len(),
+                pak_diff.modified.len()
+            );
+        }
     }
     println!(
         "unreal_pak_cli took {:?} seconds...",
@@ -249,6 +487,207 @@ fn open_file(path: &Path) -> BufReader<File> {
     }
 }
 
+fn open_pak(path: &Path, key: Option<EncryptionKey>) -> PakReader<BufReader<File>> {
+    let file = open_file(path);
+    match key {
+        Some(key) => PakReader::new_encrypted(file, key),
+        None => PakReader::new(file),
+    }
+}
+
+fn parse_key(key: &str) -> EncryptionKey {
+    match key.parse() {
+        Ok(key) => key,
+        Err(err) => {
+            eprintln!("Could not parse AES key! Error: {err}");
+            exit(1);
+        }
+    }
+}
+
+/// Parse a GUID as the engine prints it, either as 32 hex digits or with hyphens.
+/// `FGuid` is stored as four little endian u32s of 8 digits each.
+fn parse_guid(guid: &str) -> Result<[u8; 0x10], String> {
+    let guid = guid.replace('-', "");
+    if guid.len() != 32 || !guid.is_ascii() {
+        return Err("Key GUID must be 32 hex characters!".to_string());
+    }
+
+    let mut bytes = [0u8; 0x10];
+    for (i, part) in bytes.chunks_exact_mut(4).enumerate() {
+        let value = u32::from_str_radix(&guid[i * 8..i * 8 + 8], 16)
+            .map_err(|err| format!("Could not parse key GUID! Error: {err}"))?;
+        part.copy_from_slice(&value.to_le_bytes());
+    }
+    Ok(bytes)
+}
+
+/// A file on disk to write into a pak
+struct InputFile {
+    /// path of the file on disk
+    path: PathBuf,
+    /// name of the entry, relative to the mount point
+    name: String,
+    compress: bool,
+    encrypt: bool,
+}
+
+/// All files in a directory, named by their path relative to it
+fn directory_entries(indir: &Path) -> Vec<InputFile> {
+    let indir_len = indir.components().count();
+
+    // sorted so the same directory always results in the same pak
+    WalkDir::new(indir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            // path is the OS absolute path, name is the folders and file name written to the pak
+            let path = entry.into_path();
+            let mut components = path.components();
+            for _ in 0..indir_len {
+                components.next();
+            }
+
+            let mut name = components.as_path().to_string_lossy().replace('\\', "/");
+            if name.starts_with('/') {
+                name = name[1..].to_owned();
+            }
+
+            InputFile {
+                path,
+                name,
+                compress: true,
+                encrypt: true,
+            }
+        })
+        .collect()
+}
+
+/// All files listed in a response file together with the mount point to use
+fn response_file_entries(
+    path: &Path,
+    mount_point: Option<String>,
+    has_key: bool,
+) -> (String, Vec<InputFile>) {
+    let entries = match std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|content| response_file::parse(&content))
+    {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("Error reading response file {path:?}! Error: {err}");
+            exit(1);
+        }
+    };
+
+    let mount_point = mount_point.unwrap_or_else(|| response_file::common_mount_point(&entries));
+
+    let files = entries
+        .into_iter()
+        .map(|entry| {
+            let Some(name) = entry.destination.strip_prefix(&mount_point) else {
+                eprintln!(
+                    "Pak path {:?} is not inside of the mount point {mount_point:?}!",
+                    entry.destination
+                );
+                exit(1);
+            };
+            if entry.encrypt && !has_key {
+                eprintln!(
+                    "{:?} should be encrypted but no AES key was given!",
+                    entry.source
+                );
+                exit(1);
+            }
+
+            InputFile {
+                name: name.to_string(),
+                path: entry.source,
+                compress: entry.compress,
+                encrypt: entry.encrypt,
+            }
+        })
+        .collect();
+
+    (mount_point, files)
+}
+
+/// Names of all entries matching any of the include patterns (or all when there are none) and
+/// none of the exclude patterns
+fn filter_entries(
+    pak: &PakReader<BufReader<File>>,
+    include: &[Pattern],
+    exclude: &[Pattern],
+) -> Vec<String> {
+    pak.get_entry_names()
+        .into_iter()
+        .filter(|name| include.is_empty() || include.iter().any(|e| e.matches(name)))
+        .filter(|name| !exclude.iter().any(|e| e.matches(name)))
+        .cloned()
+        .collect()
+}
+
+/// Turn a path from a pak into a relative path, dropping `..` and `.` so nothing is written
+/// outside of the output directory
+fn relative_path(path: &str) -> PathBuf {
+    path.split(['/', '\\'])
+        .filter(|e| !e.is_empty() && *e != "." && *e != "..")
+        .collect()
+}
+
+/// Stream the data of an entry into a new file, creating parent directories as needed
+fn extract_entry(
+    pak: &mut PakReader<BufReader<File>>,
+    name: &String,
+    path: &Path,
+) -> Result<(), String> {
+    if let Some(dir_path) = path.parent() {
+        std::fs::create_dir_all(dir_path)
+            .map_err(|err| format!("Could not create directories {dir_path:?}: {err}"))?;
+    }
+
+    let mut entry = pak.open_entry(name).map_err(|err| err.to_string())?;
+    let mut file = File::create(path)
+        .map(BufWriter::new)
+        .map_err(|err| format!("Could not create file {path:?}: {err}"))?;
+
+    io::copy(&mut entry, &mut file)
+        .and_then(|_| file.flush())
+        .map_err(|err| format!("Could not write file {path:?}: {err}"))?;
+
+    Ok(())
+}
+
+fn parse_glob(pattern: &str) -> Pattern {
+    match Pattern::new(pattern) {
+        Ok(pattern) => pattern,
+        Err(err) => {
+            eprintln!("Could not parse glob pattern! Error: {err}");
+            exit(1);
+        }
+    }
+}
+
+/// Compressed size as a percentage of the uncompressed size
+fn ratio(compressed_size: u64, decompressed_size: u64) -> String {
+    match decompressed_size {
+        0 => "-".to_string(),
+        _ => format!(
+            "{:.1}%",
+            compressed_size as f64 / decompressed_size as f64 * 100.0
+        ),
+    }
+}
+
+fn load_index(pak: &mut PakReader<BufReader<File>>) {
+    if let Err(err) = pak.load_index() {
+        eprintln!("Error reading header! Error: {err}");
+        exit(1);
+    }
+}
+
 fn check_header(pak: &mut PakReader<BufReader<File>>) {
     match pak.load_index() {
         Ok(_) => println!("Header is ok"),
@@ -259,3 +698,43 @@ fn check_header(pak: &mut PakReader<BufReader<File>>) {
     }
     println!("Found {:?} records", pak.get_entry_names().len());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_guid;
+
+    /// Print a GUID the way the engine does with `EGuidFormats::DigitsWithHyphens`
+    fn format_guid(bytes: [u8; 0x10]) -> String {
+        let [a, b, c, d] =
+            [0, 1, 2, 3].map(|i| u32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap()));
+        format!(
+            "{a:08X}-{:04X}-{:04X}-{:04X}-{:04X}{d:08X}",
+            b >> 16,
+            b & 0xffff,
+            c >> 16,
+            c & 0xffff
+        )
+    }
+
+    #[test]
+    fn engine_guid() {
+        // FEditorObjectVersion::GUID, FGuid(0xE4B068ED, 0xF49442E9, 0xA231DA0B, 0x2E46BB41)
+        let guid = "E4B068ED-F494-42E9-A231-DA0B2E46BB41";
+        let bytes = parse_guid(guid).unwrap();
+        assert_eq!(
+            bytes,
+            [
+                0xed, 0x68, 0xb0, 0xe4, 0xe9, 0x42, 0x94, 0xf4, 0x0b, 0xda, 0x31, 0xa2, 0x41, 0xbb,
+                0x46, 0x2e
+            ]
+        );
+        assert_eq!(format_guid(bytes), guid);
+        assert_eq!(parse_guid(&guid.replace('-', "")), Ok(bytes));
+    }
+
+    #[test]
+    fn invalid_guid() {
+        assert!(parse_guid("E4B068ED").is_err());
+        assert!(parse_guid("G4B068ED-F494-42E9-A231-DA0B2E46BB41").is_err());
+    }
+}
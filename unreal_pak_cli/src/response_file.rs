@@ -0,0 +1,188 @@
+//! UnrealPak response files, listing which files go into a pak.
+//!
+//! Every line contains the path of a file on disk, the path it gets in the pak including the
+//! mount point and optional flags, e.g.
+//! `"C:/Mod/Content/Item.uasset" "../../../Game/Content/Item.uasset" -compress -encrypt`.
+
+use std::path::PathBuf;
+
+/// One file listed in a response file
+#[derive(Debug)]
+pub struct ResponseEntry {
+    /// path of the file on disk
+    pub source: PathBuf,
+    /// path in the pak, including the mount point
+    pub destination: String,
+    /// whether the entry should be compressed
+    pub compress: bool,
+    /// whether the entry should be encrypted
+    pub encrypt: bool,
+}
+
+/// Parse the content of a response file, entries are returned in the order they are listed
+pub fn parse(content: &str) -> Result<Vec<ResponseEntry>, String> {
+    let mut entries = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line_number = i + 1;
+        let mut arguments = split_arguments(line)
+            .map_err(|err| format!("{err} on line {line_number}"))?
+            .into_iter();
+
+        let Some(source) = arguments.next() else {
+            continue;
+        };
+        let Some(destination) = arguments.next() else {
+            return Err(format!("Missing pak path on line {line_number}"));
+        };
+
+        let mut entry = ResponseEntry {
+            source: PathBuf::from(source),
+            destination: destination.replace('\\', "/"),
+            compress: false,
+            encrypt: false,
+        };
+
+        for option in arguments {
+            match option.to_ascii_lowercase().as_str() {
+                "-compress" => entry.compress = true,
+                "-encrypt" => entry.encrypt = true,
+                // other UnrealPak options have no equivalent here
+                _ => eprintln!("Ignoring unknown option {option:?} on line {line_number}"),
+            }
+        }
+
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// Longest directory all destinations are in, UnrealPak uses this as the mount point
+pub fn common_mount_point(entries: &[ResponseEntry]) -> String {
+    let mut destinations = entries.iter().map(|entry| entry.destination.as_str());
+    let Some(first) = destinations.next() else {
+        return String::new();
+    };
+
+    let mut mount_point = &first[..first.rfind('/').map_or(0, |i| i + 1)];
+    for destination in destinations {
+        while !destination.starts_with(mount_point) {
+            // drop the last directory, keeping the trailing slash of its parent
+            let trimmed = &mount_point[..mount_point.len() - 1];
+            mount_point = &mount_point[..trimmed.rfind('/').map_or(0, |i| i + 1)];
+        }
+    }
+
+    mount_point.to_string()
+}
+
+/// Split a line into whitespace separated arguments, double quotes group an argument
+fn split_arguments(line: &str) -> Result<Vec<String>, String> {
+    let mut arguments = Vec::new();
+    let mut argument: Option<String> = None;
+    let mut quoted = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                argument.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && !quoted => arguments.extend(argument.take()),
+            c => argument.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quoted {
+        return Err("Unterminated quote".to_string());
+    }
+    arguments.extend(argument);
+
+    Ok(arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{common_mount_point, parse};
+
+    #[test]
+    fn quoted_paths() {
+        let entries = parse(concat!(
+            "\"C:/My Mod/Content/Item.uasset\" \"../../../Game/Content/My Item.uasset\"\n",
+            "C:\\Mod\\Content\\Item.uexp ..\\..\\..\\Game\\Content\\Item.uexp\n",
+        ))
+        .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].source,
+            Path::new("C:/My Mod/Content/Item.uasset")
+        );
+        assert_eq!(
+            entries[0].destination,
+            "../../../Game/Content/My Item.uasset"
+        );
+        assert_eq!(entries[1].source, Path::new("C:\\Mod\\Content\\Item.uexp"));
+        assert_eq!(entries[1].destination, "../../../Game/Content/Item.uexp");
+    }
+
+    #[test]
+    fn options() {
+        let entries = parse(concat!(
+            "a.uasset ../../../Game/a.uasset -compress\n",
+            "b.uasset ../../../Game/b.uasset -Encrypt -COMPRESS\n",
+            "c.uasset ../../../Game/c.uasset -rehydrate\n",
+        ))
+        .unwrap();
+
+        let flags = entries
+            .iter()
+            .map(|entry| (entry.compress, entry.encrypt))
+            .collect::<Vec<_>>();
+        assert_eq!(flags, [(true, false), (true, true), (false, false)]);
+    }
+
+    #[test]
+    fn invalid_lines() {
+        assert!(parse("a.uasset\n").is_err());
+        assert!(parse("\"a.uasset ../../../Game/a.uasset\n").is_err());
+    }
+
+    #[test]
+    fn empty() {
+        assert!(parse("").unwrap().is_empty());
+        assert!(parse("\n   \n").unwrap().is_empty());
+        assert_eq!(common_mount_point(&[]), "");
+    }
+
+    #[test]
+    fn mount_points() {
+        let mount_point = |content: &str| common_mount_point(&parse(content).unwrap());
+
+        assert_eq!(
+            mount_point("a ../../../Game/Content/a.uasset"),
+            "../../../Game/Content/"
+        );
+        assert_eq!(
+            mount_point(concat!(
+                "a ../../../Game/Content/Maps/a.umap\n",
+                "b ../../../Game/Content/Mods/b.uasset\n",
+            )),
+            "../../../Game/Content/"
+        );
+        assert_eq!(
+            mount_point(concat!(
+                "a ../../../Game/Content/a.uasset\n",
+                "b ../../../Game/ContentExtra/b.uasset\n",
+                "c ../../../Engine/c.uasset\n",
+            )),
+            "../../../"
+        );
+        // relative pak paths without a shared directory have no mount point
+        assert_eq!(mount_point("a Content/a.uasset\nb Config/b.ini"), "");
+        assert_eq!(mount_point("a a.uasset"), "");
+    }
+}